@@ -0,0 +1,355 @@
+//! Indexing a block with thousands of swaps against pools of different Relics through the updater,
+//! which attributes the fee of every swap to the owner of the inscription that sealed the pool.
+//!
+//! The owner used to be looked up once per swap, by scanning every output cached since the last
+//! commit for the sealing inscription and, on a miss, fetching the output holding it from Bitcoin
+//! Core. The `utxo_cache_scan` bench repeats these lookups on top of indexing the same block, as a
+//! baseline for the lookups of the updater.
+//!
+//! To compare two revisions, run the bench on the first with `--save-baseline before` and on the
+//! second with `--baseline before`.
+
+use {
+  bitcoin::{
+    hashes::Hash,
+    opcodes,
+    script::{self, PushBytesBuf},
+    Block, Network, OutPoint, ScriptBuf, Witness,
+  },
+  bitcoincore_rpc::{Auth, Client, RpcApi},
+  clap::Parser,
+  criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion},
+  mockcore::TransactionTemplate,
+  ord::{options::Options, settings::Settings, Index, InscriptionId},
+  relics::{
+    Enshrining, Keepsake, MintTerms, MultiMint, PriceModel, Relic, RelicId, SpacedRelic, Swap,
+    Transfer, INCEPTION_PARENT_INSCRIPTION_ID, RELIC_ID,
+  },
+  std::{fs, path::Path, str::FromStr},
+  tempfile::TempDir,
+};
+
+const POOLS: u128 = 8;
+const SWAPS: usize = 4000;
+
+/// first of the names of the pools, long enough to make sealing cheap
+const RELIC: u128 = 99246114928149462;
+const PRICE: u128 = 5_000_000;
+
+fn open(core: &mockcore::Handle, dir: &Path) -> Index {
+  let cookie_file = dir.join("cookie");
+  fs::write(&cookie_file, "username:password").unwrap();
+
+  let options = Options::try_parse_from([
+    "ord".into(),
+    "--bitcoin-rpc-url".into(),
+    core.url(),
+    "--cookie-file".into(),
+    cookie_file.to_str().unwrap().into(),
+    "--index".into(),
+    dir.join("index.redb").to_str().unwrap().into(),
+    "--chain=regtest".into(),
+    "--index-relics".into(),
+    // count burned inscriptions as inception inscriptions, the inception parent is not on regtest
+    "--integration-test".into(),
+  ])
+  .unwrap();
+
+  Index::open(&Settings::from_options(options).or_defaults().unwrap()).unwrap()
+}
+
+/// Reveal witness of an inscription with the given fields.
+fn envelope(fields: &[(u8, &[u8])]) -> Witness {
+  let mut builder = script::Builder::new()
+    .push_opcode(opcodes::OP_FALSE)
+    .push_opcode(opcodes::all::OP_IF)
+    .push_slice(b"ord");
+
+  for (tag, value) in fields {
+    let mut buf = PushBytesBuf::new();
+    buf.extend_from_slice(value).unwrap();
+    builder = builder.push_slice([*tag]).push_slice(buf);
+  }
+
+  let script = builder.push_opcode(opcodes::all::OP_ENDIF).into_script();
+
+  Witness::from_slice(&[script.into_bytes(), Vec::new()])
+}
+
+/// Mints the base token once and splits it among the given number of outputs.
+fn mint_base_token(core: &mockcore::Handle, outputs: usize) -> Vec<OutPoint> {
+  let height = usize::try_from(core.height()).unwrap();
+  core.mine_blocks(1);
+
+  let inception_parent = InscriptionId::from_str(INCEPTION_PARENT_INSCRIPTION_ID).unwrap();
+
+  let block_reward = core.tx(height + 1, 0).output[0].value.to_sat();
+  let dust_value = 10000;
+
+  let txid = core.broadcast_tx(TransactionTemplate {
+    // the inception inscription, a child of the inception parent
+    inputs: &[(
+      height + 1,
+      0,
+      0,
+      envelope(&[(3, inception_parent.txid.as_byte_array())]),
+    )],
+    outputs,
+    output_values: vec![dust_value; outputs].as_slice(),
+    // burn the inception inscription
+    op_return_index: Some(0),
+    op_return_value: Some(1),
+    op_return: Some(
+      Keepsake {
+        transfers: vec![Transfer {
+          id: RELIC_ID,
+          amount: 0,
+          output: 1 + u32::try_from(outputs).unwrap(),
+        }],
+        ..Default::default()
+      }
+      .encipher(),
+    ),
+    fee: block_reward - dust_value * outputs as u64 - 1,
+    ..Default::default()
+  });
+
+  core.mine_blocks(1);
+
+  (1..=outputs)
+    .map(|vout| OutPoint {
+      txid,
+      vout: vout.try_into().unwrap(),
+    })
+    .collect()
+}
+
+/// Enshrines a Relic with a pool that is created by its only mint, paying the sealing fee from the
+/// given base tokens. Returns the id of the Relic, the inscription that sealed it and the output
+/// holding the remaining base tokens.
+fn enshrine(
+  core: &mockcore::Handle,
+  relic: SpacedRelic,
+  base: OutPoint,
+) -> (RelicId, InscriptionId, OutPoint) {
+  let height = usize::try_from(core.height()).unwrap();
+  core.mine_blocks(1);
+
+  core.broadcast_tx(TransactionTemplate {
+    inputs: &[(height + 1, 0, 0, Witness::new())],
+    p2tr: true,
+    ..Default::default()
+  });
+
+  core.mine_blocks(Keepsake::COMMIT_CONFIRMATIONS.into());
+
+  let mut metadata = Vec::new();
+  ciborium::into_writer(&relic.to_metadata(), &mut metadata).unwrap();
+
+  let value = core.tx(height + 3, 0).output[0].value + core.get_utxo_amount(&base).unwrap();
+
+  let txid = core.broadcast_tx(TransactionTemplate {
+    inputs: &[(height + 3, 0, 0, envelope(&[(5, &metadata)]))],
+    input_outpoints: &[base],
+    op_return: Some(
+      Keepsake {
+        sealing: true,
+        enshrining: Some(Enshrining {
+          fee: Some(100),
+          mint_terms: Some(MintTerms {
+            cap: Some(1),
+            amount: Some(1_000_000),
+            price: Some(PriceModel::Fixed(PRICE)),
+            seed: Some(1_000_000),
+            ..Default::default()
+          }),
+          ..Default::default()
+        }),
+        pointer: Some(1),
+        ..Default::default()
+      }
+      .encipher(),
+    ),
+    outputs: 2,
+    // the value has to be split evenly among the outputs
+    fee: value.to_sat() % 2,
+    ..Default::default()
+  });
+
+  core.mine_blocks(1);
+
+  (
+    RelicId {
+      block: core.height(),
+      tx: 1,
+    },
+    InscriptionId { txid, index: 0 },
+    OutPoint { txid, vout: 1 },
+  )
+}
+
+/// Mints the given Relic, which creates its pool. Returns the output holding the remaining base
+/// tokens.
+fn mint(core: &mockcore::Handle, id: RelicId, base: OutPoint) -> OutPoint {
+  let txid = core.broadcast_tx(TransactionTemplate {
+    input_outpoints: &[base],
+    outputs: 2,
+    op_return: Some(
+      Keepsake {
+        mint: Some(MultiMint {
+          base_limit: PRICE,
+          count: 1,
+          is_unmint: false,
+          relic: id,
+        }),
+        transfers: vec![Transfer {
+          id,
+          amount: 0,
+          output: 1,
+        }],
+        ..Default::default()
+      }
+      .encipher(),
+    ),
+    fee: core.get_utxo_amount(&base).unwrap().to_sat() % 2,
+    ..Default::default()
+  });
+
+  core.mine_blocks(1);
+
+  OutPoint { txid, vout: 0 }
+}
+
+/// Looks up the owner of the inscription that sealed the pool of every swap in the block the way the
+/// updater did before it tracked owners. The sealing inscriptions were committed before the block,
+/// so every scan of the outputs created earlier in the block misses and the output holding the
+/// inscription is fetched from Bitcoin Core.
+fn scan_utxo_cache(client: &Client, block: &Block, sealings: &[InscriptionId]) {
+  let mut utxo_cache: Vec<(&ScriptBuf, Vec<InscriptionId>)> = Vec::new();
+
+  for (tx, sealing) in block.txdata.iter().skip(1).zip(sealings) {
+    let owner = utxo_cache
+      .iter()
+      .find(|(_, inscriptions)| inscriptions.contains(sealing))
+      .map(|(script_pubkey, _)| script_pubkey.script_hash())
+      .unwrap_or_else(|| {
+        // the sealing inscription stays on the first output of its reveal transaction
+        client
+          .get_raw_transaction_info(&sealing.txid, None)
+          .unwrap()
+          .vout[0]
+          .script_pub_key
+          .script()
+          .unwrap()
+          .script_hash()
+      });
+
+    black_box(owner);
+
+    utxo_cache.extend(
+      tx.output
+        .iter()
+        .map(|output| (&output.script_pubkey, Vec::new())),
+    );
+  }
+}
+
+/// Indexes the pools, then mines a block of swaps, leaving an index that is one block behind.
+/// Returns the block and the inscription that sealed the pool of every swap in it.
+fn setup() -> (mockcore::Handle, TempDir, Block, Vec<InscriptionId>) {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let mut base = mint_base_token(&core, 1)[0];
+  let inputs = mint_base_token(&core, SWAPS);
+
+  let (ids, pool_sealings): (Vec<RelicId>, Vec<InscriptionId>) = (0..POOLS)
+    .map(|i| {
+      let (id, sealing, change) = enshrine(&core, SpacedRelic::new(Relic(RELIC + i), 0), base);
+      base = change;
+      (id, sealing)
+    })
+    .unzip();
+
+  for id in &ids {
+    base = mint(&core, *id, base);
+  }
+
+  let dir = TempDir::new().unwrap();
+  open(&core, dir.path()).update().unwrap();
+
+  let mut sealings = Vec::with_capacity(SWAPS);
+
+  for (input, (id, sealing)) in inputs.iter().zip(ids.iter().zip(&pool_sealings).cycle()) {
+    sealings.push(*sealing);
+
+    core.broadcast_tx(TransactionTemplate {
+      input_outpoints: &[*input],
+      outputs: 1,
+      op_return: Some(
+        Keepsake {
+          swaps: vec![Swap {
+            output: Some(*id),
+            input_amount: Some(100_000),
+            is_exact_input: true,
+            ..Default::default()
+          }],
+          ..Default::default()
+        }
+        .encipher(),
+      ),
+      ..Default::default()
+    });
+  }
+
+  let block = core.mine_blocks(1).remove(0);
+
+  (core, dir, block, sealings)
+}
+
+fn bench(c: &mut Criterion) {
+  let (core, dir, block, sealings) = setup();
+
+  let client = Client::new(&core.url(), Auth::CookieFile(core.cookie_file())).unwrap();
+
+  let copy_index = || {
+    let copy = TempDir::new().unwrap();
+    fs::copy(
+      dir.path().join("index.redb"),
+      copy.path().join("index.redb"),
+    )
+    .unwrap();
+    let index = open(&core, copy.path());
+    (copy, index)
+  };
+
+  let mut group = c.benchmark_group("index_block");
+  group.sample_size(10);
+
+  group.bench_function(format!("{SWAPS}_swaps"), |b| {
+    b.iter_batched(
+      copy_index,
+      |(copy, index)| {
+        index.update().unwrap();
+        (copy, index)
+      },
+      BatchSize::PerIteration,
+    );
+  });
+
+  group.bench_function(format!("{SWAPS}_swaps_utxo_cache_scan"), |b| {
+    b.iter_batched(
+      copy_index,
+      |(copy, index)| {
+        index.update().unwrap();
+        scan_utxo_cache(&client, &block, &sealings);
+        (copy, index)
+      },
+      BatchSize::PerIteration,
+    );
+  });
+
+  group.finish();
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
@@ -117,6 +117,9 @@ pub trait Api {
     include_watchonly: Option<bool>,
   ) -> Result<Value, jsonrpc_core::Error>;
 
+  #[rpc(name = "getrawmempool")]
+  fn get_raw_mempool(&self) -> Result<Vec<Txid>, jsonrpc_core::Error>;
+
   #[rpc(name = "getrawtransaction")]
   fn get_raw_transaction(
     &self,
@@ -199,7 +202,7 @@ pub trait Api {
     &self,
     psbt: String,
     sign: Option<bool>,
-    sighash_type: Option<()>,
+    sighash_type: Option<String>,
     bip32derivs: Option<bool>,
   ) -> Result<WalletProcessPsbtResult, jsonrpc_core::Error>;
 
@@ -682,6 +682,17 @@ impl Api for Server {
     )
   }
 
+  fn get_raw_mempool(&self) -> Result<Vec<Txid>, jsonrpc_core::Error> {
+    Ok(
+      self
+        .state()
+        .mempool
+        .iter()
+        .map(|tx| tx.compute_txid())
+        .collect(),
+    )
+  }
+
   fn get_raw_transaction(
     &self,
     txid: Txid,
@@ -740,7 +751,11 @@ impl Api for Server {
         None => Err(Self::not_found()),
       }
     } else {
-      match state.transactions.get(&txid) {
+      match state
+        .transactions
+        .get(&txid)
+        .or_else(|| state.mempool.iter().find(|tx| tx.compute_txid() == txid))
+      {
         Some(tx) => Ok(Value::String(hex::encode(serialize(tx)))),
         None => Err(Self::not_found()),
       }
@@ -908,8 +923,6 @@ impl Api for Server {
     unlock: bool,
     outputs: Vec<JsonOutPoint>,
   ) -> Result<bool, jsonrpc_core::Error> {
-    assert!(!unlock);
-
     let mut state = self.state();
 
     if state.fail_lock_unspent {
@@ -921,7 +934,11 @@ impl Api for Server {
         vout: output.vout,
         txid: output.txid,
       };
-      assert!(state.locked.insert(output));
+      if unlock {
+        assert!(state.locked.remove(&output));
+      } else {
+        assert!(state.locked.insert(output));
+      }
     }
 
     Ok(true)
@@ -986,12 +1003,19 @@ impl Api for Server {
     &self,
     psbt: String,
     sign: Option<bool>,
-    sighash_type: Option<()>,
+    sighash_type: Option<String>,
     bip32derivs: Option<bool>,
   ) -> Result<WalletProcessPsbtResult, jsonrpc_core::Error> {
-    assert!(sighash_type.is_none());
     assert!(bip32derivs.is_none());
 
+    // fake schnorr signatures have a trailing sighash type byte unless signing with the default
+    let signature = match sighash_type.as_deref() {
+      None | Some("DEFAULT") => vec![0; 64],
+      Some("ALL") => [[0; 64].as_slice(), &[0x01]].concat(),
+      Some("SINGLE|ANYONECANPAY") => [[0; 64].as_slice(), &[0x83]].concat(),
+      Some(sighash_type) => panic!("unsupported sighash type: {sighash_type}"),
+    };
+
     let mut psbt = Psbt::deserialize(
       &base64::engine::general_purpose::STANDARD
         .decode(psbt)
@@ -1014,7 +1038,9 @@ impl Api for Server {
     if let Some(sign) = sign {
       if sign {
         for input in psbt.inputs.iter_mut() {
-          input.final_script_witness = Some(Witness::from_slice(&[&[0; 64]]));
+          if input.final_script_witness.is_none() {
+            input.final_script_witness = Some(Witness::from_slice(&[&signature]));
+          }
         }
       }
     }
@@ -1030,16 +1056,19 @@ impl Api for Server {
     psbt: String,
     _extract: Option<bool>,
   ) -> Result<FinalizePsbtResult, jsonrpc_core::Error> {
-    let mut transaction = Psbt::deserialize(
+    let psbt = Psbt::deserialize(
       &base64::engine::general_purpose::STANDARD
         .decode(psbt)
         .unwrap(),
     )
-    .unwrap()
-    .unsigned_tx;
+    .unwrap();
 
-    for input in &mut transaction.input {
-      if input.witness.is_empty() {
+    let mut transaction = psbt.unsigned_tx;
+
+    for (input, psbt_input) in transaction.input.iter_mut().zip(psbt.inputs) {
+      if let Some(witness) = psbt_input.final_script_witness {
+        input.witness = witness;
+      } else if input.witness.is_empty() {
         input.witness = Witness::from_slice(&[&[0; 64]]);
       }
     }
@@ -0,0 +1,226 @@
+use super::*;
+
+/// Time span covered by a single candle
+#[derive(Debug, PartialEq, Copy, Clone, Default, DeserializeFromStr, SerializeDisplay)]
+pub enum CandleInterval {
+  Minute,
+  FiveMinutes,
+  FifteenMinutes,
+  #[default]
+  Hour,
+  FourHours,
+  Day,
+  Week,
+}
+
+impl CandleInterval {
+  pub fn seconds(self) -> u32 {
+    match self {
+      Self::Minute => 60,
+      Self::FiveMinutes => 5 * 60,
+      Self::FifteenMinutes => 15 * 60,
+      Self::Hour => 60 * 60,
+      Self::FourHours => 4 * 60 * 60,
+      Self::Day => 24 * 60 * 60,
+      Self::Week => 7 * 24 * 60 * 60,
+    }
+  }
+}
+
+impl Display for CandleInterval {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(
+      f,
+      "{}",
+      match self {
+        Self::Minute => "1m",
+        Self::FiveMinutes => "5m",
+        Self::FifteenMinutes => "15m",
+        Self::Hour => "1h",
+        Self::FourHours => "4h",
+        Self::Day => "1d",
+        Self::Week => "1w",
+      }
+    )
+  }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CandleIntervalError(String);
+
+impl Display for CandleIntervalError {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(f, "invalid candle interval: {}", self.0)
+  }
+}
+
+impl std::error::Error for CandleIntervalError {}
+
+impl FromStr for CandleInterval {
+  type Err = CandleIntervalError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "1m" => Ok(Self::Minute),
+      "5m" => Ok(Self::FiveMinutes),
+      "15m" => Ok(Self::FifteenMinutes),
+      "1h" => Ok(Self::Hour),
+      "4h" => Ok(Self::FourHours),
+      "1d" => Ok(Self::Day),
+      "1w" => Ok(Self::Week),
+      _ => Err(CandleIntervalError(s.into())),
+    }
+  }
+}
+
+/// Open, high, low and close price of a Relic in base tokens, together with the traded volume.
+/// Prices are scaled like `Pool::lossy_price`.
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Candle {
+  /// start of the interval as unix timestamp
+  pub time: u32,
+  pub open: u128,
+  pub high: u128,
+  pub low: u128,
+  pub close: u128,
+  pub base_volume: u128,
+  pub quote_volume: u128,
+}
+
+impl Candle {
+  /// Aggregate the recorded pool prices into candles, intervals without any pool change are
+  /// skipped. A candle opens at the close of the previous candle.
+  ///
+  /// Block timestamps are not strictly increasing, a pool change with a timestamp before the
+  /// current candle is counted towards the current candle.
+  pub fn aggregate(prices: &[PoolPrice], interval: CandleInterval) -> Vec<Candle> {
+    let mut candles: Vec<Candle> = Vec::new();
+
+    for price in prices {
+      let time = price.timestamp - price.timestamp % interval.seconds();
+      let close = price.lossy_price();
+
+      match candles.last_mut() {
+        Some(candle) if time <= candle.time => {
+          candle.high = candle.high.max(close);
+          candle.low = candle.low.min(close);
+          candle.close = close;
+          candle.base_volume = candle.base_volume.saturating_add(price.base_volume);
+          candle.quote_volume = candle.quote_volume.saturating_add(price.quote_volume);
+        }
+        last => {
+          let open = last.map(|candle| candle.close).unwrap_or(close);
+          candles.push(Candle {
+            time,
+            open,
+            high: open.max(close),
+            low: open.min(close),
+            close,
+            base_volume: price.base_volume,
+            quote_volume: price.quote_volume,
+          });
+        }
+      }
+    }
+
+    candles
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// price of a Relic if the pool holds one base token per Relic
+  const PRICE: u128 = 100_000_000;
+
+  fn price(timestamp: u32, base_supply: u128, base_volume: u128) -> PoolPrice {
+    PoolPrice {
+      timestamp,
+      base_supply,
+      quote_supply: 1,
+      base_volume,
+      quote_volume: base_volume * 2,
+    }
+  }
+
+  #[test]
+  fn interval_from_str() {
+    for interval in [
+      CandleInterval::Minute,
+      CandleInterval::FiveMinutes,
+      CandleInterval::FifteenMinutes,
+      CandleInterval::Hour,
+      CandleInterval::FourHours,
+      CandleInterval::Day,
+      CandleInterval::Week,
+    ] {
+      assert_eq!(
+        interval.to_string().parse::<CandleInterval>().unwrap(),
+        interval
+      );
+    }
+    assert!("2h".parse::<CandleInterval>().is_err());
+  }
+
+  #[test]
+  fn aggregate() {
+    let prices = [
+      price(3600, 10, 0),
+      price(3700, 14, 4),
+      price(4000, 8, 6),
+      price(7300, 9, 1),
+      // earlier timestamp than the previous block
+      price(7100, 7, 2),
+      price(18000, 12, 3),
+    ];
+
+    assert_eq!(
+      Candle::aggregate(&prices, CandleInterval::Hour),
+      vec![
+        Candle {
+          time: 3600,
+          open: 10 * PRICE,
+          high: 14 * PRICE,
+          low: 8 * PRICE,
+          close: 8 * PRICE,
+          base_volume: 10,
+          quote_volume: 20,
+        },
+        Candle {
+          time: 7200,
+          open: 8 * PRICE,
+          high: 9 * PRICE,
+          low: 7 * PRICE,
+          close: 7 * PRICE,
+          base_volume: 3,
+          quote_volume: 6,
+        },
+        Candle {
+          time: 18000,
+          open: 7 * PRICE,
+          high: 12 * PRICE,
+          low: 7 * PRICE,
+          close: 12 * PRICE,
+          base_volume: 3,
+          quote_volume: 6,
+        },
+      ]
+    );
+
+    assert_eq!(
+      Candle::aggregate(&prices, CandleInterval::Day),
+      vec![Candle {
+        time: 0,
+        open: 10 * PRICE,
+        high: 14 * PRICE,
+        low: 7 * PRICE,
+        close: 12 * PRICE,
+        base_volume: 16,
+        quote_volume: 32,
+      }]
+    );
+
+    assert!(Candle::aggregate(&[], CandleInterval::Hour).is_empty());
+  }
+}
@@ -0,0 +1,333 @@
+use super::*;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum EventInfo {
+  InscriptionCreated {
+    charms: u16,
+    inscription_id: InscriptionId,
+    #[schemars(with = "Option<schema::SatPoint>")]
+    location: Option<SatPoint>,
+    parent_inscription_ids: Vec<InscriptionId>,
+    sequence_number: u32,
+  },
+  InscriptionTransferred {
+    inscription_id: InscriptionId,
+    #[schemars(with = "schema::SatPoint")]
+    new_location: SatPoint,
+    #[schemars(with = "schema::SatPoint")]
+    old_location: SatPoint,
+    sequence_number: u32,
+    is_base_relic: bool,
+  },
+  RuneBurned {
+    #[schemars(with = "schema::RuneId")]
+    rune_id: RuneId,
+    amount: u128,
+  },
+  RuneEtched {
+    #[schemars(with = "schema::RuneId")]
+    rune_id: RuneId,
+  },
+  RuneMinted {
+    #[schemars(with = "schema::RuneId")]
+    rune_id: RuneId,
+    amount: u128,
+  },
+  RuneTransferred {
+    #[schemars(with = "schema::RuneId")]
+    rune_id: RuneId,
+    amount: u128,
+    output: u32,
+  },
+  RelicSealed {
+    spaced_relic: SpacedRelic,
+    sequence_number: u32,
+    inscription_id: InscriptionId,
+  },
+  RelicBurned {
+    #[schemars(with = "schema::RelicId")]
+    relic_id: RelicId,
+    amount: u128,
+  },
+  RelicEnshrined {
+    #[schemars(with = "schema::RelicId")]
+    relic_id: RelicId,
+    inscription_id: InscriptionId,
+  },
+  RelicMinted {
+    #[schemars(with = "schema::RelicId")]
+    relic_id: RelicId,
+    amount: u128,
+    multiplier: u32,
+    is_unmint: bool,
+  },
+  RelicMultiMinted {
+    #[schemars(with = "schema::RelicId")]
+    relic_id: RelicId,
+    amount: u128,
+    num_mints: u8,
+    base_limit: u128,
+    is_unmint: bool,
+  },
+  RelicSpent {
+    #[schemars(with = "schema::RelicId")]
+    relic_id: RelicId,
+    amount: u128,
+    // utility field for apps, could also store output and calc address only on read
+    #[schemars(with = "schema::Address")]
+    address: Address<NetworkUnchecked>,
+  },
+  RelicReceived {
+    #[schemars(with = "schema::RelicId")]
+    relic_id: RelicId,
+    amount: u128,
+    // utility field for apps, could also store output and calc address only on read
+    #[schemars(with = "schema::Address")]
+    address: Address<NetworkUnchecked>,
+  },
+  RelicTransferred {
+    #[schemars(with = "schema::RelicId")]
+    relic_id: RelicId,
+    amount: u128,
+    output: u32,
+  },
+  RelicSwapped {
+    #[schemars(with = "schema::RelicId")]
+    relic_id: RelicId,
+    base_amount: u128,
+    quote_amount: u128,
+    fee: u128,
+    is_sell_order: bool,
+    is_exact_input: bool,
+    /// position of the swap within the swaps of the Keepsake
+    swap_index: u32,
+  },
+  RelicClaimed {
+    amount: u128,
+  },
+  RelicError {
+    operation: RelicOperation,
+    error: RelicError,
+  },
+  RelicLiquidityAdded {
+    #[schemars(with = "schema::RelicId")]
+    relic_id: RelicId,
+    base_amount: u128,
+    quote_amount: u128,
+    shares: u128,
+  },
+  RelicLiquidityRemoved {
+    #[schemars(with = "schema::RelicId")]
+    relic_id: RelicId,
+    base_amount: u128,
+    quote_amount: u128,
+    shares: u128,
+  },
+  /// Pool shares were sent to an OP_RETURN output or burned by a cenotaph, they are locked in
+  /// their pool
+  RelicSharesBurned {
+    #[schemars(with = "schema::RelicId")]
+    relic_id: RelicId,
+    shares: u128,
+  },
+  /// Blocks `first_height..=last_height` were rolled back. All events emitted in them are
+  /// retracted, the blocks of the new chain emit their events again.
+  Reorg {
+    first_height: u32,
+    last_height: u32,
+  },
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize, JsonSchema)]
+pub enum RelicOperation {
+  Seal,
+  Enshrine,
+  Mint,
+  MultiMint,
+  Unmint,
+  MultiUnmint,
+  /// the swap at `index` within the swaps of the Keepsake
+  Swap {
+    index: u32,
+  },
+  Claim,
+  AddLiquidity,
+  RemoveLiquidity,
+}
+
+impl Display for Event {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Event {
+  pub block_height: u32,
+  pub event_index: u32,
+  #[schemars(with = "schema::Txid")]
+  pub txid: Txid,
+  pub info: EventInfo,
+}
+
+/// Position of an event in the chain, formatted as `<block_height>:<event_index>`
+#[derive(
+  Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Default, DeserializeFromStr, SerializeDisplay,
+)]
+pub struct EventCursor {
+  pub block_height: u32,
+  pub event_index: u32,
+}
+
+impl Display for EventCursor {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{}:{}", self.block_height, self.event_index)
+  }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum EventCursorError {
+  Separator(String),
+  Number(ParseIntError),
+}
+
+impl Display for EventCursorError {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::Separator(s) => write!(f, "invalid event cursor: {s}"),
+      Self::Number(err) => write!(f, "invalid event cursor: {err}"),
+    }
+  }
+}
+
+impl std::error::Error for EventCursorError {}
+
+impl FromStr for EventCursor {
+  type Err = EventCursorError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (block_height, event_index) = s
+      .split_once(':')
+      .ok_or_else(|| EventCursorError::Separator(s.into()))?;
+    Ok(Self {
+      block_height: block_height.parse().map_err(EventCursorError::Number)?,
+      event_index: event_index.parse().map_err(EventCursorError::Number)?,
+    })
+  }
+}
+
+impl Event {
+  /// Reorg notification positioned at the first rolled back block. It is not stored and belongs
+  /// to no transaction.
+  pub fn reorg(first_height: u32, last_height: u32) -> Self {
+    Self {
+      block_height: first_height,
+      event_index: 0,
+      txid: Txid::all_zeros(),
+      info: EventInfo::Reorg {
+        first_height,
+        last_height,
+      },
+    }
+  }
+
+  pub fn cursor(&self) -> EventCursor {
+    EventCursor {
+      block_height: self.block_height,
+      event_index: self.event_index,
+    }
+  }
+
+  /// name of the `EventInfo` variant
+  pub fn kind(&self) -> &'static str {
+    match self.info {
+      EventInfo::InscriptionCreated { .. } => "InscriptionCreated",
+      EventInfo::InscriptionTransferred { .. } => "InscriptionTransferred",
+      EventInfo::RuneBurned { .. } => "RuneBurned",
+      EventInfo::RuneEtched { .. } => "RuneEtched",
+      EventInfo::RuneMinted { .. } => "RuneMinted",
+      EventInfo::RuneTransferred { .. } => "RuneTransferred",
+      EventInfo::RelicSealed { .. } => "RelicSealed",
+      EventInfo::RelicBurned { .. } => "RelicBurned",
+      EventInfo::RelicEnshrined { .. } => "RelicEnshrined",
+      EventInfo::RelicMinted { .. } => "RelicMinted",
+      EventInfo::RelicMultiMinted { .. } => "RelicMultiMinted",
+      EventInfo::RelicSpent { .. } => "RelicSpent",
+      EventInfo::RelicReceived { .. } => "RelicReceived",
+      EventInfo::RelicTransferred { .. } => "RelicTransferred",
+      EventInfo::RelicSwapped { .. } => "RelicSwapped",
+      EventInfo::RelicClaimed { .. } => "RelicClaimed",
+      EventInfo::RelicError { .. } => "RelicError",
+      EventInfo::RelicLiquidityAdded { .. } => "RelicLiquidityAdded",
+      EventInfo::RelicLiquidityRemoved { .. } => "RelicLiquidityRemoved",
+      EventInfo::RelicSharesBurned { .. } => "RelicSharesBurned",
+      EventInfo::Reorg { .. } => "Reorg",
+    }
+  }
+
+  /// address that sent or received Relics
+  pub fn address(&self) -> Option<&Address<NetworkUnchecked>> {
+    match &self.info {
+      EventInfo::RelicSpent { address, .. } => Some(address),
+      EventInfo::RelicReceived { address, .. } => Some(address),
+      _ => None,
+    }
+  }
+
+  pub fn is_relic_history(&self) -> bool {
+    matches!(
+      self.info,
+      EventInfo::RelicMinted { .. }
+        | EventInfo::RelicBurned { .. }
+        | EventInfo::RelicSpent { .. }
+        | EventInfo::RelicReceived { .. }
+        | EventInfo::RelicTransferred { .. }
+        | EventInfo::RelicSwapped { .. }
+        | EventInfo::RelicLiquidityAdded { .. }
+        | EventInfo::RelicLiquidityRemoved { .. }
+        | EventInfo::RelicSharesBurned { .. }
+    )
+  }
+
+  pub fn relic_id(&self) -> Option<RelicId> {
+    match self.info {
+      EventInfo::RelicEnshrined { relic_id, .. } => Some(relic_id),
+      EventInfo::RelicMinted { relic_id, .. } => Some(relic_id),
+      EventInfo::RelicMultiMinted { relic_id, .. } => Some(relic_id),
+      EventInfo::RelicBurned { relic_id, .. } => Some(relic_id),
+      EventInfo::RelicSpent { relic_id, .. } => Some(relic_id),
+      EventInfo::RelicReceived { relic_id, .. } => Some(relic_id),
+      EventInfo::RelicTransferred { relic_id, .. } => Some(relic_id),
+      EventInfo::RelicSwapped { relic_id, .. } => Some(relic_id),
+      EventInfo::RelicClaimed { .. } => Some(RELIC_ID),
+      EventInfo::RelicLiquidityAdded { relic_id, .. } => Some(relic_id),
+      EventInfo::RelicLiquidityRemoved { relic_id, .. } => Some(relic_id),
+      EventInfo::RelicSharesBurned { relic_id, .. } => Some(relic_id),
+      _ => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn cursor_from_str() {
+    assert_eq!(
+      "5:1".parse::<EventCursor>().unwrap(),
+      EventCursor {
+        block_height: 5,
+        event_index: 1,
+      }
+    );
+    assert_eq!(
+      "5".parse::<EventCursor>(),
+      Err(EventCursorError::Separator("5".into()))
+    );
+    assert!(matches!(
+      "5:x".parse::<EventCursor>(),
+      Err(EventCursorError::Number(_))
+    ));
+  }
+}
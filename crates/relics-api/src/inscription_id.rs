@@ -18,7 +18,7 @@ impl Default for InscriptionId {
 }
 
 impl InscriptionId {
-  pub(crate) fn value(self) -> Vec<u8> {
+  pub fn value(self) -> Vec<u8> {
     let index = self.index.to_le_bytes();
     let mut index_slice = index.as_slice();
 
@@ -101,6 +101,17 @@ impl FromStr for InscriptionId {
 mod tests {
   use super::*;
 
+  fn txid(n: u32) -> Txid {
+    format!("{n:x}").repeat(64).parse().unwrap()
+  }
+
+  fn inscription_id(n: u32) -> InscriptionId {
+    InscriptionId {
+      txid: txid(n),
+      index: n,
+    }
+  }
+
   #[test]
   fn display() {
     assert_eq!(
@@ -155,39 +166,42 @@ mod tests {
 
   #[test]
   fn from_str_bad_character() {
-    assert_matches!(
+    assert!(matches!(
       "→".parse::<InscriptionId>(),
-      Err(ParseError::Character('→')),
-    );
+      Err(ParseError::Character('→'))
+    ));
   }
 
   #[test]
   fn from_str_bad_length() {
-    assert_matches!("foo".parse::<InscriptionId>(), Err(ParseError::Length(3)));
+    assert!(matches!(
+      "foo".parse::<InscriptionId>(),
+      Err(ParseError::Length(3))
+    ));
   }
 
   #[test]
   fn from_str_bad_separator() {
-    assert_matches!(
+    assert!(matches!(
       "0000000000000000000000000000000000000000000000000000000000000000x0".parse::<InscriptionId>(),
-      Err(ParseError::Separator('x')),
-    );
+      Err(ParseError::Separator('x'))
+    ));
   }
 
   #[test]
   fn from_str_bad_index() {
-    assert_matches!(
+    assert!(matches!(
       "0000000000000000000000000000000000000000000000000000000000000000ifoo"
         .parse::<InscriptionId>(),
-      Err(ParseError::Index(_)),
-    );
+      Err(ParseError::Index(_))
+    ));
   }
 
   #[test]
   fn from_str_bad_txid() {
-    assert_matches!(
+    assert!(matches!(
       "x000000000000000000000000000000000000000000000000000000000000000i0".parse::<InscriptionId>(),
-      Err(ParseError::Txid(_)),
-    );
+      Err(ParseError::Txid(_))
+    ));
   }
 }
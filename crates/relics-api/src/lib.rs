@@ -0,0 +1,432 @@
+//! Types of the JSON API of the relics `ord server`, shared by the server and its clients, so that
+//! services reading the API do not have to depend on `ord`.
+
+#![allow(clippy::too_many_arguments)]
+
+use {
+  bitcoin::{
+    address::{Address, NetworkUnchecked},
+    hashes::Hash,
+    Network, OutPoint, ScriptBuf, TxOut, Txid,
+  },
+  ordinals::{Charm, Pile, RuneId, SatPoint, SpacedRune},
+  relics::{
+    BoostTerms, MintTerms, Pool, PoolPrice, RelicEntry, RelicError, RelicId, RelicState,
+    SpacedRelic, SwapDirection, RELIC_ID,
+  },
+  schemars::JsonSchema,
+  serde::{Deserialize, Deserializer, Serialize},
+  serde_with::{DeserializeFromStr, SerializeDisplay},
+  std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+    num::ParseIntError,
+    str::FromStr,
+  },
+};
+
+pub use {
+  candle::{Candle, CandleInterval, CandleIntervalError},
+  event::{Event, EventCursor, EventCursorError, EventInfo, RelicOperation},
+  inscription_id::InscriptionId,
+  snapshot::{RelicSnapshotCursor, RelicSnapshotCursorError},
+};
+
+mod candle;
+mod event;
+pub mod inscription_id;
+pub mod schema;
+mod snapshot;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct Inscription {
+  pub address: Option<String>,
+  #[schemars(with = "Vec<schema::Charm>")]
+  pub charms: Vec<Charm>,
+  pub child_count: u64,
+  pub children: Vec<InscriptionId>,
+  pub content_length: Option<usize>,
+  pub content_type: Option<String>,
+  pub effective_content_type: Option<String>,
+  pub fee: u64,
+  pub height: u32,
+  pub id: InscriptionId,
+  pub next: Option<InscriptionId>,
+  pub number: i32,
+  pub parents: Vec<InscriptionId>,
+  pub previous: Option<InscriptionId>,
+  // if this Inscription has sealed a Relic ticker
+  pub relic_sealed: Option<SpacedRelic>,
+  // if the sealed Relic ticker has already been enshrined
+  pub relic_enshrined: bool,
+  #[schemars(with = "Option<schema::SpacedRune>")]
+  pub rune: Option<SpacedRune>,
+  #[schemars(with = "Option<u64>")]
+  pub sat: Option<ordinals::Sat>,
+  #[schemars(with = "schema::SatPoint")]
+  pub satpoint: SatPoint,
+  pub timestamp: i64,
+  pub value: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct RelicInscription {
+  pub id: InscriptionId,
+  pub is_base_relic: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct Output {
+  #[schemars(with = "Option<schema::Address>")]
+  pub address: Option<Address<NetworkUnchecked>>,
+  pub indexed: bool,
+  pub inscriptions: Vec<RelicInscription>,
+  #[schemars(with = "schema::OutPoint")]
+  pub outpoint: OutPoint,
+  #[schemars(with = "BTreeMap<SpacedRelic, schema::Pile>")]
+  pub relics: BTreeMap<SpacedRelic, Pile>,
+  #[schemars(with = "BTreeMap<schema::SpacedRune, schema::Pile>")]
+  pub runes: BTreeMap<SpacedRune, Pile>,
+  pub sat_ranges: Option<Vec<(u64, u64)>>,
+  #[schemars(with = "schema::Script")]
+  pub script_pubkey: ScriptBuf,
+  /// pool shares held by the output, by the Relic of the pool
+  pub shares: BTreeMap<SpacedRelic, u128>,
+  pub spent: bool,
+  #[schemars(with = "schema::Txid")]
+  pub transaction: Txid,
+  pub value: u64,
+}
+
+impl Output {
+  pub fn new(
+    network: Network,
+    inscriptions: Vec<RelicInscription>,
+    outpoint: OutPoint,
+    tx_out: TxOut,
+    indexed: bool,
+    relics: BTreeMap<SpacedRelic, Pile>,
+    runes: BTreeMap<SpacedRune, Pile>,
+    sat_ranges: Option<Vec<(u64, u64)>>,
+    shares: BTreeMap<SpacedRelic, u128>,
+    spent: bool,
+  ) -> Self {
+    Self {
+      address: Address::from_script(&tx_out.script_pubkey, network)
+        .ok()
+        .map(|address| address.as_unchecked().clone()),
+      indexed,
+      inscriptions,
+      outpoint,
+      relics,
+      runes,
+      sat_ranges,
+      script_pubkey: tx_out.script_pubkey,
+      shares,
+      spent,
+      transaction: outpoint.txid,
+      value: tx_out.value.to_sat(),
+    }
+  }
+}
+
+/// Result of simulating a swap against the current pool states
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RelicSwapQuote {
+  #[schemars(with = "schema::RelicId")]
+  pub input: RelicId,
+  #[schemars(with = "schema::RelicId")]
+  pub output: RelicId,
+  pub input_amount: u128,
+  pub output_amount: u128,
+  /// pool changes in execution order, empty if the swap would fail
+  pub legs: Vec<RelicSwapLeg>,
+  /// the error the swap would fail with
+  pub error: Option<RelicError>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RelicSwapLeg {
+  #[schemars(with = "schema::RelicId")]
+  pub relic_id: RelicId,
+  pub direction: SwapDirection,
+  pub input: u128,
+  pub output: u128,
+  pub fee: u128,
+  /// pool price before and after this leg, see `Pool::lossy_price`
+  pub price_before: u128,
+  pub price_after: u128,
+  /// relative change of the pool price caused by this leg in basis points
+  pub price_impact_bps: u128,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RelicShares {
+  #[schemars(with = "schema::RelicId")]
+  pub id: RelicId,
+  pub spaced_relic: SpacedRelic,
+  pub pool: Option<Pool>,
+  /// pool shares held by each outpoint
+  #[schemars(with = "BTreeMap<schema::OutPoint, u128>")]
+  pub shares: BTreeMap<OutPoint, u128>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RelicHolders {
+  #[schemars(with = "schema::RelicId")]
+  pub id: RelicId,
+  pub spaced_relic: SpacedRelic,
+  /// holders sorted by balance in descending order
+  pub holders: Vec<RelicHolder>,
+  pub more: bool,
+  pub page: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RelicHolder {
+  #[schemars(with = "Option<schema::Address>")]
+  pub address: Option<Address<NetworkUnchecked>>,
+  #[schemars(with = "schema::Script")]
+  pub script_pubkey: ScriptBuf,
+  pub balance: u128,
+}
+
+/// Relic balances as they were at the end of a block
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RelicSnapshot {
+  pub height: u32,
+  /// balances sorted by output, or by script pubkey if aggregated by address
+  pub balances: Vec<RelicSnapshotBalance>,
+  /// cursor of the next page when paginating with `?after=`
+  pub next: Option<RelicSnapshotCursor>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RelicSnapshotBalance {
+  /// `None` if balances are aggregated by address
+  #[schemars(with = "Option<schema::OutPoint>")]
+  pub outpoint: Option<OutPoint>,
+  #[schemars(with = "Option<schema::Address>")]
+  pub address: Option<Address<NetworkUnchecked>>,
+  #[schemars(with = "schema::Script")]
+  pub script_pubkey: ScriptBuf,
+  #[schemars(with = "schema::RelicId")]
+  pub id: RelicId,
+  pub spaced_relic: SpacedRelic,
+  pub amount: u128,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RelicOfferValidation {
+  #[schemars(with = "schema::Txid")]
+  pub txid: Txid,
+  /// whether a buyer added inputs to the offer with `ord wallet offer accept`
+  pub accepted: bool,
+  pub valid: bool,
+  pub errors: Vec<String>,
+  /// Relic price claimed by the offer. Only bitcoin prices can be enforced, so an offer with a
+  /// Relic price is invalid.
+  #[schemars(with = "BTreeMap<SpacedRelic, schema::Pile>")]
+  pub price: BTreeMap<SpacedRelic, Pile>,
+  pub inputs: Vec<RelicOfferInput>,
+  /// Relic allocation of each output
+  #[schemars(with = "Vec<BTreeMap<SpacedRelic, schema::Pile>>")]
+  pub outputs: Vec<BTreeMap<SpacedRelic, Pile>>,
+  #[schemars(with = "BTreeMap<SpacedRelic, schema::Pile>")]
+  pub burned: BTreeMap<SpacedRelic, Pile>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RelicOfferInput {
+  #[schemars(with = "schema::OutPoint")]
+  pub outpoint: OutPoint,
+  pub spent: bool,
+  #[schemars(with = "BTreeMap<SpacedRelic, schema::Pile>")]
+  pub relics: BTreeMap<SpacedRelic, Pile>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RelicCandles {
+  #[schemars(with = "schema::RelicId")]
+  pub id: RelicId,
+  pub spaced_relic: SpacedRelic,
+  pub interval: CandleInterval,
+  pub candles: Vec<Candle>,
+}
+
+/// Relic transactions in the memory pool, simulated as if they were mined in the next block
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct MempoolRelics {
+  /// height of the block the transactions were simulated in
+  pub height: u32,
+  /// transactions in simulation order, parents before children
+  pub transactions: Vec<MempoolRelicTransaction>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct MempoolRelicTransaction {
+  #[schemars(with = "schema::Txid")]
+  pub txid: Txid,
+  /// events the transaction would emit if it was mined in the next block
+  pub events: Vec<Event>,
+  /// spent outputs holding Relics or pool shares, confirmed or pending
+  pub inputs: Vec<MempoolRelicOutput>,
+  /// outputs of this transaction that would hold Relics or pool shares
+  pub outputs: Vec<MempoolRelicOutput>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct MempoolRelicOutput {
+  #[schemars(with = "schema::OutPoint")]
+  pub outpoint: OutPoint,
+  #[schemars(with = "BTreeMap<schema::RelicId, u128>")]
+  pub balances: BTreeMap<RelicId, u128>,
+  #[schemars(with = "BTreeMap<schema::RelicId, u128>")]
+  pub shares: BTreeMap<RelicId, u128>,
+  /// pending transaction spending this output
+  #[schemars(with = "Option<schema::Txid>")]
+  pub spent_by: Option<Txid>,
+}
+
+#[derive(Debug, PartialEq, Serialize, JsonSchema)]
+pub struct Relic {
+  #[serde(flatten)]
+  pub entry: RelicEntry,
+  #[schemars(with = "schema::RelicId")]
+  pub id: RelicId,
+  pub mintable: bool,
+  pub owner: Option<InscriptionId>,
+  pub thumb: Option<InscriptionId>,
+}
+
+/// Deserialized field by field, since `#[serde(flatten)]` buffers the input, which does not
+/// support the `u128` fields of `RelicEntry`
+impl<'de> Deserialize<'de> for Relic {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    #[derive(Deserialize)]
+    struct Fields {
+      block: u64,
+      enshrining: Txid,
+      fee: u16,
+      number: u64,
+      spaced_relic: SpacedRelic,
+      symbol: Option<char>,
+      owner_sequence_number: Option<u32>,
+      boost_terms: Option<BoostTerms>,
+      mint_terms: Option<MintTerms>,
+      state: RelicState,
+      pool: Option<Pool>,
+      timestamp: u64,
+      id: RelicId,
+      mintable: bool,
+      owner: Option<InscriptionId>,
+      thumb: Option<InscriptionId>,
+    }
+
+    let fields = Fields::deserialize(deserializer)?;
+
+    Ok(Self {
+      entry: RelicEntry {
+        block: fields.block,
+        enshrining: fields.enshrining,
+        fee: fields.fee,
+        number: fields.number,
+        spaced_relic: fields.spaced_relic,
+        symbol: fields.symbol,
+        owner_sequence_number: fields.owner_sequence_number,
+        boost_terms: fields.boost_terms,
+        mint_terms: fields.mint_terms,
+        state: fields.state,
+        pool: fields.pool,
+        timestamp: fields.timestamp,
+      },
+      id: fields.id,
+      mintable: fields.mintable,
+      owner: fields.owner,
+      thumb: fields.thumb,
+    })
+  }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Relics {
+  #[schemars(with = "Vec<(schema::RelicId, Option<InscriptionId>, RelicEntry)>")]
+  pub entries: Vec<(RelicId, Option<InscriptionId>, RelicEntry)>,
+  pub more: bool,
+  pub prev: Option<usize>,
+  pub next: Option<usize>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RelicEvents {
+  pub spaced_relic: SpacedRelic,
+  pub events: Vec<Event>,
+  /// cursor of the next page when paginating with `?after=`
+  pub next: Option<EventCursor>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Sealing {
+  pub inscription: Inscription,
+  #[schemars(with = "Option<schema::Txid>")]
+  pub enshrining_tx: Option<Txid>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Sealings {
+  #[schemars(with = "Vec<(Inscription, Option<schema::Txid>)>")]
+  pub entries: Vec<(Inscription, Option<Txid>)>,
+  pub more: bool,
+  pub prev: Option<usize>,
+  pub next: Option<usize>,
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, relics::PriceModel};
+
+  #[test]
+  fn relic_json_round_trip() {
+    let relic = Relic {
+      entry: RelicEntry {
+        block: 1,
+        enshrining: Txid::all_zeros(),
+        fee: 100,
+        number: 2,
+        spaced_relic: "BEST•RELIC".parse().unwrap(),
+        symbol: Some('R'),
+        owner_sequence_number: Some(3),
+        boost_terms: None,
+        mint_terms: Some(MintTerms {
+          amount: Some(1_000),
+          price: Some(PriceModel::Formula { a: 4, b: 5 }),
+          ..Default::default()
+        }),
+        state: RelicState {
+          mints: 6,
+          issued: 6_000,
+          ..Default::default()
+        },
+        pool: Some(Pool {
+          base_supply: 7,
+          quote_supply: u128::MAX,
+          fee_bps: 100,
+          subsidy: 0,
+          shares: 10,
+          locked_shares: 1,
+          provider_fees: 0,
+        }),
+        timestamp: 8,
+      },
+      id: RelicId { block: 1, tx: 9 },
+      mintable: true,
+      owner: None,
+      thumb: None,
+    };
+
+    assert_eq!(
+      serde_json::from_str::<Relic>(&serde_json::to_string(&relic).unwrap()).unwrap(),
+      relic,
+    );
+  }
+}
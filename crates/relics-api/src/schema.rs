@@ -0,0 +1,127 @@
+//! Stand-ins describing the JSON schemas of the types of other crates, see [`relics::schema`]
+
+use {
+  super::*,
+  relics::string_schema,
+  schemars::{json_schema, Schema, SchemaGenerator},
+  std::borrow::Cow,
+};
+
+pub use relics::schema::{RelicId, Txid};
+
+/// stand-in for [`bitcoin::Address`]
+pub struct Address;
+
+string_schema!(Address, "Address", "^[0-9A-Za-z]+$", "bitcoin address");
+
+/// stand-in for [`bitcoin::BlockHash`]
+pub struct BlockHash;
+
+string_schema!(BlockHash, "BlockHash", "^[0-9a-f]{64}$", "block hash");
+
+/// stand-in for [`bitcoin::OutPoint`]
+pub struct OutPoint;
+
+string_schema!(
+  OutPoint,
+  "OutPoint",
+  "^[0-9a-f]{64}:[0-9]+$",
+  "`<txid>:<vout>`"
+);
+
+/// stand-in for [`bitcoin::ScriptBuf`]
+pub struct Script;
+
+string_schema!(Script, "Script", "^([0-9a-f]{2})*$", "hex encoded script");
+
+/// stand-in for [`ordinals::RuneId`]
+pub struct RuneId;
+
+string_schema!(
+  RuneId,
+  "RuneId",
+  "^[0-9]+:[0-9]+$",
+  "`<block>:<tx>` of the etching"
+);
+
+/// stand-in for [`ordinals::SatPoint`]
+pub struct SatPoint;
+
+string_schema!(
+  SatPoint,
+  "SatPoint",
+  "^[0-9a-f]{64}:[0-9]+:[0-9]+$",
+  "`<txid>:<vout>:<offset>`"
+);
+
+/// stand-in for [`ordinals::SpacedRune`]
+pub struct SpacedRune;
+
+string_schema!(
+  SpacedRune,
+  "SpacedRune",
+  "^[A-Z]+(•[A-Z]+)*$",
+  "Rune name with spacers"
+);
+
+/// stand-in for [`ordinals::Pile`]
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+pub struct Pile {
+  amount: u128,
+  divisibility: u8,
+  symbol: Option<char>,
+}
+
+/// stand-in for [`ordinals::Charm`]
+pub struct Charm;
+
+impl JsonSchema for Charm {
+  fn schema_name() -> Cow<'static, str> {
+    "Charm".into()
+  }
+
+  fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+      "type": "string",
+      "enum": ordinals::Charm::ALL
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>(),
+    })
+  }
+}
+
+string_schema!(
+  InscriptionId,
+  "InscriptionId",
+  "^[0-9a-f]{64}i[0-9]+$",
+  "`<txid>i<index>`"
+);
+
+string_schema!(
+  EventCursor,
+  "EventCursor",
+  "^[0-9]+:[0-9]+$",
+  "position of an event in the chain, `<block_height>:<event_index>`"
+);
+
+string_schema!(
+  RelicSnapshotCursor,
+  "RelicSnapshotCursor",
+  "^([0-9a-f]{64}:[0-9]+|([0-9a-f]{2})*)$",
+  "last output of a snapshot page, `<txid>:<vout>`, or last script pubkey as hex"
+);
+
+impl JsonSchema for CandleInterval {
+  fn schema_name() -> Cow<'static, str> {
+    "CandleInterval".into()
+  }
+
+  fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+      "type": "string",
+      "enum": ["1m", "5m", "15m", "1h", "4h", "1d", "1w"],
+    })
+  }
+}
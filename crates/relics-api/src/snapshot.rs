@@ -0,0 +1,91 @@
+use {
+  super::*,
+  bitcoin::{hex::HexToBytesError, transaction::ParseOutPointError},
+};
+
+/// Position of the last balance of a snapshot page: the output formatted as `<txid>:<vout>`, or the
+/// script pubkey formatted as hex if balances are aggregated by address
+#[derive(Debug, PartialEq, Eq, Clone, DeserializeFromStr, SerializeDisplay)]
+pub enum RelicSnapshotCursor {
+  Output(OutPoint),
+  ScriptPubkey(ScriptBuf),
+}
+
+impl Display for RelicSnapshotCursor {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Output(outpoint) => write!(f, "{outpoint}"),
+      Self::ScriptPubkey(script_pubkey) => write!(f, "{}", script_pubkey.to_hex_string()),
+    }
+  }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RelicSnapshotCursorError {
+  Output(ParseOutPointError),
+  ScriptPubkey(HexToBytesError),
+}
+
+impl Display for RelicSnapshotCursorError {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::Output(err) => write!(f, "invalid snapshot cursor: {err}"),
+      Self::ScriptPubkey(err) => write!(f, "invalid snapshot cursor: {err}"),
+    }
+  }
+}
+
+impl std::error::Error for RelicSnapshotCursorError {}
+
+impl FromStr for RelicSnapshotCursor {
+  type Err = RelicSnapshotCursorError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if s.contains(':') {
+      s.parse()
+        .map(Self::Output)
+        .map_err(RelicSnapshotCursorError::Output)
+    } else {
+      ScriptBuf::from_hex(s)
+        .map(Self::ScriptPubkey)
+        .map_err(RelicSnapshotCursorError::ScriptPubkey)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn cursor_from_str() {
+    let outpoint = OutPoint {
+      txid: Txid::all_zeros(),
+      vout: 1,
+    };
+    let script_pubkey = ScriptBuf::from_bytes(vec![0x00, 0x14, 0xab]);
+
+    for cursor in [
+      RelicSnapshotCursor::Output(outpoint),
+      RelicSnapshotCursor::ScriptPubkey(script_pubkey),
+    ] {
+      assert_eq!(
+        cursor.to_string().parse::<RelicSnapshotCursor>(),
+        Ok(cursor)
+      );
+    }
+
+    assert_eq!(
+      RelicSnapshotCursor::ScriptPubkey(ScriptBuf::from_bytes(vec![0x00, 0x14])).to_string(),
+      "0014"
+    );
+    assert!(matches!(
+      "0014:x".parse::<RelicSnapshotCursor>(),
+      Err(RelicSnapshotCursorError::Output(_))
+    ));
+    assert!(matches!(
+      "001".parse::<RelicSnapshotCursor>(),
+      Err(RelicSnapshotCursorError::ScriptPubkey(_))
+    ));
+  }
+}
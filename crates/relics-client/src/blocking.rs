@@ -0,0 +1,239 @@
+use super::*;
+
+/// Blocking client, must not be used within an async runtime
+#[derive(Debug, Clone)]
+pub struct Client {
+  client: reqwest::blocking::Client,
+  url: Url,
+}
+
+impl Client {
+  /// Client for the server at `url`, authenticating with `credentials` if the server requires it
+  pub fn new(url: Url, credentials: Option<(&str, &str)>) -> Result<Self> {
+    Ok(Self::with_client(
+      url,
+      reqwest::blocking::Client::builder()
+        .default_headers(default_headers(credentials))
+        .build()?,
+    ))
+  }
+
+  /// Client sending its requests with `client`, which must send `Accept: application/json`
+  pub fn with_client(url: Url, client: reqwest::blocking::Client) -> Self {
+    Self { client, url }
+  }
+
+  fn get<T: DeserializeOwned>(&self, route: Route) -> Result<Option<T>> {
+    let response = self.client.get(self.url.join(&route.to_string())?).send()?;
+
+    if response.status() == StatusCode::NOT_FOUND {
+      return Ok(None);
+    }
+
+    if !response.status().is_success() {
+      return Err(Error::Status {
+        status: response.status(),
+        message: response.text()?,
+      });
+    }
+
+    Ok(Some(response.json()?))
+  }
+
+  fn post<T: DeserializeOwned>(&self, route: Route, body: &impl Serialize) -> Result<T> {
+    let response = self
+      .client
+      .post(self.url.join(&route.to_string())?)
+      .json(body)
+      .send()?;
+
+    if !response.status().is_success() {
+      return Err(Error::Status {
+        status: response.status(),
+        message: response.text()?,
+      });
+    }
+
+    Ok(response.json()?)
+  }
+
+  /// Relic `relic`, `None` if it was not enshrined
+  pub fn relic(&self, relic: impl Into<RelicQuery>) -> Result<Option<Relic>> {
+    self.get(Route::Relic(relic.into()))
+  }
+
+  /// Page `page` of all Relics, newest first
+  pub fn relics(&self, page: usize) -> Result<Option<Relics>> {
+    self.get(Route::Relics(page))
+  }
+
+  /// Page `page` of the events of `relic`, newest first
+  pub fn relic_events(
+    &self,
+    relic: impl Into<RelicQuery>,
+    page: usize,
+  ) -> Result<Option<RelicEvents>> {
+    self.get(Route::RelicEvents {
+      relic: relic.into(),
+      page,
+      after: None,
+    })
+  }
+
+  /// Events of `relic` after `after`, oldest first, pass the `next` cursor of the response to get
+  /// the following page
+  pub fn relic_events_after(
+    &self,
+    relic: impl Into<RelicQuery>,
+    after: &EventCursor,
+  ) -> Result<Option<RelicEvents>> {
+    self.get(Route::relic_events(relic.into(), after))
+  }
+
+  /// Events of the block at `height`, after `after` if given
+  pub fn block_events(
+    &self,
+    height: u32,
+    after: Option<&EventCursor>,
+  ) -> Result<Option<Vec<Event>>> {
+    self.get(Route::BlockEvents {
+      height,
+      after: after.map(ToString::to_string),
+    })
+  }
+
+  /// Events of the transactions `txids`, after `after` if given
+  pub fn transaction_events(
+    &self,
+    txids: &[Txid],
+    after: Option<&EventCursor>,
+  ) -> Result<Vec<Event>> {
+    self.post(
+      Route::Events {
+        after: after.map(ToString::to_string),
+      },
+      &txids,
+    )
+  }
+
+  /// Inscription that sealed `relic` and the transaction that enshrined it
+  pub fn sealing(&self, relic: impl Into<RelicQuery>) -> Result<Option<Sealing>> {
+    self.get(Route::Sealing(relic.into()))
+  }
+
+  /// Output `outpoint` with its inscriptions and Relic balances
+  pub fn output(&self, outpoint: OutPoint) -> Result<Option<Output>> {
+    self.get(Route::Output(outpoint))
+  }
+
+  /// Relic artifact of the transaction `txid`, `None` if the server does not know the transaction
+  /// and `Some(None)` if it has no artifact
+  pub fn decipher(&self, txid: Txid) -> Result<Option<Option<RelicArtifact>>> {
+    self.get(Route::Decipher(txid))
+  }
+
+  /// Relic artifact of `transaction`, which does not have to be broadcast
+  pub fn decipher_transaction(&self, transaction: &Transaction) -> Result<Option<RelicArtifact>> {
+    Ok(self.get(Route::decipher_raw(transaction))?.flatten())
+  }
+
+  /// Check that the offer `psbt`, encoded as base64, can be accepted, see `ord wallet offer`
+  pub fn validate_offer(&self, psbt: &str) -> Result<RelicOfferValidation> {
+    self.post(Route::Offer, &psbt)
+  }
+
+  /// Page `page` of all sealings, newest first
+  pub fn sealings(&self, page: usize) -> Result<Option<Sealings>> {
+    self.get(Route::Sealings(page))
+  }
+
+  /// Candles of the price of `relic` in its pool
+  pub fn relic_candles(
+    &self,
+    relic: impl Into<RelicQuery>,
+    interval: CandleInterval,
+  ) -> Result<Option<RelicCandles>> {
+    self.get(Route::Candles {
+      relic: relic.into(),
+      interval,
+    })
+  }
+
+  /// Page `page` of the holders of `relic`, largest balance first
+  pub fn relic_holders(
+    &self,
+    relic: impl Into<RelicQuery>,
+    page: usize,
+  ) -> Result<Option<RelicHolders>> {
+    self.get(Route::Holders {
+      relic: relic.into(),
+      page,
+    })
+  }
+
+  /// Liquidity shares of the pool of `relic`
+  pub fn relic_shares(&self, relic: impl Into<RelicQuery>) -> Result<Option<RelicShares>> {
+    self.get(Route::RelicShares(relic.into()))
+  }
+
+  /// Balances of all Relics by output
+  pub fn balances(&self) -> Result<Option<BTreeMap<SpacedRelic, BTreeMap<OutPoint, u128>>>> {
+    self.get(Route::Balances)
+  }
+
+  /// Liquidity shares of all pools by output
+  pub fn shares(&self) -> Result<Option<BTreeMap<SpacedRelic, BTreeMap<OutPoint, u128>>>> {
+    self.get(Route::Shares)
+  }
+
+  /// Base tokens claimable by the owners of sealing inscriptions
+  pub fn claimable(&self) -> Result<Option<BTreeMap<RelicOwner, u128>>> {
+    self.get(Route::Claimable)
+  }
+
+  /// Quote of `swap` against the pools as of the last indexed block, the amount of a swap that does
+  /// not specify one is zero
+  pub fn quote(&self, swap: &Swap) -> Result<Option<RelicSwapQuote>> {
+    self.get(Route::Quote(swap.clone()))
+  }
+
+  /// Page of the Relic balances of all outputs at `height`, starting after the cursor `after` of
+  /// the previous page
+  pub fn snapshot(
+    &self,
+    height: u32,
+    after: Option<&RelicSnapshotCursor>,
+  ) -> Result<Option<RelicSnapshot>> {
+    self.get(Route::snapshot(height, false, after))
+  }
+
+  /// Page of the Relic balances at `height`, aggregated by address, starting after the cursor
+  /// `after` of the previous page
+  pub fn snapshot_by_address(
+    &self,
+    height: u32,
+    after: Option<&RelicSnapshotCursor>,
+  ) -> Result<Option<RelicSnapshot>> {
+    self.get(Route::snapshot(height, true, after))
+  }
+
+  /// Relic transactions in the memory pool, `None` if the server does not simulate them
+  pub fn mempool_relics(&self) -> Result<Option<MempoolRelics>> {
+    self.get(Route::MempoolRelics)
+  }
+
+  /// Relic balances of `outpoint` after the transactions in the memory pool
+  pub fn mempool_relic_output(&self, outpoint: OutPoint) -> Result<Option<MempoolRelicOutput>> {
+    self.get(Route::MempoolOutput(outpoint))
+  }
+
+  /// Quote of `swap` against the pools after the transactions in the memory pool
+  pub fn mempool_quote(&self, swap: &Swap) -> Result<Option<RelicSwapQuote>> {
+    self.get(Route::MempoolQuote(swap.clone()))
+  }
+
+  /// Relic transaction `txid` in the memory pool, `None` if it is not pending
+  pub fn mempool_relic_transaction(&self, txid: Txid) -> Result<Option<MempoolRelicTransaction>> {
+    self.get(Route::MempoolTransaction(txid))
+  }
+}
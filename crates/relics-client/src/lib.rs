@@ -0,0 +1,652 @@
+//! Typed client for the JSON API of the relics `ord server`.
+//!
+//! The response types are defined by the `relics-api` crate, which the server uses too, so that
+//! services reading the API do not have to depend on `ord`.
+//!
+//! [`Client`] is async, [`blocking::Client`] blocks the current thread.
+
+use {
+  base64::Engine,
+  bitcoin::{consensus, OutPoint, Transaction, Txid},
+  relics::{RelicArtifact, RelicId, RelicOwner, SpacedRelic, Swap},
+  relics_api::{
+    CandleInterval, Event, EventCursor, MempoolRelicOutput, MempoolRelicTransaction, MempoolRelics,
+    Output, Relic, RelicCandles, RelicEvents, RelicHolders, RelicOfferValidation, RelicShares,
+    RelicSnapshot, RelicSnapshotCursor, RelicSwapQuote, Relics, Sealing, Sealings,
+  },
+  reqwest::{
+    header::{self, HeaderMap, HeaderValue},
+    StatusCode, Url,
+  },
+  serde::{de::DeserializeOwned, Serialize},
+  std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+  },
+};
+
+pub mod blocking;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+  #[error("invalid URL: {0}")]
+  Url(#[from] url::ParseError),
+  #[error("request failed: {0}")]
+  Request(#[from] reqwest::Error),
+  #[error("server returned {status}: {message}")]
+  Status { status: StatusCode, message: String },
+}
+
+pub type Result<T = (), E = Error> = std::result::Result<T, E>;
+
+/// Relic as accepted by the routes that look up a single Relic
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RelicQuery {
+  Id(RelicId),
+  Number(u64),
+  Spaced(SpacedRelic),
+}
+
+impl Display for RelicQuery {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::Id(id) => write!(f, "{id}"),
+      Self::Number(number) => write!(f, "{number}"),
+      Self::Spaced(spaced_relic) => write!(f, "{spaced_relic}"),
+    }
+  }
+}
+
+impl From<RelicId> for RelicQuery {
+  fn from(id: RelicId) -> Self {
+    Self::Id(id)
+  }
+}
+
+impl From<SpacedRelic> for RelicQuery {
+  fn from(spaced_relic: SpacedRelic) -> Self {
+    Self::Spaced(spaced_relic)
+  }
+}
+
+/// Path and query of a route, relative to the URL of the server
+#[derive(Debug, PartialEq)]
+enum Route {
+  Balances,
+  BlockEvents {
+    height: u32,
+    after: Option<String>,
+  },
+  Candles {
+    relic: RelicQuery,
+    interval: CandleInterval,
+  },
+  Claimable,
+  Decipher(Txid),
+  DecipherRaw(String),
+  Events {
+    after: Option<String>,
+  },
+  Holders {
+    relic: RelicQuery,
+    page: usize,
+  },
+  MempoolOutput(OutPoint),
+  MempoolQuote(Swap),
+  MempoolRelics,
+  MempoolTransaction(Txid),
+  Offer,
+  Output(OutPoint),
+  Quote(Swap),
+  Relic(RelicQuery),
+  RelicEvents {
+    relic: RelicQuery,
+    page: usize,
+    after: Option<String>,
+  },
+  RelicShares(RelicQuery),
+  Relics(usize),
+  Sealing(RelicQuery),
+  Sealings(usize),
+  Shares,
+  Snapshot {
+    height: u32,
+    by_address: bool,
+    after: Option<String>,
+  },
+}
+
+impl Route {
+  /// events after a cursor are requested from the first page
+  fn relic_events(relic: RelicQuery, after: &impl Display) -> Self {
+    Self::RelicEvents {
+      relic,
+      page: 0,
+      after: Some(after.to_string()),
+    }
+  }
+
+  fn snapshot(height: u32, by_address: bool, after: Option<&RelicSnapshotCursor>) -> Self {
+    Self::Snapshot {
+      height,
+      by_address,
+      after: after.map(ToString::to_string),
+    }
+  }
+
+  fn decipher_raw(transaction: &Transaction) -> Self {
+    Self::DecipherRaw(consensus::encode::serialize_hex(transaction))
+  }
+}
+
+/// Query of the quote routes, the amount of `swap` is zero if it does not specify one
+fn quote_query(swap: &Swap) -> String {
+  let mut query = Vec::new();
+
+  if let Some(input) = swap.input {
+    query.push(format!("input={input}"));
+  }
+
+  if let Some(output) = swap.output {
+    query.push(format!("output={output}"));
+  }
+
+  if !swap.via.is_empty() {
+    query.push(format!(
+      "via={}",
+      swap
+        .via
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join(",")
+    ));
+  }
+
+  let (amount, limit) = if swap.is_exact_input {
+    (swap.input_amount, swap.output_amount)
+  } else {
+    (swap.output_amount, swap.input_amount)
+  };
+
+  query.push(format!("amount={}", amount.unwrap_or_default()));
+
+  if let Some(limit) = limit {
+    query.push(format!("limit={limit}"));
+  }
+
+  query.push(format!("exact_input={}", swap.is_exact_input));
+
+  query.join("&")
+}
+
+impl Display for Route {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    let after = match self {
+      Self::Balances => return write!(f, "/relics/balances"),
+      Self::BlockEvents { height, after } => {
+        write!(f, "/events/{height}")?;
+        after
+      }
+      Self::Candles { relic, interval } => {
+        return write!(f, "/relic/{relic}/candles?interval={interval}")
+      }
+      Self::Claimable => return write!(f, "/relics/claimable"),
+      Self::Decipher(txid) => return write!(f, "/relics/decipher/{txid}"),
+      Self::DecipherRaw(hex) => return write!(f, "/relics/decipher/raw/{hex}"),
+      Self::Events { after } => {
+        write!(f, "/events")?;
+        after
+      }
+      Self::Holders { relic, page } => return write!(f, "/relic/{relic}/holders/{page}"),
+      Self::MempoolOutput(outpoint) => return write!(f, "/mempool/relics/output/{outpoint}"),
+      Self::MempoolQuote(swap) => return write!(f, "/mempool/relics/quote?{}", quote_query(swap)),
+      Self::MempoolRelics => return write!(f, "/mempool/relics"),
+      Self::MempoolTransaction(txid) => return write!(f, "/mempool/relics/tx/{txid}"),
+      Self::Offer => return write!(f, "/relics/offer"),
+      Self::Output(outpoint) => return write!(f, "/output/{outpoint}"),
+      Self::Quote(swap) => return write!(f, "/relics/quote?{}", quote_query(swap)),
+      Self::Relic(relic) => return write!(f, "/relic/{relic}"),
+      Self::RelicEvents { relic, page, after } => {
+        write!(f, "/events/{relic}/{page}")?;
+        after
+      }
+      Self::RelicShares(relic) => return write!(f, "/relic/{relic}/shares"),
+      Self::Relics(page) => return write!(f, "/relics/{page}"),
+      Self::Sealing(relic) => return write!(f, "/sealing/{relic}"),
+      Self::Sealings(page) => return write!(f, "/sealings/{page}"),
+      Self::Shares => return write!(f, "/relics/shares"),
+      Self::Snapshot {
+        height,
+        by_address,
+        after,
+      } => {
+        if *by_address {
+          write!(f, "/relics/snapshot/{height}/addresses")?;
+        } else {
+          write!(f, "/relics/snapshot/{height}")?;
+        }
+        after
+      }
+    };
+
+    match after {
+      Some(after) => write!(f, "?after={after}"),
+      None => Ok(()),
+    }
+  }
+}
+
+fn default_headers(credentials: Option<(&str, &str)>) -> HeaderMap {
+  let mut headers = HeaderMap::new();
+
+  headers.insert(header::ACCEPT, HeaderValue::from_static("application/json"));
+
+  if let Some((username, password)) = credentials {
+    let credentials =
+      base64::engine::general_purpose::STANDARD.encode(format!("{username}:{password}"));
+    let mut authorization = HeaderValue::from_str(&format!("Basic {credentials}")).unwrap();
+    authorization.set_sensitive(true);
+    headers.insert(header::AUTHORIZATION, authorization);
+  }
+
+  headers
+}
+
+/// Async client, see [`blocking::Client`] for a client that can be used outside of an async
+/// runtime
+#[derive(Debug, Clone)]
+pub struct Client {
+  client: reqwest::Client,
+  url: Url,
+}
+
+impl Client {
+  /// Client for the server at `url`, authenticating with `credentials` if the server requires it
+  pub fn new(url: Url, credentials: Option<(&str, &str)>) -> Result<Self> {
+    Ok(Self::with_client(
+      url,
+      reqwest::Client::builder()
+        .default_headers(default_headers(credentials))
+        .build()?,
+    ))
+  }
+
+  /// Client sending its requests with `client`, which must send `Accept: application/json`
+  pub fn with_client(url: Url, client: reqwest::Client) -> Self {
+    Self { client, url }
+  }
+
+  async fn get<T: DeserializeOwned>(&self, route: Route) -> Result<Option<T>> {
+    let response = self
+      .client
+      .get(self.url.join(&route.to_string())?)
+      .send()
+      .await?;
+
+    if response.status() == StatusCode::NOT_FOUND {
+      return Ok(None);
+    }
+
+    if !response.status().is_success() {
+      return Err(Error::Status {
+        status: response.status(),
+        message: response.text().await?,
+      });
+    }
+
+    Ok(Some(response.json().await?))
+  }
+
+  async fn post<T: DeserializeOwned>(&self, route: Route, body: &impl Serialize) -> Result<T> {
+    let response = self
+      .client
+      .post(self.url.join(&route.to_string())?)
+      .json(body)
+      .send()
+      .await?;
+
+    if !response.status().is_success() {
+      return Err(Error::Status {
+        status: response.status(),
+        message: response.text().await?,
+      });
+    }
+
+    Ok(response.json().await?)
+  }
+
+  /// Relic `relic`, `None` if it was not enshrined
+  pub async fn relic(&self, relic: impl Into<RelicQuery>) -> Result<Option<Relic>> {
+    self.get(Route::Relic(relic.into())).await
+  }
+
+  /// Page `page` of all Relics, newest first
+  pub async fn relics(&self, page: usize) -> Result<Option<Relics>> {
+    self.get(Route::Relics(page)).await
+  }
+
+  /// Page `page` of the events of `relic`, newest first
+  pub async fn relic_events(
+    &self,
+    relic: impl Into<RelicQuery>,
+    page: usize,
+  ) -> Result<Option<RelicEvents>> {
+    self
+      .get(Route::RelicEvents {
+        relic: relic.into(),
+        page,
+        after: None,
+      })
+      .await
+  }
+
+  /// Events of `relic` after `after`, oldest first, pass the `next` cursor of the response to get
+  /// the following page
+  pub async fn relic_events_after(
+    &self,
+    relic: impl Into<RelicQuery>,
+    after: &EventCursor,
+  ) -> Result<Option<RelicEvents>> {
+    self.get(Route::relic_events(relic.into(), after)).await
+  }
+
+  /// Events of the block at `height`, after `after` if given
+  pub async fn block_events(
+    &self,
+    height: u32,
+    after: Option<&EventCursor>,
+  ) -> Result<Option<Vec<Event>>> {
+    self
+      .get(Route::BlockEvents {
+        height,
+        after: after.map(ToString::to_string),
+      })
+      .await
+  }
+
+  /// Events of the transactions `txids`, after `after` if given
+  pub async fn transaction_events(
+    &self,
+    txids: &[Txid],
+    after: Option<&EventCursor>,
+  ) -> Result<Vec<Event>> {
+    self
+      .post(
+        Route::Events {
+          after: after.map(ToString::to_string),
+        },
+        &txids,
+      )
+      .await
+  }
+
+  /// Inscription that sealed `relic` and the transaction that enshrined it
+  pub async fn sealing(&self, relic: impl Into<RelicQuery>) -> Result<Option<Sealing>> {
+    self.get(Route::Sealing(relic.into())).await
+  }
+
+  /// Output `outpoint` with its inscriptions and Relic balances
+  pub async fn output(&self, outpoint: OutPoint) -> Result<Option<Output>> {
+    self.get(Route::Output(outpoint)).await
+  }
+
+  /// Relic artifact of the transaction `txid`, `None` if the server does not know the transaction
+  /// and `Some(None)` if it has no artifact
+  pub async fn decipher(&self, txid: Txid) -> Result<Option<Option<RelicArtifact>>> {
+    self.get(Route::Decipher(txid)).await
+  }
+
+  /// Relic artifact of `transaction`, which does not have to be broadcast
+  pub async fn decipher_transaction(
+    &self,
+    transaction: &Transaction,
+  ) -> Result<Option<RelicArtifact>> {
+    Ok(self.get(Route::decipher_raw(transaction)).await?.flatten())
+  }
+
+  /// Check that the offer `psbt`, encoded as base64, can be accepted, see `ord wallet offer`
+  pub async fn validate_offer(&self, psbt: &str) -> Result<RelicOfferValidation> {
+    self.post(Route::Offer, &psbt).await
+  }
+
+  /// Page `page` of all sealings, newest first
+  pub async fn sealings(&self, page: usize) -> Result<Option<Sealings>> {
+    self.get(Route::Sealings(page)).await
+  }
+
+  /// Candles of the price of `relic` in its pool
+  pub async fn relic_candles(
+    &self,
+    relic: impl Into<RelicQuery>,
+    interval: CandleInterval,
+  ) -> Result<Option<RelicCandles>> {
+    self
+      .get(Route::Candles {
+        relic: relic.into(),
+        interval,
+      })
+      .await
+  }
+
+  /// Page `page` of the holders of `relic`, largest balance first
+  pub async fn relic_holders(
+    &self,
+    relic: impl Into<RelicQuery>,
+    page: usize,
+  ) -> Result<Option<RelicHolders>> {
+    self
+      .get(Route::Holders {
+        relic: relic.into(),
+        page,
+      })
+      .await
+  }
+
+  /// Liquidity shares of the pool of `relic`
+  pub async fn relic_shares(&self, relic: impl Into<RelicQuery>) -> Result<Option<RelicShares>> {
+    self.get(Route::RelicShares(relic.into())).await
+  }
+
+  /// Balances of all Relics by output
+  pub async fn balances(&self) -> Result<Option<BTreeMap<SpacedRelic, BTreeMap<OutPoint, u128>>>> {
+    self.get(Route::Balances).await
+  }
+
+  /// Liquidity shares of all pools by output
+  pub async fn shares(&self) -> Result<Option<BTreeMap<SpacedRelic, BTreeMap<OutPoint, u128>>>> {
+    self.get(Route::Shares).await
+  }
+
+  /// Base tokens claimable by the owners of sealing inscriptions
+  pub async fn claimable(&self) -> Result<Option<BTreeMap<RelicOwner, u128>>> {
+    self.get(Route::Claimable).await
+  }
+
+  /// Quote of `swap` against the pools as of the last indexed block, the amount of a swap that does
+  /// not specify one is zero
+  pub async fn quote(&self, swap: &Swap) -> Result<Option<RelicSwapQuote>> {
+    self.get(Route::Quote(swap.clone())).await
+  }
+
+  /// Page of the Relic balances of all outputs at `height`, starting after the cursor `after` of
+  /// the previous page
+  pub async fn snapshot(
+    &self,
+    height: u32,
+    after: Option<&RelicSnapshotCursor>,
+  ) -> Result<Option<RelicSnapshot>> {
+    self.get(Route::snapshot(height, false, after)).await
+  }
+
+  /// Page of the Relic balances at `height`, aggregated by address, starting after the cursor
+  /// `after` of the previous page
+  pub async fn snapshot_by_address(
+    &self,
+    height: u32,
+    after: Option<&RelicSnapshotCursor>,
+  ) -> Result<Option<RelicSnapshot>> {
+    self.get(Route::snapshot(height, true, after)).await
+  }
+
+  /// Relic transactions in the memory pool, `None` if the server does not simulate them
+  pub async fn mempool_relics(&self) -> Result<Option<MempoolRelics>> {
+    self.get(Route::MempoolRelics).await
+  }
+
+  /// Relic balances of `outpoint` after the transactions in the memory pool
+  pub async fn mempool_relic_output(
+    &self,
+    outpoint: OutPoint,
+  ) -> Result<Option<MempoolRelicOutput>> {
+    self.get(Route::MempoolOutput(outpoint)).await
+  }
+
+  /// Quote of `swap` against the pools after the transactions in the memory pool
+  pub async fn mempool_quote(&self, swap: &Swap) -> Result<Option<RelicSwapQuote>> {
+    self.get(Route::MempoolQuote(swap.clone())).await
+  }
+
+  /// Relic transaction `txid` in the memory pool, `None` if it is not pending
+  pub async fn mempool_relic_transaction(
+    &self,
+    txid: Txid,
+  ) -> Result<Option<MempoolRelicTransaction>> {
+    self.get(Route::MempoolTransaction(txid)).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  #[test]
+  fn route_paths() {
+    let relic = RelicQuery::Spaced("BEST•RELIC".parse().unwrap());
+
+    #[track_caller]
+    fn case(route: Route, expected: &str) {
+      assert_eq!(route.to_string(), expected);
+    }
+
+    case(Route::Relic(relic), "/relic/BEST•RELIC");
+    case(
+      Route::Relic(RelicId { block: 1, tx: 2 }.into()),
+      "/relic/1:2",
+    );
+    case(Route::Relics(3), "/relics/3");
+    case(
+      Route::RelicEvents {
+        relic,
+        page: 2,
+        after: None,
+      },
+      "/events/BEST•RELIC/2",
+    );
+    case(
+      Route::relic_events(relic, &"5:1"),
+      "/events/BEST•RELIC/0?after=5:1",
+    );
+    case(
+      Route::BlockEvents {
+        height: 7,
+        after: None,
+      },
+      "/events/7",
+    );
+    case(
+      Route::Events {
+        after: Some("5:1".into()),
+      },
+      "/events?after=5:1",
+    );
+    case(Route::Sealing(relic), "/sealing/BEST•RELIC");
+    case(
+      Route::Output(OutPoint::null()),
+      &format!("/output/{}", OutPoint::null()),
+    );
+    case(
+      Route::Decipher(Txid::from_raw_hash(bitcoin::hashes::Hash::all_zeros())),
+      "/relics/decipher/0000000000000000000000000000000000000000000000000000000000000000",
+    );
+    case(Route::Offer, "/relics/offer");
+    case(Route::Sealings(4), "/sealings/4");
+    case(
+      Route::Candles {
+        relic,
+        interval: CandleInterval::Hour,
+      },
+      "/relic/BEST•RELIC/candles?interval=1h",
+    );
+    case(
+      Route::Holders { relic, page: 1 },
+      "/relic/BEST•RELIC/holders/1",
+    );
+    case(Route::RelicShares(relic), "/relic/BEST•RELIC/shares");
+    case(Route::Balances, "/relics/balances");
+    case(Route::Shares, "/relics/shares");
+    case(Route::Claimable, "/relics/claimable");
+    case(
+      Route::Snapshot {
+        height: 9,
+        by_address: false,
+        after: None,
+      },
+      "/relics/snapshot/9",
+    );
+    case(
+      Route::Snapshot {
+        height: 9,
+        by_address: true,
+        after: Some("0014ab".into()),
+      },
+      "/relics/snapshot/9/addresses?after=0014ab",
+    );
+    case(Route::MempoolRelics, "/mempool/relics");
+    case(
+      Route::MempoolOutput(OutPoint::null()),
+      &format!("/mempool/relics/output/{}", OutPoint::null()),
+    );
+    case(
+      Route::MempoolTransaction(Txid::from_raw_hash(bitcoin::hashes::Hash::all_zeros())),
+      "/mempool/relics/tx/0000000000000000000000000000000000000000000000000000000000000000",
+    );
+  }
+
+  #[test]
+  fn quote_paths() {
+    let id = RelicId { block: 1, tx: 2 };
+
+    assert_eq!(
+      Route::Quote(Swap {
+        output: Some(id),
+        input_amount: Some(100),
+        is_exact_input: true,
+        ..Default::default()
+      })
+      .to_string(),
+      "/relics/quote?output=1:2&amount=100&exact_input=true",
+    );
+
+    assert_eq!(
+      Route::MempoolQuote(Swap {
+        input: Some(id),
+        output: Some(RelicId { block: 3, tx: 4 }),
+        input_amount: Some(50),
+        output_amount: Some(10),
+        is_exact_input: false,
+        via: vec![RelicId { block: 5, tx: 6 }, RelicId { block: 7, tx: 8 }],
+      })
+      .to_string(),
+      "/mempool/relics/quote?input=1:2&output=3:4&via=5:6,7:8&amount=10&limit=50&exact_input=false",
+    );
+  }
+
+  #[test]
+  fn credentials_are_sent_as_basic_auth() {
+    let headers = default_headers(Some(("foo", "bar")));
+    assert_eq!(headers[header::ACCEPT], "application/json");
+    assert_eq!(headers[header::AUTHORIZATION], "Basic Zm9vOmJhcg==");
+    assert!(default_headers(None).get(header::AUTHORIZATION).is_none());
+  }
+}
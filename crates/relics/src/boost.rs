@@ -0,0 +1,222 @@
+use {
+  super::*,
+  bitcoin::hashes::{sha256, Hash, HashEngine},
+};
+
+/// Derivation of the random value that decides the multiplier of a boosted mint.
+///
+/// The derivation is consensus critical: every indexer must arrive at the same value for the same
+/// mint, otherwise balances diverge. Which derivation applies is decided by the height of the block
+/// containing the mint, see [`ProtocolUpgrade::DeterministicMints`].
+#[derive(Debug, PartialEq, Copy, Clone, Eq)]
+pub enum BoostRandomness {
+  /// SipHash-1-3 with zero keys over `block || len(txid) || txid || mint_index`, which is what
+  /// `std::hash::DefaultHasher` produced up to Rust 1.86. Pinned here, since the standard library
+  /// does not guarantee this algorithm across releases.
+  Legacy,
+  /// Tagged SHA-256 (BIP-340 style) with tag `relics/boost` over
+  /// `block || tx || txid || mint_index`.
+  TaggedSha256,
+}
+
+impl BoostRandomness {
+  /// Boost rolls are drawn from `0..RANGE`, chances in `BoostTerms` are given in ppm
+  pub const RANGE: u32 = 1_000_000;
+  pub const TAG: &'static str = "relics/boost";
+
+  /// First block height at which boost rolls use [`BoostRandomness::TaggedSha256`]
+  pub fn activation_height(network: Network) -> u32 {
+    ProtocolUpgrade::DeterministicMints.activation_height(network)
+  }
+
+  pub fn at_height(network: Network, height: u32) -> Self {
+    if height < Self::activation_height(network) {
+      Self::Legacy
+    } else {
+      Self::TaggedSha256
+    }
+  }
+
+  /// Random value in `0..RANGE` for the mint with the given index of `relic_id` in `txid`.
+  pub fn roll(self, relic_id: RelicId, txid: Txid, mint_index: u128) -> u32 {
+    let seed = match self {
+      Self::Legacy => Self::legacy_seed(relic_id, txid, mint_index),
+      Self::TaggedSha256 => Self::tagged_sha256_seed(relic_id, txid, mint_index),
+    };
+    u32::try_from(seed % u64::from(Self::RANGE)).unwrap()
+  }
+
+  fn legacy_seed(relic_id: RelicId, txid: Txid, mint_index: u128) -> u64 {
+    // byte stream fed to the Hasher by `u64::hash`, `Txid::hash` and `u128::hash`,
+    // note: the transaction index of the Relic ID was never part of the legacy seed
+    let mut message = Vec::with_capacity(64);
+    message.extend_from_slice(&relic_id.block.to_le_bytes());
+    message.extend_from_slice(&32u64.to_le_bytes());
+    message.extend_from_slice(txid.as_byte_array());
+    message.extend_from_slice(&mint_index.to_le_bytes());
+    siphash13(&message)
+  }
+
+  fn tagged_sha256_seed(relic_id: RelicId, txid: Txid, mint_index: u128) -> u64 {
+    let tag = sha256::Hash::hash(Self::TAG.as_bytes());
+    let mut engine = sha256::Hash::engine();
+    engine.input(tag.as_byte_array());
+    engine.input(tag.as_byte_array());
+    engine.input(&relic_id.block.to_le_bytes());
+    engine.input(&relic_id.tx.to_le_bytes());
+    engine.input(txid.as_byte_array());
+    engine.input(&mint_index.to_le_bytes());
+    let digest = sha256::Hash::from_engine(engine);
+    u64::from_le_bytes(digest.as_byte_array()[..8].try_into().unwrap())
+  }
+}
+
+/// SipHash-1-3 with `k0 = k1 = 0`
+fn siphash13(message: &[u8]) -> u64 {
+  let mut v = [
+    0x736f6d6570736575u64,
+    0x646f72616e646f6du64,
+    0x6c7967656e657261u64,
+    0x7465646279746573u64,
+  ];
+
+  fn round(v: &mut [u64; 4]) {
+    v[0] = v[0].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(13);
+    v[1] ^= v[0];
+    v[0] = v[0].rotate_left(32);
+    v[2] = v[2].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(16);
+    v[3] ^= v[2];
+    v[0] = v[0].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(21);
+    v[3] ^= v[0];
+    v[2] = v[2].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(17);
+    v[1] ^= v[2];
+    v[2] = v[2].rotate_left(32);
+  }
+
+  let chunks = message.chunks_exact(8);
+  let tail = chunks.remainder();
+
+  for chunk in chunks {
+    let m = u64::from_le_bytes(chunk.try_into().unwrap());
+    v[3] ^= m;
+    round(&mut v);
+    v[0] ^= m;
+  }
+
+  let mut last = (message.len() as u64) << 56;
+  for (i, byte) in tail.iter().enumerate() {
+    last |= u64::from(*byte) << (8 * i);
+  }
+
+  v[3] ^= last;
+  round(&mut v);
+  v[0] ^= last;
+
+  v[2] ^= 0xff;
+  round(&mut v);
+  round(&mut v);
+  round(&mut v);
+
+  v[0] ^ v[1] ^ v[2] ^ v[3]
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  const TXID: &str = "4e00929ef9849c20364d331e9b25d40b2f2f2ef8081d3cc769fd83c78d075f05";
+
+  fn case(randomness: BoostRandomness, relic_id: RelicId, txid: &str, mint_index: u128, roll: u32) {
+    assert_eq!(
+      randomness.roll(relic_id, txid.parse().unwrap(), mint_index),
+      roll,
+      "{randomness:?} {relic_id} {txid} {mint_index}"
+    );
+  }
+
+  #[test]
+  fn legacy_golden_vectors() {
+    let relic_id = RelicId::new(850_000, 1).unwrap();
+    case(BoostRandomness::Legacy, relic_id, TXID, 0, 326_848);
+    case(BoostRandomness::Legacy, relic_id, TXID, 1, 601_006);
+    case(
+      BoostRandomness::Legacy,
+      RelicId::new(871_234, 42).unwrap(),
+      "0000000000000000000000000000000000000000000000000000000000000000",
+      7,
+      396_245,
+    );
+    case(
+      BoostRandomness::Legacy,
+      RELIC_ID,
+      "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+      123_456_789,
+      623_650,
+    );
+  }
+
+  #[test]
+  fn tagged_sha256_golden_vectors() {
+    let relic_id = RelicId::new(850_000, 1).unwrap();
+    case(BoostRandomness::TaggedSha256, relic_id, TXID, 0, 448_166);
+    case(BoostRandomness::TaggedSha256, relic_id, TXID, 1, 780_663);
+    case(
+      BoostRandomness::TaggedSha256,
+      RelicId::new(871_234, 42).unwrap(),
+      "0000000000000000000000000000000000000000000000000000000000000000",
+      7,
+      924_832,
+    );
+    case(
+      BoostRandomness::TaggedSha256,
+      RELIC_ID,
+      "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+      123_456_789,
+      297_029,
+    );
+  }
+
+  #[test]
+  fn only_tagged_sha256_commits_to_relic_tx_index() {
+    let a = RelicId::new(850_000, 1).unwrap();
+    let b = RelicId::new(850_000, 2).unwrap();
+    let txid = TXID.parse().unwrap();
+    assert_eq!(
+      BoostRandomness::Legacy.roll(a, txid, 0),
+      BoostRandomness::Legacy.roll(b, txid, 0)
+    );
+    assert_eq!(BoostRandomness::TaggedSha256.roll(b, txid, 0), 301_836);
+  }
+
+  #[test]
+  fn siphash13_reference_vector() {
+    // empty message, k0 = k1 = 0
+    assert_eq!(siphash13(&[]), 0xd1fba762150c532c);
+  }
+
+  #[test]
+  fn activation() {
+    for network in [
+      Network::Bitcoin,
+      Network::Testnet,
+      Network::Signet,
+      Network::Regtest,
+    ] {
+      let height = BoostRandomness::activation_height(network);
+      if height > 0 {
+        assert_eq!(
+          BoostRandomness::at_height(network, height - 1),
+          BoostRandomness::Legacy
+        );
+      }
+      assert_eq!(
+        BoostRandomness::at_height(network, height),
+        BoostRandomness::TaggedSha256
+      );
+    }
+  }
+}
@@ -30,58 +30,83 @@ pub struct MultiMint {
   pub relic: RelicId,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Copy, Clone, Eq)]
+#[derive(Serialize, Debug, PartialEq, Copy, Clone, Eq)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 #[serde(untagged)]
 pub enum PriceModel {
   // Legacy: a fixed price as a number.
   Fixed(u128),
-  // New: formula pricing.
+  // New: formula pricing, price(x) = a * exp(x / b) for the mint with index x.
   Formula { a: u128, b: u128 },
 }
 
-impl PriceModel {
-  pub fn compute_price(&self, x: u128) -> Option<u128> {
-    match *self {
-      PriceModel::Fixed(price) => Some(price),
-      PriceModel::Formula { a, b } => {
-        // no zero‐division
-        if b == 0 {
-          return None;
-        }
+/// Deserialized by hand, since `#[serde(untagged)]` buffers the input, which does not support
+/// `u128`. Self-describing formats without `u128` integers, like JSON, are limited to fixed prices
+/// of at most `u64::MAX`.
+impl<'de> Deserialize<'de> for PriceModel {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    struct Visitor;
 
-        // compute supply_ratio = x/b
-        let supply_ratio = x as f64 / b as f64;
-        if !supply_ratio.is_finite() {
-          return None;
-        }
+    impl<'de> de::Visitor<'de> for Visitor {
+      type Value = PriceModel;
 
-        // avoid exp overflow: exp(supply_ratio) > u128::MAX/a when
-        // supply_ratio > ln(u128::MAX as f64 / a as f64)
-        let ln_bound = (u128::MAX as f64 / a as f64).ln();
-        if supply_ratio > ln_bound {
-          return None;
-        }
+      fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.write_str("a fixed price or a price formula")
+      }
+
+      fn visit_u64<E: de::Error>(self, price: u64) -> Result<PriceModel, E> {
+        Ok(PriceModel::Fixed(price.into()))
+      }
+
+      fn visit_u128<E: de::Error>(self, price: u128) -> Result<PriceModel, E> {
+        Ok(PriceModel::Fixed(price))
+      }
 
-        let price_f = (a as f64) * supply_ratio.exp();
-        // final safety check (should always pass here)
-        if !price_f.is_finite() || price_f > u128::MAX as f64 {
-          return None;
+      fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<PriceModel, A::Error> {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Formula {
+          a: u128,
+          b: u128,
         }
 
-        Some(price_f as u128)
+        let Formula { a, b } = Formula::deserialize(de::value::MapAccessDeserializer::new(map))?;
+
+        Ok(PriceModel::Formula { a, b })
+      }
+    }
+
+    deserializer.deserialize_any(Visitor)
+  }
+}
+
+impl PriceModel {
+  /// Computes the price of the mint with index `x`.
+  pub fn compute_price(&self, x: u128, arithmetic: PriceArithmetic) -> Option<u128> {
+    match (*self, arithmetic) {
+      (PriceModel::Fixed(price), _) => Some(price),
+      (PriceModel::Formula { a, b }, PriceArithmetic::Legacy) => Self::legacy_price(a, b, x),
+      (PriceModel::Formula { a, b }, PriceArithmetic::FixedPoint) => {
+        let next = price::cumulative_price(a, b, x.checked_add(1)?)?;
+        Some(next.saturating_sub(price::cumulative_price(a, b, x)?))
       }
     }
   }
 
   /// Computes the total price for `count` mints starting at mint index `start`.
-  pub fn compute_total_price(&self, start: u128, count: u8) -> Option<u128> {
-    match *self {
-      PriceModel::Fixed(price) => price.checked_mul(count as u128),
-      PriceModel::Formula { .. } => {
+  pub fn compute_total_price(
+    &self,
+    start: u128,
+    count: u8,
+    arithmetic: PriceArithmetic,
+  ) -> Option<u128> {
+    match (*self, arithmetic) {
+      (PriceModel::Fixed(price), _) => price.checked_mul(count as u128),
+      (PriceModel::Formula { .. }, PriceArithmetic::Legacy) => {
         let mut total = 0u128;
         for i in 0..count {
           let idx = start.checked_add(i as u128)?; // mint index
-          if let Some(computed_price) = self.compute_price(idx) {
+          if let Some(computed_price) = self.compute_price(idx, arithmetic) {
             total = total.checked_add(computed_price)?;
           } else {
             return None;
@@ -89,7 +114,57 @@ impl PriceModel {
         }
         Some(total)
       }
+      (PriceModel::Formula { a, b }, PriceArithmetic::FixedPoint) => {
+        let end = price::cumulative_price(a, b, start.checked_add(count.into())?)?;
+        Some(end.saturating_sub(price::cumulative_price(a, b, start)?))
+      }
+    }
+  }
+
+  /// Computes the total price of the first `mints` mints, in constant time unless the legacy
+  /// arithmetic has to sum up the formula price of every mint.
+  pub fn compute_cumulative_price(&self, mints: u128, arithmetic: PriceArithmetic) -> Option<u128> {
+    match (*self, arithmetic) {
+      (PriceModel::Fixed(price), _) => price.checked_mul(mints),
+      (PriceModel::Formula { a, b }, PriceArithmetic::Legacy) => {
+        let mut total = 0u128;
+        for mint_index in 0..mints {
+          total = total.checked_add(Self::legacy_price(a, b, mint_index)?)?;
+        }
+        Some(total)
+      }
+      (PriceModel::Formula { a, b }, PriceArithmetic::FixedPoint) => {
+        price::cumulative_price(a, b, mints)
+      }
+    }
+  }
+
+  fn legacy_price(a: u128, b: u128, x: u128) -> Option<u128> {
+    // no zero‐division
+    if b == 0 {
+      return None;
+    }
+
+    // compute supply_ratio = x/b
+    let supply_ratio = x as f64 / b as f64;
+    if !supply_ratio.is_finite() {
+      return None;
+    }
+
+    // avoid exp overflow: exp(supply_ratio) > u128::MAX/a when
+    // supply_ratio > ln(u128::MAX as f64 / a as f64)
+    let ln_bound = (u128::MAX as f64 / a as f64).ln();
+    if supply_ratio > ln_bound {
+      return None;
     }
+
+    let price_f = (a as f64) * supply_ratio.exp();
+    // final safety check (should always pass here)
+    if !price_f.is_finite() || price_f > u128::MAX as f64 {
+      return None;
+    }
+
+    Some(price_f as u128)
   }
 }
 
@@ -97,6 +172,7 @@ impl PriceModel {
 /// Afterward, the liquidity pool is immediately opened with the total RELIC collected during minting and the Relics seed supply.
 /// If the Relic never mints out, no pool is created and the collected RELIC are locked.
 #[derive(Default, Serialize, Deserialize, Debug, PartialEq, Copy, Clone, Eq)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct MintTerms {
   /// amount of quote tokens minted per mint
   pub amount: Option<u128>,
@@ -118,6 +194,7 @@ pub struct MintTerms {
 
 /// If set give people the chance to get boosts (multipliers) on their mints
 #[derive(Default, Serialize, Deserialize, Debug, PartialEq, Copy, Clone, Eq)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct BoostTerms {
   // chance to get a rare mint in ppm
   pub rare_chance: Option<u32>,
@@ -174,16 +251,23 @@ impl BoostTerms {
 
 impl MintTerms {
   // computes the price for one mint (not one token)
-  pub fn compute_price(&self, minted: u128) -> Option<u128> {
-    self.price.and_then(|p| p.compute_price(minted))
+  pub fn compute_price(&self, minted: u128, arithmetic: PriceArithmetic) -> Option<u128> {
+    self.price.and_then(|p| p.compute_price(minted, arithmetic))
   }
 
   /// Computes the total price for `count` mints starting at mint index `start`.
-  pub fn compute_total_price(&self, start: u128, count: u8) -> Option<u128> {
-    self.price.and_then(|p| p.compute_total_price(start, count))
+  pub fn compute_total_price(
+    &self,
+    start: u128,
+    count: u8,
+    arithmetic: PriceArithmetic,
+  ) -> Option<u128> {
+    self
+      .price
+      .and_then(|p| p.compute_total_price(start, count, arithmetic))
   }
 
-  fn validate(&self) -> Result<(), RelicFlaw> {
+  fn validate(&self, arithmetic: PriceArithmetic) -> Result<(), RelicFlaw> {
     let cap = self
       .cap
       .ok_or(RelicFlaw::InvalidEnshriningTermsMissingOrZeroCap)?;
@@ -201,6 +285,13 @@ impl MintTerms {
         cap.checked_mul(p).is_some(),
         RelicFlaw::InvalidEnshriningTermsFixedPriceCapOverflow
       ),
+      Some(PriceModel::Formula { a, b }) if arithmetic == PriceArithmetic::FixedPoint => {
+        // every mint up to the cap must be priced, so the cumulative price of all mints must fit
+        ensure!(
+          a > 0 && b > 0 && price::cumulative_price(a, b, cap).is_some(),
+          RelicFlaw::InvalidEnshriningTermsInvalidPriceFormula
+        );
+      }
       Some(PriceModel::Formula { a, b }) => {
         ensure!(
           a > 0 && b > 0,
@@ -242,6 +333,8 @@ impl MintTerms {
 impl Enshrining {
   /// All Relics come with the same divisibility
   pub const DIVISIBILITY: u8 = 8;
+  /// Pool fees are capped at 10%, higher fees are reduced to it
+  pub const MAX_FEE_BPS: u16 = 1_000;
   pub const MAX_SPACERS: u32 = 0b00000111_11111111_11111111_11111111;
 
   pub fn max_supply(&self) -> Option<u128> {
@@ -271,12 +364,14 @@ impl Enshrining {
     seed.checked_add(cap.checked_mul(amount)?.checked_mul(max_boost.into())?)
   }
 
-  pub fn validate(&self) -> Result<(), RelicFlaw> {
+  /// Validate the enshrining, formula prices are checked for the `arithmetic` of the block the
+  /// enshrining is included in
+  pub fn validate(&self, arithmetic: PriceArithmetic) -> Result<(), RelicFlaw> {
     let terms = self
       .mint_terms
       .as_ref()
       .ok_or(RelicFlaw::InvalidEnshriningTermsMissingOrZeroCap)?;
-    terms.validate()?;
+    terms.validate(arithmetic)?;
 
     if let Some(bt) = &self.boost_terms {
       bt.validate(terms.amount)?;
@@ -312,35 +407,73 @@ mod tests {
   use super::*;
   use pretty_assertions::assert_eq;
 
+  #[test]
+  fn price_model_json_round_trip() {
+    for price in [
+      PriceModel::Fixed(1000),
+      PriceModel::Fixed(u64::MAX.into()),
+      PriceModel::Formula { a: 1, b: u128::MAX },
+    ] {
+      assert_eq!(
+        serde_json::from_str::<PriceModel>(&serde_json::to_string(&price).unwrap()).unwrap(),
+        price,
+      );
+    }
+
+    assert_eq!(
+      serde_json::from_str::<MintTerms>(r#"{"amount":1,"price":{"a":2,"b":3}}"#)
+        .unwrap()
+        .price,
+      Some(PriceModel::Formula { a: 2, b: 3 }),
+    );
+
+    assert!(serde_json::from_str::<PriceModel>(r#"{"a":1,"b":2,"c":3}"#).is_err());
+  }
+
   #[test]
   fn test_price_model_fixed() {
     let fixed_price = PriceModel::Fixed(1000);
-    assert_eq!(fixed_price.compute_price(0), Some(1000));
-    assert_eq!(fixed_price.compute_price(1), Some(1000));
-    assert_eq!(fixed_price.compute_price(100), Some(1000));
+    assert_eq!(
+      fixed_price.compute_price(0, PriceArithmetic::Legacy),
+      Some(1000)
+    );
+    assert_eq!(
+      fixed_price.compute_price(1, PriceArithmetic::Legacy),
+      Some(1000)
+    );
+    assert_eq!(
+      fixed_price.compute_price(100, PriceArithmetic::Legacy),
+      Some(1000)
+    );
   }
 
   #[test]
   fn test_price_model_formula() {
     // a = 1, b = 1: price = e^(x)
     let mut m = PriceModel::Formula { a: 1, b: 1 };
-    assert_eq!(m.compute_price(0), Some(1)); // e^0 = 1
-    assert_eq!(m.compute_price(1), Some(2)); // ⌊e^1⌋ = 2
-    assert_eq!(m.compute_price(2), Some(7)); // ⌊e^2⌋ = 7
-    assert_eq!(m.compute_price(3), Some(20)); // ⌊e^3⌋ = 20
-    assert_eq!(m.compute_price(4), Some(54)); // ⌊e^4⌋ = 54
+    assert_eq!(m.compute_price(0, PriceArithmetic::Legacy), Some(1)); // e^0 = 1
+    assert_eq!(m.compute_price(1, PriceArithmetic::Legacy), Some(2)); // ⌊e^1⌋ = 2
+    assert_eq!(m.compute_price(2, PriceArithmetic::Legacy), Some(7)); // ⌊e^2⌋ = 7
+    assert_eq!(m.compute_price(3, PriceArithmetic::Legacy), Some(20)); // ⌊e^3⌋ = 20
+    assert_eq!(m.compute_price(4, PriceArithmetic::Legacy), Some(54)); // ⌊e^4⌋ = 54
 
     // a = 10, b = 2: price = 10 * e^(x/2)
     m = PriceModel::Formula { a: 10, b: 2 };
-    assert_eq!(m.compute_price(0), Some(10)); // 10*e^0 = 10
-    assert_eq!(m.compute_price(1), Some(16)); // ⌊10*e^0.5⌋ = 16
-    assert_eq!(m.compute_price(2), Some(27)); // ⌊10*e^1⌋ = 27
-    assert_eq!(m.compute_price(4), Some(73)); // ⌊10*e^2⌋ = 73
+    assert_eq!(m.compute_price(0, PriceArithmetic::Legacy), Some(10)); // 10*e^0 = 10
+    assert_eq!(m.compute_price(1, PriceArithmetic::Legacy), Some(16)); // ⌊10*e^0.5⌋ = 16
+    assert_eq!(m.compute_price(2, PriceArithmetic::Legacy), Some(27)); // ⌊10*e^1⌋ = 27
+    assert_eq!(m.compute_price(4, PriceArithmetic::Legacy), Some(73)); // ⌊10*e^2⌋ = 73
 
     // b = 0 → None
-    assert_eq!(PriceModel::Formula { a: 5, b: 0 }.compute_price(123), None);
+    assert_eq!(
+      PriceModel::Formula { a: 5, b: 0 }.compute_price(123, PriceArithmetic::Legacy),
+      None
+    );
     // overflow case: supply_ratio > ln(u128::MAX/a) → None
-    assert_eq!(PriceModel::Formula { a: 1, b: 1 }.compute_price(89), None);
+    assert_eq!(
+      PriceModel::Formula { a: 1, b: 1 }.compute_price(89, PriceArithmetic::Legacy),
+      None
+    );
   }
 
   #[test]
@@ -350,25 +483,150 @@ mod tests {
       b: 6_994,
     };
 
-    assert_eq!(formula.compute_total_price(0, 3), Some(87_841_555));
-    assert_eq!(formula.compute_total_price(3, 3), Some(87_879_241));
-    assert_eq!(formula.compute_total_price(10, 5), Some(146_633_032));
-    assert_eq!(formula.compute_total_price(15000, 1), Some(250_003_485));
+    assert_eq!(
+      formula.compute_total_price(0, 3, PriceArithmetic::Legacy),
+      Some(87_841_555)
+    );
+    assert_eq!(
+      formula.compute_total_price(3, 3, PriceArithmetic::Legacy),
+      Some(87_879_241)
+    );
+    assert_eq!(
+      formula.compute_total_price(10, 5, PriceArithmetic::Legacy),
+      Some(146_633_032)
+    );
+    assert_eq!(
+      formula.compute_total_price(15000, 1, PriceArithmetic::Legacy),
+      Some(250_003_485)
+    );
+  }
+
+  #[test]
+  fn test_price_model_formula_fixed_point() {
+    let m = PriceModel::Formula { a: 1, b: 1 };
+    let prices = (0..5)
+      .map(|x| m.compute_price(x, PriceArithmetic::FixedPoint))
+      .collect::<Vec<Option<u128>>>();
+    // ⌊e^2⌋ = 7, but the first three prices add up to ⌊1 + e + e^2⌋ = 11
+    assert_eq!(prices, [Some(1), Some(2), Some(8), Some(20), Some(54)]);
+    assert_eq!(
+      m.compute_price(10, PriceArithmetic::FixedPoint),
+      Some(22_026)
+    );
+
+    let formula = PriceModel::Formula {
+      a: 29_276_332,
+      b: 6_994,
+    };
+    let arithmetic = PriceArithmetic::FixedPoint;
+    assert_eq!(
+      formula.compute_total_price(0, 3, arithmetic),
+      Some(87_841_555)
+    );
+    assert_eq!(
+      formula.compute_total_price(3, 3, arithmetic),
+      Some(87_879_242)
+    );
+    assert_eq!(
+      formula.compute_total_price(10, 5, arithmetic),
+      Some(146_633_034)
+    );
+    assert_eq!(
+      formula.compute_total_price(15000, 1, arithmetic),
+      Some(250_003_486)
+    );
+
+    // totals match the sum of single prices and the cumulative price
+    let total = (15_000..15_255)
+      .map(|x| formula.compute_price(x, arithmetic).unwrap())
+      .sum::<u128>();
+    assert_eq!(
+      formula.compute_total_price(15_000, 255, arithmetic),
+      Some(total)
+    );
+    assert_eq!(
+      formula.compute_cumulative_price(15_255, arithmetic),
+      Some(
+        formula
+          .compute_cumulative_price(15_000, arithmetic)
+          .unwrap()
+          + total
+      )
+    );
+
+    assert_eq!(
+      PriceModel::Formula { a: 5, b: 0 }.compute_price(123, arithmetic),
+      None
+    );
+    assert_eq!(
+      PriceModel::Formula { a: 1, b: 1 }.compute_price(89, arithmetic),
+      None
+    );
+  }
+
+  #[test]
+  fn test_cumulative_price() {
+    assert_eq!(
+      PriceModel::Fixed(1000).compute_cumulative_price(16_800, PriceArithmetic::FixedPoint),
+      Some(16_800_000)
+    );
+
+    let formula = PriceModel::Formula { a: 10, b: 2 };
+    assert_eq!(
+      formula.compute_cumulative_price(5, PriceArithmetic::Legacy),
+      Some(10 + 16 + 27 + 44 + 73)
+    );
+    assert_eq!(
+      formula.compute_cumulative_price(5, PriceArithmetic::FixedPoint),
+      Some(172)
+    );
+  }
+
+  #[test]
+  fn formula_cap_must_keep_the_cumulative_price_in_range() {
+    let terms = |cap| MintTerms {
+      amount: Some(1),
+      cap: Some(cap),
+      price: Some(PriceModel::Formula { a: 1, b: 1_000_000 }),
+      ..default()
+    };
+
+    for arithmetic in [PriceArithmetic::Legacy, PriceArithmetic::FixedPoint] {
+      assert_eq!(terms(70_000_000).validate(arithmetic), Ok(()));
+    }
+
+    // the last mint is priced below u128::MAX, but the price of all mints exceeds it
+    assert_eq!(terms(80_000_000).validate(PriceArithmetic::Legacy), Ok(()));
+    assert_eq!(
+      PriceModel::Formula { a: 1, b: 1_000_000 }
+        .compute_cumulative_price(80_000_000, PriceArithmetic::FixedPoint),
+      None
+    );
+    assert_eq!(
+      terms(80_000_000).validate(PriceArithmetic::FixedPoint),
+      Err(RelicFlaw::InvalidEnshriningTermsInvalidPriceFormula)
+    );
   }
 
   #[test]
   fn test_formula_with_starting_price_exact() {
     let formula = PriceModel::Formula { a: 10, b: 1 };
 
-    assert_eq!(formula.compute_price(0), Some(10));
-    assert_eq!(formula.compute_price(1), Some(27));
-    assert_eq!(formula.compute_price(2), Some(73));
+    assert_eq!(formula.compute_price(0, PriceArithmetic::Legacy), Some(10));
+    assert_eq!(formula.compute_price(1, PriceArithmetic::Legacy), Some(27));
+    assert_eq!(formula.compute_price(2, PriceArithmetic::Legacy), Some(73));
   }
 
   #[test]
   fn test_zero_division_protection() {
     let div_zero_formula = PriceModel::Formula { a: 1, b: 1 };
-    assert_eq!(div_zero_formula.compute_price(100), None);
-    assert_eq!(div_zero_formula.compute_price(10), Some(22_026));
+    assert_eq!(
+      div_zero_formula.compute_price(100, PriceArithmetic::Legacy),
+      None
+    );
+    assert_eq!(
+      div_zero_formula.compute_price(10, PriceArithmetic::Legacy),
+      Some(22_026)
+    );
   }
 }
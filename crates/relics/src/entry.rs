@@ -0,0 +1,378 @@
+use super::*;
+
+#[derive(Debug, Hash, Eq, PartialEq, PartialOrd, Ord, Copy, Clone, Serialize, Deserialize)]
+pub struct RelicOwner(pub ScriptHash);
+
+impl Default for RelicOwner {
+  fn default() -> Self {
+    Self(ScriptHash::all_zeros())
+  }
+}
+
+#[derive(Debug, Default, PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct RelicState {
+  pub burned: u128,
+  pub mints: u128,
+  pub unmints: u128,
+  /// base tokens paid for the mints, less the refunds of unmints
+  pub paid: u128,
+  /// number of the first mints that were priced with [`PriceArithmetic::Legacy`]
+  pub legacy_mints: u128,
+  /// amount issued by the mints, including their boosts, less the amounts returned by unmints
+  pub issued: u128,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub struct RelicEntry {
+  pub block: u64,
+  #[cfg_attr(feature = "schemars", schemars(with = "schema::Txid"))]
+  pub enshrining: Txid,
+  pub fee: u16,
+  pub number: u64,
+  pub spaced_relic: SpacedRelic,
+  pub symbol: Option<char>,
+  pub owner_sequence_number: Option<u32>,
+  pub boost_terms: Option<BoostTerms>,
+  pub mint_terms: Option<MintTerms>,
+  pub state: RelicState,
+  pub pool: Option<Pool>,
+  pub timestamp: u64,
+}
+
+impl RelicEntry {
+  pub fn mintable(
+    &self,
+    base_balance: u128,
+    num_mints: u8,
+    base_limit: u128,
+    arithmetic: PriceArithmetic,
+  ) -> Result<Vec<(u128, u128)>, RelicError> {
+    let terms = self.mint_terms.ok_or(RelicError::Unmintable)?;
+    if self.is_free() && num_mints > 1 {
+      return Err(RelicError::MaxMintPerTxExceeded(1));
+    }
+    if let Some(max_tx) = terms.tx_cap {
+      if num_mints > max_tx {
+        return Err(RelicError::MaxMintPerTxExceeded(max_tx));
+      }
+    }
+
+    let cap = terms.cap.unwrap_or_default();
+    let current_mints = self.state.mints;
+
+    let remaining = cap.saturating_sub(current_mints); // mints left
+    if remaining == 0 {
+      return Err(RelicError::MintCap(cap)); // none left
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    let actual_mints = remaining.min(u128::from(num_mints).min(u128::from(u8::MAX))) as u8;
+
+    let total_price = terms
+      .compute_total_price(current_mints, actual_mints, arithmetic)
+      .ok_or(RelicError::PriceComputationError)?;
+
+    if base_limit < total_price {
+      return Err(RelicError::MintBaseLimitExceeded(base_limit, total_price));
+    }
+    if base_balance < total_price {
+      return Err(RelicError::MintInsufficientBalance(total_price));
+    }
+
+    let mut results = Vec::with_capacity(actual_mints as usize);
+    for i in 0..actual_mints {
+      let mint_idx = current_mints + u128::from(i);
+      let price = terms
+        .compute_price(mint_idx, arithmetic)
+        .ok_or(RelicError::PriceComputationError)?;
+      let amount = terms.amount.unwrap_or_default();
+      results.push((amount, price));
+    }
+
+    Ok(results)
+  }
+
+  // base_min = minimum base tokens to accept
+  pub fn unmintable(
+    &self,
+    balance: u128,
+    num_mints: u8,
+    base_min: u128,
+    arithmetic: PriceArithmetic,
+  ) -> Result<Vec<(u128, u128)>, RelicError> {
+    let terms = self.mint_terms.as_ref().ok_or(RelicError::Unmintable)?;
+    let max_unmints = u128::from(terms.max_unmints.ok_or(RelicError::UnmintNotAllowed)?);
+    if self.state.mints < u128::from(num_mints) {
+      return Err(RelicError::NoMintsToUnmint);
+    }
+    if self.is_free() {
+      return Err(RelicError::UnmintNotAllowed);
+    }
+    let cap = terms.cap.unwrap_or(0);
+    if cap != 0 && self.state.mints == cap {
+      return Err(RelicError::UnmintNotAllowed);
+    }
+    if self.state.unmints + u128::from(num_mints) > max_unmints {
+      return Err(RelicError::UnmintNotAllowed);
+    }
+    let mut results = Vec::with_capacity(num_mints as usize);
+    let mut total_amount: u128 = 0;
+    let mut total_price: u128 = 0;
+    for i in 0..num_mints {
+      let mint_index = self.state.mints - 1 - u128::from(i);
+      // refund the price that was paid for the mint
+      let arithmetic = if mint_index < self.state.legacy_mints {
+        PriceArithmetic::Legacy
+      } else {
+        arithmetic
+      };
+      let price = match terms.price {
+        Some(PriceModel::Fixed(fixed)) => fixed,
+        Some(PriceModel::Formula { .. }) => terms
+          .compute_price(mint_index, arithmetic)
+          .ok_or(RelicError::PriceComputationError)?,
+        None => return Err(RelicError::PriceComputationError),
+      };
+      let amount = terms.amount.unwrap_or_default();
+      total_amount = total_amount.saturating_add(amount);
+      total_price = total_price.saturating_add(price);
+      results.push((amount, price));
+    }
+    if balance < total_amount {
+      return Err(RelicError::MintInsufficientBalance(total_amount));
+    }
+    if total_price < base_min {
+      return Err(RelicError::MintBaseLimitExceeded(base_min, total_price));
+    }
+    Ok(results)
+  }
+
+  pub fn swap(&self, swap: PoolSwap, balance: Option<u128>) -> Result<BalanceDiff, RelicError> {
+    // fail the swap if pool does not exist (yet)
+    let Some(pool) = self.pool else {
+      return Err(RelicError::SwapNotAvailable);
+    };
+
+    // fail the swap if either the base supply or quote supply is 0
+    if pool.base_supply == 0 || pool.quote_supply == 0 {
+      return Err(RelicError::SwapNotAvailable);
+    }
+
+    // also fail if pool still has subsidy -> means not fully minted
+    if pool.subsidy > 0 {
+      return Err(RelicError::SwapNotAvailable);
+    }
+
+    match pool.calculate(swap) {
+      Ok(diff) => {
+        if let Some(balance) = balance {
+          if diff.input > balance {
+            return Err(RelicError::SwapInsufficientBalance(diff.input));
+          }
+        }
+        Ok(diff)
+      }
+      Err(cause) => Err(RelicError::SwapFailed(cause)),
+    }
+  }
+
+  pub fn liquidity(
+    &self,
+    liquidity: Liquidity,
+    base_balance: u128,
+    quote_balance: u128,
+    shares_balance: u128,
+  ) -> Result<LiquidityDiff, RelicError> {
+    // liquidity can only be added to or removed from a pool that is open for swaps
+    let Some(pool) = self.pool else {
+      return Err(RelicError::LiquidityNotAvailable);
+    };
+    if pool.base_supply == 0 || pool.quote_supply == 0 || pool.subsidy > 0 {
+      return Err(RelicError::LiquidityNotAvailable);
+    }
+
+    let non_zero = |amount: u128| (amount > 0).then_some(amount);
+
+    if liquidity.is_remove {
+      if liquidity.shares > shares_balance {
+        return Err(RelicError::LiquidityInsufficientShares(liquidity.shares));
+      }
+      pool
+        .remove_liquidity(
+          liquidity.shares,
+          non_zero(liquidity.base_amount),
+          non_zero(liquidity.quote_amount),
+        )
+        .map_err(RelicError::LiquidityFailed)
+    } else {
+      let diff = pool
+        .add_liquidity(
+          liquidity.base_amount,
+          liquidity.quote_amount,
+          non_zero(liquidity.shares),
+        )
+        .map_err(RelicError::LiquidityFailed)?;
+      if diff.base_amount > base_balance || diff.quote_amount > quote_balance {
+        return Err(RelicError::LiquidityInsufficientBalance(
+          diff.base_amount,
+          diff.quote_amount,
+        ));
+      }
+      Ok(diff)
+    }
+  }
+
+  /// Lock burned shares in the pool, returns the provider fees paid to the owner, see
+  /// [`Pool::burn_shares`]
+  pub fn burn_shares(&mut self, shares: u128) -> Result<u128, RelicError> {
+    self
+      .pool
+      .as_mut()
+      .ok_or(RelicError::LiquidityNotAvailable)?
+      .burn_shares(shares)
+      .map_err(RelicError::LiquidityFailed)
+  }
+
+  /// max supply of this token: maximum amount of tokens that can be minted plus
+  /// the additional amount that is created for the pool after minting is complete
+  pub fn max_supply(&self) -> u128 {
+    self
+      .mint_terms
+      .map(|terms| {
+        terms.amount.unwrap_or_default() * terms.cap.unwrap_or_default()
+          + terms.seed.unwrap_or_default()
+      })
+      .unwrap_or_default()
+  }
+
+  pub fn is_free(&self) -> bool {
+    self
+      .mint_terms
+      .as_ref()
+      .map(|terms| terms.price.is_none() || matches!(terms.price, Some(PriceModel::Fixed(0))))
+      .unwrap_or(false)
+  }
+
+  /// circulating supply of tokens: either minted or swapped out of the pool minus burned
+  pub fn circulating_supply(&self) -> u128 {
+    let amount = self
+      .mint_terms
+      .and_then(|terms| terms.amount)
+      .unwrap_or_default();
+    let seed = self
+      .mint_terms
+      .and_then(|terms| terms.seed)
+      .unwrap_or_default();
+    let pool_quote_supply = self.pool.map(|pool| pool.quote_supply).unwrap_or(seed);
+    self.state.mints * amount + seed - pool_quote_supply - self.state.burned
+  }
+
+  pub fn locked_base_supply(&self) -> u128 {
+    if let Some(pool) = self.pool {
+      if pool.base_supply > 0 {
+        // pool was already bootstrapped, the provider fees are held by the pool as well
+        return pool.base_supply + pool.provider_fees;
+      } else if pool.subsidy > 0 {
+        return pool.subsidy; // lp is sponsored
+      }
+    }
+
+    // otherwise the mint payments, not re-priced since the arithmetic may have changed
+    self.state.paid
+  }
+}
+
+impl Default for RelicEntry {
+  fn default() -> Self {
+    Self {
+      block: 0,
+      enshrining: Txid::all_zeros(),
+      fee: 100, // 1%
+      number: 0,
+      spaced_relic: SpacedRelic::default(),
+      symbol: None,
+      owner_sequence_number: None,
+      boost_terms: None,
+      mint_terms: None,
+      state: RelicState::default(),
+      pool: None,
+      timestamp: 0,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn mintable_default() {
+    assert_eq!(
+      RelicEntry::default().mintable(0, 1, 0, PriceArithmetic::FixedPoint),
+      Err(RelicError::Unmintable)
+    );
+  }
+
+  #[test]
+  fn mints_are_refunded_with_the_price_paid() {
+    let terms = MintTerms {
+      amount: Some(10),
+      cap: Some(100),
+      max_unmints: Some(10),
+      price: Some(PriceModel::Formula {
+        a: 1_000_000_000_000,
+        b: 7,
+      }),
+      ..default()
+    };
+
+    let entry = RelicEntry {
+      mint_terms: Some(terms),
+      state: RelicState {
+        mints: 3,
+        paid: 1_234,
+        legacy_mints: 2,
+        ..default()
+      },
+      ..default()
+    };
+
+    assert_eq!(
+      entry.unmintable(30, 3, 0, PriceArithmetic::FixedPoint),
+      Ok(vec![
+        (
+          10,
+          terms.compute_price(2, PriceArithmetic::FixedPoint).unwrap()
+        ),
+        (10, terms.compute_price(1, PriceArithmetic::Legacy).unwrap()),
+        (10, terms.compute_price(0, PriceArithmetic::Legacy).unwrap()),
+      ])
+    );
+
+    // the mint payments are never re-priced
+    assert_eq!(entry.locked_base_supply(), 1_234);
+  }
+
+  #[test]
+  fn shares_can_only_be_burned_in_a_bootstrapped_pool() {
+    let mut entry = RelicEntry::default();
+    assert_eq!(entry.burn_shares(1), Err(RelicError::LiquidityNotAvailable));
+
+    // the pool of a Relic that is not minted out yet has no shares
+    entry.pool = Some(Pool {
+      base_supply: 0,
+      quote_supply: 0,
+      fee_bps: 100,
+      subsidy: 10_000,
+      shares: 0,
+      locked_shares: 0,
+      provider_fees: 0,
+    });
+    assert_eq!(
+      entry.burn_shares(1),
+      Err(RelicError::LiquidityFailed(PoolError::Overflow))
+    );
+    assert_eq!(entry.pool.unwrap().locked_shares, 0);
+  }
+}
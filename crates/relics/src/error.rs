@@ -0,0 +1,107 @@
+use super::*;
+
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
+pub enum RelicError {
+  SealingAlreadyExists(SpacedRelic),
+  SealingInsufficientBalance(u128),
+  SealingBaseToken,
+  SealingNotFound,
+  Unmintable,
+  MintCap(u128),
+  MintInsufficientBalance(u128),
+  UnmintInsufficientBalance(u128),
+  UnmintNotAllowed,
+  NoMintsToUnmint,
+  MaxMintPerTxExceeded(u8),
+  MintBaseLimitExceeded(u128, u128),
+  MintBlockCapExceeded(u32),
+  MissingSubsidy(u128),
+  SwapNotAvailable,
+  SwapFailed(PoolError),
+  SwapInsufficientBalance(u128),
+  InscriptionMissing,
+  InscriptionMetadataMissing,
+  InvalidMetadata,
+  PriceComputationError,
+  RelicAlreadyEnshrined,
+  RelicNotFound(#[cfg_attr(feature = "schemars", schemars(with = "schema::RelicId"))] RelicId),
+  RelicOwnerOnly,
+  NoClaimableBalance,
+  LiquidityNotAvailable,
+  LiquidityFailed(PoolError),
+  LiquidityInsufficientBalance(u128, u128),
+  LiquidityInsufficientShares(u128),
+}
+
+impl std::error::Error for RelicError {}
+
+impl Display for RelicError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self {
+      RelicError::Unmintable => write!(f, "not mintable"),
+      RelicError::MintCap(cap) => write!(f, "limited to {cap} mints"),
+      RelicError::MintInsufficientBalance(price) => {
+        write!(f, "insufficient balance for mint price of {price}")
+      }
+      RelicError::UnmintNotAllowed => write!(f, "unmint not allowed (here)"),
+      RelicError::NoMintsToUnmint => write!(f, "no mints to unmint"),
+      RelicError::MaxMintPerTxExceeded(max) => {
+        write!(f, "maximum mints per transaction exceeded: {max}")
+      }
+      RelicError::MintBaseLimitExceeded(limit, price) => {
+        write!(f, "mint base limit exceeded: limit {limit}, price {price}")
+      }
+      RelicError::UnmintInsufficientBalance(required) => {
+        write!(
+          f,
+          "insufficient minted token balance for unmint: required {required}"
+        )
+      }
+      RelicError::MintBlockCapExceeded(limit) => write!(
+        f,
+        "max mints per block exceeded: only {limit} allowed per block"
+      ),
+      RelicError::MissingSubsidy(subsidy) => write!(f, "missing subsidy for mint: {subsidy}"),
+      RelicError::PriceComputationError => write!(f, "price computation error"),
+      RelicError::SwapNotAvailable => write!(f, "liquidity pool for swap not available (yet)"),
+      RelicError::SwapFailed(cause) => write!(f, "swap failed: {cause}"),
+      RelicError::SwapInsufficientBalance(required) => {
+        write!(f, "insufficient balance for swap {required}")
+      }
+      RelicError::InscriptionMissing => write!(f, "no Inscription found in transaction"),
+      RelicError::InscriptionMetadataMissing => write!(f, "no metadata on Inscription found"),
+      RelicError::InvalidMetadata => write!(
+        f,
+        "Inscription metadata does not contain a valid Relic ticker"
+      ),
+      RelicError::SealingAlreadyExists(relic) => {
+        write!(f, "Relic has already been sealed: {relic}")
+      }
+      RelicError::SealingInsufficientBalance(fee) => {
+        write!(f, "insufficient balance for sealing fee: {fee}")
+      }
+      RelicError::SealingNotFound => write!(f, "Sealing not found"),
+      RelicError::SealingBaseToken => write!(f, "Sealing base token is invalid"),
+      RelicError::RelicAlreadyEnshrined => write!(f, "Relic has already been enshrined"),
+      RelicError::RelicNotFound(id) => write!(f, "Relic not found: {id}"),
+      RelicError::RelicOwnerOnly => {
+        write!(f, "this operation can only be performed by the Relic owner")
+      }
+      RelicError::NoClaimableBalance => {
+        write!(f, "unable to claim: No claimable balance for given output")
+      }
+      RelicError::LiquidityNotAvailable => write!(f, "liquidity pool not available (yet)"),
+      RelicError::LiquidityFailed(cause) => write!(f, "liquidity change failed: {cause}"),
+      RelicError::LiquidityInsufficientBalance(base, quote) => {
+        write!(
+          f,
+          "insufficient balance for liquidity: required {base} base tokens and {quote} Relics"
+        )
+      }
+      RelicError::LiquidityInsufficientShares(required) => {
+        write!(f, "insufficient pool shares: required {required}")
+      }
+    }
+  }
+}
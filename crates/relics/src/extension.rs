@@ -0,0 +1,48 @@
+use super::*;
+
+/// Additions to the Keepsake format made after Relics launched.
+///
+/// Recognizing them is consensus critical: before its activation height the fields of an extension
+/// are unrecognized, so a Keepsake using them is a cenotaph and burns its inputs, like it did before
+/// the extension existed. See [`ProtocolUpgrade::KeepsakeExtensions`].
+#[derive(Debug, PartialEq, Copy, Clone, Eq)]
+pub enum KeepsakeExtension {
+  /// `SwapVia` routes of multi-hop swaps
+  SwapRoutes,
+  /// `SwapBatch` and `SwapBatchVia` batches of swaps
+  SwapBatches,
+  /// `Liquidity` additions to and removals from pools, and `ShareTransfer` allocations of pool
+  /// shares
+  Liquidity,
+}
+
+impl KeepsakeExtension {
+  /// First block height at which the fields of the extension are recognized
+  pub fn activation_height(self, network: Network) -> u32 {
+    match self {
+      Self::SwapRoutes | Self::SwapBatches | Self::Liquidity => {
+        ProtocolUpgrade::KeepsakeExtensions.activation_height(network)
+      }
+    }
+  }
+
+  pub fn is_active(self, network: Network, height: u32) -> bool {
+    height >= self.activation_height(network)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn activation() {
+    assert!(!KeepsakeExtension::SwapRoutes.is_active(Network::Bitcoin, 989_999));
+    assert!(KeepsakeExtension::SwapRoutes.is_active(Network::Bitcoin, 990_000));
+    assert!(KeepsakeExtension::SwapRoutes.is_active(Network::Regtest, 0));
+    assert!(!KeepsakeExtension::SwapBatches.is_active(Network::Signet, 299_999));
+    assert!(KeepsakeExtension::SwapBatches.is_active(Network::Signet, 300_000));
+    assert!(!KeepsakeExtension::Liquidity.is_active(Network::Testnet, 4_949_999));
+    assert!(KeepsakeExtension::Liquidity.is_active(Network::Regtest, 0));
+  }
+}
@@ -21,10 +21,13 @@ pub enum RelicFlaw {
   InvalidEnshriningBoostUltraRareAmountOverflow,
   InvalidEnshriningMaxSupplyCalculation,
   InvalidEnshriningSubsidyRules,
+  InvalidLiquidity,
   InvalidBaseTokenMint,
   InvalidBaseTokenUnmint,
   InvalidScript,
   InvalidSwap,
+  InvalidSwapRoute,
+  InvalidSwapRouteLength,
   Opcode,
   TrailingIntegers,
   TransferFlag,
@@ -58,6 +61,7 @@ impl Display for RelicFlaw {
       Self::InvalidEnshriningBoostUltraRareAmountOverflow => write!(f, "invalid enshrining: amount * ultra_rare_multiplier_cap calculation overflowed"),
       Self::InvalidEnshriningMaxSupplyCalculation => write!(f, "invalid enshrining: max supply calculation failed (check for overflows or invalid term/boost values)"),
       Self::InvalidEnshriningSubsidyRules => write!(f, "invalid enshrining: subsidy rules violated (subsidy requires zero price, or zero price requires subsidy)"),
+      Self::InvalidLiquidity => write!(f, "invalid liquidity: a Relic other than the base token is required"),
       Self::InvalidBaseTokenMint => write!(
         f,
         "invalid mint: to mint the base token eligible inscriptions must be burned"
@@ -65,6 +69,8 @@ impl Display for RelicFlaw {
       Self::InvalidBaseTokenUnmint => write!(f, "cannot unmint base token"),
       Self::InvalidScript => write!(f, "invalid script in OP_RETURN"),
       Self::InvalidSwap => write!(f, "invalid swap: input and output cannot be the same Relic"),
+      Self::InvalidSwapRoute => write!(f, "invalid swap route: Relics must not repeat or include the base token"),
+      Self::InvalidSwapRouteLength => write!(f, "invalid swap route: routes must not pass through more than {} Relics", Swap::MAX_HOPS),
       Self::Opcode => write!(f, "non-pushdata opcode in OP_RETURN"),
       Self::TrailingIntegers => write!(f, "trailing integers in body"),
       Self::TransferFlag => write!(f, "unrecognized flag in transfer"),
@@ -1,5 +1,8 @@
 use {super::*, flag::Flag, message::Message, tag::Tag};
 
+pub use explanation::{KeepsakeExplanation, KeepsakeField, KeepsakeFlag};
+
+mod explanation;
 mod flag;
 mod message;
 mod tag;
@@ -21,8 +24,12 @@ pub struct Keepsake {
   pub enshrining: Option<Enshrining>,
   /// multi mint (also unmint) given Relic
   pub mint: Option<MultiMint>,
-  /// execute token swap
-  pub swap: Option<Swap>,
+  /// execute token swaps, in order
+  pub swaps: Vec<Swap>,
+  /// add or remove liquidity of a Relic pool
+  pub liquidity: Option<Liquidity>,
+  /// allocation of pool shares to outputs, the id of a transfer is the Relic of the pool
+  pub share_transfers: Vec<Transfer>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -36,7 +43,31 @@ impl Keepsake {
   pub const MAGIC_NUMBER: opcodes::Opcode = opcodes::all::OP_PUSHNUM_15;
   pub const COMMIT_CONFIRMATIONS: u16 = 6;
 
+  /// Decipher `transaction` recognizing every [`KeepsakeExtension`] and checking formula prices for
+  /// [`PriceArithmetic::FixedPoint`], use [`Keepsake::decipher_at`] to decipher it with the rules of
+  /// a block
   pub fn decipher(transaction: &Transaction) -> Option<RelicArtifact> {
+    Self::decipher_with(transaction, |_| true, PriceArithmetic::FixedPoint)
+  }
+
+  /// Decipher `transaction` as included in a block at `height` on `network`
+  pub fn decipher_at(
+    transaction: &Transaction,
+    network: Network,
+    height: u32,
+  ) -> Option<RelicArtifact> {
+    Self::decipher_with(
+      transaction,
+      |extension| extension.is_active(network, height),
+      PriceArithmetic::at_height(network, height),
+    )
+  }
+
+  fn decipher_with(
+    transaction: &Transaction,
+    is_active: impl Fn(KeepsakeExtension) -> bool,
+    arithmetic: PriceArithmetic,
+  ) -> Option<RelicArtifact> {
     let payload = match Keepsake::payload(transaction) {
       Some(Payload::Valid(payload)) => payload,
       Some(Payload::Invalid(flaw)) => {
@@ -136,19 +167,105 @@ impl Keepsake {
       None
     };
 
-    let swap = Flag::Swap.take(&mut flags).then(|| Swap {
-      input: get_relic_id(Tag::SwapInput, &mut fields),
-      output: get_relic_id(Tag::SwapOutput, &mut fields),
-      input_amount: get_non_zero(Tag::SwapInputAmount, &mut fields),
-      output_amount: get_non_zero(Tag::SwapOutputAmount, &mut fields),
-      is_exact_input: Flag::SwapExactInput.take(&mut flags),
-    });
+    let mut swaps = Vec::new();
+
+    if Flag::Swap.take(&mut flags) {
+      swaps.push(Swap {
+        input: get_relic_id(Tag::SwapInput, &mut fields),
+        output: get_relic_id(Tag::SwapOutput, &mut fields),
+        input_amount: get_non_zero(Tag::SwapInputAmount, &mut fields),
+        output_amount: get_non_zero(Tag::SwapOutputAmount, &mut fields),
+        is_exact_input: Flag::SwapExactInput.take(&mut flags),
+        via: if is_active(KeepsakeExtension::SwapRoutes) {
+          std::iter::from_fn(|| get_relic_id(Tag::SwapVia, &mut fields)).collect()
+        } else {
+          Vec::new()
+        },
+      });
+    }
+
+    if is_active(KeepsakeExtension::SwapBatches) {
+      // additional swaps of a batch are encoded as fixed size tuples, zero values mean "not set"
+      let batch_start = swaps.len();
+      while let Some(swap) = Tag::SwapBatch.take(
+        &mut fields,
+        |[input_block, input_tx, output_block, output_tx, input_amount, output_amount, is_exact_input]| {
+          let relic_id = |block: u128, tx: u128| {
+            let id = RelicId::new(block.try_into().ok()?, tx.try_into().ok()?)?;
+            Some((id != RelicId::default()).then_some(id))
+          };
+          Some(Swap {
+            input: relic_id(input_block, input_tx)?,
+            output: relic_id(output_block, output_tx)?,
+            input_amount: (input_amount > 0).then_some(input_amount),
+            output_amount: (output_amount > 0).then_some(output_amount),
+            is_exact_input: match is_exact_input {
+              0 => false,
+              1 => true,
+              _ => return None,
+            },
+            via: Vec::new(),
+          })
+        },
+      ) {
+        swaps.push(swap);
+      }
+
+      // routes of batched swaps reference the swap by its index within the batch
+      if is_active(KeepsakeExtension::SwapRoutes) {
+        while let Some((index, id)) = Tag::SwapBatchVia.take(&mut fields, |[index, block, tx]| {
+          let index = batch_start.checked_add(usize::try_from(index).ok()?)?;
+          (index < swaps.len()).then_some(())?;
+          Some((
+            index,
+            RelicId::new(block.try_into().ok()?, tx.try_into().ok()?)?,
+          ))
+        }) {
+          swaps[index].via.push(id);
+        }
+      }
+    }
+
+    let liquidity = (is_active(KeepsakeExtension::Liquidity) && Flag::Liquidity.take(&mut flags))
+      .then(|| Liquidity {
+        // a missing Relic is caught by the validation below
+        relic: get_relic_id(Tag::LiquidityRelic, &mut fields).unwrap_or_default(),
+        base_amount: Tag::LiquidityBaseAmount
+          .take(&mut fields, |[val]| Some(val))
+          .unwrap_or_default(),
+        quote_amount: Tag::LiquidityQuoteAmount
+          .take(&mut fields, |[val]| Some(val))
+          .unwrap_or_default(),
+        shares: Tag::LiquidityShares
+          .take(&mut fields, |[val]| Some(val))
+          .unwrap_or_default(),
+        is_remove: Tag::LiquidityIsRemove
+          .take(&mut fields, |[val]| Some(val != 0))
+          .unwrap_or(false),
+      });
+
+    let mut share_transfers = Vec::new();
+
+    if is_active(KeepsakeExtension::Liquidity) {
+      // shares of the pool of a Relic, the base token and the default id have no pool
+      while let Some(transfer) =
+        Tag::ShareTransfer.take(&mut fields, |[block, tx, amount, output]| {
+          let id = RelicId::new(block.try_into().ok()?, tx.try_into().ok()?)?;
+          if id == RELIC_ID || id == RelicId::default() {
+            return None;
+          }
+          Transfer::from_integers(transaction, id, amount, output)
+        })
+      {
+        share_transfers.push(transfer);
+      }
+    }
 
     let pointer = get_output_option(Tag::Pointer, &mut fields);
     let claim = get_output_option(Tag::Claim, &mut fields);
 
     if let Some(enshrining) = &enshrining {
-      if let Err(err) = enshrining.validate() {
+      if let Err(err) = enshrining.validate(arithmetic) {
         flaw.get_or_insert(err);
       }
     }
@@ -162,12 +279,24 @@ impl Keepsake {
       flaw.get_or_insert(RelicFlaw::InvalidBaseTokenMint);
     }
 
-    // make sure to not swap from and to the same token
-    if swap
-      .map(|swap| swap.input.unwrap_or(RELIC_ID) == swap.output.unwrap_or(RELIC_ID))
-      .unwrap_or_default()
+    // liquidity can only be provided to the pools of Relics, the base token has no pool
+    if liquidity
+      .as_ref()
+      .map(|liquidity| liquidity.relic == RELIC_ID || liquidity.relic == RelicId::default())
+      .unwrap_or(false)
     {
-      flaw.get_or_insert(RelicFlaw::InvalidSwap);
+      flaw.get_or_insert(RelicFlaw::InvalidLiquidity);
+    }
+
+    for swap in &swaps {
+      // make sure to not swap from and to the same token
+      if swap.input.unwrap_or(RELIC_ID) == swap.output.unwrap_or(RELIC_ID) {
+        flaw.get_or_insert(RelicFlaw::InvalidSwap);
+      } else if swap.via.len() > Swap::MAX_HOPS {
+        flaw.get_or_insert(RelicFlaw::InvalidSwapRouteLength);
+      } else if !swap.is_valid() {
+        flaw.get_or_insert(RelicFlaw::InvalidSwapRoute);
+      }
     }
 
     if flags != 0 {
@@ -189,7 +318,9 @@ impl Keepsake {
       sealing,
       enshrining,
       mint: multi_mint,
-      swap,
+      swaps,
+      liquidity,
+      share_transfers,
     }))
   }
 
@@ -254,7 +385,7 @@ impl Keepsake {
       );
     }
 
-    if let Some(swap) = &self.swap {
+    if let Some(swap) = self.swaps.first() {
       Flag::Swap.set(&mut flags);
 
       if swap.is_exact_input {
@@ -269,6 +400,50 @@ impl Keepsake {
       }
       Tag::SwapInputAmount.encode_option(swap.input_amount, &mut payload);
       Tag::SwapOutputAmount.encode_option(swap.output_amount, &mut payload);
+      for RelicId { block, tx } in &swap.via {
+        Tag::SwapVia.encode([(*block).into(), (*tx).into()], &mut payload);
+      }
+    }
+
+    for (index, swap) in self.swaps.iter().skip(1).enumerate() {
+      let input = swap.input.unwrap_or_default();
+      let output = swap.output.unwrap_or_default();
+      Tag::SwapBatch.encode(
+        [
+          input.block.into(),
+          input.tx.into(),
+          output.block.into(),
+          output.tx.into(),
+          swap.input_amount.unwrap_or_default(),
+          swap.output_amount.unwrap_or_default(),
+          swap.is_exact_input.into(),
+        ],
+        &mut payload,
+      );
+      for RelicId { block, tx } in &swap.via {
+        Tag::SwapBatchVia.encode([index as u128, (*block).into(), (*tx).into()], &mut payload);
+      }
+    }
+
+    if let Some(liquidity) = self.liquidity {
+      Flag::Liquidity.set(&mut flags);
+      if liquidity.is_remove {
+        Tag::LiquidityIsRemove.encode([1], &mut payload);
+      }
+      Tag::LiquidityRelic.encode(
+        [liquidity.relic.block.into(), liquidity.relic.tx.into()],
+        &mut payload,
+      );
+      Tag::LiquidityBaseAmount.encode([liquidity.base_amount], &mut payload);
+      Tag::LiquidityQuoteAmount.encode([liquidity.quote_amount], &mut payload);
+      Tag::LiquidityShares.encode([liquidity.shares], &mut payload);
+    }
+
+    for Transfer { id, amount, output } in &self.share_transfers {
+      Tag::ShareTransfer.encode(
+        [id.block.into(), id.tx.into(), *amount, (*output).into()],
+        &mut payload,
+      );
     }
 
     if flags != 0 {
@@ -846,6 +1021,257 @@ mod tests {
     assert_eq!(decoded, RelicArtifact::Keepsake(unmint_keepsake));
   }
 
+  #[test]
+  fn liquidity_round_trip() {
+    for is_remove in [false, true] {
+      let keepsake = Keepsake {
+        liquidity: Some(Liquidity {
+          relic: relic_id(42),
+          base_amount: 1000,
+          quote_amount: 200,
+          shares: 300,
+          is_remove,
+        }),
+        ..default()
+      };
+
+      let transaction = Transaction {
+        input: Vec::new(),
+        output: vec![TxOut {
+          script_pubkey: keepsake.encipher(),
+          value: Amount::ZERO,
+        }],
+        lock_time: LockTime::ZERO,
+        version: Version(2),
+      };
+
+      assert_eq!(
+        Keepsake::decipher(&transaction).unwrap(),
+        RelicArtifact::Keepsake(keepsake),
+      );
+    }
+  }
+
+  #[test]
+  fn decipher_liquidity() {
+    assert_eq!(
+      decipher(&[
+        Tag::Flags.into(),
+        Flag::Liquidity.mask(),
+        Tag::LiquidityRelic.into(),
+        1,
+        Tag::LiquidityRelic.into(),
+        2,
+        Tag::LiquidityShares.into(),
+        5,
+        Tag::LiquidityIsRemove.into(),
+        1,
+      ]),
+      RelicArtifact::Keepsake(Keepsake {
+        liquidity: Some(Liquidity {
+          relic: relic_id(2),
+          shares: 5,
+          is_remove: true,
+          ..default()
+        }),
+        ..default()
+      }),
+    );
+  }
+
+  #[test]
+  fn invalid_liquidity_produces_cenotaph() {
+    // missing Relic
+    assert_eq!(
+      decipher(&[
+        Tag::Flags.into(),
+        Flag::Liquidity.mask(),
+        Tag::LiquidityBaseAmount.into(),
+        5
+      ]),
+      RelicArtifact::Cenotaph(RelicCenotaph {
+        flaw: Some(RelicFlaw::InvalidLiquidity),
+      }),
+    );
+    // base token
+    assert_eq!(
+      decipher(&[
+        Tag::Flags.into(),
+        Flag::Liquidity.mask(),
+        Tag::LiquidityRelic.into(),
+        1,
+        Tag::LiquidityRelic.into(),
+        0,
+      ]),
+      RelicArtifact::Cenotaph(RelicCenotaph {
+        flaw: Some(RelicFlaw::InvalidLiquidity),
+      }),
+    );
+    // liquidity fields without flag
+    assert_eq!(
+      decipher(&[Tag::LiquidityShares.into(), 5]),
+      RelicArtifact::Cenotaph(RelicCenotaph {
+        flaw: Some(RelicFlaw::UnrecognizedEvenTag),
+      }),
+    );
+  }
+
+  #[test]
+  fn liquidity_before_activation_is_cenotaph() {
+    let keepsake = Keepsake {
+      liquidity: Some(Liquidity {
+        relic: relic_id(42),
+        base_amount: 1000,
+        quote_amount: 200,
+        ..default()
+      }),
+      ..default()
+    };
+
+    let transaction = Transaction {
+      input: Vec::new(),
+      output: vec![TxOut {
+        script_pubkey: keepsake.encipher(),
+        value: Amount::ZERO,
+      }],
+      lock_time: LockTime::ZERO,
+      version: Version(2),
+    };
+
+    let activation_height = KeepsakeExtension::Liquidity.activation_height(Network::Bitcoin);
+
+    assert_eq!(
+      Keepsake::decipher_at(&transaction, Network::Bitcoin, activation_height - 1).unwrap(),
+      RelicArtifact::Cenotaph(RelicCenotaph {
+        flaw: Some(RelicFlaw::UnrecognizedFlag),
+      }),
+    );
+
+    assert_eq!(
+      Keepsake::decipher_at(&transaction, Network::Bitcoin, activation_height).unwrap(),
+      RelicArtifact::Keepsake(keepsake),
+    );
+  }
+
+  #[test]
+  fn share_transfers_round_trip() {
+    let keepsake = Keepsake {
+      share_transfers: vec![
+        Transfer {
+          id: relic_id(42),
+          amount: 100,
+          output: 1,
+        },
+        Transfer {
+          id: relic_id(43),
+          amount: 0,
+          output: 2,
+        },
+      ],
+      ..default()
+    };
+
+    let transaction = Transaction {
+      input: Vec::new(),
+      output: vec![
+        TxOut {
+          script_pubkey: keepsake.encipher(),
+          value: Amount::ZERO,
+        },
+        TxOut {
+          script_pubkey: ScriptBuf::new(),
+          value: Amount::ZERO,
+        },
+      ],
+      lock_time: LockTime::ZERO,
+      version: Version(2),
+    };
+
+    assert_eq!(
+      Keepsake::decipher(&transaction).unwrap(),
+      RelicArtifact::Keepsake(keepsake),
+    );
+  }
+
+  #[test]
+  fn invalid_share_transfers_produce_cenotaph() {
+    // output out of range
+    assert_eq!(
+      decipher(&[
+        Tag::ShareTransfer.into(),
+        1,
+        Tag::ShareTransfer.into(),
+        42,
+        Tag::ShareTransfer.into(),
+        100,
+        Tag::ShareTransfer.into(),
+        2,
+      ]),
+      RelicArtifact::Cenotaph(RelicCenotaph {
+        flaw: Some(RelicFlaw::UnrecognizedEvenTag),
+      }),
+    );
+    // the base token has no pool
+    assert_eq!(
+      decipher(&[
+        Tag::ShareTransfer.into(),
+        1,
+        Tag::ShareTransfer.into(),
+        0,
+        Tag::ShareTransfer.into(),
+        100,
+        Tag::ShareTransfer.into(),
+        1,
+      ]),
+      RelicArtifact::Cenotaph(RelicCenotaph {
+        flaw: Some(RelicFlaw::UnrecognizedEvenTag),
+      }),
+    );
+    // incomplete transfer
+    assert_eq!(
+      decipher(&[Tag::ShareTransfer.into(), 1, Tag::ShareTransfer.into(), 42]),
+      RelicArtifact::Cenotaph(RelicCenotaph {
+        flaw: Some(RelicFlaw::UnrecognizedEvenTag),
+      }),
+    );
+  }
+
+  #[test]
+  fn share_transfers_before_activation_are_cenotaph() {
+    let keepsake = Keepsake {
+      share_transfers: vec![Transfer {
+        id: relic_id(42),
+        amount: 100,
+        output: 0,
+      }],
+      ..default()
+    };
+
+    let transaction = Transaction {
+      input: Vec::new(),
+      output: vec![TxOut {
+        script_pubkey: keepsake.encipher(),
+        value: Amount::ZERO,
+      }],
+      lock_time: LockTime::ZERO,
+      version: Version(2),
+    };
+
+    let activation_height = KeepsakeExtension::Liquidity.activation_height(Network::Bitcoin);
+
+    assert_eq!(
+      Keepsake::decipher_at(&transaction, Network::Bitcoin, activation_height - 1).unwrap(),
+      RelicArtifact::Cenotaph(RelicCenotaph {
+        flaw: Some(RelicFlaw::UnrecognizedEvenTag),
+      }),
+    );
+
+    assert_eq!(
+      Keepsake::decipher_at(&transaction, Network::Bitcoin, activation_height).unwrap(),
+      RelicArtifact::Keepsake(keepsake),
+    );
+  }
+
   #[test]
   fn invalid_boost_term_multipliers_create_cenotaph() {
     let invalid_multipliers = BoostTerms {
@@ -1437,15 +1863,18 @@ mod tests {
           is_unmint: false,
           relic: relic_id_with_block(5, 0),
         }),
-        swap: Some(Swap {
+        swaps: vec![Swap {
           input: Some(relic_id(42)),
           output: Some(relic_id(43)),
           input_amount: Some(123),
           output_amount: Some(456),
           is_exact_input: true,
-        }),
+          via: Vec::new(),
+        }],
         pointer: Some(0),
         claim: Some(0),
+        liquidity: None,
+        share_transfers: Vec::new(),
       }),
     );
   }
@@ -1783,15 +2212,18 @@ mod tests {
           is_unmint: false,
           relic: relic_id(5),
         }),
-        swap: Some(Swap {
+        swaps: vec![Swap {
           input: Some(relic_id(42)),
           output: Some(relic_id(43)),
           input_amount: Some(123),
           output_amount: Some(456),
           is_exact_input: true,
-        }),
+          via: Vec::new(),
+        }],
         pointer: Some(0),
         claim: Some(0),
+        liquidity: None,
+        share_transfers: Vec::new(),
       },
       &[
         Tag::Symbol.into(),
@@ -1911,6 +2343,437 @@ mod tests {
     );
   }
 
+  #[test]
+  fn decipher_swap_route() {
+    assert_eq!(
+      decipher(&[
+        Tag::Flags.into(),
+        Flag::Swap.mask() | Flag::SwapExactInput.mask(),
+        Tag::SwapInput.into(),
+        1,
+        Tag::SwapInput.into(),
+        1,
+        Tag::SwapOutput.into(),
+        1,
+        Tag::SwapOutput.into(),
+        3,
+        Tag::SwapInputAmount.into(),
+        100,
+        Tag::SwapOutputAmount.into(),
+        50,
+        Tag::SwapVia.into(),
+        1,
+        Tag::SwapVia.into(),
+        2,
+        Tag::SwapVia.into(),
+        2,
+        Tag::SwapVia.into(),
+        0,
+      ]),
+      RelicArtifact::Keepsake(Keepsake {
+        swaps: vec![Swap {
+          input: Some(relic_id(1)),
+          output: Some(relic_id(3)),
+          input_amount: Some(100),
+          output_amount: Some(50),
+          is_exact_input: true,
+          via: vec![relic_id(2), relic_id_with_block(2, 0)],
+        }],
+        ..default()
+      }),
+    );
+  }
+
+  #[test]
+  fn swap_route_before_activation_is_cenotaph() {
+    let keepsake = Keepsake {
+      swaps: vec![Swap {
+        input: Some(relic_id(1)),
+        output: Some(relic_id(3)),
+        input_amount: Some(100),
+        is_exact_input: true,
+        via: vec![relic_id(2)],
+        ..default()
+      }],
+      ..default()
+    };
+
+    let transaction = Transaction {
+      input: Vec::new(),
+      output: vec![TxOut {
+        script_pubkey: keepsake.encipher(),
+        value: Amount::ZERO,
+      }],
+      lock_time: LockTime::ZERO,
+      version: Version(2),
+    };
+
+    let activation_height = KeepsakeExtension::SwapRoutes.activation_height(Network::Bitcoin);
+
+    assert_eq!(
+      Keepsake::decipher_at(&transaction, Network::Bitcoin, activation_height - 1).unwrap(),
+      RelicArtifact::Cenotaph(RelicCenotaph {
+        flaw: Some(RelicFlaw::UnrecognizedEvenTag),
+      }),
+    );
+
+    assert_eq!(
+      Keepsake::decipher_at(&transaction, Network::Bitcoin, activation_height).unwrap(),
+      RelicArtifact::Keepsake(keepsake),
+    );
+  }
+
+  #[test]
+  fn swap_via_without_swap_flag_produces_cenotaph() {
+    assert_eq!(
+      decipher(&[Tag::SwapVia.into(), 1, Tag::SwapVia.into(), 2]),
+      RelicArtifact::Cenotaph(RelicCenotaph {
+        flaw: Some(RelicFlaw::UnrecognizedEvenTag),
+      }),
+    );
+  }
+
+  #[test]
+  fn truncated_swap_via_produces_cenotaph() {
+    assert_eq!(
+      decipher(&[
+        Tag::Flags.into(),
+        Flag::Swap.mask(),
+        Tag::SwapInput.into(),
+        1,
+        Tag::SwapInput.into(),
+        1,
+        Tag::SwapVia.into(),
+        1,
+      ]),
+      RelicArtifact::Cenotaph(RelicCenotaph {
+        flaw: Some(RelicFlaw::UnrecognizedEvenTag),
+      }),
+    );
+  }
+
+  #[test]
+  fn invalid_swap_route_produces_cenotaph() {
+    #[track_caller]
+    fn case(via: [u128; 2]) {
+      assert_eq!(
+        decipher(&[
+          Tag::Flags.into(),
+          Flag::Swap.mask(),
+          Tag::SwapInput.into(),
+          1,
+          Tag::SwapInput.into(),
+          1,
+          Tag::SwapVia.into(),
+          via[0],
+          Tag::SwapVia.into(),
+          via[1],
+        ]),
+        RelicArtifact::Cenotaph(RelicCenotaph {
+          flaw: Some(RelicFlaw::InvalidSwapRoute),
+        }),
+      );
+    }
+
+    // route through the base token
+    case([1, 0]);
+    // route through the input Relic
+    case([1, 1]);
+  }
+
+  #[test]
+  fn swap_route_longer_than_max_hops_produces_cenotaph() {
+    fn integers(hops: u32) -> Vec<u128> {
+      let mut integers = vec![
+        Tag::Flags.into(),
+        Flag::Swap.mask(),
+        Tag::SwapInput.into(),
+        2,
+      ];
+      integers.extend([Tag::SwapInput.into(), 1]);
+      for tx in 2..hops + 2 {
+        integers.extend([Tag::SwapVia.into(), 2, Tag::SwapVia.into(), tx.into()]);
+      }
+      integers
+    }
+
+    let RelicArtifact::Keepsake(keepsake) = decipher(&integers(Swap::MAX_HOPS as u32)) else {
+      panic!("route of the maximum length should be valid");
+    };
+    assert_eq!(keepsake.swaps[0].via.len(), Swap::MAX_HOPS);
+
+    assert_eq!(
+      decipher(&integers(Swap::MAX_HOPS as u32 + 1)),
+      RelicArtifact::Cenotaph(RelicCenotaph {
+        flaw: Some(RelicFlaw::InvalidSwapRouteLength),
+      }),
+    );
+  }
+
+  #[test]
+  fn swap_route_round_trip() {
+    let keepsake = Keepsake {
+      swaps: vec![Swap {
+        input: Some(relic_id(1)),
+        output: None,
+        input_amount: None,
+        output_amount: Some(500),
+        is_exact_input: false,
+        via: vec![relic_id(7), relic_id_with_block(3, 2)],
+      }],
+      ..default()
+    };
+
+    let transaction = Transaction {
+      input: Vec::new(),
+      output: vec![TxOut {
+        script_pubkey: keepsake.encipher(),
+        value: Amount::ZERO,
+      }],
+      lock_time: LockTime::ZERO,
+      version: Version(2),
+    };
+
+    assert_eq!(
+      Keepsake::decipher(&transaction).unwrap(),
+      RelicArtifact::Keepsake(keepsake),
+    );
+  }
+
+  #[test]
+  fn decipher_swap_batch() {
+    assert_eq!(
+      decipher(&[
+        Tag::Flags.into(),
+        Flag::Swap.mask(),
+        Tag::SwapOutput.into(),
+        1,
+        Tag::SwapOutput.into(),
+        1,
+        Tag::SwapOutputAmount.into(),
+        10,
+        Tag::SwapBatch.into(),
+        1,
+        Tag::SwapBatch.into(),
+        2,
+        Tag::SwapBatch.into(),
+        0,
+        Tag::SwapBatch.into(),
+        0,
+        Tag::SwapBatch.into(),
+        20,
+        Tag::SwapBatch.into(),
+        0,
+        Tag::SwapBatch.into(),
+        1,
+        Tag::SwapBatch.into(),
+        0,
+        Tag::SwapBatch.into(),
+        0,
+        Tag::SwapBatch.into(),
+        1,
+        Tag::SwapBatch.into(),
+        3,
+        Tag::SwapBatch.into(),
+        0,
+        Tag::SwapBatch.into(),
+        30,
+        Tag::SwapBatch.into(),
+        0,
+        Tag::SwapBatchVia.into(),
+        1,
+        Tag::SwapBatchVia.into(),
+        1,
+        Tag::SwapBatchVia.into(),
+        4,
+      ]),
+      RelicArtifact::Keepsake(Keepsake {
+        swaps: vec![
+          Swap {
+            output: Some(relic_id(1)),
+            output_amount: Some(10),
+            ..default()
+          },
+          Swap {
+            input: Some(relic_id(2)),
+            input_amount: Some(20),
+            is_exact_input: true,
+            ..default()
+          },
+          Swap {
+            output: Some(relic_id(3)),
+            output_amount: Some(30),
+            via: vec![relic_id(4)],
+            ..default()
+          },
+        ],
+        ..default()
+      }),
+    );
+  }
+
+  #[test]
+  fn swap_batch_without_swap_flag_is_valid() {
+    assert_eq!(
+      decipher(&[
+        Tag::SwapBatch.into(),
+        1,
+        Tag::SwapBatch.into(),
+        2,
+        Tag::SwapBatch.into(),
+        0,
+        Tag::SwapBatch.into(),
+        0,
+        Tag::SwapBatch.into(),
+        20,
+        Tag::SwapBatch.into(),
+        0,
+        Tag::SwapBatch.into(),
+        1,
+      ]),
+      RelicArtifact::Keepsake(Keepsake {
+        swaps: vec![Swap {
+          input: Some(relic_id(2)),
+          input_amount: Some(20),
+          is_exact_input: true,
+          ..default()
+        }],
+        ..default()
+      }),
+    );
+  }
+
+  #[test]
+  fn swap_batch_before_activation_is_cenotaph() {
+    let keepsake = Keepsake {
+      swaps: vec![
+        Swap {
+          output: Some(relic_id(1)),
+          output_amount: Some(10),
+          ..default()
+        },
+        Swap {
+          input: Some(relic_id(2)),
+          input_amount: Some(20),
+          is_exact_input: true,
+          ..default()
+        },
+      ],
+      ..default()
+    };
+
+    let transaction = Transaction {
+      input: Vec::new(),
+      output: vec![TxOut {
+        script_pubkey: keepsake.encipher(),
+        value: Amount::ZERO,
+      }],
+      lock_time: LockTime::ZERO,
+      version: Version(2),
+    };
+
+    let activation_height = KeepsakeExtension::SwapBatches.activation_height(Network::Bitcoin);
+
+    assert_eq!(
+      Keepsake::decipher_at(&transaction, Network::Bitcoin, activation_height - 1).unwrap(),
+      RelicArtifact::Cenotaph(RelicCenotaph {
+        flaw: Some(RelicFlaw::UnrecognizedEvenTag),
+      }),
+    );
+
+    assert_eq!(
+      Keepsake::decipher_at(&transaction, Network::Bitcoin, activation_height).unwrap(),
+      RelicArtifact::Keepsake(keepsake),
+    );
+  }
+
+  #[test]
+  fn invalid_swap_batch_produces_cenotaph() {
+    #[track_caller]
+    fn case(integers: &[u128], flaw: RelicFlaw) {
+      assert_eq!(
+        decipher(integers),
+        RelicArtifact::Cenotaph(RelicCenotaph { flaw: Some(flaw) }),
+      );
+    }
+
+    let swap = |is_exact_input| {
+      [1, 2, 0, 0, 20, 0, is_exact_input]
+        .into_iter()
+        .flat_map(|value| [Tag::SwapBatch.into(), value])
+        .collect::<Vec<u128>>()
+    };
+
+    // exact input flag must be 0 or 1
+    case(&swap(2), RelicFlaw::UnrecognizedEvenTag);
+
+    // truncated tuple
+    case(&swap(1)[..12], RelicFlaw::UnrecognizedEvenTag);
+
+    // route of a swap that is not part of the batch
+    let mut integers = swap(1);
+    integers.extend([
+      Tag::SwapBatchVia.into(),
+      1,
+      Tag::SwapBatchVia.into(),
+      1,
+      Tag::SwapBatchVia.into(),
+      4,
+    ]);
+    case(&integers, RelicFlaw::UnrecognizedEvenTag);
+
+    // input and output of a batched swap must differ
+    let mut integers = swap(1);
+    integers[1] = 0;
+    integers[3] = 0;
+    case(&integers, RelicFlaw::InvalidSwap);
+  }
+
+  #[test]
+  fn swap_batch_round_trip() {
+    let keepsake = Keepsake {
+      swaps: vec![
+        Swap {
+          input: Some(relic_id(1)),
+          input_amount: Some(100),
+          is_exact_input: true,
+          ..default()
+        },
+        Swap {
+          output: Some(relic_id(2)),
+          output_amount: Some(200),
+          input_amount: Some(300),
+          via: vec![relic_id(5), relic_id(6)],
+          ..default()
+        },
+        Swap {
+          input: Some(relic_id(3)),
+          output: Some(relic_id(4)),
+          input_amount: Some(400),
+          is_exact_input: true,
+          via: vec![relic_id(7)],
+          ..default()
+        },
+      ],
+      ..default()
+    };
+
+    let transaction = Transaction {
+      input: Vec::new(),
+      output: vec![TxOut {
+        script_pubkey: keepsake.encipher(),
+        value: Amount::ZERO,
+      }],
+      lock_time: LockTime::ZERO,
+      version: Version(2),
+    };
+
+    assert_eq!(
+      Keepsake::decipher(&transaction).unwrap(),
+      RelicArtifact::Keepsake(keepsake),
+    );
+  }
+
   // #[test]
   // fn invalid_deadline_produces_cenotaph() {
   //   assert_eq!(
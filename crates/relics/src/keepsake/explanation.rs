@@ -0,0 +1,225 @@
+use super::*;
+
+/// Field by field breakdown of the Keepsake of a transaction, to debug its encoding
+#[derive(Default, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct KeepsakeExplanation {
+  /// tag/value pairs preceding the body, in payload order
+  pub fields: Vec<KeepsakeField>,
+  /// flags set in the first flags field
+  pub flags: Vec<KeepsakeFlag>,
+  /// transfers in the body, up to the first invalid one
+  pub transfers: Vec<Transfer>,
+  /// flaw that turned the Keepsake into a cenotaph
+  pub flaw: Option<RelicFlaw>,
+  /// description of the flaw
+  pub reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct KeepsakeField {
+  pub tag: u128,
+  /// name of the tag, unrecognized tags have none
+  pub name: Option<String>,
+  pub value: u128,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct KeepsakeFlag {
+  pub bit: u8,
+  /// name of the flag, unrecognized flags have none
+  pub name: Option<String>,
+}
+
+impl Keepsake {
+  /// Explain the Keepsake of a transaction, `None` if it has none
+  pub fn explain(transaction: &Transaction) -> Option<KeepsakeExplanation> {
+    let flaw = match Keepsake::decipher(transaction)? {
+      RelicArtifact::Cenotaph(cenotaph) => cenotaph.flaw,
+      RelicArtifact::Keepsake(_) => None,
+    };
+
+    let reason = flaw.map(|flaw| flaw.to_string());
+
+    // payloads with an invalid script or varint have no fields to show
+    let Some(Payload::Valid(payload)) = Keepsake::payload(transaction) else {
+      return Some(KeepsakeExplanation {
+        flaw,
+        reason,
+        ..Default::default()
+      });
+    };
+    let integers = Keepsake::integers(&payload).unwrap_or_default();
+
+    let fields = integers
+      .chunks(2)
+      .take_while(|pair| Tag::Body != pair[0])
+      .filter_map(|pair| {
+        Some(KeepsakeField {
+          tag: pair[0],
+          name: Tag::from_u128(pair[0]).map(|tag| format!("{tag:?}")),
+          value: *pair.get(1)?,
+        })
+      })
+      .collect::<Vec<KeepsakeField>>();
+
+    let flags = fields
+      .iter()
+      .find(|field| Tag::Flags == field.tag)
+      .map(|field| {
+        (0..128)
+          .filter(|bit| field.value & 1 << bit != 0)
+          .map(|bit| KeepsakeFlag {
+            bit,
+            name: Flag::from_bit(bit).map(|flag| format!("{flag:?}")),
+          })
+          .collect()
+      })
+      .unwrap_or_default();
+
+    Some(KeepsakeExplanation {
+      fields,
+      flags,
+      transfers: Message::from_integers(transaction, &integers).transfers,
+      flaw,
+      reason,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    bitcoin::{absolute::LockTime, transaction::Version, Amount, TxOut},
+    pretty_assertions::assert_eq,
+  };
+
+  fn transaction(script_pubkey: ScriptBuf) -> Transaction {
+    Transaction {
+      version: Version(2),
+      lock_time: LockTime::ZERO,
+      input: Vec::new(),
+      output: vec![
+        TxOut {
+          script_pubkey,
+          value: Amount::ZERO,
+        },
+        TxOut {
+          script_pubkey: ScriptBuf::new(),
+          value: Amount::ZERO,
+        },
+      ],
+    }
+  }
+
+  #[test]
+  fn no_keepsake() {
+    assert_eq!(Keepsake::explain(&transaction(ScriptBuf::new())), None);
+  }
+
+  #[test]
+  fn explain_keepsake() {
+    let keepsake = Keepsake {
+      transfers: vec![Transfer {
+        id: RelicId { block: 5, tx: 1 },
+        amount: 100,
+        output: 1,
+      }],
+      mint: Some(MultiMint {
+        count: 2,
+        base_limit: 1000,
+        is_unmint: false,
+        relic: RelicId { block: 5, tx: 1 },
+      }),
+      pointer: Some(1),
+      ..Default::default()
+    };
+
+    let field = |tag: u128, name: &str, value: u128| KeepsakeField {
+      tag,
+      name: Some(name.into()),
+      value,
+    };
+
+    assert_eq!(
+      Keepsake::explain(&transaction(keepsake.encipher())).unwrap(),
+      KeepsakeExplanation {
+        fields: vec![
+          field(80, "MultiMintCount", 2),
+          field(82, "MultiMintBaseLimit", 1000),
+          field(84, "MultiMintRelic", 5),
+          field(84, "MultiMintRelic", 1),
+          field(2, "Flags", 1 << 6),
+          field(4, "Pointer", 1),
+        ],
+        flags: vec![KeepsakeFlag {
+          bit: 6,
+          name: Some("MultiMint".into()),
+        }],
+        transfers: keepsake.transfers,
+        flaw: None,
+        reason: None,
+      }
+    );
+  }
+
+  #[test]
+  fn explain_cenotaph() {
+    let mut payload = Vec::new();
+    for integer in [2, 1 << 9 | 1 << 1, 20, 7] {
+      varint::encode_to_vec(integer, &mut payload);
+    }
+    let push: &script::PushBytes = payload.as_slice().try_into().unwrap();
+
+    let explanation = Keepsake::explain(&transaction(
+      script::Builder::new()
+        .push_opcode(opcodes::all::OP_RETURN)
+        .push_opcode(Keepsake::MAGIC_NUMBER)
+        .push_slice(push)
+        .into_script(),
+    ))
+    .unwrap();
+
+    assert_eq!(
+      explanation.fields[1],
+      KeepsakeField {
+        tag: 20,
+        name: None,
+        value: 7,
+      }
+    );
+    assert_eq!(
+      explanation.flags,
+      [
+        KeepsakeFlag {
+          bit: 1,
+          name: Some("Sealing".into()),
+        },
+        KeepsakeFlag { bit: 9, name: None },
+      ]
+    );
+    assert_eq!(explanation.flaw, Some(RelicFlaw::UnrecognizedFlag));
+    assert_eq!(explanation.reason.unwrap(), "unrecognized field");
+  }
+
+  #[test]
+  fn explain_invalid_script() {
+    let explanation = Keepsake::explain(&transaction(
+      script::Builder::new()
+        .push_opcode(opcodes::all::OP_RETURN)
+        .push_opcode(Keepsake::MAGIC_NUMBER)
+        .push_opcode(opcodes::all::OP_VERIFY)
+        .into_script(),
+    ))
+    .unwrap();
+
+    assert_eq!(
+      explanation,
+      KeepsakeExplanation {
+        flaw: Some(RelicFlaw::Opcode),
+        reason: Some("non-pushdata opcode in OP_RETURN".into()),
+        ..Default::default()
+      }
+    );
+  }
+}
@@ -1,3 +1,4 @@
+#[derive(Copy, Clone, Debug)]
 pub(super) enum Flag {
   // Commitment = 0,
   Sealing = 1,
@@ -7,11 +8,28 @@ pub(super) enum Flag {
   SwapExactInput = 5,
   MultiMint = 6,
   BoostTerms = 7,
+  Liquidity = 8,
   #[allow(unused)]
   Cenotaph = 127,
 }
 
 impl Flag {
+  const ALL: [Self; 9] = [
+    Self::Sealing,
+    Self::Enshrining,
+    Self::MintTerms,
+    Self::Swap,
+    Self::SwapExactInput,
+    Self::MultiMint,
+    Self::BoostTerms,
+    Self::Liquidity,
+    Self::Cenotaph,
+  ];
+
+  pub(super) fn from_bit(bit: u8) -> Option<Self> {
+    Self::ALL.into_iter().find(|flag| *flag as u8 == bit)
+  }
+
   pub(super) fn mask(self) -> u128 {
     1 << self as u128
   }
@@ -44,6 +62,13 @@ mod tests {
     assert_eq!(Flag::Cenotaph.mask(), 1 << 127);
   }
 
+  #[test]
+  fn from_bit() {
+    assert!(matches!(Flag::from_bit(2), Some(Flag::Enshrining)));
+    assert!(matches!(Flag::from_bit(127), Some(Flag::Cenotaph)));
+    assert!(Flag::from_bit(0).is_none());
+  }
+
   #[test]
   fn take() {
     let mut flags = 4;
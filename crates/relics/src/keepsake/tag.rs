@@ -33,6 +33,16 @@ pub(super) enum Tag {
   SwapOutput = 92,
   SwapInputAmount = 94,
   SwapOutputAmount = 96,
+  SwapVia = 100,
+  SwapBatch = 102,
+  SwapBatchVia = 104,
+  // Liquidity
+  LiquidityRelic = 106,
+  LiquidityBaseAmount = 108,
+  LiquidityQuoteAmount = 110,
+  LiquidityShares = 112,
+  LiquidityIsRemove = 114,
+  ShareTransfer = 116,
   // Subsidy
   Subsidy = 98,
 
@@ -47,6 +57,52 @@ pub(super) enum Tag {
 }
 
 impl Tag {
+  const ALL: [Self; 39] = [
+    Self::Body,
+    Self::Flags,
+    Self::Pointer,
+    Self::Claim,
+    Self::Seed,
+    Self::Amount,
+    Self::Cap,
+    Self::Price,
+    Self::PriceFormulaA,
+    Self::PriceFormulaB,
+    Self::MaxUnmints,
+    Self::BlockCap,
+    Self::TxCap,
+    Self::Fee,
+    Self::RareChance,
+    Self::RareMultiplierCap,
+    Self::UltraRareChance,
+    Self::UltraRareMultiplierCap,
+    Self::MultiMintCount,
+    Self::MultiMintBaseLimit,
+    Self::MultiMintRelic,
+    Self::MultiMintIsUnmint,
+    Self::SwapInput,
+    Self::SwapOutput,
+    Self::SwapInputAmount,
+    Self::SwapOutputAmount,
+    Self::SwapVia,
+    Self::SwapBatch,
+    Self::SwapBatchVia,
+    Self::LiquidityRelic,
+    Self::LiquidityBaseAmount,
+    Self::LiquidityQuoteAmount,
+    Self::LiquidityShares,
+    Self::LiquidityIsRemove,
+    Self::ShareTransfer,
+    Self::Subsidy,
+    Self::Cenotaph,
+    Self::Symbol,
+    Self::Nop,
+  ];
+
+  pub(super) fn from_u128(tag: u128) -> Option<Self> {
+    Self::ALL.into_iter().find(|known| *known == tag)
+  }
+
   pub(super) fn take<const N: usize, T>(
     self,
     fields: &mut HashMap<u128, VecDeque<u128>>,
@@ -107,6 +163,17 @@ mod tests {
     assert_eq!(2u128, Tag::Flags.into());
   }
 
+  #[test]
+  fn from_u128_known_tags() {
+    for tag in Tag::ALL {
+      assert_eq!(
+        u128::from(Tag::from_u128(tag.into()).unwrap()),
+        u128::from(tag)
+      );
+    }
+    assert!(Tag::from_u128(20).is_none());
+  }
+
   #[test]
   fn partial_eq() {
     assert_eq!(Tag::Body, 0);
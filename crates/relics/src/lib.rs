@@ -2,31 +2,43 @@ extern crate core;
 
 use {
   bitcoin::{
-    constants::MAX_SCRIPT_ELEMENT_SIZE, opcodes, script, script::Instruction, Network, ScriptBuf,
-    Transaction,
+    constants::MAX_SCRIPT_ELEMENT_SIZE, hashes::Hash, opcodes, script, script::Instruction,
+    Network, ScriptBuf, ScriptHash, Transaction, Txid,
   },
   ordinals::Rune,
-  serde::{Deserialize, Serialize},
+  serde::{de, Deserialize, Deserializer, Serialize},
   serde_with::{DeserializeFromStr, SerializeDisplay},
   std::{
-    collections::{HashMap, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
     fmt::{self, Debug, Display, Formatter},
+    mem,
     str::FromStr,
   },
 };
 
+#[cfg(feature = "schemars")]
+use schemars::JsonSchema;
+
 pub use {
   artifact::RelicArtifact,
+  boost::BoostRandomness,
   cenotaph::RelicCenotaph,
   enshrining::{BoostTerms, Enshrining, MintTerms, MultiMint, PriceModel},
+  entry::{RelicEntry, RelicOwner, RelicState},
+  error::RelicError,
+  extension::KeepsakeExtension,
   flaw::RelicFlaw,
-  keepsake::Keepsake,
+  keepsake::{Keepsake, KeepsakeExplanation, KeepsakeField, KeepsakeFlag},
+  liquidity::Liquidity,
   ordinals::{varint, RuneId as RelicId},
   pool::*,
+  pool_price::PoolPrice,
+  price::PriceArithmetic,
   relic::Relic,
   spaced_relic::SpacedRelic,
   swap::Swap,
   transfer::Transfer,
+  upgrade::ProtocolUpgrade,
 };
 
 macro_rules! ensure {
@@ -48,12 +60,22 @@ fn default<T: Default>() -> T {
 }
 
 mod artifact;
+mod boost;
 mod cenotaph;
 mod enshrining;
+mod entry;
+mod error;
+mod extension;
 mod flaw;
 mod keepsake;
+mod liquidity;
 mod pool;
+mod pool_price;
+mod price;
 mod relic;
+#[cfg(feature = "schemars")]
+pub mod schema;
 pub mod spaced_relic;
 mod swap;
 mod transfer;
+mod upgrade;
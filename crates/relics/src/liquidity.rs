@@ -0,0 +1,18 @@
+use super::*;
+
+/// Add liquidity to or remove liquidity from the pool of a Relic.
+///
+/// Liquidity is always added and removed in proportion to the current pool supplies.
+#[derive(Default, Serialize, Deserialize, Debug, PartialEq, Copy, Clone, Eq)]
+pub struct Liquidity {
+  /// The Relic whose pool to add liquidity to or remove liquidity from.
+  pub relic: RelicId,
+  /// When adding, the maximum base token to deposit; when removing, the minimum base token to receive.
+  pub base_amount: u128,
+  /// When adding, the maximum Relics to deposit; when removing, the minimum Relics to receive.
+  pub quote_amount: u128,
+  /// When adding, the minimum pool shares to receive; when removing, the pool shares to redeem.
+  pub shares: u128,
+  /// True if liquidity is removed, false if liquidity is added.
+  pub is_remove: bool,
+}
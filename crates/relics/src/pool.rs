@@ -3,6 +3,7 @@ use SwapDirection::{BaseToQuote, QuoteToBase};
 use super::*;
 
 #[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum SwapDirection {
   BaseToQuote,
   QuoteToBase,
@@ -42,10 +43,22 @@ pub struct BalanceDiff {
 }
 
 #[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct LiquidityDiff {
+  pub base_amount: u128,
+  pub quote_amount: u128,
+  pub shares: u128,
+  pub is_remove: bool,
+  /// part of `base_amount` paid into or out of the provider fees of the pool
+  pub provider_fees: u128,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub enum PoolError {
   Underflow,
   Overflow,
   Slippage,
+  ZeroShares,
 }
 
 impl Display for PoolError {
@@ -54,16 +67,26 @@ impl Display for PoolError {
       PoolError::Underflow => write!(f, "pool supply underflow"),
       PoolError::Overflow => write!(f, "pool supply overflow"),
       PoolError::Slippage => write!(f, "slippage over acceptable limit set by the user"),
+      PoolError::ZeroShares => write!(f, "liquidity too small to be worth a single pool share"),
     }
   }
 }
 
 #[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(JsonSchema))]
 pub struct Pool {
   pub base_supply: u128,
   pub quote_supply: u128,
   pub fee_bps: u16,
   pub subsidy: u128,
+  /// total pool shares, including the locked shares
+  pub shares: u128,
+  /// shares of the liquidity the pool was created with, these can never be redeemed
+  /// and the fees earned by them are paid to the owner of the Relic
+  pub locked_shares: u128,
+  /// fees earned by the shares that are not locked, kept apart from the supplies so they are not
+  /// shared with the locked shares, and paid out proportionally when liquidity is removed
+  pub provider_fees: u128,
 }
 
 impl Pool {
@@ -165,7 +188,10 @@ impl Pool {
     }
   }
 
-  pub fn apply(&mut self, diff: BalanceDiff) {
+  /// Apply a swap to the pool supplies. The part of the fee earned by liquidity providers is added
+  /// to the provider fees, the remaining fee is returned and belongs to the owner of the Relic.
+  pub fn apply(&mut self, diff: BalanceDiff) -> u128 {
+    let provider_fee = self.provider_fee(diff.fee);
     match diff.direction {
       BaseToQuote => {
         self.base_supply += diff.input - diff.fee;
@@ -176,6 +202,142 @@ impl Pool {
         self.base_supply -= diff.output + diff.fee;
       }
     }
+    self.provider_fees += provider_fee;
+    diff.fee - provider_fee
+  }
+
+  /// share of the fee that is earned by liquidity providers, proportional to their pool shares
+  fn provider_fee(&self, fee: u128) -> u128 {
+    if self.shares == 0 {
+      return 0;
+    }
+    // cannot overflow for realistic supplies, all fees go to the owner if it would
+    fee
+      .checked_mul(self.shares - self.locked_shares)
+      .map(|n| n / self.shares)
+      .unwrap_or_default()
+  }
+
+  /// Calculate a proportional deposit of at most the given amounts in exchange for pool shares.
+  pub fn add_liquidity(
+    &self,
+    max_base: u128,
+    max_quote: u128,
+    min_shares: Option<u128>,
+  ) -> Result<LiquidityDiff, PoolError> {
+    if self.shares == 0 || self.base_supply == 0 || self.quote_supply == 0 {
+      return Err(PoolError::ZeroShares);
+    }
+    // new shares buy into the provider fees, otherwise they would dilute the fees earned so far
+    let free_shares = self.shares - self.locked_shares;
+    let base_value = if free_shares == 0 {
+      self.base_supply
+    } else {
+      self
+        .base_supply
+        .checked_add(Self::mul_div_ceil(
+          self.provider_fees,
+          self.shares,
+          free_shares,
+        )?)
+        .ok_or(PoolError::Overflow)?
+    };
+    let shares = Self::mul_div(max_base, self.shares, base_value)?.min(Self::mul_div(
+      max_quote,
+      self.shares,
+      self.quote_supply,
+    )?);
+    if shares == 0 {
+      return Err(PoolError::ZeroShares);
+    }
+    if let Some(min_shares) = min_shares {
+      if shares < min_shares {
+        return Err(PoolError::Slippage);
+      }
+    }
+    // round in favor of the pool
+    let base_amount = Self::mul_div_ceil(shares, base_value, self.shares)?;
+    let quote_amount = Self::mul_div_ceil(shares, self.quote_supply, self.shares)?;
+    self.shares.checked_add(shares).ok_or(PoolError::Overflow)?;
+    Ok(LiquidityDiff {
+      base_amount,
+      quote_amount,
+      shares,
+      is_remove: false,
+      provider_fees: base_amount - Self::mul_div_ceil(shares, self.base_supply, self.shares)?,
+    })
+  }
+
+  /// Calculate the proportional withdrawal of the given number of pool shares.
+  pub fn remove_liquidity(
+    &self,
+    shares: u128,
+    min_base: Option<u128>,
+    min_quote: Option<u128>,
+  ) -> Result<LiquidityDiff, PoolError> {
+    if shares == 0 {
+      return Err(PoolError::ZeroShares);
+    }
+    let free_shares = self.shares - self.locked_shares;
+    if shares > free_shares {
+      return Err(PoolError::Underflow);
+    }
+    // round in favor of the pool
+    let provider_fees = Self::mul_div(shares, self.provider_fees, free_shares)?;
+    let base_amount = Self::mul_div(shares, self.base_supply, self.shares)?
+      .checked_add(provider_fees)
+      .ok_or(PoolError::Overflow)?;
+    let quote_amount = Self::mul_div(shares, self.quote_supply, self.shares)?;
+    if base_amount < min_base.unwrap_or_default() || quote_amount < min_quote.unwrap_or_default() {
+      return Err(PoolError::Slippage);
+    }
+    Ok(LiquidityDiff {
+      base_amount,
+      quote_amount,
+      shares,
+      is_remove: true,
+      provider_fees,
+    })
+  }
+
+  pub fn apply_liquidity(&mut self, diff: LiquidityDiff) {
+    if diff.is_remove {
+      self.base_supply -= diff.base_amount - diff.provider_fees;
+      self.quote_supply -= diff.quote_amount;
+      self.shares -= diff.shares;
+      self.provider_fees -= diff.provider_fees;
+    } else {
+      self.base_supply += diff.base_amount - diff.provider_fees;
+      self.quote_supply += diff.quote_amount;
+      self.shares += diff.shares;
+      self.provider_fees += diff.provider_fees;
+    }
+  }
+
+  /// Lock burned shares. The liquidity behind them can never be removed and the fees they earn are
+  /// paid to the owner of the Relic, like those of the locked initial liquidity. Once all shares are
+  /// locked, nobody is left to claim the provider fees, so they are returned to be paid to the owner.
+  pub fn burn_shares(&mut self, shares: u128) -> Result<u128, PoolError> {
+    let locked_shares = self
+      .locked_shares
+      .checked_add(shares)
+      .ok_or(PoolError::Overflow)?;
+    if locked_shares > self.shares {
+      return Err(PoolError::Overflow);
+    }
+    self.locked_shares = locked_shares;
+    if self.locked_shares < self.shares {
+      return Ok(0);
+    }
+    Ok(mem::take(&mut self.provider_fees))
+  }
+
+  fn mul_div(a: u128, b: u128, c: u128) -> Result<u128, PoolError> {
+    Ok(a.checked_mul(b).ok_or(PoolError::Overflow)? / c)
+  }
+
+  fn mul_div_ceil(a: u128, b: u128, c: u128) -> Result<u128, PoolError> {
+    Ok(a.checked_mul(b).ok_or(PoolError::Overflow)?.div_ceil(c))
   }
 
   pub fn lossy_price(&self) -> u128 {
@@ -203,9 +365,18 @@ mod tests {
       quote_supply,
       fee_bps,
       subsidy,
+      shares: 0,
+      locked_shares: 0,
+      provider_fees: 0,
     }
   }
 
+  fn with_shares(mut pool: Pool, shares: u128, locked_shares: u128) -> Pool {
+    pool.shares = shares;
+    pool.locked_shares = locked_shares;
+    pool
+  }
+
   #[test]
   fn low_liquidity() {
     let mut pool = new(100, 100, 100, 0);
@@ -729,4 +900,269 @@ mod tests {
     // 1 to 1000: price is 0.00100000
     case(100, 100_000, 100000);
   }
+
+  #[test]
+  fn fees_without_providers_go_to_owner() {
+    let mut pool = with_shares(new(100_000, 100_000, 100, 0), 100_000, 100_000);
+    let diff = pool
+      .calculate(PoolSwap::Input {
+        direction: BaseToQuote,
+        input: 1000,
+        min_output: None,
+      })
+      .unwrap();
+    assert_eq!(pool.apply(diff), 10);
+    assert_eq!(
+      pool,
+      with_shares(new(100_990, 99_020, 100, 0), 100_000, 100_000)
+    );
+  }
+
+  #[test]
+  fn fees_are_split_by_shares() {
+    // a quarter of all shares is held by liquidity providers
+    let mut pool = with_shares(new(100_000, 100_000, 100, 0), 400, 300);
+    let diff = pool
+      .calculate(PoolSwap::Input {
+        direction: BaseToQuote,
+        input: 1000,
+        min_output: None,
+      })
+      .unwrap();
+    assert_eq!(diff.fee, 10);
+    assert_eq!(pool.apply(diff), 8);
+    assert_eq!(
+      pool,
+      Pool {
+        provider_fees: 2,
+        ..with_shares(new(100_990, 99_020, 100, 0), 400, 300)
+      }
+    );
+
+    let diff = pool
+      .calculate(PoolSwap::Input {
+        direction: QuoteToBase,
+        input: 1000,
+        min_output: None,
+      })
+      .unwrap();
+    assert_eq!(diff.fee, 11);
+    assert_eq!(pool.apply(diff), 9);
+    assert_eq!(
+      pool,
+      Pool {
+        provider_fees: 4,
+        ..with_shares(new(100_990 - diff.output - 11, 100_020, 100, 0), 400, 300)
+      }
+    );
+  }
+
+  #[test]
+  fn add_liquidity() {
+    let pool = with_shares(new(10_000, 1_000, 100, 0), 10_000, 10_000);
+    // quote amount is the limiting factor
+    assert_eq!(
+      pool.add_liquidity(5_000, 100, None),
+      Ok(LiquidityDiff {
+        base_amount: 1_000,
+        quote_amount: 100,
+        shares: 1_000,
+        is_remove: false,
+        provider_fees: 0,
+      })
+    );
+    // deposits are rounded up in favor of the pool
+    assert_eq!(
+      pool.add_liquidity(1_005, 1_000, None),
+      Ok(LiquidityDiff {
+        base_amount: 1_005,
+        quote_amount: 101,
+        shares: 1_005,
+        is_remove: false,
+        provider_fees: 0,
+      })
+    );
+    assert_eq!(
+      pool.add_liquidity(1_000, 100, Some(1_001)),
+      Err(PoolError::Slippage)
+    );
+    assert_eq!(
+      pool.add_liquidity(1_000, 0, None),
+      Err(PoolError::ZeroShares)
+    );
+    assert_eq!(
+      new(0, 0, 100, 1_000).add_liquidity(1_000, 100, None),
+      Err(PoolError::ZeroShares)
+    );
+  }
+
+  #[test]
+  fn remove_liquidity() {
+    let mut pool = with_shares(new(10_000, 1_000, 100, 0), 10_000, 10_000);
+    let diff = pool.add_liquidity(1_000, 100, None).unwrap();
+    pool.apply_liquidity(diff);
+    assert_eq!(
+      pool,
+      with_shares(new(11_000, 1_100, 100, 0), 11_000, 10_000)
+    );
+
+    // locked shares can not be redeemed
+    assert_eq!(
+      pool.remove_liquidity(1_001, None, None),
+      Err(PoolError::Underflow)
+    );
+    assert_eq!(
+      pool.remove_liquidity(0, None, None),
+      Err(PoolError::ZeroShares)
+    );
+    assert_eq!(
+      pool.remove_liquidity(1_000, Some(1_001), None),
+      Err(PoolError::Slippage)
+    );
+    assert_eq!(
+      pool.remove_liquidity(1_000, None, Some(101)),
+      Err(PoolError::Slippage)
+    );
+
+    let diff = pool
+      .remove_liquidity(1_000, Some(1_000), Some(100))
+      .unwrap();
+    assert_eq!(
+      diff,
+      LiquidityDiff {
+        base_amount: 1_000,
+        quote_amount: 100,
+        shares: 1_000,
+        is_remove: true,
+        provider_fees: 0,
+      }
+    );
+    pool.apply_liquidity(diff);
+    assert_eq!(
+      pool,
+      with_shares(new(10_000, 1_000, 100, 0), 10_000, 10_000)
+    );
+  }
+
+  #[test]
+  fn providers_earn_fees() {
+    let mut pool = with_shares(new(10_000, 10_000, 100, 0), 10_000, 10_000);
+    let diff = pool.add_liquidity(10_000, 10_000, None).unwrap();
+    pool.apply_liquidity(diff);
+
+    let diff = pool
+      .calculate(PoolSwap::Input {
+        direction: BaseToQuote,
+        input: 1000,
+        min_output: None,
+      })
+      .unwrap();
+    assert_eq!(diff.fee, 10);
+    assert_eq!(diff.output, 943);
+    // the provider holds half of the shares and earns half of the fee
+    assert_eq!(pool.apply(diff), 5);
+    assert_eq!(
+      pool,
+      Pool {
+        provider_fees: 5,
+        ..with_shares(new(20_990, 19_057, 100, 0), 20_000, 10_000)
+      }
+    );
+
+    // new shares pay their part of the provider fees
+    assert_eq!(
+      pool.add_liquidity(2_100, 10_000, None),
+      Ok(LiquidityDiff {
+        base_amount: 2_100,
+        quote_amount: 1_906,
+        shares: 2_000,
+        is_remove: false,
+        provider_fees: 1,
+      })
+    );
+
+    assert_eq!(
+      pool.remove_liquidity(10_000, None, None),
+      Ok(LiquidityDiff {
+        base_amount: 10_500,
+        quote_amount: 9_528,
+        shares: 10_000,
+        is_remove: true,
+        provider_fees: 5,
+      })
+    );
+    pool.apply_liquidity(pool.remove_liquidity(10_000, None, None).unwrap());
+    assert_eq!(
+      pool,
+      with_shares(new(10_495, 9_529, 100, 0), 10_000, 10_000)
+    );
+  }
+
+  #[test]
+  fn burned_shares_are_locked() {
+    let mut pool = with_shares(new(20_000, 20_000, 100, 0), 20_000, 10_000);
+    pool.provider_fees = 10;
+
+    assert_eq!(pool.burn_shares(5_000), Ok(0));
+    assert_eq!(
+      pool,
+      Pool {
+        provider_fees: 10,
+        ..with_shares(new(20_000, 20_000, 100, 0), 20_000, 15_000)
+      }
+    );
+
+    // burned shares can not be redeemed
+    assert_eq!(
+      pool.remove_liquidity(5_001, None, None),
+      Err(PoolError::Underflow)
+    );
+
+    // the remaining shares earn the provider fees of the burned shares
+    assert_eq!(
+      pool.remove_liquidity(5_000, None, None),
+      Ok(LiquidityDiff {
+        base_amount: 5_010,
+        quote_amount: 5_000,
+        shares: 5_000,
+        is_remove: true,
+        provider_fees: 10,
+      })
+    );
+  }
+
+  #[test]
+  fn provider_fees_are_paid_to_the_owner_once_all_shares_are_burned() {
+    let mut pool = with_shares(new(20_000, 20_000, 100, 0), 20_000, 10_000);
+    pool.provider_fees = 10;
+
+    assert_eq!(pool.burn_shares(10_000), Ok(10));
+    assert_eq!(
+      pool,
+      with_shares(new(20_000, 20_000, 100, 0), 20_000, 20_000)
+    );
+
+    // new shares are priced by the supplies alone, without fees nobody can claim
+    assert_eq!(
+      pool.add_liquidity(1_000, 1_000, None),
+      Ok(LiquidityDiff {
+        base_amount: 1_000,
+        quote_amount: 1_000,
+        shares: 1_000,
+        is_remove: false,
+        provider_fees: 0,
+      })
+    );
+  }
+
+  #[test]
+  fn burning_more_shares_than_unlocked_fails() {
+    let mut pool = with_shares(new(20_000, 20_000, 100, 0), 20_000, 10_000);
+    assert_eq!(pool.burn_shares(10_001), Err(PoolError::Overflow));
+    assert_eq!(pool.burn_shares(u128::MAX), Err(PoolError::Overflow));
+    assert_eq!(
+      pool,
+      with_shares(new(20_000, 20_000, 100, 0), 20_000, 10_000)
+    );
+  }
 }
@@ -0,0 +1,20 @@
+use super::*;
+
+#[derive(Debug, Default, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub struct PoolPrice {
+  pub timestamp: u32,
+  pub base_supply: u128,
+  pub quote_supply: u128,
+  /// base tokens traded, zero if the pool was not changed by a swap
+  pub base_volume: u128,
+  /// Relics traded, zero if the pool was not changed by a swap
+  pub quote_volume: u128,
+}
+
+impl PoolPrice {
+  /// same as `Pool::lossy_price`
+  pub fn lossy_price(&self) -> u128 {
+    ((self.base_supply as f64 / self.quote_supply as f64)
+      * 10f64.powf(Enshrining::DIVISIBILITY as f64)) as u128
+  }
+}
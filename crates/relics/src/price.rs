@@ -0,0 +1,355 @@
+use super::*;
+
+/// Arithmetic used to price mints of Relics with [`PriceModel::Formula`].
+///
+/// Pricing is consensus critical: the price of a mint decides the base token balances and the pool
+/// a Relic is seeded with once minted out, so every indexer must arrive at the same price. Which
+/// arithmetic applies is decided by the height of the block containing the mint, see
+/// [`ProtocolUpgrade::DeterministicMints`].
+#[derive(Debug, PartialEq, Copy, Clone, Eq)]
+pub enum PriceArithmetic {
+  /// `a * exp(x / b)` evaluated with `f64` and truncated, totals are summed mint by mint. The
+  /// result depends on the `exp` implementation of the platform.
+  Legacy,
+  /// Integer arithmetic on the cumulative price of the first `k` mints
+  ///
+  /// `S(k) = a * exp(0 / b) + … + a * exp((k - 1) / b) = a * (exp(k / b) - 1) / (exp(1 / b) - 1)`
+  ///
+  /// which is evaluated in closed form with 128 bit binary floating point numbers. Every operation
+  /// truncates, so the computed `S(k)` has a relative error below 2^-112 and is at most 1 away
+  /// from `⌊S(k)⌋` while `S(k) < 2^112`. The price of mint `x` is `S(x + 1) - S(x)`, which differs
+  /// by less than 3 from `a * exp(x / b)`, and prices always add up to the cumulative price.
+  FixedPoint,
+}
+
+impl PriceArithmetic {
+  /// First block height at which formula prices use [`PriceArithmetic::FixedPoint`]
+  pub fn activation_height(network: Network) -> u32 {
+    ProtocolUpgrade::DeterministicMints.activation_height(network)
+  }
+
+  pub fn at_height(network: Network, height: u32) -> Self {
+    if height < Self::activation_height(network) {
+      Self::Legacy
+    } else {
+      Self::FixedPoint
+    }
+  }
+}
+
+/// `⌊a * (exp(mints / b) - 1) / (exp(1 / b) - 1)⌋`, the price of the first `mints` mints, see
+/// [`PriceArithmetic::FixedPoint`]. `None` if `b` is zero or the price does not fit into a u128.
+pub(crate) fn cumulative_price(a: u128, b: u128, mints: u128) -> Option<u128> {
+  if b == 0 {
+    return None;
+  }
+  let numerator = exp_m1(mints, b)?;
+  let denominator = exp_m1(1, b)?;
+  Float::from_u128(a)
+    .mul(numerator)
+    .div(denominator)
+    .to_u128()
+}
+
+/// `exp(n / b) - 1`, `None` if `n / b` is 128 or more, which overflows any cumulative price
+fn exp_m1(n: u128, b: u128) -> Option<Float> {
+  let (q, r) = (n / b, n % b);
+  if q >= 128 {
+    return None;
+  }
+
+  // exp(t) - 1 for the fractional part t = r / b by its Taylor series, each term is less than half
+  // of the previous one, so the remaining terms are negligible once a term vanishes against the sum
+  let t = Float::from_u128(r).div(Float::from_u128(b));
+  let mut sum = Float::ZERO;
+  let mut term = Float::ONE;
+  for k in 1u128.. {
+    term = term.mul(t).div(Float::from_u128(k));
+    if term.is_zero() || (!sum.is_zero() && term.e < sum.e - 128) {
+      break;
+    }
+    sum = sum.add(term);
+  }
+
+  if q == 0 {
+    return Some(sum);
+  }
+
+  // exp(q) from the binary digits of the integer part
+  let mut exp = Float::ONE;
+  for (i, power) in Float::EXP_POW2.iter().enumerate() {
+    if (q >> i) & 1 == 1 {
+      exp = exp.mul(*power);
+    }
+  }
+
+  Some(exp.mul(Float::ONE.add(sum)).sub(Float::ONE))
+}
+
+/// Non-negative binary floating point number `m * 2^e`, the mantissa of a non-zero number has
+/// exactly 128 significant bits.
+///
+/// Products and quotients are truncated to 128 significant bits, a relative error below 2^-127.
+/// Sums and differences truncate the smaller operand, an absolute error below 2^-127 times the
+/// larger operand.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Float {
+  m: u128,
+  e: i32,
+}
+
+impl Float {
+  const ZERO: Self = Self { m: 0, e: 0 };
+  const ONE: Self = Self {
+    m: 1 << 127,
+    e: -127,
+  };
+
+  /// `exp(2^i)` for `i` in `0..7`, rounded down
+  const EXP_POW2: [Self; 7] = [
+    Self {
+      m: 0xadf85458a2bb4a9aafdc5620273d3cf1,
+      e: -126,
+    },
+    Self {
+      m: 0xec7325c6a6ed6e619d1dd1035b455dd4,
+      e: -125,
+    },
+    Self {
+      m: 0xda64817139d2c33c6b69dfedc9edcb68,
+      e: -122,
+    },
+    Self {
+      m: 0xba4f53ea38636f85f007042540ae8ef3,
+      e: -116,
+    },
+    Self {
+      m: 0x87975e854001024911f8b84415af72f7,
+      e: -104,
+    },
+    Self {
+      m: 0x8fa1fe625b3163ec23c4200c210ba03a,
+      e: -81,
+    },
+    Self {
+      m: 0xa12cc167acbe6902e71eada76d818bab,
+      e: -35,
+    },
+  ];
+
+  fn from_u128(n: u128) -> Self {
+    if n == 0 {
+      return Self::ZERO;
+    }
+    let shift = n.leading_zeros();
+    Self {
+      m: n << shift,
+      e: -i32::try_from(shift).unwrap(),
+    }
+  }
+
+  fn is_zero(self) -> bool {
+    self.m == 0
+  }
+
+  /// largest integer not above this number, `None` if it does not fit into a u128
+  fn to_u128(self) -> Option<u128> {
+    if self.e > 0 {
+      None
+    } else if self.e <= -128 {
+      Some(0)
+    } else {
+      Some(self.m >> -self.e)
+    }
+  }
+
+  fn add(self, other: Self) -> Self {
+    if self.is_zero() {
+      return other;
+    }
+    if other.is_zero() {
+      return self;
+    }
+    let (larger, smaller) = if self.e >= other.e {
+      (self, other)
+    } else {
+      (other, self)
+    };
+    let aligned = Self::shr(smaller.m, larger.e - smaller.e);
+    match larger.m.overflowing_add(aligned) {
+      (m, false) => Self { m, e: larger.e },
+      (m, true) => Self {
+        m: (m >> 1) | 1 << 127,
+        e: larger.e + 1,
+      },
+    }
+  }
+
+  /// difference of this number and a number not larger than it
+  fn sub(self, other: Self) -> Self {
+    if other.is_zero() {
+      return self;
+    }
+    let m = self.m - Self::shr(other.m, self.e - other.e);
+    if m == 0 {
+      return Self::ZERO;
+    }
+    let shift = m.leading_zeros();
+    Self {
+      m: m << shift,
+      e: self.e - i32::try_from(shift).unwrap(),
+    }
+  }
+
+  fn mul(self, other: Self) -> Self {
+    if self.is_zero() || other.is_zero() {
+      return Self::ZERO;
+    }
+    // both mantissas are at least 2^127, so the product has 255 or 256 significant bits
+    let (hi, lo) = widening_mul(self.m, other.m);
+    if hi >> 127 == 1 {
+      Self {
+        m: hi,
+        e: self.e + other.e + 128,
+      }
+    } else {
+      Self {
+        m: hi << 1 | lo >> 127,
+        e: self.e + other.e + 127,
+      }
+    }
+  }
+
+  fn div(self, other: Self) -> Self {
+    assert!(!other.is_zero(), "division by zero");
+    if self.is_zero() {
+      return Self::ZERO;
+    }
+    // the dividend is scaled so that the quotient has exactly 128 significant bits
+    if self.m < other.m {
+      Self {
+        m: div_wide(self.m, 0, other.m),
+        e: self.e - other.e - 128,
+      }
+    } else {
+      Self {
+        m: div_wide(self.m >> 1, self.m << 127, other.m),
+        e: self.e - other.e - 127,
+      }
+    }
+  }
+
+  fn shr(m: u128, shift: i32) -> u128 {
+    u32::try_from(shift)
+      .ok()
+      .and_then(|shift| m.checked_shr(shift))
+      .unwrap_or(0)
+  }
+}
+
+/// 256 bit product of `x` and `y` as `(high, low)` halves
+fn widening_mul(x: u128, y: u128) -> (u128, u128) {
+  const MASK: u128 = u64::MAX as u128;
+  let (x1, x0) = (x >> 64, x & MASK);
+  let (y1, y0) = (y >> 64, y & MASK);
+  let (p00, p01, p10, p11) = (x0 * y0, x0 * y1, x1 * y0, x1 * y1);
+  let mid = (p00 >> 64) + (p01 & MASK) + (p10 & MASK);
+  (
+    p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64),
+    mid << 64 | p00 & MASK,
+  )
+}
+
+/// `(hi * 2^128 + lo) / d` rounded down, the quotient fits into a u128 since `hi < d`
+fn div_wide(hi: u128, lo: u128, d: u128) -> u128 {
+  let mut remainder = hi;
+  let mut quotient = 0;
+  for i in (0..128).rev() {
+    let carry = remainder >> 127 == 1;
+    remainder = remainder << 1 | (lo >> i) & 1;
+    quotient <<= 1;
+    if carry || remainder >= d {
+      remainder = remainder.wrapping_sub(d);
+      quotient |= 1;
+    }
+  }
+  quotient
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn activation() {
+    assert_eq!(
+      PriceArithmetic::at_height(Network::Bitcoin, 979_999),
+      PriceArithmetic::Legacy
+    );
+    assert_eq!(
+      PriceArithmetic::at_height(Network::Bitcoin, 980_000),
+      PriceArithmetic::FixedPoint
+    );
+    assert_eq!(
+      PriceArithmetic::at_height(Network::Regtest, 0),
+      PriceArithmetic::FixedPoint
+    );
+  }
+
+  #[test]
+  fn arithmetic() {
+    assert_eq!(widening_mul(u128::MAX, u128::MAX), (u128::MAX - 1, 1));
+    assert_eq!(div_wide(1, 0, 3), u128::MAX / 3);
+    assert_eq!(div_wide(u128::MAX - 1, 1, u128::MAX), u128::MAX);
+
+    let float = |n| Float::from_u128(n);
+    assert_eq!(float(6).mul(float(7)).to_u128(), Some(42));
+    assert_eq!(float(42).div(float(6)).to_u128(), Some(7));
+    assert_eq!(float(7).div(float(2)).to_u128(), Some(3));
+    assert_eq!(float(u128::MAX).add(float(1)).to_u128(), None);
+    assert_eq!(
+      float(u128::MAX).sub(float(1)).to_u128(),
+      Some(u128::MAX - 1)
+    );
+    assert_eq!(float(5).sub(float(5)), Float::ZERO);
+    assert_eq!(float(1).div(float(3)).to_u128(), Some(0));
+  }
+
+  #[test]
+  fn exp_pow2() {
+    let mut exp = Float::EXP_POW2[0];
+    for power in &Float::EXP_POW2[1..] {
+      exp = exp.mul(exp);
+      // squaring loses at most a few bits
+      assert_eq!(exp.e, power.e);
+      assert!(power.m - exp.m < 1 << 8);
+    }
+  }
+
+  #[test]
+  fn cumulative_price_golden() {
+    // computed with 200 digits of precision
+    assert_eq!(cumulative_price(1, 1, 1), Some(1));
+    assert_eq!(cumulative_price(1, 1, 2), Some(3));
+    assert_eq!(cumulative_price(1, 1, 5), Some(85));
+    assert_eq!(cumulative_price(10, 2, 5), Some(172));
+    assert_eq!(cumulative_price(29_276_332, 6_994, 3), Some(87_841_555));
+    assert_eq!(
+      cumulative_price(29_276_332, 6_994, 16_800),
+      Some(2_056_837_103_853)
+    );
+    assert_eq!(
+      cumulative_price(8_045_250, 4_001, 16_800),
+      Some(2_111_864_190_980)
+    );
+    // beyond 2^112 only the relative error is bounded
+    let exact = 261_285_008_362_139_814_510_516_610_517_596_936_801;
+    assert!(cumulative_price(1, 1, 89).unwrap().abs_diff(exact) < exact >> 112);
+    assert_eq!(cumulative_price(1, 1, 90), None);
+    assert_eq!(cumulative_price(1, 1, 0), Some(0));
+    assert_eq!(cumulative_price(0, 7, 100), Some(0));
+    assert_eq!(cumulative_price(1, 0, 1), None);
+    assert_eq!(cumulative_price(1, 1, 128), None);
+    assert_eq!(cumulative_price(u128::MAX, 1_000_000, 1_000_000), None);
+  }
+}
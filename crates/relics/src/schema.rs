@@ -0,0 +1,54 @@
+//! JSON schemas of types that are serialized as strings. Types of other crates are described by
+//! stand-ins of the same name, for use with `#[schemars(with = "schema::Txid")]`. Only built with the
+//! `schemars` feature, which the API crate enables, so the protocol itself doesn't depend on it.
+
+use super::*;
+
+/// Implement `JsonSchema` for `$ty` as a string matching `$pattern`
+#[macro_export]
+macro_rules! string_schema {
+  ($ty:ty, $name:literal, $pattern:literal, $description:literal) => {
+    impl ::schemars::JsonSchema for $ty {
+      fn schema_name() -> ::std::borrow::Cow<'static, str> {
+        $name.into()
+      }
+
+      fn json_schema(_generator: &mut ::schemars::SchemaGenerator) -> ::schemars::Schema {
+        ::schemars::json_schema!({
+          "type": "string",
+          "pattern": $pattern,
+          "description": $description,
+        })
+      }
+    }
+  };
+}
+
+/// stand-in for [`bitcoin::Txid`]
+pub struct Txid;
+
+string_schema!(Txid, "Txid", "^[0-9a-f]{64}$", "transaction ID");
+
+/// stand-in for [`crate::RelicId`]
+pub struct RelicId;
+
+string_schema!(
+  RelicId,
+  "RelicId",
+  "^[0-9]+:[0-9]+$",
+  "`<block>:<tx>` of the enshrining"
+);
+
+string_schema!(
+  SpacedRelic,
+  "SpacedRelic",
+  "^[A-Z]+(•[A-Z]+)*$",
+  "Relic name with spacers"
+);
+
+string_schema!(
+  RelicOwner,
+  "RelicOwner",
+  "^[0-9a-f]{40}$",
+  "hash of the script pubkey holding the inscription that owns a Relic"
+);
@@ -1,6 +1,6 @@
 use super::*;
 
-#[derive(Default, Serialize, Deserialize, Debug, PartialEq, Copy, Clone, Eq)]
+#[derive(Default, Serialize, Deserialize, Debug, PartialEq, Clone, Eq)]
 pub struct Swap {
   /// specifies input token, defaults to RELIC
   pub input: Option<RelicId>,
@@ -13,4 +13,143 @@ pub struct Swap {
   /// if false, this is an exact-output order
   /// if true, this is an exact-input order
   pub is_exact_input: bool,
+  /// Relics to route the swap through, in order
+  /// every hop trades against the base token, e.g. A -> RELIC -> B -> RELIC -> C for `via: [B]`
+  /// note: exact-output routes execute with the smallest input that yields the requested output
+  /// when the legs are executed in order, so the output may slightly exceed the requested amount
+  #[serde(default)]
+  pub via: Vec<RelicId>,
+}
+
+impl Swap {
+  /// maximum number of Relics a swap can be routed through, longer routes are cenotaphs
+  pub const MAX_HOPS: usize = 8;
+
+  /// all Relics traded along this swap, starting with the input and ending with the output Relic
+  pub fn path(&self) -> Vec<RelicId> {
+    let mut path = Vec::with_capacity(self.via.len() + 2);
+    path.push(self.input.unwrap_or(RELIC_ID));
+    path.extend_from_slice(&self.via);
+    path.push(self.output.unwrap_or(RELIC_ID));
+    path
+  }
+
+  /// pools traded against in execution order, together with the direction of each trade
+  pub fn legs(&self) -> Vec<(RelicId, SwapDirection)> {
+    let mut legs = Vec::new();
+    for hop in self.path().windows(2) {
+      if hop[0] != RELIC_ID {
+        legs.push((hop[0], SwapDirection::QuoteToBase));
+      }
+      if hop[1] != RELIC_ID {
+        legs.push((hop[1], SwapDirection::BaseToQuote));
+      }
+    }
+    legs
+  }
+
+  /// a route must not visit the same Relic twice and must not route through the base token
+  pub fn is_valid(&self) -> bool {
+    let mut visited = HashSet::new();
+    self.path().into_iter().all(|id| visited.insert(id)) && !self.via.contains(&RELIC_ID)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  fn id(tx: u32) -> RelicId {
+    RelicId { block: 2, tx }
+  }
+
+  #[test]
+  fn legs() {
+    assert_eq!(
+      Swap {
+        output: Some(id(1)),
+        ..default()
+      }
+      .legs(),
+      vec![(id(1), SwapDirection::BaseToQuote)]
+    );
+    assert_eq!(
+      Swap {
+        input: Some(id(1)),
+        output: Some(id(2)),
+        ..default()
+      }
+      .legs(),
+      vec![
+        (id(1), SwapDirection::QuoteToBase),
+        (id(2), SwapDirection::BaseToQuote)
+      ]
+    );
+    assert_eq!(
+      Swap {
+        input: Some(id(1)),
+        via: vec![id(2)],
+        ..default()
+      }
+      .legs(),
+      vec![
+        (id(1), SwapDirection::QuoteToBase),
+        (id(2), SwapDirection::BaseToQuote),
+        (id(2), SwapDirection::QuoteToBase),
+      ]
+    );
+    assert_eq!(
+      Swap {
+        input: Some(id(1)),
+        output: Some(id(3)),
+        via: vec![id(2)],
+        ..default()
+      }
+      .legs(),
+      vec![
+        (id(1), SwapDirection::QuoteToBase),
+        (id(2), SwapDirection::BaseToQuote),
+        (id(2), SwapDirection::QuoteToBase),
+        (id(3), SwapDirection::BaseToQuote),
+      ]
+    );
+  }
+
+  #[test]
+  fn is_valid() {
+    assert!(Swap {
+      input: Some(id(1)),
+      ..default()
+    }
+    .is_valid());
+    assert!(Swap {
+      input: Some(id(1)),
+      output: Some(id(3)),
+      via: vec![id(2), id(4)],
+      ..default()
+    }
+    .is_valid());
+    assert!(!Swap::default().is_valid());
+    assert!(!Swap {
+      input: Some(id(1)),
+      output: Some(id(3)),
+      via: vec![RELIC_ID],
+      ..default()
+    }
+    .is_valid());
+    assert!(!Swap {
+      input: Some(id(1)),
+      output: Some(id(3)),
+      via: vec![id(2), id(1)],
+      ..default()
+    }
+    .is_valid());
+    assert!(!Swap {
+      input: Some(id(1)),
+      output: Some(id(3)),
+      via: vec![id(2), id(2)],
+      ..default()
+    }
+    .is_valid());
+  }
 }
@@ -0,0 +1,64 @@
+use super::*;
+
+/// Consensus changes made to Relics after launch, each activated from a fixed block height per
+/// network.
+///
+/// This is the only table of activation heights, every feature looks up the upgrade that introduced
+/// it. The heights of a released upgrade must never change, since every indexer has to apply it
+/// from the same block on.
+#[derive(Debug, PartialEq, Copy, Clone, Eq)]
+pub enum ProtocolUpgrade {
+  /// Mints are evaluated the same way on every platform: boost rolls use
+  /// [`BoostRandomness::TaggedSha256`] and formula prices use [`PriceArithmetic::FixedPoint`].
+  /// This only changes how existing Keepsakes are evaluated.
+  DeterministicMints,
+  /// The fields of every [`KeepsakeExtension`] are recognized. Until then Keepsakes using them are
+  /// cenotaphs and burn their inputs, so this activates after
+  /// [`ProtocolUpgrade::DeterministicMints`], 10,000 blocks later on mainnet and signet and 50,000
+  /// on testnet with its faster blocks, to leave wallets and indexers time to upgrade before the new
+  /// fields take effect.
+  KeepsakeExtensions,
+}
+
+impl ProtocolUpgrade {
+  /// First block height at which the upgrade is active
+  pub fn activation_height(self, network: Network) -> u32 {
+    let (bitcoin, signet, testnet) = match self {
+      Self::DeterministicMints => (980_000, 290_000, 4_900_000),
+      Self::KeepsakeExtensions => (990_000, 300_000, 4_950_000),
+    };
+
+    match network {
+      Network::Bitcoin => bitcoin,
+      Network::Signet => signet,
+      Network::Testnet => testnet,
+      _ => 0,
+    }
+  }
+
+  pub fn is_active(self, network: Network, height: u32) -> bool {
+    height >= self.activation_height(network)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn activation() {
+    assert!(!ProtocolUpgrade::DeterministicMints.is_active(Network::Bitcoin, 979_999));
+    assert!(ProtocolUpgrade::DeterministicMints.is_active(Network::Bitcoin, 980_000));
+    assert!(ProtocolUpgrade::KeepsakeExtensions.is_active(Network::Regtest, 0));
+  }
+
+  #[test]
+  fn keepsake_extensions_activate_after_deterministic_mints() {
+    for network in [Network::Bitcoin, Network::Signet, Network::Testnet] {
+      assert!(
+        ProtocolUpgrade::KeepsakeExtensions.activation_height(network)
+          > ProtocolUpgrade::DeterministicMints.activation_height(network)
+      );
+    }
+  }
+}
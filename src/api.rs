@@ -3,11 +3,19 @@ use {
   serde_hex::{SerHex, Strict},
 };
 
-pub use crate::{
-  subcommand::decode::RawOutput as Decode,
-  templates::{
-    BlocksHtml as Blocks, RelicEventsHtml as RelicEvents, RelicHtml, RuneHtml as Rune,
-    RunesHtml as Runes, StatusHtml as Status, TransactionHtml as Transaction,
+pub use {
+  crate::{
+    subcommand::decode::RawOutput as Decode,
+    templates::{
+      BlocksHtml as Blocks, RelicHtml, RuneHtml as Rune, RunesHtml as Runes, StatusHtml as Status,
+      TransactionHtml as Transaction,
+    },
+  },
+  relics_api::{
+    Inscription, MempoolRelicOutput, MempoolRelicTransaction, MempoolRelics, Output, Relic,
+    RelicCandles, RelicEvents, RelicHolder, RelicHolders, RelicInscription, RelicOfferInput,
+    RelicOfferValidation, RelicShares, RelicSnapshot, RelicSnapshotBalance, RelicSnapshotCursor,
+    RelicSwapLeg, RelicSwapQuote, Relics, Sealing, Sealings,
   },
 };
 
@@ -92,33 +100,6 @@ pub struct ChildInscriptions {
   pub page: usize,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
-pub struct Inscription {
-  pub address: Option<String>,
-  pub charms: Vec<Charm>,
-  pub child_count: u64,
-  pub children: Vec<InscriptionId>,
-  pub content_length: Option<usize>,
-  pub content_type: Option<String>,
-  pub effective_content_type: Option<String>,
-  pub fee: u64,
-  pub height: u32,
-  pub id: InscriptionId,
-  pub next: Option<InscriptionId>,
-  pub number: i32,
-  pub parents: Vec<InscriptionId>,
-  pub previous: Option<InscriptionId>,
-  // if this Inscription has sealed a Relic ticker
-  pub relic_sealed: Option<SpacedRelic>,
-  // if the sealed Relic ticker has already been enshrined
-  pub relic_enshrined: bool,
-  pub rune: Option<SpacedRune>,
-  pub sat: Option<ordinals::Sat>,
-  pub satpoint: SatPoint,
-  pub timestamp: i64,
-  pub value: Option<u64>,
-}
-
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct InscriptionRecursive {
   pub charms: Vec<Charm>,
@@ -157,58 +138,6 @@ pub struct Inscriptions {
   pub page_index: u32,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
-pub struct RelicInscription {
-  pub id: InscriptionId,
-  pub is_base_relic: bool,
-}
-
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
-pub struct Output {
-  pub address: Option<Address<NetworkUnchecked>>,
-  pub indexed: bool,
-  pub inscriptions: Vec<RelicInscription>,
-  pub outpoint: OutPoint,
-  pub relics: BTreeMap<SpacedRelic, Pile>,
-  pub runes: BTreeMap<SpacedRune, Pile>,
-  pub sat_ranges: Option<Vec<(u64, u64)>>,
-  pub script_pubkey: ScriptBuf,
-  pub spent: bool,
-  pub transaction: Txid,
-  pub value: u64,
-}
-
-impl Output {
-  pub fn new(
-    chain: Chain,
-    inscriptions: Vec<RelicInscription>,
-    outpoint: OutPoint,
-    tx_out: TxOut,
-    indexed: bool,
-    relics: BTreeMap<SpacedRelic, Pile>,
-    runes: BTreeMap<SpacedRune, Pile>,
-    sat_ranges: Option<Vec<(u64, u64)>>,
-    spent: bool,
-  ) -> Self {
-    Self {
-      address: chain
-        .address_from_script(&tx_out.script_pubkey)
-        .ok()
-        .map(|address| uncheck(&address)),
-      indexed,
-      inscriptions,
-      outpoint,
-      relics,
-      runes,
-      sat_ranges,
-      script_pubkey: tx_out.script_pubkey,
-      spent,
-      transaction: outpoint.txid,
-      value: tx_out.value.to_sat(),
-    }
-  }
-}
-
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Sat {
   pub block: u32,
@@ -246,14 +175,5 @@ pub struct AddressInfo {
   pub inscriptions: Vec<InscriptionId>,
   pub sat_balance: u64,
   pub runes_balances: Vec<(SpacedRune, Decimal, Option<char>)>,
-}
-
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
-pub struct Relic {
-  #[serde(flatten)]
-  pub entry: RelicEntry,
-  pub id: RelicId,
-  pub mintable: bool,
-  pub owner: Option<InscriptionId>,
-  pub thumb: Option<InscriptionId>,
+  pub relics_balances: Vec<(SpacedRelic, Decimal, Option<char>)>,
 }
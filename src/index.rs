@@ -2,22 +2,24 @@ use {
   self::{
     entry::{
       Entry, HeaderValue, InscriptionEntry, InscriptionEntryValue, InscriptionIdValue,
-      OutPointValue, RelicEntryValue, RelicIdValue, RelicOwnerValue, RuneEntryValue, RuneIdValue,
-      SatPointValue, SatRange, TxidValue,
+      OutPointValue, PoolPriceValue, RelicEntryValue, RelicIdValue, RelicOwnerValue,
+      RuneEntryValue, RuneIdValue, SatPointValue, SatRange, TxidValue,
     },
+    event::{EventKeyValue, EventValue, LegacyEventValue},
     lot::Lot,
+    mempool::MempoolRelics,
     reorg::Reorg,
     updater::Updater,
     utxo_entry::{ParsedUtxoEntry, UtxoEntry, UtxoEntryBuf},
   },
   super::*,
   crate::{
-    relics::RelicError,
+    relics::{RelicAllocation, RelicError, RelicSupplyAudit},
     runes::MintError,
     subcommand::{find::FindRangeOutput, server::query},
     templates::StatusHtml,
   },
-  bitcoin::block::Header,
+  bitcoin::{block::Header, psbt::Psbt},
   bitcoincore_rpc::{
     json::{GetBlockHeaderResult, GetBlockStatsResult},
     Client,
@@ -32,21 +34,24 @@ use {
   },
   relics_protocol::INCEPTION_PARENT_INSCRIPTION_ID,
   std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     io::{BufWriter, Write},
-    sync::Once,
+    ops::Bound,
+    sync::{Once, RwLock},
   },
 };
 
 pub use self::{
   entry::SpacedRelicValue,
-  event::{Event, EventInfo, RelicOperation},
+  event::{Event, EventCursor, EventInfo, RelicOperation},
 };
 
 pub(crate) mod entry;
 pub mod event;
 mod fetcher;
 mod lot;
+mod mempool;
+mod relics_swap;
 mod reorg;
 mod rtx;
 mod updater;
@@ -55,29 +60,82 @@ mod utxo_entry;
 #[cfg(test)]
 pub(crate) mod testing;
 
-const SCHEMA_VERSION: u64 = 29;
+const SCHEMA_VERSION: u64 = 32;
+
+/// indexes of this schema store events in the multimap tables `TRANSACTION_ID_TO_EVENTS` and
+/// `RELIC_ID_TO_EVENTS`, they are migrated to the event key tables on open
+const LEGACY_EVENTS_SCHEMA_VERSION: u64 = 31;
+
+/// columns of `relics.csv` written by `Index::export_relics`
+const RELIC_EXPORT_HEADER: [&str; 31] = [
+  "id",
+  "relic",
+  "number",
+  "block",
+  "enshrining",
+  "timestamp",
+  "symbol",
+  "fee",
+  "owner_sequence_number",
+  "mint_amount",
+  "mint_cap",
+  "mint_block_cap",
+  "mint_tx_cap",
+  "mint_max_unmints",
+  "mint_price",
+  "mint_formula_a",
+  "mint_formula_b",
+  "mint_seed",
+  "boost_terms",
+  "burned",
+  "mints",
+  "unmints",
+  "paid",
+  "issued",
+  "pool_base_supply",
+  "pool_quote_supply",
+  "pool_fee_bps",
+  "pool_subsidy",
+  "pool_shares",
+  "pool_locked_shares",
+  "pool_provider_fees",
+];
+
+/// number of committed events buffered for each event stream subscriber
+const EVENT_STREAM_CAPACITY: usize = 10_000;
+
+/// number of reorg notifications kept for event stream subscribers that lag behind
+const REORGS_KEPT: usize = 100;
 
 define_multimap_table! { SAT_TO_SEQUENCE_NUMBER, u64, u32 }
 define_multimap_table! { SEQUENCE_NUMBER_TO_CHILDREN, u32, u32 }
 define_multimap_table! { SCRIPT_PUBKEY_TO_OUTPOINT, &[u8], OutPointValue }
-define_multimap_table! { TRANSACTION_ID_TO_EVENTS, &TxidValue, Event }
-define_multimap_table! { RELIC_ID_TO_EVENTS, RelicIdValue, Event }
+define_multimap_table! { TRANSACTION_ID_TO_EVENT_KEYS, &TxidValue, &EventKeyValue }
+define_multimap_table! { RELIC_ID_TO_EVENTS, RelicIdValue, LegacyEventValue }
+define_multimap_table! { TRANSACTION_ID_TO_EVENTS, &TxidValue, LegacyEventValue }
+define_table! { EVENT_KEY_TO_EVENT, &EventKeyValue, EventValue }
 define_table! { HEIGHT_TO_BLOCK_HEADER, u32, &HeaderValue }
 define_table! { HEIGHT_TO_LAST_SEQUENCE_NUMBER, u32, u32 }
 define_table! { HOME_INSCRIPTIONS, u32, InscriptionIdValue }
 define_table! { INSCRIPTION_ID_TO_SEQUENCE_NUMBER, InscriptionIdValue, u32 }
 define_table! { INSCRIPTION_NUMBER_TO_SEQUENCE_NUMBER, i32, u32 }
 define_table! { OUTPOINT_TO_RELIC_BALANCES, &OutPointValue, &[u8] }
+define_table! { OUTPOINT_TO_RELIC_HISTORY, &OutPointValue, (u32, Option<u32>, &[u8], &[u8]) }
+define_table! { OUTPOINT_TO_RELIC_SHARES, &OutPointValue, &[u8] }
 define_table! { OUTPOINT_TO_RUNE_BALANCES, &OutPointValue, &[u8] }
 define_table! { OUTPOINT_TO_UTXO_ENTRY, &OutPointValue, &UtxoEntry }
 define_table! { RELIC_TO_SEQUENCE_NUMBER, u128, u32 }
 define_table! { SEQUENCE_NUMBER_TO_SPACED_RELIC, u32, SpacedRelicValue }
+define_table! { RELIC_ID_TO_EVENT_KEYS, (RelicIdValue, u32), &EventKeyValue }
+define_table! { RELIC_ID_TO_HOLDERS, (RelicIdValue, u128, &[u8]), () }
+define_table! { RELIC_ID_TO_POOL_PRICES, (RelicIdValue, u32, u32), PoolPriceValue }
 define_table! { RELIC_ID_TO_RELIC_ENTRY, RelicIdValue, RelicEntryValue }
 define_table! { RELIC_TO_RELIC_ID, u128, RelicIdValue }
 define_table! { RELIC_OWNER_TO_CLAIMABLE, &RelicOwnerValue, u128 }
 define_table! { RUNE_ID_TO_RUNE_ENTRY, RuneIdValue, RuneEntryValue }
 define_table! { RUNE_TO_RUNE_ID, u128, RuneIdValue }
 define_table! { SAT_TO_SATPOINT, u64, &SatPointValue }
+define_table! { SCRIPT_PUBKEY_TO_RELIC_BALANCE, (&[u8], RelicIdValue), u128 }
 define_table! { SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY, u32, InscriptionEntryValue }
 define_table! { SEQUENCE_NUMBER_TO_RUNE_ID, u32, RuneIdValue }
 define_table! { SEQUENCE_NUMBER_TO_SATPOINT, u32, &SatPointValue }
@@ -107,6 +165,8 @@ pub(crate) enum Statistic {
   UnboundInscriptions = 16,
   IndexRelics = 17,
   Relics = 18,
+  IndexRelicPrices = 19,
+  IndexRelicHistory = 20,
 }
 
 impl Statistic {
@@ -217,18 +277,27 @@ pub struct Index {
   database: Database,
   durability: redb::Durability,
   event_sender: Option<tokio::sync::mpsc::Sender<Event>>,
+  event_stream: tokio::sync::broadcast::Sender<Event>,
   first_index_height: u32,
   genesis_block_coinbase_transaction: Transaction,
   genesis_block_coinbase_txid: Txid,
   height_limit: Option<u32>,
   index_addresses: bool,
   index_inscriptions: bool,
+  index_memory_pool: bool,
+  index_relic_history: bool,
+  index_relic_prices: bool,
   index_relics: bool,
   index_runes: bool,
   index_sats: bool,
   index_transactions: bool,
   path: PathBuf,
+  pending_events: Mutex<Vec<Event>>,
+  /// latest reorg notifications, each with the number of notifications published up to it since
+  /// the index was opened
+  reorgs: Mutex<VecDeque<(usize, Event)>>,
   settings: Settings,
+  mempool_relics: RwLock<MempoolRelics>,
   started: DateTime<Utc>,
   unrecoverably_reorged: AtomicBool,
 }
@@ -291,13 +360,18 @@ impl Index {
     {
       Ok(database) => {
         {
-          let schema_version = database
+          let mut schema_version = database
             .begin_read()?
             .open_table(STATISTIC_TO_COUNT)?
             .get(&Statistic::Schema.key())?
             .map(|x| x.value())
             .unwrap_or(0);
 
+          if schema_version == LEGACY_EVENTS_SCHEMA_VERSION {
+            Self::migrate_events(&database, durability)?;
+            schema_version = SCHEMA_VERSION;
+          }
+
           match schema_version.cmp(&SCHEMA_VERSION) {
             cmp::Ordering::Less =>
               bail!(
@@ -330,24 +404,30 @@ impl Index {
         tx.open_multimap_table(SAT_TO_SEQUENCE_NUMBER)?;
         tx.open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)?;
         tx.open_multimap_table(SEQUENCE_NUMBER_TO_CHILDREN)?;
-        tx.open_multimap_table(RELIC_ID_TO_EVENTS)?;
-        tx.open_multimap_table(TRANSACTION_ID_TO_EVENTS)?;
+        tx.open_multimap_table(TRANSACTION_ID_TO_EVENT_KEYS)?;
+        tx.open_table(EVENT_KEY_TO_EVENT)?;
         tx.open_table(HEIGHT_TO_BLOCK_HEADER)?;
         tx.open_table(HEIGHT_TO_LAST_SEQUENCE_NUMBER)?;
         tx.open_table(HOME_INSCRIPTIONS)?;
         tx.open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?;
         tx.open_table(INSCRIPTION_NUMBER_TO_SEQUENCE_NUMBER)?;
         tx.open_table(OUTPOINT_TO_RELIC_BALANCES)?;
+        tx.open_table(OUTPOINT_TO_RELIC_HISTORY)?;
+        tx.open_table(OUTPOINT_TO_RELIC_SHARES)?;
         tx.open_table(OUTPOINT_TO_RUNE_BALANCES)?;
         tx.open_table(OUTPOINT_TO_UTXO_ENTRY)?;
         tx.open_table(RELIC_TO_SEQUENCE_NUMBER)?;
         tx.open_table(SEQUENCE_NUMBER_TO_SPACED_RELIC)?;
+        tx.open_table(RELIC_ID_TO_EVENT_KEYS)?;
+        tx.open_table(RELIC_ID_TO_HOLDERS)?;
+        tx.open_table(RELIC_ID_TO_POOL_PRICES)?;
         tx.open_table(RELIC_ID_TO_RELIC_ENTRY)?;
         tx.open_table(RELIC_TO_RELIC_ID)?;
         tx.open_table(RELIC_OWNER_TO_CLAIMABLE)?;
         tx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
         tx.open_table(RUNE_TO_RUNE_ID)?;
         tx.open_table(SAT_TO_SATPOINT)?;
+        tx.open_table(SCRIPT_PUBKEY_TO_RELIC_BALANCE)?;
         tx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
         tx.open_table(SEQUENCE_NUMBER_TO_RUNE_ID)?;
         tx.open_table(SEQUENCE_NUMBER_TO_SATPOINT)?;
@@ -376,6 +456,18 @@ impl Index {
             u64::from(settings.index_relics_raw()),
           )?;
 
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexRelicHistory,
+            u64::from(settings.index_relic_history_raw()),
+          )?;
+
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexRelicPrices,
+            u64::from(settings.index_relic_prices_raw()),
+          )?;
+
           Self::set_statistic(
             &mut statistics,
             Statistic::IndexRunes,
@@ -496,6 +588,8 @@ impl Index {
     };
 
     let index_addresses;
+    let index_relic_history;
+    let index_relic_prices;
     let index_relics;
     let index_runes;
     let index_sats;
@@ -507,6 +601,8 @@ impl Index {
       let statistics = tx.open_table(STATISTIC_TO_COUNT)?;
       index_addresses = Self::is_statistic_set(&statistics, Statistic::IndexAddresses)?;
       index_inscriptions = Self::is_statistic_set(&statistics, Statistic::IndexInscriptions)?;
+      index_relic_history = Self::is_statistic_set(&statistics, Statistic::IndexRelicHistory)?;
+      index_relic_prices = Self::is_statistic_set(&statistics, Statistic::IndexRelicPrices)?;
       index_relics = Self::is_statistic_set(&statistics, Statistic::IndexRelics)?;
       index_runes = Self::is_statistic_set(&statistics, Statistic::IndexRunes)?;
       index_sats = Self::is_statistic_set(&statistics, Statistic::IndexSats)?;
@@ -532,22 +628,91 @@ impl Index {
       database,
       durability,
       event_sender,
+      event_stream: tokio::sync::broadcast::channel(EVENT_STREAM_CAPACITY).0,
       first_index_height,
       genesis_block_coinbase_transaction,
       height_limit: settings.height_limit(),
       index_addresses,
+      index_memory_pool: settings.index_memory_pool(),
+      index_relic_history,
+      index_relic_prices,
       index_relics,
       index_runes,
       index_sats,
       index_transactions,
       index_inscriptions,
       settings: settings.clone(),
+      mempool_relics: default(),
       path,
+      pending_events: default(),
+      reorgs: default(),
       started: Utc::now(),
       unrecoverably_reorged: AtomicBool::new(false),
     })
   }
 
+  /// Move the events of an index of `LEGACY_EVENTS_SCHEMA_VERSION` from the multimap tables, which
+  /// stored whole events, to the event key tables
+  fn migrate_events(database: &Database, durability: redb::Durability) -> Result {
+    log::info!("Migrating events to event keys…");
+
+    let mut wtx = database.begin_write()?;
+    wtx.set_durability(durability);
+
+    {
+      let mut event_key_to_event = wtx.open_table(EVENT_KEY_TO_EVENT)?;
+      let mut relic_id_to_event_keys = wtx.open_table(RELIC_ID_TO_EVENT_KEYS)?;
+      let mut transaction_id_to_event_keys =
+        wtx.open_multimap_table(TRANSACTION_ID_TO_EVENT_KEYS)?;
+
+      // every event was stored with its transaction
+      for result in wtx.open_multimap_table(TRANSACTION_ID_TO_EVENTS)?.iter()? {
+        let (txid, events) = result?;
+        for event in events {
+          let event = event?.value();
+          let key = event.cursor().store();
+          event_key_to_event.insert(&key, &event)?;
+          transaction_id_to_event_keys.insert(txid.value(), &key)?;
+        }
+      }
+
+      // the history of each relic was sorted by position in the chain, which numbers it
+      for result in wtx.open_multimap_table(RELIC_ID_TO_EVENTS)?.iter()? {
+        let (id, events) = result?;
+        for (number, event) in (0..).zip(events) {
+          relic_id_to_event_keys.insert((id.value(), number), &event?.value().cursor().store())?;
+        }
+      }
+
+      Self::set_statistic(
+        &mut wtx.open_table(STATISTIC_TO_COUNT)?,
+        Statistic::Schema,
+        SCHEMA_VERSION,
+      )?;
+    }
+
+    wtx.delete_multimap_table(TRANSACTION_ID_TO_EVENTS)?;
+    wtx.delete_multimap_table(RELIC_ID_TO_EVENTS)?;
+
+    // savepoints would restore the legacy tables, so they are replaced by one of the migrated index
+    for savepoint in wtx.list_persistent_savepoints()?.collect::<Vec<u64>>() {
+      wtx.delete_persistent_savepoint(savepoint)?;
+    }
+
+    wtx.commit()?;
+
+    if let redb::Durability::None = durability {
+      return Ok(());
+    }
+
+    let mut wtx = database.begin_write()?;
+    wtx.set_durability(durability);
+    wtx.persistent_savepoint()?;
+    wtx.commit()?;
+
+    Ok(())
+  }
+
   pub fn have_full_utxo_index(&self) -> bool {
     self.first_index_height == 0
   }
@@ -589,6 +754,23 @@ impl Index {
     self.index_relics
   }
 
+  /// Relic balances are aggregated by address if both Relics and addresses are indexed
+  pub fn has_relic_address_index(&self) -> bool {
+    self.index_relics && self.index_addresses
+  }
+
+  pub fn has_relic_history_index(&self) -> bool {
+    self.index_relic_history
+  }
+
+  pub fn has_relic_price_index(&self) -> bool {
+    self.index_relic_prices
+  }
+
+  pub fn has_mempool_relic_index(&self) -> bool {
+    self.index_memory_pool && self.index_relics
+  }
+
   pub fn has_rune_index(&self) -> bool {
     self.index_runes
   }
@@ -760,6 +942,9 @@ impl Index {
         Err(err) => {
           log::info!("{}", err.to_string());
 
+          // events of the aborted write transaction are never streamed
+          self.pending_events.lock().unwrap().clear();
+
           match err.downcast_ref() {
             Some(&reorg::Error::Recoverable { height, depth }) => {
               Reorg::handle_reorg(self, height, depth)?;
@@ -860,6 +1045,197 @@ impl Index {
     Ok(())
   }
 
+  /// Write all Relic entries, Relic balances of unspent outputs and Relic events as `relics.csv`,
+  /// `balances.csv` and `events.csv` to `dir`. Rows are streamed from a single read transaction.
+  pub fn export_relics(&self, dir: &Path) -> Result {
+    fs::create_dir_all(dir)?;
+
+    let rtx = self.database.begin_read()?;
+
+    log::info!("exporting relics to {}", dir.display());
+
+    let relic_id_to_relic_entry = rtx.open_table(RELIC_ID_TO_RELIC_ENTRY)?;
+
+    let mut writer = csv::Writer::from_path(dir.join("relics.csv"))?;
+
+    writer.write_record(RELIC_EXPORT_HEADER)?;
+
+    for result in relic_id_to_relic_entry.iter()? {
+      let (id, entry) = result?;
+      writer.write_record(Self::relic_export_record(
+        RelicId::load(id.value()),
+        &RelicEntry::load(entry.value()),
+      )?)?;
+    }
+
+    writer.flush()?;
+
+    let outpoint_to_utxo_entry = rtx.open_table(OUTPOINT_TO_UTXO_ENTRY)?;
+
+    let mut spaced_relics = HashMap::new();
+    let mut writer = csv::Writer::from_path(dir.join("balances.csv"))?;
+
+    writer.write_record(["outpoint", "address", "id", "relic", "amount"])?;
+
+    for result in rtx.open_table(OUTPOINT_TO_RELIC_BALANCES)?.iter()? {
+      let (outpoint, balances_buffer) = result?;
+      let outpoint = OutPoint::load(*outpoint.value());
+
+      let script_pubkey = if self.index_addresses {
+        outpoint_to_utxo_entry
+          .get(&outpoint.store())?
+          .map(|entry| ScriptBuf::from_bytes(entry.value().parse(self).script_pubkey().to_vec()))
+      } else {
+        self
+          .get_transaction(outpoint.txid)?
+          .and_then(|tx| tx.output.into_iter().nth(outpoint.vout.try_into().unwrap()))
+          .map(|output| output.script_pubkey)
+      };
+
+      let address = script_pubkey
+        .and_then(|script_pubkey| {
+          self
+            .settings
+            .chain()
+            .address_from_script(&script_pubkey)
+            .ok()
+        })
+        .map(|address| address.to_string())
+        .unwrap_or_default();
+
+      let balances_buffer = balances_buffer.value();
+      let mut i = 0;
+      while i < balances_buffer.len() {
+        let ((id, amount), length) = Index::decode_rune_balance(&balances_buffer[i..]).unwrap();
+        i += length;
+
+        let spaced_relic = match spaced_relics.get(&id) {
+          Some(spaced_relic) => *spaced_relic,
+          None => {
+            let spaced_relic = relic_id_to_relic_entry
+              .get(&id.store())?
+              .map(|entry| RelicEntry::load(entry.value()).spaced_relic)
+              .with_context(|| format!("missing entry for relic {id}"))?;
+            spaced_relics.insert(id, spaced_relic);
+            spaced_relic
+          }
+        };
+
+        writer.write_record([
+          outpoint.to_string(),
+          address.clone(),
+          id.to_string(),
+          spaced_relic.to_string(),
+          amount.to_string(),
+        ])?;
+      }
+
+      if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+        break;
+      }
+    }
+
+    writer.flush()?;
+
+    let mut writer = csv::Writer::from_path(dir.join("events.csv"))?;
+
+    writer.write_record([
+      "relic_id",
+      "block_height",
+      "event_index",
+      "txid",
+      "kind",
+      "address",
+      "info",
+    ])?;
+
+    let event_key_to_event = rtx.open_table(EVENT_KEY_TO_EVENT)?;
+
+    for result in rtx.open_table(RELIC_ID_TO_EVENT_KEYS)?.iter()? {
+      let (key, event_key) = result?;
+      let (id, _number) = key.value();
+      let event = Self::load_event(&event_key_to_event, event_key.value())?;
+
+      writer.write_record([
+        RelicId::load(id).to_string(),
+        event.block_height.to_string(),
+        event.event_index.to_string(),
+        event.txid.to_string(),
+        event.kind().to_string(),
+        event
+          .address()
+          .map(|address| address.clone().assume_checked().to_string())
+          .unwrap_or_default(),
+        serde_json::to_string(&event.info)?,
+      ])?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+  }
+
+  /// Row of `relics.csv`, optional fields that are not set are left empty
+  fn relic_export_record(
+    id: RelicId,
+    entry: &RelicEntry,
+  ) -> Result<[String; RELIC_EXPORT_HEADER.len()]> {
+    fn field<T: ToString>(value: Option<T>) -> String {
+      value.map(|value| value.to_string()).unwrap_or_default()
+    }
+
+    let terms = entry.mint_terms;
+    let price = terms.and_then(|terms| terms.price);
+    let pool = entry.pool;
+
+    Ok([
+      id.to_string(),
+      entry.spaced_relic.to_string(),
+      entry.number.to_string(),
+      entry.block.to_string(),
+      entry.enshrining.to_string(),
+      entry.timestamp.to_string(),
+      field(entry.symbol),
+      entry.fee.to_string(),
+      field(entry.owner_sequence_number),
+      field(terms.and_then(|terms| terms.amount)),
+      field(terms.and_then(|terms| terms.cap)),
+      field(terms.and_then(|terms| terms.block_cap)),
+      field(terms.and_then(|terms| terms.tx_cap)),
+      field(terms.and_then(|terms| terms.max_unmints)),
+      field(match price {
+        Some(PriceModel::Fixed(price)) => Some(price),
+        _ => None,
+      }),
+      field(match price {
+        Some(PriceModel::Formula { a, .. }) => Some(a),
+        _ => None,
+      }),
+      field(match price {
+        Some(PriceModel::Formula { b, .. }) => Some(b),
+        _ => None,
+      }),
+      field(terms.and_then(|terms| terms.seed)),
+      entry
+        .boost_terms
+        .map(|boost_terms| serde_json::to_string(&boost_terms))
+        .transpose()?
+        .unwrap_or_default(),
+      entry.state.burned.to_string(),
+      entry.state.mints.to_string(),
+      entry.state.unmints.to_string(),
+      entry.state.paid.to_string(),
+      entry.state.issued.to_string(),
+      field(pool.map(|pool| pool.base_supply)),
+      field(pool.map(|pool| pool.quote_supply)),
+      field(pool.map(|pool| pool.fee_bps)),
+      field(pool.map(|pool| pool.subsidy)),
+      field(pool.map(|pool| pool.shares)),
+      field(pool.map(|pool| pool.locked_shares)),
+      field(pool.map(|pool| pool.provider_fees)),
+    ])
+  }
+
   fn begin_read(&self) -> Result<rtx::Rtx> {
     Ok(rtx::Rtx(self.database.begin_read()?))
   }
@@ -1041,6 +1417,7 @@ impl Index {
     }
   }
 
+  /// events of the relic from new to old, `page_size + 1` events are returned if there are more
   pub fn events_for_relic(
     &self,
     relic: Relic,
@@ -1057,28 +1434,226 @@ impl Index {
       return Ok(None);
     };
 
-    let events = rtx
-      .open_multimap_table(RELIC_ID_TO_EVENTS)?
-      .get(id)?
+    let event_key_to_event = rtx.open_table(EVENT_KEY_TO_EVENT)?;
+    let relic_id_to_event_keys = rtx.open_table(RELIC_ID_TO_EVENT_KEYS)?;
+
+    // events are numbered in chain order, so the page is found by the numbers of its events
+    let skipped = u64::try_from(page_index.saturating_mul(page_size)).unwrap_or(u64::MAX);
+    let Some(newest) = u64::from(Self::relic_event_count(&relic_id_to_event_keys, id)?)
+      .checked_sub(skipped)
+      .and_then(|count| count.checked_sub(1))
+    else {
+      return Ok(Some(Vec::new()));
+    };
+    let newest = u32::try_from(newest).unwrap();
+    let oldest = newest.saturating_sub(u32::try_from(page_size).unwrap_or(u32::MAX));
+
+    let events = relic_id_to_event_keys
+      .range((id, oldest)..=(id, newest))?
       .rev()
-      .skip(page_index * page_size)
+      .map(|result| Self::load_event(&event_key_to_event, result?.1.value()))
+      .collect::<Result<Vec<Event>>>()?;
+
+    Ok(Some(events))
+  }
+
+  /// events of the relic after the cursor from old to new, `page_size + 1` events are returned if
+  /// there are more
+  pub fn events_for_relic_after(
+    &self,
+    relic: Relic,
+    after: EventCursor,
+    page_size: usize,
+  ) -> Result<Option<Vec<Event>>> {
+    let rtx = self.database.begin_read()?;
+
+    let Some(id) = rtx
+      .open_table(RELIC_TO_RELIC_ID)?
+      .get(relic.0)?
+      .map(|guard| guard.value())
+    else {
+      return Ok(None);
+    };
+
+    let event_key_to_event = rtx.open_table(EVENT_KEY_TO_EVENT)?;
+    let relic_id_to_event_keys = rtx.open_table(RELIC_ID_TO_EVENT_KEYS)?;
+
+    let after = after.store();
+
+    // event keys increase with the event numbers, so the first event after the cursor is found by
+    // binary search
+    let mut low = 0;
+    let mut high = Self::relic_event_count(&relic_id_to_event_keys, id)?;
+    while low < high {
+      let middle = low + (high - low) / 2;
+      let key = *relic_id_to_event_keys
+        .get((id, middle))?
+        .with_context(|| format!("missing event {middle} of relic {}", RelicId::load(id)))?
+        .value();
+      if key <= after {
+        low = middle + 1;
+      } else {
+        high = middle;
+      }
+    }
+
+    let events = relic_id_to_event_keys
+      .range((id, low)..=(id, u32::MAX))?
       .take(page_size.saturating_add(1))
-      .map(|result| result.map(|entry| entry.value()).map_err(|err| err.into()))
+      .map(|result| Self::load_event(&event_key_to_event, result?.1.value()))
       .collect::<Result<Vec<Event>>>()?;
 
     Ok(Some(events))
   }
 
-  pub fn events_for_tx(&self, txid: Txid) -> Result<Vec<Event>> {
+  /// number of events in the history of the relic
+  fn relic_event_count(
+    relic_id_to_event_keys: &impl ReadableTable<(RelicIdValue, u32), &'static EventKeyValue>,
+    id: RelicIdValue,
+  ) -> Result<u32> {
+    Ok(
+      relic_id_to_event_keys
+        .range((id, 0)..=(id, u32::MAX))?
+        .next_back()
+        .transpose()?
+        .map(|(key, _)| key.value().1 + 1)
+        .unwrap_or_default(),
+    )
+  }
+
+  fn load_event(
+    event_key_to_event: &impl ReadableTable<&'static EventKeyValue, EventValue>,
+    key: &EventKeyValue,
+  ) -> Result<Event> {
+    Ok(
+      event_key_to_event
+        .get(key)?
+        .with_context(|| format!("missing event {}", EventCursor::load(*key)))?
+        .value(),
+    )
+  }
+
+  /// receive events as soon as the write transaction they were emitted in is committed
+  pub fn subscribe_events(&self) -> tokio::sync::broadcast::Receiver<Event> {
+    self.event_stream.subscribe()
+  }
+
+  /// like `subscribe_events`, but also returns the number of reorgs published before subscribing
+  pub(crate) fn subscribe_events_and_reorgs(
+    &self,
+  ) -> (tokio::sync::broadcast::Receiver<Event>, usize) {
+    let reorgs = self.reorgs.lock().unwrap();
+    (
+      self.event_stream.subscribe(),
+      reorgs.back().map_or(0, |(count, _)| *count),
+    )
+  }
+
+  /// reorg notifications published after the first `count` ones, lagging subscribers may have
+  /// missed them, and the number of notifications published so far
+  pub(crate) fn reorgs_since(&self, count: usize) -> (usize, Vec<Event>) {
+    let reorgs = self.reorgs.lock().unwrap();
+    (
+      reorgs.back().map_or(0, |(count, _)| *count),
+      reorgs
+        .iter()
+        .filter(|(published, _)| *published > count)
+        .map(|(_, event)| event.clone())
+        .collect(),
+    )
+  }
+
+  fn publish_events(&self) {
+    for event in self.pending_events.lock().unwrap().drain(..) {
+      // sending only fails if there are no subscribers
+      self.event_stream.send(event).ok();
+    }
+  }
+
+  /// notify event consumers that the blocks `first_height..=last_height` were rolled back
+  pub(crate) fn publish_reorg(&self, first_height: u32, last_height: u32) -> Result {
+    let event = Event::reorg(first_height, last_height);
+    if let Some(sender) = &self.event_sender {
+      sender.blocking_send(event.clone())?;
+    }
+    let mut reorgs = self.reorgs.lock().unwrap();
+    let count = reorgs.back().map_or(0, |(count, _)| *count) + 1;
+    reorgs.push_back((count, event.clone()));
+    if reorgs.len() > REORGS_KEPT {
+      // the oldest notification is merged into the next one, which then covers the blocks rolled
+      // back by both
+      let (_, oldest) = reorgs.pop_front().unwrap();
+      let (count, next) = reorgs.pop_front().unwrap();
+      let (
+        EventInfo::Reorg {
+          first_height: oldest_first,
+          last_height: oldest_last,
+        },
+        EventInfo::Reorg {
+          first_height,
+          last_height,
+        },
+      ) = (oldest.info, next.info)
+      else {
+        unreachable!()
+      };
+      reorgs.push_front((
+        count,
+        Event::reorg(first_height.min(oldest_first), last_height.max(oldest_last)),
+      ));
+    }
+    self.event_stream.send(event).ok();
+    Ok(())
+  }
+
+  /// events emitted in the block at the given height, in emission order
+  pub fn events_for_block(&self, height: u32) -> Result<Option<Vec<Event>>> {
     let rtx = self.database.begin_read()?;
 
+    if rtx
+      .open_table(HEIGHT_TO_BLOCK_HEADER)?
+      .get(height)?
+      .is_none()
+    {
+      return Ok(None);
+    }
+
+    let first = EventCursor {
+      block_height: height,
+      event_index: 0,
+    }
+    .store();
+
+    let last = EventCursor {
+      block_height: height,
+      event_index: u32::MAX,
+    }
+    .store();
+
     let events = rtx
-      .open_multimap_table(TRANSACTION_ID_TO_EVENTS)?
-      .get(&txid.store())?
-      .map(|result| result.map(|entry| entry.value()).map_err(|err| err.into()))
+      .open_table(EVENT_KEY_TO_EVENT)?
+      .range::<&EventKeyValue>(&first..=&last)?
+      .map(|result| {
+        result
+          .map(|(_key, event)| event.value())
+          .map_err(|err| err.into())
+      })
       .collect::<Result<Vec<Event>>>()?;
 
-    Ok(events)
+    Ok(Some(events))
+  }
+
+  /// events emitted by the transaction, in emission order
+  pub fn events_for_tx(&self, txid: Txid) -> Result<Vec<Event>> {
+    let rtx = self.database.begin_read()?;
+
+    let event_key_to_event = rtx.open_table(EVENT_KEY_TO_EVENT)?;
+
+    rtx
+      .open_multimap_table(TRANSACTION_ID_TO_EVENT_KEYS)?
+      .get(&txid.store())?
+      .map(|result| Self::load_event(&event_key_to_event, result?.value()))
+      .collect()
   }
 
   pub fn relic(
@@ -1413,6 +1988,37 @@ impl Index {
     Ok(balances)
   }
 
+  /// pool shares held by `outpoint`, by the Relic of the pool
+  pub fn get_relic_shares_for_outpoint(
+    &self,
+    outpoint: OutPoint,
+  ) -> Result<BTreeMap<SpacedRelic, u128>> {
+    let rtx = self.database.begin_read()?;
+
+    let outpoint_to_shares = rtx.open_table(OUTPOINT_TO_RELIC_SHARES)?;
+
+    let id_to_relic_entries = rtx.open_table(RELIC_ID_TO_RELIC_ENTRY)?;
+
+    let Some(shares) = outpoint_to_shares.get(&outpoint.store())? else {
+      return Ok(BTreeMap::new());
+    };
+
+    let shares_buffer = shares.value();
+
+    let mut shares = BTreeMap::new();
+    let mut i = 0;
+    while i < shares_buffer.len() {
+      let ((id, amount), length) = Index::decode_rune_balance(&shares_buffer[i..]).unwrap();
+      i += length;
+
+      let entry = RelicEntry::load(id_to_relic_entries.get(id.store())?.unwrap().value());
+
+      shares.insert(entry.spaced_relic, amount);
+    }
+
+    Ok(shares)
+  }
+
   pub fn get_relic_balance_map(&self) -> Result<BTreeMap<SpacedRelic, BTreeMap<OutPoint, Pile>>> {
     let outpoint_balances = self.get_relic_balances()?;
 
@@ -1464,18 +2070,348 @@ impl Index {
       );
     }
 
-    Ok(relic_balances)
+    Ok(relic_balances)
+  }
+
+  pub fn get_relic_balances(&self) -> Result<Vec<(OutPoint, Vec<(RelicId, u128)>)>> {
+    self.get_outpoint_relic_amounts(OUTPOINT_TO_RELIC_BALANCES)
+  }
+
+  /// pool shares held by outpoints, by the Relic of the pool
+  pub fn get_relic_shares(&self) -> Result<Vec<(OutPoint, Vec<(RelicId, u128)>)>> {
+    self.get_outpoint_relic_amounts(OUTPOINT_TO_RELIC_SHARES)
+  }
+
+  pub fn get_relic_share_map(&self) -> Result<BTreeMap<SpacedRelic, BTreeMap<OutPoint, u128>>> {
+    let outpoint_shares = self.get_relic_shares()?;
+
+    let rtx = self.database.begin_read()?;
+
+    let relic_id_to_relic_entry = rtx.open_table(RELIC_ID_TO_RELIC_ENTRY)?;
+
+    let mut relic_shares: BTreeMap<SpacedRelic, BTreeMap<OutPoint, u128>> = BTreeMap::new();
+
+    for (outpoint, shares) in outpoint_shares {
+      for (relic_id, amount) in shares {
+        let spaced_relic = RelicEntry::load(
+          relic_id_to_relic_entry
+            .get(&relic_id.store())?
+            .unwrap()
+            .value(),
+        )
+        .spaced_relic;
+        relic_shares
+          .entry(spaced_relic)
+          .or_default()
+          .insert(outpoint, amount);
+      }
+    }
+
+    Ok(relic_shares)
+  }
+
+  /// Simulate a swap against the current pool states, using the same calculation as the indexer.
+  /// The input balance is not checked.
+  pub fn quote_relic_swap(&self, swap: &Swap) -> Result<api::RelicSwapQuote> {
+    let rtx = self.database.begin_read()?;
+    let relic_id_to_relic_entry = rtx.open_table(RELIC_ID_TO_RELIC_ENTRY)?;
+
+    Self::quote_swap(swap, |id| {
+      Ok(
+        relic_id_to_relic_entry
+          .get(&id.store())?
+          .map(|entry| RelicEntry::load(entry.value())),
+      )
+    })
+  }
+
+  /// quote a swap against the pool states after all pending Relic transactions
+  pub fn quote_mempool_relic_swap(&self, swap: &Swap) -> Result<api::RelicSwapQuote> {
+    let mempool_relics = self.mempool_relics.read().unwrap();
+    let rtx = self.database.begin_read()?;
+    let relic_id_to_relic_entry = rtx.open_table(RELIC_ID_TO_RELIC_ENTRY)?;
+
+    Self::quote_swap(swap, |id| {
+      if let Some(entry) = mempool_relics.relic_entry(id) {
+        return Ok(Some(entry));
+      }
+      Ok(
+        relic_id_to_relic_entry
+          .get(&id.store())?
+          .map(|entry| RelicEntry::load(entry.value())),
+      )
+    })
+  }
+
+  fn quote_swap(
+    swap: &Swap,
+    load_relic_entry: impl Fn(RelicId) -> Result<Option<RelicEntry>>,
+  ) -> Result<api::RelicSwapQuote> {
+    let mut quote = api::RelicSwapQuote {
+      input: swap.input.unwrap_or(RELIC_ID),
+      output: swap.output.unwrap_or(RELIC_ID),
+      input_amount: 0,
+      output_amount: 0,
+      legs: Vec::new(),
+      error: None,
+    };
+
+    let diffs = match relics_swap::calculate(swap, None, &load_relic_entry)? {
+      Ok(diffs) => diffs,
+      Err(error) => {
+        quote.error = Some(error);
+        return Ok(quote);
+      }
+    };
+
+    // replay the swap on the pools to find the price impact of every leg
+    let mut pools: HashMap<RelicId, Pool> = HashMap::new();
+    for (relic_id, diff) in &diffs {
+      if !pools.contains_key(relic_id) {
+        pools.insert(
+          *relic_id,
+          load_relic_entry(*relic_id)?.unwrap().pool.unwrap(),
+        );
+      }
+      let pool = pools.get_mut(relic_id).unwrap();
+      let price_before = pool.lossy_price();
+      pool.apply(*diff);
+      let price_after = pool.lossy_price();
+      quote.legs.push(api::RelicSwapLeg {
+        relic_id: *relic_id,
+        direction: diff.direction,
+        input: diff.input,
+        output: diff.output,
+        fee: diff.fee,
+        price_before,
+        price_after,
+        price_impact_bps: price_after
+          .abs_diff(price_before)
+          .saturating_mul(10_000)
+          .checked_div(price_before)
+          .unwrap_or_default(),
+      });
+    }
+
+    quote.input_amount = diffs.first().unwrap().1.input;
+    quote.output_amount = diffs.last().unwrap().1.output;
+
+    Ok(quote)
+  }
+
+  /// simulate the Relic transactions currently in the memory pool on top of the index, unless
+  /// neither changed since the last simulation
+  pub fn update_mempool_relics(&self) -> Result {
+    let mempool_relics = MempoolRelics::update(self, &self.mempool_relics.read().unwrap())?;
+    if let Some(mempool_relics) = mempool_relics {
+      *self.mempool_relics.write().unwrap() = mempool_relics;
+    }
+    Ok(())
+  }
+
+  pub fn get_mempool_relics(&self) -> api::MempoolRelics {
+    self.mempool_relics.read().unwrap().relics().clone()
+  }
+
+  pub fn get_mempool_relic_transaction(&self, txid: Txid) -> Option<api::MempoolRelicTransaction> {
+    self
+      .mempool_relics
+      .read()
+      .unwrap()
+      .transaction(txid)
+      .cloned()
+  }
+
+  pub fn get_mempool_relic_output(&self, outpoint: OutPoint) -> Result<api::MempoolRelicOutput> {
+    self.mempool_relics.read().unwrap().output(self, outpoint)
+  }
+
+  /// pool supplies after every change of the pool of the given Relic, in chain order
+  pub fn get_relic_pool_prices(&self, relic_id: RelicId) -> Result<Vec<PoolPrice>> {
+    let id = relic_id.store();
+    self
+      .database
+      .begin_read()?
+      .open_table(RELIC_ID_TO_POOL_PRICES)?
+      .range((id, 0, 0)..=(id, u32::MAX, u32::MAX))?
+      .map(|entry| Ok(PoolPrice::load(entry?.1.value())))
+      .collect()
+  }
+
+  /// Relic balances of the outputs unspent at the end of block `height`, aggregated by script
+  /// pubkey if `by_address` is set. Returns the balances of the first `page_size` outputs, or script
+  /// pubkeys, after `after` and the cursor of the next page.
+  ///
+  /// Pages of outputs are read from the history in key order starting after the cursor. Aggregating
+  /// by address reads every output, but only keeps the totals of the script pubkeys on the page.
+  pub fn get_relic_snapshot(
+    &self,
+    height: u32,
+    by_address: bool,
+    page_size: usize,
+    after: Option<&api::RelicSnapshotCursor>,
+  ) -> Result<api::RelicSnapshot> {
+    let rtx = self.database.begin_read()?;
+
+    let relic_id_to_relic_entry = rtx.open_table(RELIC_ID_TO_RELIC_ENTRY)?;
+
+    let outpoint_to_relic_history = rtx.open_table(OUTPOINT_TO_RELIC_HISTORY)?;
+
+    let after_outpoint = match after {
+      Some(api::RelicSnapshotCursor::Output(outpoint)) if !by_address => Some(outpoint.store()),
+      Some(api::RelicSnapshotCursor::ScriptPubkey(_)) if by_address => None,
+      Some(cursor) => bail!("snapshot cursor {cursor} does not match the snapshot"),
+      None => None,
+    };
+
+    let unspent_balances = outpoint_to_relic_history
+      .range::<&OutPointValue>((
+        after_outpoint
+          .as_ref()
+          .map_or(Bound::Unbounded, Bound::Excluded),
+        Bound::Unbounded,
+      ))?
+      .map(
+        |entry| -> Result<Option<(OutPoint, ScriptBuf, Vec<(RelicId, u128)>)>> {
+          let (outpoint, record) = entry?;
+          let (created, spent, script_pubkey, balances_buffer) = record.value();
+
+          if created > height || spent.is_some_and(|spent| spent <= height) {
+            return Ok(None);
+          }
+
+          let mut balances = Vec::new();
+          let mut i = 0;
+          while i < balances_buffer.len() {
+            let ((id, amount), length) = Index::decode_rune_balance(&balances_buffer[i..]).unwrap();
+            i += length;
+            balances.push((id, amount));
+          }
+
+          Ok(Some((
+            OutPoint::load(*outpoint.value()),
+            ScriptBuf::from_bytes(script_pubkey.to_vec()),
+            balances,
+          )))
+        },
+      );
+
+    let mut outputs = Vec::new();
+    let mut next = None;
+
+    if by_address {
+      let after_script_pubkey = match after {
+        Some(api::RelicSnapshotCursor::ScriptPubkey(script_pubkey)) => Some(script_pubkey),
+        _ => None,
+      };
+
+      // totals of the first `page_size` script pubkeys after the cursor and of the one after them,
+      // which starts the next page, script pubkeys from `bound` on are beyond the page
+      let mut totals: BTreeMap<ScriptBuf, BTreeMap<RelicId, u128>> = BTreeMap::new();
+      let mut bound: Option<ScriptBuf> = None;
+
+      for unspent in unspent_balances {
+        let Some((_outpoint, script_pubkey, balances)) = unspent? else {
+          continue;
+        };
+
+        if after_script_pubkey.is_some_and(|after| script_pubkey <= *after)
+          || bound.as_ref().is_some_and(|bound| script_pubkey >= *bound)
+        {
+          continue;
+        }
+
+        let relics = totals.entry(script_pubkey).or_default();
+        for (id, amount) in balances {
+          let total = relics.entry(id).or_default();
+          *total = total.saturating_add(amount);
+        }
+
+        if totals.len() > page_size.saturating_add(1) {
+          bound = totals.pop_last().map(|(script_pubkey, _)| script_pubkey);
+        }
+      }
+
+      if totals.len() > page_size {
+        totals.pop_last();
+        next = totals
+          .last_key_value()
+          .map(|(script_pubkey, _)| api::RelicSnapshotCursor::ScriptPubkey(script_pubkey.clone()));
+      }
+
+      for (script_pubkey, relics) in totals {
+        for (id, amount) in relics {
+          outputs.push((OutPoint::null(), script_pubkey.clone(), id, amount));
+        }
+      }
+    } else {
+      let mut count = 0;
+
+      for unspent in unspent_balances {
+        let Some((outpoint, script_pubkey, balances)) = unspent? else {
+          continue;
+        };
+
+        if count == page_size {
+          next = outputs
+            .last()
+            .map(|(outpoint, _, _, _)| api::RelicSnapshotCursor::Output(*outpoint));
+          break;
+        }
+
+        count += 1;
+
+        for (id, amount) in balances {
+          outputs.push((outpoint, script_pubkey.clone(), id, amount));
+        }
+      }
+    }
+
+    let mut spaced_relics = HashMap::new();
+    let mut balances = Vec::new();
+
+    for (outpoint, script_pubkey, id, amount) in outputs {
+      let spaced_relic = match spaced_relics.get(&id) {
+        Some(spaced_relic) => *spaced_relic,
+        None => {
+          let spaced_relic = relic_id_to_relic_entry
+            .get(&id.store())?
+            .map(|entry| RelicEntry::load(entry.value()).spaced_relic)
+            .with_context(|| format!("missing entry for relic {id}"))?;
+          spaced_relics.insert(id, spaced_relic);
+          spaced_relic
+        }
+      };
+
+      balances.push(api::RelicSnapshotBalance {
+        outpoint: (!by_address).then_some(outpoint),
+        address: self
+          .settings
+          .chain()
+          .address_from_script(&script_pubkey)
+          .ok()
+          .map(|address| uncheck(&address)),
+        script_pubkey,
+        id,
+        spaced_relic,
+        amount,
+      });
+    }
+
+    Ok(api::RelicSnapshot {
+      height,
+      balances,
+      next,
+    })
   }
 
-  pub fn get_relic_balances(&self) -> Result<Vec<(OutPoint, Vec<(RelicId, u128)>)>> {
+  fn get_outpoint_relic_amounts(
+    &self,
+    table: TableDefinition<&'static OutPointValue, &'static [u8]>,
+  ) -> Result<Vec<(OutPoint, Vec<(RelicId, u128)>)>> {
     let mut result = Vec::new();
 
-    for entry in self
-      .database
-      .begin_read()?
-      .open_table(OUTPOINT_TO_RELIC_BALANCES)?
-      .iter()?
-    {
+    for entry in self.database.begin_read()?.open_table(table)?.iter()? {
       let (outpoint, balances_buffer) = entry?;
       let outpoint = OutPoint::load(*outpoint.value());
       let balances_buffer = balances_buffer.value();
@@ -1494,6 +2430,174 @@ impl Index {
     Ok(result)
   }
 
+  /// Check an offer created with `ord wallet offer create`, and accepted with `ord wallet offer
+  /// accept` if it has more than one input, against the current Relic balances of its inputs
+  pub fn validate_relic_offer(&self, psbt: &Psbt) -> Result<api::RelicOfferValidation> {
+    let rtx = self.database.begin_read()?;
+
+    let outpoint_to_balances = rtx.open_table(OUTPOINT_TO_RELIC_BALANCES)?;
+    let id_to_relic_entries = rtx.open_table(RELIC_ID_TO_RELIC_ENTRY)?;
+
+    let mut entries = HashMap::new();
+    let mut piles = |balances: &BTreeMap<RelicId, u128>| -> Result<BTreeMap<SpacedRelic, Pile>> {
+      let mut piles = BTreeMap::new();
+      for (id, amount) in balances {
+        let entry = match entries.get(id) {
+          Some(entry) => *entry,
+          None => {
+            let entry = RelicEntry::load(
+              id_to_relic_entries
+                .get(id.store())?
+                .with_context(|| format!("relic {id} not found"))?
+                .value(),
+            );
+            entries.insert(*id, entry);
+            entry
+          }
+        };
+        piles.insert(
+          entry.spaced_relic,
+          Pile {
+            amount: *amount,
+            divisibility: Enshrining::DIVISIBILITY,
+            symbol: entry.symbol,
+          },
+        );
+      }
+      Ok(piles)
+    };
+
+    let mut errors = Vec::new();
+
+    let price = RelicAllocation::offer(&psbt.proprietary)?;
+
+    if price.is_none() {
+      errors.push("PSBT is not a relic offer".to_string());
+    }
+
+    // the signature of the seller doesn't commit to the keepsake of the accepting transaction,
+    // which could simply not pay a relic price
+    if price.as_ref().is_some_and(|price| !price.is_empty()) {
+      errors.push("offer is priced in relics, only bitcoin prices can be enforced".to_string());
+    }
+
+    let mut inputs = Vec::new();
+    let mut balances = Vec::new();
+
+    for (txin, input) in psbt.unsigned_tx.input.iter().zip(&psbt.inputs) {
+      let outpoint = txin.previous_output;
+
+      let input_balances = outpoint_to_balances
+        .get(&outpoint.store())?
+        .map(|buffer| RelicAllocation::decode(buffer.value()))
+        .transpose()?
+        .unwrap_or_default();
+
+      let spent = self.is_output_spent(outpoint)?;
+
+      // the balances of spent outputs are no longer indexed, so they can't be compared
+      if spent {
+        errors.push(format!("input {outpoint} is spent"));
+      } else if RelicAllocation::annotation(&input.proprietary)?.as_ref() != Some(&input_balances) {
+        errors.push(format!(
+          "relic balances of input {outpoint} do not match the PSBT annotation"
+        ));
+      }
+
+      inputs.push(api::RelicOfferInput {
+        outpoint,
+        spent,
+        relics: piles(&input_balances)?,
+      });
+
+      balances.push(input_balances);
+    }
+
+    // pool shares don't change how the Relics of the inputs are allocated
+    let allocation = RelicAllocation::new(
+      &psbt.unsigned_tx,
+      &balances,
+      &[],
+      self.settings.chain().network(),
+      self.block_count()?,
+    );
+
+    let accepted = psbt.unsigned_tx.input.len() > 1;
+
+    if accepted {
+      for (vout, (output, expected)) in psbt.outputs.iter().zip(&allocation.outputs).enumerate() {
+        if RelicAllocation::annotation(&output.proprietary)?.as_ref() != Some(expected) {
+          errors.push(format!(
+            "relic allocation of output {vout} does not match the PSBT annotation"
+          ));
+        }
+      }
+    }
+
+    Ok(api::RelicOfferValidation {
+      txid: psbt.unsigned_tx.compute_txid(),
+      accepted,
+      valid: errors.is_empty(),
+      errors,
+      price: piles(&price.unwrap_or_default())?,
+      inputs,
+      outputs: allocation
+        .outputs
+        .iter()
+        .map(&mut piles)
+        .collect::<Result<Vec<BTreeMap<SpacedRelic, Pile>>>>()?,
+      burned: piles(&allocation.burned)?,
+    })
+  }
+
+  /// Audit the supply of every Relic against the balances, pools and claimable amounts, and the
+  /// shares of every pool against the shares held by outputs, read in a single transaction
+  pub fn audit_relic_supply(&self) -> Result<Vec<RelicSupplyAudit>> {
+    let rtx = self.begin_read()?;
+
+    let mut entries = Vec::new();
+    for result in rtx.0.open_table(RELIC_ID_TO_RELIC_ENTRY)?.iter()? {
+      let (id, entry) = result?;
+      entries.push((RelicId::load(id.value()), RelicEntry::load(entry.value())));
+    }
+
+    let mut balances: HashMap<RelicId, u128> = HashMap::new();
+    for result in rtx.0.open_table(OUTPOINT_TO_RELIC_BALANCES)?.iter()? {
+      let (_outpoint, balances_buffer) = result?;
+      let balances_buffer = balances_buffer.value();
+      let mut i = 0;
+      while i < balances_buffer.len() {
+        let ((id, amount), length) = Index::decode_rune_balance(&balances_buffer[i..]).unwrap();
+        i += length;
+        let balance = balances.entry(id).or_default();
+        *balance = balance.saturating_add(amount);
+      }
+    }
+
+    let mut shares: HashMap<RelicId, u128> = HashMap::new();
+    for result in rtx.0.open_table(OUTPOINT_TO_RELIC_SHARES)?.iter()? {
+      let (_outpoint, shares_buffer) = result?;
+      let shares_buffer = shares_buffer.value();
+      let mut i = 0;
+      while i < shares_buffer.len() {
+        let ((id, amount), length) = Index::decode_rune_balance(&shares_buffer[i..]).unwrap();
+        i += length;
+        let total = shares.entry(id).or_default();
+        *total = total.saturating_add(amount);
+      }
+    }
+
+    let mut claimable = 0u128;
+    for result in rtx.0.open_table(RELIC_OWNER_TO_CLAIMABLE)?.iter()? {
+      let (_owner, amount) = result?;
+      claimable = claimable.saturating_add(amount.value());
+    }
+
+    Ok(RelicSupplyAudit::audit(
+      &entries, &balances, &shares, claimable,
+    ))
+  }
+
   pub fn get_relic_claimable(&self) -> Result<Vec<(RelicOwner, u128)>> {
     let mut result = Vec::new();
 
@@ -1837,6 +2941,25 @@ impl Index {
     Ok(Some(RelicEntry::load(entry.value()).spaced_relic))
   }
 
+  /// Relic ticker sealed by the inscription `inscription_id`
+  pub fn get_sealed_relic(&self, inscription_id: InscriptionId) -> Result<Option<SpacedRelic>> {
+    let rtx = self.database.begin_read()?;
+
+    let Some(sequence_number) = rtx
+      .open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?
+      .get(&inscription_id.store())?
+    else {
+      return Ok(None);
+    };
+
+    Ok(
+      rtx
+        .open_table(SEQUENCE_NUMBER_TO_SPACED_RELIC)?
+        .get(sequence_number.value())?
+        .map(|entry| SpacedRelic::load(entry.value())),
+    )
+  }
+
   pub fn get_etching(&self, txid: Txid) -> Result<Option<SpacedRune>> {
     let rtx = self.database.begin_read()?;
 
@@ -2882,6 +4005,82 @@ impl Index {
     )
   }
 
+  pub(crate) fn get_relic_balances_for_script(
+    &self,
+    script_pubkey: &Script,
+  ) -> Result<Vec<(SpacedRelic, Decimal, Option<char>)>> {
+    let rtx = self.database.begin_read()?;
+
+    let relic_id_to_relic_entry = rtx.open_table(RELIC_ID_TO_RELIC_ENTRY)?;
+
+    let mut balances = Vec::new();
+
+    for result in rtx.open_table(SCRIPT_PUBKEY_TO_RELIC_BALANCE)?.range(
+      (script_pubkey.as_bytes(), (0, 0))..=(script_pubkey.as_bytes(), (u64::MAX, u32::MAX)),
+    )? {
+      let (key, balance) = result?;
+      let (_script_pubkey, id) = key.value();
+      let entry = RelicEntry::load(relic_id_to_relic_entry.get(id)?.unwrap().value());
+      balances.push((
+        entry.spaced_relic,
+        Decimal {
+          value: balance.value(),
+          scale: Enshrining::DIVISIBILITY,
+        },
+        entry.symbol,
+      ));
+    }
+
+    Ok(balances)
+  }
+
+  /// script pubkeys holding the given Relic, sorted by balance in descending order
+  pub fn get_relic_holders(
+    &self,
+    id: RelicId,
+    page_size: usize,
+    page_index: usize,
+  ) -> Result<(Vec<(ScriptBuf, u128)>, bool)> {
+    let next_id = match id.tx.checked_add(1) {
+      Some(tx) => RelicId {
+        block: id.block,
+        tx,
+      },
+      None => RelicId {
+        block: id.block + 1,
+        tx: 0,
+      },
+    };
+
+    let no_script_pubkey: &[u8] = &[];
+
+    let mut holders = self
+      .database
+      .begin_read()?
+      .open_table(RELIC_ID_TO_HOLDERS)?
+      .range((id.store(), 0, no_script_pubkey)..(next_id.store(), 0, no_script_pubkey))?
+      .rev()
+      .skip(page_index.saturating_mul(page_size))
+      .take(page_size.saturating_add(1))
+      .map(|result| {
+        result
+          .map(|(key, _)| {
+            let (_id, balance, script_pubkey) = key.value();
+            (ScriptBuf::from_bytes(script_pubkey.to_vec()), balance)
+          })
+          .map_err(|err| err.into())
+      })
+      .collect::<Result<Vec<(ScriptBuf, u128)>>>()?;
+
+    let more = holders.len() > page_size;
+
+    if more {
+      holders.pop();
+    }
+
+    Ok((holders, more))
+  }
+
   pub(crate) fn get_sat_balances_for_outputs(&self, outputs: &Vec<OutPoint>) -> Result<u64> {
     let outpoint_to_utxo_entry = self
       .database
@@ -2978,6 +4177,7 @@ impl Index {
 
     let relics = self.get_relic_balances_for_outpoint(outpoint)?;
     let runes = self.get_rune_balances_for_output(outpoint)?;
+    let shares = self.get_relic_shares_for_outpoint(outpoint)?;
     let spent = self.is_output_spent(outpoint)?;
 
     Ok(Some((
@@ -2995,6 +4195,7 @@ impl Index {
         runes,
         sat_ranges,
         script_pubkey: txout.script_pubkey.clone(),
+        shares,
         spent,
         transaction: outpoint.txid,
         value: txout.value.to_sat(),
@@ -4045,6 +5246,98 @@ mod tests {
       format!("index at `{}{delimiter}regtest{delimiter}index.redb` appears to have been built with an older, incompatible version of ord, consider deleting and rebuilding the index: index schema 0, ord schema {SCHEMA_VERSION}", path.display()));
   }
 
+  #[test]
+  fn legacy_events_are_migrated() {
+    let (tempdir, relic, blocks, history) = {
+      let context = Context::builder().arg("--index-relics").build();
+
+      context.mint_base_token(1, 1);
+      context.mint_base_token(2, 1);
+      context.mint_base_token(1, 2);
+
+      let relic = context.index.get_relic_by_id(RELIC_ID).unwrap().unwrap();
+      let height = context.index.block_count().unwrap();
+      let blocks = (0..height)
+        .map(|height| context.index.events_for_block(height).unwrap().unwrap())
+        .collect::<Vec<Vec<Event>>>();
+      let history = context.index.events_for_relic(relic, 100, 0).unwrap();
+      assert!(history.as_ref().unwrap().len() > 2);
+
+      // store the events in the legacy tables
+      let wtx = context.index.database.begin_write().unwrap();
+
+      {
+        let mut transaction_id_to_events =
+          wtx.open_multimap_table(TRANSACTION_ID_TO_EVENTS).unwrap();
+        let mut relic_id_to_events = wtx.open_multimap_table(RELIC_ID_TO_EVENTS).unwrap();
+
+        for event in blocks.iter().flatten() {
+          transaction_id_to_events
+            .insert(&event.txid.store(), event)
+            .unwrap();
+          if event.is_relic_history() {
+            relic_id_to_events
+              .insert(event.relic_id().unwrap().store(), event)
+              .unwrap();
+          }
+        }
+
+        wtx
+          .open_table(STATISTIC_TO_COUNT)
+          .unwrap()
+          .insert(&Statistic::Schema.key(), &LEGACY_EVENTS_SCHEMA_VERSION)
+          .unwrap();
+      }
+
+      wtx.delete_table(EVENT_KEY_TO_EVENT).unwrap();
+      wtx.delete_table(RELIC_ID_TO_EVENT_KEYS).unwrap();
+      wtx
+        .delete_multimap_table(TRANSACTION_ID_TO_EVENT_KEYS)
+        .unwrap();
+
+      wtx.commit().unwrap();
+
+      (context.tempdir, relic, blocks, history)
+    };
+
+    let context = Context::builder()
+      .arg("--index-relics")
+      .tempdir(tempdir)
+      .build();
+
+    for (height, events) in (0..).zip(&blocks) {
+      assert_eq!(
+        &context.index.events_for_block(height).unwrap().unwrap(),
+        events
+      );
+      for event in events {
+        assert!(context
+          .index
+          .events_for_tx(event.txid)
+          .unwrap()
+          .contains(event));
+      }
+    }
+
+    assert_eq!(
+      context.index.events_for_relic(relic, 100, 0).unwrap(),
+      history
+    );
+
+    let rtx = context.index.database.begin_read().unwrap();
+    assert_eq!(
+      rtx
+        .open_table(STATISTIC_TO_COUNT)
+        .unwrap()
+        .get(&Statistic::Schema.key())
+        .unwrap()
+        .unwrap()
+        .value(),
+      SCHEMA_VERSION
+    );
+    assert!(rtx.open_multimap_table(RELIC_ID_TO_EVENTS).is_err());
+  }
+
   #[test]
   fn new_schema_gives_correct_error() {
     let tempdir = {
@@ -7062,6 +8355,137 @@ mod tests {
     );
   }
 
+  #[test]
+  fn subscribers_receive_committed_events() {
+    const RUNE: u128 = 99246114928149462;
+
+    let context = Context::builder().arg("--index-runes").build();
+
+    let mut receiver = context.index.subscribe_events();
+
+    let (txid, id) = context.etch(
+      Runestone {
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+    );
+
+    let mut events = Vec::new();
+    while let Ok(event) = receiver.try_recv() {
+      events.push(event);
+    }
+
+    assert!(events.contains(&Event {
+      block_height: 8,
+      event_index: 0,
+      txid,
+      info: EventInfo::RuneEtched { rune_id: id },
+    }));
+
+    assert_eq!(
+      context.index.events_for_block(8).unwrap().unwrap(),
+      events
+        .into_iter()
+        .filter(|event| event.block_height == 8)
+        .collect::<Vec<Event>>()
+    );
+  }
+
+  #[test]
+  fn reorgs_are_published() {
+    let (event_sender, mut event_receiver) = tokio::sync::mpsc::channel(1024);
+    let mut context = Context::builder().event_sender(event_sender).build();
+    context.index.set_durability(redb::Durability::Immediate);
+
+    let mut receiver = context.index.subscribe_events();
+
+    // the tip is indexed separately, so a savepoint from before it can be restored
+    context.mine_blocks(4);
+    context.mine_blocks(1);
+
+    context.core.invalidate_tip();
+    context.mine_blocks(2);
+
+    let reorg = |receiver: &mut dyn FnMut() -> Option<Event>| {
+      let mut reorgs = Vec::new();
+      while let Some(event) = receiver() {
+        if let EventInfo::Reorg { .. } = event.info {
+          reorgs.push(event);
+        }
+      }
+      assert_eq!(reorgs.len(), 1);
+      reorgs.remove(0)
+    };
+
+    let sent = reorg(&mut || event_receiver.try_recv().ok());
+    let streamed = reorg(&mut || receiver.try_recv().ok());
+
+    assert_eq!(sent, streamed);
+
+    let EventInfo::Reorg {
+      first_height,
+      last_height,
+    } = sent.info
+    else {
+      unreachable!()
+    };
+
+    assert!(first_height <= 5);
+    assert_eq!(last_height, 5);
+    assert_eq!(sent.block_height, first_height);
+    assert_eq!(context.index.reorgs_since(0), (1, vec![sent]));
+  }
+
+  #[test]
+  fn mempool_relics_are_simulated_while_the_index_is_written() {
+    let context = Context::builder()
+      .arg("--index-relics")
+      .arg("--index-memory-pool")
+      .build();
+
+    context.mine_blocks(1);
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, Default::default())],
+      ..default()
+    });
+
+    // the simulation does not wait for the write transaction of the indexer
+    let wtx = context.index.begin_write().unwrap();
+    context.index.update_mempool_relics().unwrap();
+    wtx.abort().unwrap();
+
+    assert_eq!(context.index.get_mempool_relics().height, 2);
+    assert!(context.index.get_mempool_relic_transaction(txid).is_none());
+  }
+
+  #[test]
+  fn old_reorgs_are_merged() {
+    let context = Context::builder().build();
+
+    for height in 0..u32::try_from(REORGS_KEPT).unwrap() + 2 {
+      context
+        .index
+        .publish_reorg(height + 10, height + 11)
+        .unwrap();
+    }
+
+    let (count, reorgs) = context.index.reorgs_since(0);
+    assert_eq!(count, REORGS_KEPT + 2);
+    assert_eq!(reorgs.len(), REORGS_KEPT);
+    assert_eq!(reorgs[0], Event::reorg(10, 13));
+    assert_eq!(reorgs[1], Event::reorg(13, 14));
+
+    // a subscriber that missed merged notifications is sent the merged one
+    assert_eq!(context.index.reorgs_since(1), (count, reorgs.clone()));
+    assert_eq!(context.index.reorgs_since(3), (count, reorgs[1..].to_vec()));
+    assert_eq!(context.index.reorgs_since(count), (count, Vec::new()));
+  }
+
   #[test]
   fn rune_event_sender_channel() {
     const RUNE: u128 = 99246114928149462;
@@ -7313,4 +8737,58 @@ mod tests {
     // zero
     assert_eq!(Statistic::Schema.key(), 0);
   }
+
+  #[test]
+  fn relic_export_records_match_the_header() {
+    let entry = RelicEntry {
+      spaced_relic: SpacedRelic {
+        relic: Relic(99246114928149462),
+        spacers: 1,
+      },
+      state: RelicState {
+        burned: 1,
+        mints: 2,
+        unmints: 3,
+        paid: 4,
+        legacy_mints: 0,
+        issued: 12,
+      },
+      pool: Some(Pool {
+        base_supply: 5,
+        quote_supply: 6,
+        fee_bps: 7,
+        subsidy: 8,
+        shares: 9,
+        locked_shares: 10,
+        provider_fees: 11,
+      }),
+      ..default()
+    };
+
+    let record = Index::relic_export_record(RelicId { block: 1, tx: 2 }, &entry).unwrap();
+
+    let column = |name| {
+      record[RELIC_EXPORT_HEADER
+        .iter()
+        .position(|column| *column == name)
+        .unwrap()]
+      .as_str()
+    };
+
+    assert_eq!(column("id"), "1:2");
+    assert_eq!(column("relic"), entry.spaced_relic.to_string());
+    assert_eq!(column("symbol"), "");
+    assert_eq!(column("burned"), "1");
+    assert_eq!(column("mints"), "2");
+    assert_eq!(column("unmints"), "3");
+    assert_eq!(column("paid"), "4");
+    assert_eq!(column("issued"), "12");
+    assert_eq!(column("pool_base_supply"), "5");
+    assert_eq!(column("pool_quote_supply"), "6");
+    assert_eq!(column("pool_fee_bps"), "7");
+    assert_eq!(column("pool_subsidy"), "8");
+    assert_eq!(column("pool_shares"), "9");
+    assert_eq!(column("pool_locked_shares"), "10");
+    assert_eq!(column("pool_provider_fees"), "11");
+  }
 }
@@ -1,5 +1,7 @@
 use {super::*, bitcoin::ScriptHash};
 
+pub use relics_protocol::{PoolPrice, RelicEntry, RelicOwner, RelicState};
+
 impl Entry for Relic {
   type Value = u128;
 
@@ -26,15 +28,6 @@ impl Entry for SpacedRelic {
   }
 }
 
-#[derive(Debug, Hash, Eq, PartialEq, PartialOrd, Ord, Copy, Clone, Serialize, Deserialize)]
-pub struct RelicOwner(pub ScriptHash);
-
-impl Default for RelicOwner {
-  fn default() -> Self {
-    Self(ScriptHash::all_zeros())
-  }
-}
-
 pub type RelicOwnerValue = [u8; 20];
 
 impl Entry for RelicOwner {
@@ -49,234 +42,31 @@ impl Entry for RelicOwner {
   }
 }
 
-#[derive(Debug, Default, PartialEq, Copy, Clone, Serialize, Deserialize)]
-pub struct RelicState {
-  pub burned: u128,
-  pub mints: u128,
-  pub unmints: u128,
-}
-
-pub type RelicStateValue = (u128, u128, u128);
+pub type RelicStateValue = (u128, u128, u128, u128, u128, u128);
 
 impl Entry for RelicState {
   type Value = RelicStateValue;
 
-  fn load((burned, mints, unmints): Self::Value) -> Self {
+  fn load((burned, mints, unmints, paid, legacy_mints, issued): Self::Value) -> Self {
     Self {
       burned,
       mints,
       unmints,
+      paid,
+      legacy_mints,
+      issued,
     }
   }
 
   fn store(self) -> Self::Value {
-    (self.burned, self.mints, self.unmints)
-  }
-}
-
-#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
-pub struct RelicEntry {
-  pub block: u64,
-  pub enshrining: Txid,
-  pub fee: u16,
-  pub number: u64,
-  pub spaced_relic: SpacedRelic,
-  pub symbol: Option<char>,
-  pub owner_sequence_number: Option<u32>,
-  pub boost_terms: Option<BoostTerms>,
-  pub mint_terms: Option<MintTerms>,
-  pub state: RelicState,
-  pub pool: Option<Pool>,
-  pub timestamp: u64,
-}
-
-impl RelicEntry {
-  pub fn mintable(
-    &self,
-    base_balance: u128,
-    num_mints: u8,
-    base_limit: u128,
-  ) -> Result<Vec<(u128, u128)>, RelicError> {
-    let terms = self.mint_terms.ok_or(RelicError::Unmintable)?;
-    if self.is_free() && num_mints > 1 {
-      return Err(RelicError::MaxMintPerTxExceeded(1));
-    }
-    if let Some(max_tx) = terms.tx_cap {
-      if num_mints > max_tx {
-        return Err(RelicError::MaxMintPerTxExceeded(max_tx));
-      }
-    }
-
-    let cap = terms.cap.unwrap_or_default();
-    let current_mints = self.state.mints;
-
-    let remaining = cap.saturating_sub(current_mints); // mints left
-    if remaining == 0 {
-      return Err(RelicError::MintCap(cap)); // none left
-    }
-    #[allow(clippy::cast_possible_truncation)]
-    let actual_mints = remaining.min(u128::from(num_mints).min(u128::from(u8::MAX))) as u8;
-
-    let total_price = terms
-      .compute_total_price(current_mints, actual_mints)
-      .ok_or(RelicError::PriceComputationError)?;
-
-    if base_limit < total_price {
-      return Err(RelicError::MintBaseLimitExceeded(base_limit, total_price));
-    }
-    if base_balance < total_price {
-      return Err(RelicError::MintInsufficientBalance(total_price));
-    }
-
-    let mut results = Vec::with_capacity(actual_mints as usize);
-    for i in 0..actual_mints {
-      let mint_idx = current_mints + u128::from(i);
-      let price = terms
-        .compute_price(mint_idx)
-        .ok_or(RelicError::PriceComputationError)?;
-      let amount = terms.amount.unwrap_or_default();
-      results.push((amount, price));
-    }
-
-    Ok(results)
-  }
-
-  // base_min = minimum base tokens to accept
-  pub fn unmintable(
-    &self,
-    balance: u128,
-    num_mints: u8,
-    base_min: u128,
-  ) -> Result<Vec<(u128, u128)>, RelicError> {
-    let terms = self.mint_terms.as_ref().ok_or(RelicError::Unmintable)?;
-    let max_unmints = u128::from(terms.max_unmints.ok_or(RelicError::UnmintNotAllowed)?);
-    if self.state.mints < u128::from(num_mints) {
-      return Err(RelicError::NoMintsToUnmint);
-    }
-    if self.is_free() {
-      return Err(RelicError::UnmintNotAllowed);
-    }
-    let cap = terms.cap.unwrap_or(0);
-    if cap != 0 && self.state.mints == cap {
-      return Err(RelicError::UnmintNotAllowed);
-    }
-    if self.state.unmints + u128::from(num_mints) > max_unmints {
-      return Err(RelicError::UnmintNotAllowed);
-    }
-    let mut results = Vec::with_capacity(num_mints as usize);
-    let mut total_amount: u128 = 0;
-    let mut total_price: u128 = 0;
-    for i in 0..num_mints {
-      let mint_index = self.state.mints - 1 - u128::from(i);
-      let price = match terms.price {
-        Some(PriceModel::Fixed(fixed)) => fixed,
-        Some(PriceModel::Formula { .. }) => terms
-          .compute_price(mint_index)
-          .ok_or(RelicError::PriceComputationError)?,
-        None => return Err(RelicError::PriceComputationError),
-      };
-      let amount = terms.amount.unwrap_or_default();
-      total_amount = total_amount.saturating_add(amount);
-      total_price = total_price.saturating_add(price);
-      results.push((amount, price));
-    }
-    if balance < total_amount {
-      return Err(RelicError::MintInsufficientBalance(total_amount));
-    }
-    if total_price < base_min {
-      return Err(RelicError::MintBaseLimitExceeded(base_min, total_price));
-    }
-    Ok(results)
-  }
-
-  pub fn swap(&self, swap: PoolSwap, balance: Option<u128>) -> Result<BalanceDiff, RelicError> {
-    // fail the swap if pool does not exist (yet)
-    let Some(pool) = self.pool else {
-      return Err(RelicError::SwapNotAvailable);
-    };
-
-    // fail the swap if either the base supply or quote supply is 0
-    if pool.base_supply == 0 || pool.quote_supply == 0 {
-      return Err(RelicError::SwapNotAvailable);
-    }
-
-    // also fail if pool still has subsidy -> means not fully minted
-    if pool.subsidy > 0 {
-      return Err(RelicError::SwapNotAvailable);
-    }
-
-    match pool.calculate(swap) {
-      Ok(diff) => {
-        if let Some(balance) = balance {
-          if diff.input > balance {
-            return Err(RelicError::SwapInsufficientBalance(diff.input));
-          }
-        }
-        Ok(diff)
-      }
-      Err(cause) => Err(RelicError::SwapFailed(cause)),
-    }
-  }
-
-  /// max supply of this token: maximum amount of tokens that can be minted plus
-  /// the additional amount that is created for the pool after minting is complete
-  pub fn max_supply(&self) -> u128 {
-    self
-      .mint_terms
-      .map(|terms| {
-        terms.amount.unwrap_or_default() * terms.cap.unwrap_or_default()
-          + terms.seed.unwrap_or_default()
-      })
-      .unwrap_or_default()
-  }
-
-  pub fn is_free(&self) -> bool {
-    self
-      .mint_terms
-      .as_ref()
-      .map(|terms| terms.price.is_none() || matches!(terms.price, Some(PriceModel::Fixed(0))))
-      .unwrap_or(false)
-  }
-
-  /// circulating supply of tokens: either minted or swapped out of the pool minus burned
-  pub fn circulating_supply(&self) -> u128 {
-    let amount = self
-      .mint_terms
-      .and_then(|terms| terms.amount)
-      .unwrap_or_default();
-    let seed = self
-      .mint_terms
-      .and_then(|terms| terms.seed)
-      .unwrap_or_default();
-    let pool_quote_supply = self.pool.map(|pool| pool.quote_supply).unwrap_or(seed);
-    self.state.mints * amount + seed - pool_quote_supply - self.state.burned
-  }
-
-  pub fn locked_base_supply(&self) -> u128 {
-    if let Some(pool) = self.pool {
-      if pool.base_supply > 0 {
-        return pool.base_supply; // pool was already bootstrapped
-      } else if pool.subsidy > 0 {
-        return pool.subsidy; // lp is sponsored
-      }
-    }
-
-    // otherwise take from the mint
-    if let Some(terms) = self.mint_terms {
-      match terms.price {
-        Some(PriceModel::Fixed(fixed)) => self.state.mints * fixed,
-        Some(PriceModel::Formula { .. }) => {
-          let mut total: u128 = 0;
-          for mint_index in 0..self.state.mints {
-            total = total.saturating_add(terms.compute_price(mint_index).unwrap_or(0));
-          }
-          total
-        }
-        None => 0,
-      }
-    } else {
-      0
-    }
+    (
+      self.burned,
+      self.mints,
+      self.unmints,
+      self.paid,
+      self.legacy_mints,
+      self.issued,
+    )
   }
 }
 
@@ -381,17 +171,30 @@ impl Entry for BoostTerms {
   }
 }
 
-pub type PoolValue = (u128, u128, u16, u128);
+pub type PoolValue = (
+  u128, // base supply
+  u128, // quote supply
+  u16,  // fee bps
+  u128, // subsidy
+  u128, // shares
+  u128, // locked shares
+  u128, // provider fees
+);
 
 impl Entry for Pool {
   type Value = PoolValue;
 
-  fn load((base_supply, quote_supply, fee_bps, subsidy): Self::Value) -> Self {
+  fn load(
+    (base_supply, quote_supply, fee_bps, subsidy, shares, locked_shares, provider_fees): Self::Value,
+  ) -> Self {
     Self {
       base_supply,
       quote_supply,
       fee_bps,
       subsidy,
+      shares,
+      locked_shares,
+      provider_fees,
     }
   }
 
@@ -401,6 +204,42 @@ impl Entry for Pool {
       self.quote_supply,
       self.fee_bps,
       self.subsidy,
+      self.shares,
+      self.locked_shares,
+      self.provider_fees,
+    )
+  }
+}
+
+/// Pool supplies after a change of the pool, only recorded with `--index-relic-prices`
+pub type PoolPriceValue = (
+  u32,  // timestamp
+  u128, // base supply
+  u128, // quote supply
+  u128, // base volume
+  u128, // quote volume
+);
+
+impl Entry for PoolPrice {
+  type Value = PoolPriceValue;
+
+  fn load((timestamp, base_supply, quote_supply, base_volume, quote_volume): Self::Value) -> Self {
+    Self {
+      timestamp,
+      base_supply,
+      quote_supply,
+      base_volume,
+      quote_volume,
+    }
+  }
+
+  fn store(self) -> Self::Value {
+    (
+      self.timestamp,
+      self.base_supply,
+      self.quote_supply,
+      self.base_volume,
+      self.quote_volume,
     )
   }
 }
@@ -420,25 +259,6 @@ pub type RelicEntryValue = (
   u64,                     // timestamp
 );
 
-impl Default for RelicEntry {
-  fn default() -> Self {
-    Self {
-      block: 0,
-      enshrining: Txid::all_zeros(),
-      fee: 100, // 1%
-      number: 0,
-      spaced_relic: SpacedRelic::default(),
-      symbol: None,
-      owner_sequence_number: None,
-      boost_terms: None,
-      mint_terms: None,
-      state: RelicState::default(),
-      pool: None,
-      timestamp: 0,
-    }
-  }
-}
-
 impl Entry for RelicEntry {
   type Value = RelicEntryValue;
 
@@ -556,12 +376,18 @@ mod tests {
         burned: 33,
         mints: 44,
         unmints: 17,
+        paid: 352,
+        legacy_mints: 3,
+        issued: 176,
       },
       pool: Some(Pool {
         base_supply: 321,
         quote_supply: 123,
         fee_bps: 13,
         subsidy: 10_000,
+        shares: 400,
+        locked_shares: 300,
+        provider_fees: 0,
       }),
       timestamp: 10,
     };
@@ -589,8 +415,8 @@ mod tests {
         Some(22),
         None,
       )),
-      (33, 44, 17),
-      Some((321, 123, 13, 10_000)),
+      (33, 44, 17, 352, 3, 176),
+      Some((321, 123, 13, 10_000, 400, 300, 0)),
       10,
     );
 
@@ -605,10 +431,15 @@ mod tests {
   }
 
   #[test]
-  fn mintable_default() {
-    assert_eq!(
-      RelicEntry::default().mintable(0, 1, 0),
-      Err(RelicError::Unmintable)
-    );
+  fn pool_price_entry() {
+    let entry = PoolPrice {
+      timestamp: 1,
+      base_supply: 2,
+      quote_supply: 3,
+      base_volume: 4,
+      quote_volume: 5,
+    };
+    assert_eq!(entry.store(), (1, 2, 3, 4, 5));
+    assert_eq!(PoolPrice::load((1, 2, 3, 4, 5)), entry);
   }
 }
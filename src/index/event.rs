@@ -1,154 +1,35 @@
 use {super::*, bincode::Options, redb::TypeName, std::cmp::Ordering};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub enum EventInfo {
-  InscriptionCreated {
-    charms: u16,
-    inscription_id: InscriptionId,
-    location: Option<SatPoint>,
-    parent_inscription_ids: Vec<InscriptionId>,
-    sequence_number: u32,
-  },
-  InscriptionTransferred {
-    inscription_id: InscriptionId,
-    new_location: SatPoint,
-    old_location: SatPoint,
-    sequence_number: u32,
-    is_base_relic: bool,
-  },
-  RuneBurned {
-    rune_id: RuneId,
-    amount: u128,
-  },
-  RuneEtched {
-    rune_id: RuneId,
-  },
-  RuneMinted {
-    rune_id: RuneId,
-    amount: u128,
-  },
-  RuneTransferred {
-    rune_id: RuneId,
-    amount: u128,
-    output: u32,
-  },
-  RelicSealed {
-    spaced_relic: SpacedRelic,
-    sequence_number: u32,
-    inscription_id: InscriptionId,
-  },
-  RelicBurned {
-    relic_id: RelicId,
-    amount: u128,
-  },
-  RelicEnshrined {
-    relic_id: RelicId,
-    inscription_id: InscriptionId,
-  },
-  RelicMinted {
-    relic_id: RelicId,
-    amount: u128,
-    multiplier: u32,
-    is_unmint: bool,
-  },
-  RelicMultiMinted {
-    relic_id: RelicId,
-    amount: u128,
-    num_mints: u8,
-    base_limit: u128,
-    is_unmint: bool,
-  },
-  RelicSpent {
-    relic_id: RelicId,
-    amount: u128,
-    // utility field for apps, could also store output and calc address only on read
-    address: Address<NetworkUnchecked>,
-  },
-  RelicReceived {
-    relic_id: RelicId,
-    amount: u128,
-    // utility field for apps, could also store output and calc address only on read
-    address: Address<NetworkUnchecked>,
-  },
-  RelicTransferred {
-    relic_id: RelicId,
-    amount: u128,
-    output: u32,
-  },
-  RelicSwapped {
-    relic_id: RelicId,
-    base_amount: u128,
-    quote_amount: u128,
-    fee: u128,
-    is_sell_order: bool,
-    is_exact_input: bool,
-  },
-  RelicClaimed {
-    amount: u128,
-  },
-  RelicError {
-    operation: RelicOperation,
-    error: RelicError,
-  },
-}
+pub use relics_api::{Event, EventCursor, EventInfo, RelicOperation};
 
-#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
-pub enum RelicOperation {
-  Seal,
-  Enshrine,
-  Mint,
-  MultiMint,
-  Unmint,
-  MultiUnmint,
-  Swap,
-  Claim,
-}
+/// `EventCursor` as fixed-width big-endian bytes, so keys sort in chain order
+pub(crate) type EventKeyValue = [u8; 8];
 
-impl Display for Event {
-  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-    write!(f, "{:?}", self)
-  }
-}
+impl Entry for EventCursor {
+  type Value = EventKeyValue;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Event {
-  pub block_height: u32,
-  pub event_index: u32,
-  pub txid: Txid,
-  pub info: EventInfo,
-}
-
-impl Event {
-  pub fn is_relic_history(&self) -> bool {
-    matches!(
-      self.info,
-      EventInfo::RelicMinted { .. }
-        | EventInfo::RelicBurned { .. }
-        | EventInfo::RelicSpent { .. }
-        | EventInfo::RelicReceived { .. }
-        | EventInfo::RelicTransferred { .. }
-        | EventInfo::RelicSwapped { .. }
-    )
+  fn load(value: Self::Value) -> Self {
+    Self {
+      block_height: u32::from_be_bytes(value[..4].try_into().unwrap()),
+      event_index: u32::from_be_bytes(value[4..].try_into().unwrap()),
+    }
   }
 
-  pub fn relic_id(&self) -> Option<RelicId> {
-    match self.info {
-      EventInfo::RelicEnshrined { relic_id, .. } => Some(relic_id),
-      EventInfo::RelicMinted { relic_id, .. } => Some(relic_id),
-      EventInfo::RelicBurned { relic_id, .. } => Some(relic_id),
-      EventInfo::RelicSpent { relic_id, .. } => Some(relic_id),
-      EventInfo::RelicReceived { relic_id, .. } => Some(relic_id),
-      EventInfo::RelicTransferred { relic_id, .. } => Some(relic_id),
-      EventInfo::RelicSwapped { relic_id, .. } => Some(relic_id),
-      EventInfo::RelicClaimed { .. } => Some(RELIC_ID),
-      _ => None,
-    }
+  fn store(self) -> Self::Value {
+    let mut value = [0; 8];
+    value[..4].copy_from_slice(&self.block_height.to_be_bytes());
+    value[4..].copy_from_slice(&self.event_index.to_be_bytes());
+    value
   }
 }
 
-impl redb::Value for Event {
+/// Stores an [`Event`] in the event table, encoded with bincode
+#[derive(Debug)]
+pub enum EventValue {}
+
+impl redb::Value for EventValue {
   type SelfType<'a>
-    = Self
+    = Event
   where
     Self: 'a;
   type AsBytes<'a>
@@ -182,19 +63,50 @@ impl redb::Value for Event {
   }
 }
 
-impl redb::Key for Event {
+/// Stores an [`Event`] in the multimap tables of indexes of schema 31, which are migrated on open.
+/// Events were compared by their position in the chain, which takes deserializing both.
+#[derive(Debug)]
+pub(crate) enum LegacyEventValue {}
+
+impl redb::Value for LegacyEventValue {
+  type SelfType<'a>
+    = Event
+  where
+    Self: 'a;
+  type AsBytes<'a>
+    = Vec<u8>
+  where
+    Self: 'a;
+
+  fn fixed_width() -> Option<usize> {
+    None
+  }
+
+  fn from_bytes<'a>(data: &'a [u8]) -> Self::SelfType<'a>
+  where
+    Self: 'a,
+  {
+    <EventValue as redb::Value>::from_bytes(data)
+  }
+
+  fn as_bytes<'a, 'b: 'a>(value: &'a Self::SelfType<'b>) -> Self::AsBytes<'a>
+  where
+    Self: 'a,
+    Self: 'b,
+  {
+    <EventValue as redb::Value>::as_bytes(value)
+  }
+
+  fn type_name() -> TypeName {
+    <EventValue as redb::Value>::type_name()
+  }
+}
+
+impl redb::Key for LegacyEventValue {
   fn compare(data1: &[u8], data2: &[u8]) -> Ordering {
-    // Note: If performance becomes an issue here because of deserialization,
-    // we should be able to sort the items based on their byte representation.
-    // For that to work we need to disable varint encoding, switch to big-endian,
-    // and then sort based on the first 8 bytes of each item.
-    // data1.cmp(data2)
-    let options = bincode::DefaultOptions::new();
-    let item1: Event = options.deserialize(data1).unwrap();
-    let item2: Event = options.deserialize(data2).unwrap();
-    let key1 = (item1.block_height, item1.event_index);
-    let key2 = (item2.block_height, item2.event_index);
-    key1.cmp(&key2)
+    <Self as redb::Value>::from_bytes(data1)
+      .cursor()
+      .cmp(&<Self as redb::Value>::from_bytes(data2).cursor())
   }
 }
 
@@ -202,8 +114,12 @@ pub struct EventEmitter<'a, 'tx> {
   pub block_height: u32,
   pub event_index: u32,
   pub event_sender: Option<&'a tokio::sync::mpsc::Sender<Event>>,
-  pub relic_id_to_events: &'a mut MultimapTable<'tx, RelicIdValue, Event>,
-  pub transaction_id_to_events: &'a mut MultimapTable<'tx, &'static TxidValue, Event>,
+  /// events waiting for the write transaction to be committed before they are streamed
+  pub pending_events: Option<&'a Mutex<Vec<Event>>>,
+  pub event_key_to_event: &'a mut Table<'tx, &'static EventKeyValue, EventValue>,
+  pub relic_id_to_event_keys: &'a mut Table<'tx, (RelicIdValue, u32), &'static EventKeyValue>,
+  pub transaction_id_to_event_keys:
+    &'a mut MultimapTable<'tx, &'static TxidValue, &'static EventKeyValue>,
 }
 
 impl EventEmitter<'_, '_> {
@@ -218,17 +134,61 @@ impl EventEmitter<'_, '_> {
     if let Some(sender) = self.event_sender {
       sender.blocking_send(event.clone())?;
     }
-    // store all events with the TX
+    if let Some(pending_events) = self.pending_events {
+      pending_events.lock().unwrap().push(event.clone());
+    }
+    let key = event.cursor().store();
+    self.event_key_to_event.insert(&key, &event)?;
+    // index all events by TX
     self
-      .transaction_id_to_events
-      .insert(&txid.store(), &event)?;
-    // store some of the events with the relic
+      .transaction_id_to_event_keys
+      .insert(&txid.store(), &key)?;
+    // index some of the events by relic, numbered in chain order
     if event.is_relic_history() {
       if let Some(relic_id) = event.relic_id() {
-        self.relic_id_to_events.insert(relic_id.store(), &event)?;
+        let id = relic_id.store();
+        let number = self
+          .relic_id_to_event_keys
+          .range((id, 0)..=(id, u32::MAX))?
+          .next_back()
+          .transpose()?
+          .map(|(key, _)| key.value().1 + 1)
+          .unwrap_or_default();
+        self.relic_id_to_event_keys.insert((id, number), &key)?;
       }
     }
 
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn event_keys_sort_like_cursors() {
+    let cursors = [
+      EventCursor {
+        block_height: 3,
+        event_index: 1,
+      },
+      EventCursor {
+        block_height: 3,
+        event_index: 256,
+      },
+      EventCursor {
+        block_height: 256,
+        event_index: 0,
+      },
+    ];
+
+    for cursor in cursors {
+      assert_eq!(EventCursor::load(cursor.store()), cursor);
+    }
+
+    assert!(cursors
+      .windows(2)
+      .all(|pair| pair[0].store() < pair[1].store()));
+  }
+}
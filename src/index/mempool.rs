@@ -0,0 +1,310 @@
+use super::*;
+
+/// Relic transactions in the memory pool, simulated on top of the committed index without
+/// touching it.
+///
+/// Sealings, enshrinings and base token mints depend on inscriptions, which are not indexed for
+/// pending transactions, so they are predicted to fail.
+#[derive(Default)]
+pub(crate) struct MempoolRelics {
+  relics: api::MempoolRelics,
+  /// Relic entries changed by pending transactions
+  entries: HashMap<RelicId, RelicEntry>,
+  /// position of each pending transaction in `relics.transactions`
+  positions: HashMap<Txid, usize>,
+  /// confirmed outputs spent by pending transactions
+  spent: HashMap<OutPoint, Txid>,
+  /// all transactions in the memory pool, so every transaction is only fetched once
+  transactions: HashMap<Txid, Transaction>,
+  /// tip of the index the transactions were simulated on
+  tip: Option<BlockHash>,
+}
+
+impl MempoolRelics {
+  /// Simulate the transactions in the memory pool, or return `None` if neither the memory pool nor
+  /// the tip of the index changed since `previous`.
+  pub(crate) fn update(index: &Index, previous: &Self) -> Result<Option<Self>> {
+    let mempool = index.client.get_raw_mempool()?;
+    let tip = index.block_hash(None)?;
+
+    if tip == previous.tip
+      && mempool.len() == previous.transactions.len()
+      && mempool
+        .iter()
+        .all(|txid| previous.transactions.contains_key(txid))
+    {
+      return Ok(None);
+    }
+
+    let mut transactions = HashMap::new();
+    let mut txids = Vec::new();
+
+    for txid in mempool {
+      let transaction = match previous.transactions.get(&txid) {
+        Some(transaction) => transaction.clone(),
+        // the transaction may have been mined or evicted since the call to getrawmempool
+        None => match index
+          .client
+          .get_raw_transaction(&txid, None)
+          .into_option()?
+        {
+          Some(transaction) => transaction,
+          None => continue,
+        },
+      };
+      transactions.insert(txid, transaction);
+      txids.push(txid);
+    }
+
+    let height = index.block_count()?;
+
+    if !index.index_relics || height < index.settings.first_relic_height() {
+      return Ok(Some(Self {
+        relics: api::MempoolRelics {
+          height,
+          transactions: Vec::new(),
+        },
+        transactions,
+        tip,
+        ..default()
+      }));
+    }
+
+    // parents have to be simulated before their children
+    let mut ordered = Vec::new();
+    let mut visited = HashSet::new();
+    for txid in &txids {
+      Self::visit(*txid, &transactions, &mut visited, &mut ordered);
+    }
+
+    let rtx = index.database.begin_read()?;
+    let outpoint_to_relic_balances = rtx.open_table(OUTPOINT_TO_RELIC_BALANCES)?;
+    let outpoint_to_relic_shares = rtx.open_table(OUTPOINT_TO_RELIC_SHARES)?;
+
+    // only simulate transactions with a keepsake or spending Relics or pool shares
+    let mut txdata = Vec::new();
+    let mut relevant = HashSet::new();
+    for txid in ordered {
+      let tx = &transactions[&txid];
+      let mut is_relevant = Keepsake::decipher(tx).is_some();
+      for input in &tx.input {
+        let outpoint = input.previous_output;
+        is_relevant |= relevant.contains(&outpoint.txid)
+          || outpoint_to_relic_balances.get(&outpoint.store())?.is_some()
+          || outpoint_to_relic_shares.get(&outpoint.store())?.is_some();
+      }
+      if is_relevant {
+        relevant.insert(txid);
+        txdata.push((tx.clone(), txid));
+      }
+    }
+
+    let block_time = u32::try_from(Utc::now().timestamp()).unwrap_or(u32::MAX);
+
+    let (pending, entries) =
+      Updater::simulate_relic_transactions(index, height, block_time, &txdata, |wtx| {
+        let outpoint_to_relic_balances = wtx.open_table(OUTPOINT_TO_RELIC_BALANCES)?;
+        let outpoint_to_relic_shares = wtx.open_table(OUTPOINT_TO_RELIC_SHARES)?;
+        let relic_id_to_relic_entry = wtx.open_table(RELIC_ID_TO_RELIC_ENTRY)?;
+        let event_key_to_event = wtx.open_table(EVENT_KEY_TO_EVENT)?;
+        let transaction_id_to_event_keys = wtx.open_multimap_table(TRANSACTION_ID_TO_EVENT_KEYS)?;
+
+        let mut pending = Vec::new();
+        let mut entries = HashMap::new();
+
+        for (tx, txid) in &txdata {
+          let events = transaction_id_to_event_keys
+            .get(&txid.store())?
+            .map(|result| Index::load_event(&event_key_to_event, result?.value()))
+            .collect::<Result<Vec<Event>>>()?;
+
+          for relic_id in events.iter().filter_map(Event::relic_id) {
+            if let Some(entry) = relic_id_to_relic_entry.get(&relic_id.store())? {
+              entries.insert(relic_id, RelicEntry::load(entry.value()));
+            }
+          }
+
+          let mut outputs = Vec::new();
+          for vout in 0..tx.output.len() {
+            let outpoint = OutPoint {
+              txid: *txid,
+              vout: vout.try_into().unwrap(),
+            };
+            let output = Self::read_output(
+              outpoint,
+              &outpoint_to_relic_balances,
+              &outpoint_to_relic_shares,
+            )?;
+            if !output.balances.is_empty() || !output.shares.is_empty() {
+              outputs.push(output);
+            }
+          }
+
+          pending.push((*txid, events, outputs));
+        }
+
+        Ok((pending, entries))
+      })?;
+
+    let mut mempool_relics = Self {
+      relics: api::MempoolRelics {
+        height,
+        transactions: Vec::new(),
+      },
+      entries,
+      transactions,
+      tip,
+      ..default()
+    };
+
+    for (txid, events, outputs) in pending {
+      let outpoints = mempool_relics.transactions[&txid]
+        .input
+        .iter()
+        .map(|input| input.previous_output)
+        .collect::<Vec<OutPoint>>();
+
+      let mut inputs = Vec::new();
+      for outpoint in outpoints {
+        let input = match mempool_relics.pending_output_mut(outpoint) {
+          Some(output) => {
+            output.spent_by = Some(txid);
+            output.clone()
+          }
+          None => {
+            mempool_relics.spent.insert(outpoint, txid);
+            Self::read_output(
+              outpoint,
+              &outpoint_to_relic_balances,
+              &outpoint_to_relic_shares,
+            )?
+          }
+        };
+        if !input.balances.is_empty() || !input.shares.is_empty() {
+          inputs.push(api::MempoolRelicOutput {
+            spent_by: Some(txid),
+            ..input
+          });
+        }
+      }
+
+      mempool_relics
+        .positions
+        .insert(txid, mempool_relics.relics.transactions.len());
+
+      mempool_relics
+        .relics
+        .transactions
+        .push(api::MempoolRelicTransaction {
+          txid,
+          events,
+          inputs,
+          outputs,
+        });
+    }
+
+    Ok(Some(mempool_relics))
+  }
+
+  pub(crate) fn relics(&self) -> &api::MempoolRelics {
+    &self.relics
+  }
+
+  pub(crate) fn transaction(&self, txid: Txid) -> Option<&api::MempoolRelicTransaction> {
+    self
+      .positions
+      .get(&txid)
+      .map(|position| &self.relics.transactions[*position])
+  }
+
+  /// pending balances of an output, falls back to the committed balances for confirmed outputs
+  pub(crate) fn output(
+    &self,
+    index: &Index,
+    outpoint: OutPoint,
+  ) -> Result<api::MempoolRelicOutput> {
+    if let Some(output) = self.pending_output(outpoint) {
+      return Ok(output.clone());
+    }
+
+    let rtx = index.database.begin_read()?;
+
+    Ok(api::MempoolRelicOutput {
+      spent_by: self.spent.get(&outpoint).copied(),
+      ..Self::read_output(
+        outpoint,
+        &rtx.open_table(OUTPOINT_TO_RELIC_BALANCES)?,
+        &rtx.open_table(OUTPOINT_TO_RELIC_SHARES)?,
+      )?
+    })
+  }
+
+  /// Relic entry including the changes of pending transactions
+  pub(crate) fn relic_entry(&self, relic_id: RelicId) -> Option<RelicEntry> {
+    self.entries.get(&relic_id).copied()
+  }
+
+  fn pending_output(&self, outpoint: OutPoint) -> Option<&api::MempoolRelicOutput> {
+    self
+      .transaction(outpoint.txid)?
+      .outputs
+      .iter()
+      .find(|output| output.outpoint == outpoint)
+  }
+
+  fn pending_output_mut(&mut self, outpoint: OutPoint) -> Option<&mut api::MempoolRelicOutput> {
+    let position = *self.positions.get(&outpoint.txid)?;
+    self.relics.transactions[position]
+      .outputs
+      .iter_mut()
+      .find(|output| output.outpoint == outpoint)
+  }
+
+  fn read_output<T: ReadableTable<&'static OutPointValue, &'static [u8]>>(
+    outpoint: OutPoint,
+    outpoint_to_relic_balances: &T,
+    outpoint_to_relic_shares: &T,
+  ) -> Result<api::MempoolRelicOutput> {
+    Ok(api::MempoolRelicOutput {
+      outpoint,
+      balances: Self::read_amounts(outpoint, outpoint_to_relic_balances)?,
+      shares: Self::read_amounts(outpoint, outpoint_to_relic_shares)?,
+      spent_by: None,
+    })
+  }
+
+  fn read_amounts(
+    outpoint: OutPoint,
+    table: &impl ReadableTable<&'static OutPointValue, &'static [u8]>,
+  ) -> Result<BTreeMap<RelicId, u128>> {
+    let mut amounts = BTreeMap::new();
+    if let Some(guard) = table.get(&outpoint.store())? {
+      let buffer = guard.value();
+      let mut i = 0;
+      while i < buffer.len() {
+        let ((id, amount), len) = Index::decode_rune_balance(&buffer[i..]).unwrap();
+        i += len;
+        *amounts.entry(id).or_default() += amount;
+      }
+    }
+    Ok(amounts)
+  }
+
+  fn visit(
+    txid: Txid,
+    transactions: &HashMap<Txid, Transaction>,
+    visited: &mut HashSet<Txid>,
+    ordered: &mut Vec<Txid>,
+  ) {
+    let Some(tx) = transactions.get(&txid) else {
+      return;
+    };
+    if !visited.insert(txid) {
+      return;
+    }
+    for input in &tx.input {
+      Self::visit(input.previous_output.txid, transactions, visited, ordered);
+    }
+    ordered.push(txid);
+  }
+}
@@ -0,0 +1,309 @@
+use {super::*, relics_protocol::PoolError};
+
+/// Calculate the pool changes of a swap against the current pool states, without applying them.
+///
+/// The changes are returned in execution order and have to be applied to the pool of their Relic
+/// one after another. This is used by the indexer to execute swaps and by the server to quote
+/// them, so a quote always matches the execution against the same pool states. The input balance
+/// is only checked if given.
+pub(crate) fn calculate(
+  swap: &Swap,
+  input_balance: Option<u128>,
+  load_relic_entry: impl Fn(RelicId) -> Result<Option<RelicEntry>>,
+) -> Result<Result<Vec<(RelicId, BalanceDiff)>, RelicError>> {
+  if !swap.via.is_empty() {
+    let legs = swap.legs();
+    let mut entries = HashMap::new();
+    for (id, _) in &legs {
+      if !entries.contains_key(id) {
+        entries.insert(*id, load_relic_entry(*id)?);
+      }
+    }
+    return Ok(calculate_route(swap, &legs, entries, input_balance));
+  }
+  let input = swap.input.unwrap_or(RELIC_ID);
+  let output = swap.output.unwrap_or(RELIC_ID);
+  let input_entry = load_relic_entry(input)?;
+  let output_entry = load_relic_entry(output)?;
+  Ok(
+    calculate_direct(
+      swap,
+      input,
+      &input_entry,
+      output,
+      &output_entry,
+      input_balance,
+    )
+    .map(|(sell, buy)| {
+      sell
+        .map(|diff| (input, diff))
+        .into_iter()
+        .chain(buy.map(|diff| (output, diff)))
+        .collect()
+    }),
+  )
+}
+
+fn calculate_direct(
+  swap: &Swap,
+  input: RelicId,
+  input_entry: &Option<RelicEntry>,
+  output: RelicId,
+  output_entry: &Option<RelicEntry>,
+  input_balance: Option<u128>,
+) -> Result<(Option<BalanceDiff>, Option<BalanceDiff>), RelicError> {
+  let simple_swap = |direction: SwapDirection| {
+    if swap.is_exact_input {
+      PoolSwap::Input {
+        direction,
+        input: swap.input_amount.unwrap_or_default(),
+        min_output: swap.output_amount,
+      }
+    } else {
+      PoolSwap::Output {
+        direction,
+        output: swap.output_amount.unwrap_or_default(),
+        max_input: swap.input_amount,
+      }
+    }
+  };
+  let input_entry = input_entry.ok_or(RelicError::RelicNotFound(input))?;
+  let output_entry = output_entry.ok_or(RelicError::RelicNotFound(output))?;
+  match (input, output) {
+    // buy output relic
+    (RELIC_ID, _) => Ok((
+      None,
+      Some(output_entry.swap(simple_swap(SwapDirection::BaseToQuote), input_balance)?),
+    )),
+    // sell input relic
+    (_, RELIC_ID) => Ok((
+      Some(input_entry.swap(simple_swap(SwapDirection::QuoteToBase), input_balance)?),
+      None,
+    )),
+    // dual swap: sell input relic to buy output relic
+    _ => {
+      if swap.is_exact_input {
+        // sell input
+        let diff_sell = input_entry.swap(
+          PoolSwap::Input {
+            direction: SwapDirection::QuoteToBase,
+            input: swap.input_amount.unwrap_or_default(),
+            // no slippage check here, we check on the other swap
+            min_output: None,
+          },
+          input_balance,
+        )?;
+        // buy output
+        let diff_buy = output_entry.swap(
+          PoolSwap::Input {
+            direction: SwapDirection::BaseToQuote,
+            input: diff_sell.output,
+            // slippage check is performed on the second swap, on slippage error both swaps will not be executed
+            min_output: swap.output_amount,
+          },
+          None,
+        )?;
+        Ok((Some(diff_sell), Some(diff_buy)))
+      } else {
+        // calculate the "buy" first to determine how many base tokens we need to get out of the "sell"
+        let diff_buy = output_entry.swap(
+          PoolSwap::Output {
+            direction: SwapDirection::BaseToQuote,
+            output: swap.output_amount.unwrap_or_default(),
+            // no slippage check here, we check on the other swap
+            max_input: None,
+          },
+          None,
+        )?;
+        // sell input
+        let diff_sell = input_entry.swap(
+          PoolSwap::Output {
+            direction: SwapDirection::QuoteToBase,
+            output: diff_buy.input,
+            // slippage check is performed on the second swap, on slippage error both swaps will not be executed
+            max_input: swap.input_amount,
+          },
+          input_balance,
+        )?;
+        Ok((Some(diff_sell), Some(diff_buy)))
+      }
+    }
+  }
+}
+
+/// Calculate a swap routed through intermediate Relics, fails if any leg fails.
+///
+/// Every intermediate Relic is bought and sold against the same pool, so later legs see the effect
+/// of earlier legs. Exact-output routes are executed with the smallest input for which executing
+/// the legs in order yields the requested output, so the quote and the execution use the same
+/// sequence of pool states.
+fn calculate_route(
+  swap: &Swap,
+  legs: &[(RelicId, SwapDirection)],
+  entries: HashMap<RelicId, Option<RelicEntry>>,
+  input_balance: Option<u128>,
+) -> Result<Vec<(RelicId, BalanceDiff)>, RelicError> {
+  if swap.is_exact_input {
+    return execute_route(
+      legs,
+      entries,
+      swap.input_amount.unwrap_or_default(),
+      swap.output_amount,
+      input_balance,
+    );
+  }
+
+  let output = swap.output_amount.unwrap_or_default();
+
+  // estimate the input backwards along the route against the pool states at the start of the swap
+  let mut estimate = output;
+  for (id, direction) in legs.iter().rev() {
+    let entry = entries[id].ok_or(RelicError::RelicNotFound(*id))?;
+    estimate = entry
+      .swap(
+        PoolSwap::Output {
+          direction: *direction,
+          output: estimate,
+          max_input: None,
+        },
+        None,
+      )?
+      .input;
+  }
+
+  let yields_output =
+    |input: u128| match execute_route(legs, entries.clone(), input, Some(output), None) {
+      Ok(_) => Ok(true),
+      Err(RelicError::SwapFailed(PoolError::Slippage)) => Ok(false),
+      Err(error) => Err(error),
+    };
+
+  let input = smallest_input(
+    estimate,
+    swap.input_amount.unwrap_or(u128::MAX),
+    yields_output,
+  )?;
+
+  execute_route(legs, entries, input, Some(output), input_balance)
+}
+
+/// Find the smallest input up to `max_input` that yields the output, starting from the estimate.
+/// Errors other than slippage are returned as they are, since the input chosen must not depend on
+/// them.
+fn smallest_input(
+  estimate: u128,
+  max_input: u128,
+  yields_output: impl Fn(u128) -> Result<bool, RelicError>,
+) -> Result<u128, RelicError> {
+  // find an input that yields the output, starting from the estimate
+  let mut low = 0;
+  let mut high = estimate.max(1);
+  while !yields_output(high)? {
+    if high >= max_input {
+      return Err(RelicError::SwapFailed(PoolError::Slippage));
+    }
+    low = high;
+    high = high.saturating_mul(2).min(max_input);
+  }
+
+  // and narrow it down to the smallest such input
+  while high - low > 1 {
+    let mid = low + (high - low) / 2;
+    if yields_output(mid)? {
+      high = mid;
+    } else {
+      low = mid;
+    }
+  }
+
+  if high > max_input {
+    return Err(RelicError::SwapFailed(PoolError::Slippage));
+  }
+
+  Ok(high)
+}
+
+/// Execute the legs of a route in order with the given input, each leg trading the output of the
+/// previous one. The slippage check is performed on the last leg only, on slippage error no leg is
+/// executed.
+fn execute_route(
+  legs: &[(RelicId, SwapDirection)],
+  mut entries: HashMap<RelicId, Option<RelicEntry>>,
+  input: u128,
+  min_output: Option<u128>,
+  input_balance: Option<u128>,
+) -> Result<Vec<(RelicId, BalanceDiff)>, RelicError> {
+  let mut diffs = Vec::with_capacity(legs.len());
+  let mut amount = input;
+  for (i, (id, direction)) in legs.iter().enumerate() {
+    let entry = entries
+      .get_mut(id)
+      .unwrap()
+      .as_mut()
+      .ok_or(RelicError::RelicNotFound(*id))?;
+    let diff = entry.swap(
+      PoolSwap::Input {
+        direction: *direction,
+        input: amount,
+        min_output: min_output.filter(|_| i == legs.len() - 1),
+      },
+      input_balance.filter(|_| i == 0),
+    )?;
+    // later legs of the route must see the effect of earlier legs on the same pool
+    entry.pool.as_mut().unwrap().apply(diff);
+    amount = diff.output;
+    diffs.push((*id, diff));
+  }
+  Ok(diffs)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn smallest_input_is_found_from_below_and_above_the_estimate() {
+    assert_eq!(
+      smallest_input(10, u128::MAX, |input| Ok(input >= 37)),
+      Ok(37)
+    );
+    assert_eq!(
+      smallest_input(100, u128::MAX, |input| Ok(input >= 37)),
+      Ok(37)
+    );
+  }
+
+  #[test]
+  fn smallest_input_is_capped_by_the_maximum_input() {
+    assert_eq!(
+      smallest_input(10, 36, |input| Ok(input >= 37)),
+      Err(RelicError::SwapFailed(PoolError::Slippage))
+    );
+    assert_eq!(smallest_input(10, 37, |input| Ok(input >= 37)), Ok(37));
+  }
+
+  #[test]
+  fn smallest_input_propagates_errors_while_narrowing() {
+    // the estimate yields the output, the first input tried below it fails
+    assert_eq!(
+      smallest_input(100, u128::MAX, |input| if input == 100 {
+        Ok(true)
+      } else {
+        Err(RelicError::SwapFailed(PoolError::Overflow))
+      }),
+      Err(RelicError::SwapFailed(PoolError::Overflow))
+    );
+  }
+
+  #[test]
+  fn smallest_input_propagates_errors_while_growing() {
+    assert_eq!(
+      smallest_input(10, u128::MAX, |input| if input < 20 {
+        Ok(false)
+      } else {
+        Err(RelicError::SwapNotAvailable)
+      }),
+      Err(RelicError::SwapNotAvailable)
+    );
+  }
+}
@@ -60,6 +60,8 @@ impl Reorg {
       panic!("set index durability to `Durability::Immediate` to test reorg handling");
     }
 
+    let block_count = index.block_count()?;
+
     let mut wtx = index.begin_write()?;
 
     let oldest_savepoint =
@@ -70,10 +72,13 @@ impl Reorg {
     Index::increment_statistic(&wtx, Statistic::Commits, 1)?;
     wtx.commit()?;
 
-    log::info!(
-      "successfully rolled back database to height {}",
-      index.begin_read()?.block_count()?
-    );
+    let rolled_back_block_count = index.begin_read()?.block_count()?;
+
+    log::info!("successfully rolled back database to height {rolled_back_block_count}");
+
+    if rolled_back_block_count < block_count {
+      index.publish_reorg(rolled_back_block_count, block_count - 1)?;
+    }
 
     Ok(())
   }
@@ -305,6 +305,7 @@ impl Context {
 
     let mut entry = Self::base_token_entry();
     entry.state.mints += u128::from(n);
+    entry.state.issued += u128::from(n) * entry.mint_terms.unwrap().amount.unwrap();
 
     (txid, entry)
   }
@@ -512,12 +513,45 @@ impl Context {
       pretty_assert_eq!(events, self.index.events_for_tx(txid).unwrap());
     }
     for (relic_id, events) in relic_events {
+      pretty_assert_eq!(
+        Some(events[1..].to_vec()),
+        self
+          .index
+          .events_for_relic_after(relic_id, events[0].cursor(), 100)
+          .unwrap(),
+      );
       let mut actual = self.index.events_for_relic(relic_id, 100, 0).unwrap();
       if let Some(events) = actual.as_mut() {
         // the API returns items from new to old, hence reverse the order here
         events.reverse();
       }
-      pretty_assert_eq!(Some(events), actual);
+      pretty_assert_eq!(Some(events.clone()), actual);
+      // pages are returned with the first event of the next page, which tells if there is one
+      let newest_first = events.iter().rev().cloned().collect::<Vec<Event>>();
+      for page_index in 0..=newest_first.len() / 2 {
+        let page = newest_first
+          .iter()
+          .skip(page_index * 2)
+          .take(3)
+          .cloned()
+          .collect::<Vec<Event>>();
+        pretty_assert_eq!(
+          Some(page),
+          self
+            .index
+            .events_for_relic(relic_id, 2, page_index)
+            .unwrap(),
+        );
+      }
+      for (i, event) in events.iter().enumerate() {
+        pretty_assert_eq!(
+          Some(events[i + 1..].to_vec()),
+          self
+            .index
+            .events_for_relic_after(relic_id, event.cursor(), 100)
+            .unwrap(),
+        );
+      }
     }
   }
 }
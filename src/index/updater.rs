@@ -6,6 +6,7 @@ use {
   },
   super::{fetcher::Fetcher, *},
   futures::future::try_join_all,
+  redb::backends::InMemoryBackend,
   tokio::sync::{
     broadcast::{self, error::TryRecvError},
     mpsc::{self},
@@ -83,6 +84,7 @@ impl Updater<'_> {
 
     let mut uncommitted = 0;
     let mut utxo_cache = HashMap::new();
+    let mut inscription_owners = HashMap::new();
     while let Ok(block) = rx.recv() {
       self.index_block(
         &mut output_sender,
@@ -90,6 +92,7 @@ impl Updater<'_> {
         &mut wtx,
         block,
         &mut utxo_cache,
+        &mut inscription_owners,
       )?;
 
       if let Some(progress_bar) = &mut progress_bar {
@@ -109,6 +112,8 @@ impl Updater<'_> {
       if uncommitted == self.index.settings.commit_interval() {
         self.commit(wtx, utxo_cache)?;
         utxo_cache = HashMap::new();
+        // committing writes the current satpoints of all inscriptions
+        inscription_owners.clear();
         uncommitted = 0;
         wtx = self.index.begin_write()?;
         let height = wtx
@@ -319,12 +324,15 @@ impl Updater<'_> {
     wtx: &mut WriteTransaction,
     block: BlockData,
     utxo_cache: &mut HashMap<OutPoint, UtxoEntryBuf>,
+    inscription_owners: &mut HashMap<u32, Option<RelicOwner>>,
   ) -> Result<()> {
     Reorg::detect_reorg(&block, self.height, self.index)?;
 
     let start = Instant::now();
     let mut sat_ranges_written = 0;
     let mut outputs_in_block = 0;
+    // script pubkeys of the outputs spent in this block, only tracked if addresses are indexed
+    let mut spent_script_pubkeys = HashMap::new();
 
     log::info!(
       "Block {} at {} with {} transactions…",
@@ -338,15 +346,21 @@ impl Updater<'_> {
       wtx.open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?;
     let mut statistic_to_count = wtx.open_table(STATISTIC_TO_COUNT)?;
 
-    let mut relic_id_to_events = wtx.open_multimap_table(RELIC_ID_TO_EVENTS)?;
-    let mut transaction_id_to_events = wtx.open_multimap_table(TRANSACTION_ID_TO_EVENTS)?;
+    let mut event_key_to_event = wtx.open_table(EVENT_KEY_TO_EVENT)?;
+    let mut relic_id_to_event_keys = wtx.open_table(RELIC_ID_TO_EVENT_KEYS)?;
+    let mut transaction_id_to_event_keys = wtx.open_multimap_table(TRANSACTION_ID_TO_EVENT_KEYS)?;
 
     let mut emitter = EventEmitter {
       block_height: self.height,
       event_index: 0,
       event_sender: self.index.event_sender.as_ref(),
-      relic_id_to_events: &mut relic_id_to_events,
-      transaction_id_to_events: &mut transaction_id_to_events,
+      // events are only buffered for streaming if there are subscribers, subscribers replay the
+      // stored events of blocks indexed before they subscribed
+      pending_events: (self.index.event_stream.receiver_count() > 0)
+        .then_some(&self.index.pending_events),
+      event_key_to_event: &mut event_key_to_event,
+      relic_id_to_event_keys: &mut relic_id_to_event_keys,
+      transaction_id_to_event_keys: &mut transaction_id_to_event_keys,
     };
 
     if self.index.index_inscriptions || self.index.index_addresses || self.index.index_sats {
@@ -355,11 +369,13 @@ impl Updater<'_> {
         txout_receiver,
         output_sender,
         utxo_cache,
+        inscription_owners,
         wtx,
         &mut inscription_id_to_sequence_number,
         &mut statistic_to_count,
         &mut sat_ranges_written,
         &mut outputs_in_block,
+        &mut spent_script_pubkeys,
         &mut emitter,
       )?;
     }
@@ -373,51 +389,20 @@ impl Updater<'_> {
           9999,
         )?;
       }
-      let mut outpoint_to_relic_balances = wtx.open_table(OUTPOINT_TO_RELIC_BALANCES)?;
-      let mut relic_id_to_relic_entry = wtx.open_table(RELIC_ID_TO_RELIC_ENTRY)?;
-      let mut relic_to_relic_id = wtx.open_table(RELIC_TO_RELIC_ID)?;
-      let mut relic_owner_to_claimable = wtx.open_table(RELIC_OWNER_TO_CLAIMABLE)?;
-      let mut transaction_id_to_relic = wtx.open_table(TRANSACTION_ID_TO_RELIC)?;
-      let mut sequence_number_to_spaced_relic = wtx.open_table(SEQUENCE_NUMBER_TO_SPACED_RELIC)?;
-      let mut relic_to_sequence_number = wtx.open_table(RELIC_TO_SEQUENCE_NUMBER)?;
-      let sequence_number_to_satpoint = wtx.open_table(SEQUENCE_NUMBER_TO_SATPOINT)?;
-      let sequence_number_to_inscription_entry =
-        wtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
-
-      let relics = statistic_to_count
-        .get(&Statistic::Relics.into())?
-        .map(|x| x.value())
-        .unwrap_or(0);
-
-      let mut relic_updater = RelicUpdater {
-        block_time: block.header.time,
-        burned: HashMap::new(),
-        claimable: HashMap::new(),
-        unsafe_txids: HashSet::new(),
-        index: self.index,
-        height: self.height,
-        id_to_entry: &mut relic_id_to_relic_entry,
-        inscription_id_to_sequence_number: &inscription_id_to_sequence_number,
-        mints_in_block: HashMap::new(),
-        outpoint_to_balances: &mut outpoint_to_relic_balances,
-        relic_owner_to_claimable: &mut relic_owner_to_claimable,
-        relic_to_id: &mut relic_to_relic_id,
-        relics,
-        statistic_to_count: &mut statistic_to_count,
-        transaction_id_to_relic: &mut transaction_id_to_relic,
+      Self::index_relic_transactions(
+        self.index,
+        self.height,
+        block.header.time,
+        wtx,
+        &block.txdata,
+        &inscription_id_to_sequence_number,
+        &mut statistic_to_count,
         utxo_cache,
-        sequence_number_to_inscription_entry: &sequence_number_to_inscription_entry,
-        sequence_number_to_satpoint: &sequence_number_to_satpoint,
-        sequence_number_to_spaced_relic: &mut sequence_number_to_spaced_relic,
-        relic_to_sequence_number: &mut relic_to_sequence_number,
-        event_emitter: &mut emitter,
-      };
-
-      for (i, (tx, txid)) in block.txdata.iter().enumerate() {
-        relic_updater.index_relics(u32::try_from(i).unwrap(), tx, *txid)?;
-      }
-
-      relic_updater.update()?;
+        &spent_script_pubkeys,
+        inscription_owners,
+        &mut emitter,
+        false,
+      )?;
     }
 
     if self.index.index_runes && self.height >= self.index.settings.first_rune_height() {
@@ -472,17 +457,200 @@ impl Updater<'_> {
     Ok(())
   }
 
+  fn index_relic_transactions<'wtx>(
+    index: &Index,
+    height: u32,
+    block_time: u32,
+    wtx: &'wtx WriteTransaction,
+    txdata: &[(Transaction, Txid)],
+    inscription_id_to_sequence_number: &Table<'wtx, (u128, u128, u32), u32>,
+    statistic_to_count: &mut Table<'wtx, u64, u64>,
+    utxo_cache: &HashMap<OutPoint, UtxoEntryBuf>,
+    spent_script_pubkeys: &HashMap<OutPoint, ScriptBuf>,
+    inscription_owners: &mut HashMap<u32, Option<RelicOwner>>,
+    emitter: &mut EventEmitter<'_, 'wtx>,
+    simulation: bool,
+  ) -> Result {
+    // pending transactions have no history, prices or holders
+    let mut outpoint_to_relic_balances = wtx.open_table(OUTPOINT_TO_RELIC_BALANCES)?;
+    let mut outpoint_to_relic_history = (index.index_relic_history && !simulation)
+      .then(|| wtx.open_table(OUTPOINT_TO_RELIC_HISTORY))
+      .transpose()?;
+    let mut outpoint_to_relic_shares = wtx.open_table(OUTPOINT_TO_RELIC_SHARES)?;
+    let mut relic_id_to_pool_prices = (index.index_relic_prices && !simulation)
+      .then(|| wtx.open_table(RELIC_ID_TO_POOL_PRICES))
+      .transpose()?;
+    let mut relic_id_to_relic_entry = wtx.open_table(RELIC_ID_TO_RELIC_ENTRY)?;
+    let mut relic_to_relic_id = wtx.open_table(RELIC_TO_RELIC_ID)?;
+    let mut relic_owner_to_claimable = wtx.open_table(RELIC_OWNER_TO_CLAIMABLE)?;
+    let mut relic_id_to_holders = (index.index_addresses && !simulation)
+      .then(|| wtx.open_table(RELIC_ID_TO_HOLDERS))
+      .transpose()?;
+    let mut script_pubkey_to_relic_balance = (index.index_addresses && !simulation)
+      .then(|| wtx.open_table(SCRIPT_PUBKEY_TO_RELIC_BALANCE))
+      .transpose()?;
+    let mut transaction_id_to_relic = wtx.open_table(TRANSACTION_ID_TO_RELIC)?;
+    let mut sequence_number_to_spaced_relic = wtx.open_table(SEQUENCE_NUMBER_TO_SPACED_RELIC)?;
+    let mut relic_to_sequence_number = wtx.open_table(RELIC_TO_SEQUENCE_NUMBER)?;
+    let sequence_number_to_satpoint = wtx.open_table(SEQUENCE_NUMBER_TO_SATPOINT)?;
+    let sequence_number_to_inscription_entry =
+      wtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+
+    let relics = statistic_to_count
+      .get(&Statistic::Relics.into())?
+      .map(|x| x.value())
+      .unwrap_or(0);
+
+    let mut relic_updater = RelicUpdater {
+      block_time,
+      burned: HashMap::new(),
+      claimable: HashMap::new(),
+      unsafe_txids: HashSet::new(),
+      index,
+      height,
+      holder_changes: HashMap::new(),
+      id_to_entry: &mut relic_id_to_relic_entry,
+      inscription_id_to_sequence_number,
+      inscription_owners,
+      mints_in_block: HashMap::new(),
+      outpoint_to_balances: &mut outpoint_to_relic_balances,
+      outpoint_to_history: outpoint_to_relic_history.as_mut(),
+      outpoint_to_shares: &mut outpoint_to_relic_shares,
+      pool_prices: relic_id_to_pool_prices.as_mut(),
+      relic_id_to_holders: relic_id_to_holders.as_mut(),
+      relic_owner_to_claimable: &mut relic_owner_to_claimable,
+      relic_to_id: &mut relic_to_relic_id,
+      relics,
+      script_pubkey_to_relic_balance: script_pubkey_to_relic_balance.as_mut(),
+      simulation,
+      statistic_to_count,
+      spent_script_pubkeys,
+      transaction_id_to_relic: &mut transaction_id_to_relic,
+      utxo_cache,
+      sequence_number_to_inscription_entry: &sequence_number_to_inscription_entry,
+      sequence_number_to_satpoint: &sequence_number_to_satpoint,
+      sequence_number_to_spaced_relic: &mut sequence_number_to_spaced_relic,
+      relic_to_sequence_number: &mut relic_to_sequence_number,
+      event_emitter: emitter,
+    };
+
+    for (i, (tx, txid)) in txdata.iter().enumerate() {
+      relic_updater.index_relics(u32::try_from(i).unwrap(), tx, *txid)?;
+    }
+
+    relic_updater.update()?;
+
+    Ok(())
+  }
+
+  /// Run the Relic indexer over pending transactions as if they were mined at `height`, `read` is
+  /// called with the resulting state.
+  ///
+  /// The transactions are indexed into a scratch database in memory, seeded with the committed rows
+  /// they can read: the balances and shares of the outputs they spend, all relic entries with the
+  /// satpoints of their owner inscriptions, and the statistics.
+  pub(super) fn simulate_relic_transactions<T>(
+    index: &Index,
+    height: u32,
+    block_time: u32,
+    txdata: &[(Transaction, Txid)],
+    read: impl FnOnce(&WriteTransaction) -> Result<T>,
+  ) -> Result<T> {
+    let scratch = Database::builder().create_with_backend(InMemoryBackend::new())?;
+    let mut wtx = scratch.begin_write()?;
+    wtx.set_durability(redb::Durability::None);
+
+    {
+      let rtx = index.database.begin_read()?;
+
+      let outpoint_to_relic_balances = rtx.open_table(OUTPOINT_TO_RELIC_BALANCES)?;
+      let outpoint_to_relic_shares = rtx.open_table(OUTPOINT_TO_RELIC_SHARES)?;
+      let mut scratch_balances = wtx.open_table(OUTPOINT_TO_RELIC_BALANCES)?;
+      let mut scratch_shares = wtx.open_table(OUTPOINT_TO_RELIC_SHARES)?;
+      for (tx, _) in txdata {
+        for input in &tx.input {
+          let key = input.previous_output.store();
+          if let Some(balances) = outpoint_to_relic_balances.get(&key)? {
+            scratch_balances.insert(&key, balances.value())?;
+          }
+          if let Some(shares) = outpoint_to_relic_shares.get(&key)? {
+            scratch_shares.insert(&key, shares.value())?;
+          }
+        }
+      }
+
+      let sequence_number_to_satpoint = rtx.open_table(SEQUENCE_NUMBER_TO_SATPOINT)?;
+      let mut scratch_entries = wtx.open_table(RELIC_ID_TO_RELIC_ENTRY)?;
+      let mut scratch_satpoints = wtx.open_table(SEQUENCE_NUMBER_TO_SATPOINT)?;
+      for result in rtx.open_table(RELIC_ID_TO_RELIC_ENTRY)?.iter()? {
+        let (id, entry) = result?;
+        let entry = entry.value();
+        if let Some(sequence_number) = RelicEntry::load(entry).owner_sequence_number {
+          if let Some(satpoint) = sequence_number_to_satpoint.get(sequence_number)? {
+            scratch_satpoints.insert(sequence_number, satpoint.value())?;
+          }
+        }
+        scratch_entries.insert(id.value(), entry)?;
+      }
+
+      let mut scratch_statistics = wtx.open_table(STATISTIC_TO_COUNT)?;
+      for result in rtx.open_table(STATISTIC_TO_COUNT)?.iter()? {
+        let (statistic, count) = result?;
+        scratch_statistics.insert(statistic.value(), count.value())?;
+      }
+    }
+
+    {
+      let inscription_id_to_sequence_number = wtx.open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?;
+      let mut statistic_to_count = wtx.open_table(STATISTIC_TO_COUNT)?;
+      let mut event_key_to_event = wtx.open_table(EVENT_KEY_TO_EVENT)?;
+      let mut relic_id_to_event_keys = wtx.open_table(RELIC_ID_TO_EVENT_KEYS)?;
+      let mut transaction_id_to_event_keys =
+        wtx.open_multimap_table(TRANSACTION_ID_TO_EVENT_KEYS)?;
+
+      let mut emitter = EventEmitter {
+        block_height: height,
+        event_index: 0,
+        // events of pending transactions are not published
+        event_sender: None,
+        pending_events: None,
+        event_key_to_event: &mut event_key_to_event,
+        relic_id_to_event_keys: &mut relic_id_to_event_keys,
+        transaction_id_to_event_keys: &mut transaction_id_to_event_keys,
+      };
+
+      Self::index_relic_transactions(
+        index,
+        height,
+        block_time,
+        &wtx,
+        txdata,
+        &inscription_id_to_sequence_number,
+        &mut statistic_to_count,
+        &HashMap::new(),
+        &HashMap::new(),
+        &mut HashMap::new(),
+        &mut emitter,
+        true,
+      )?;
+    }
+
+    read(&wtx)
+  }
+
   fn index_utxo_entries<'wtx>(
     &mut self,
     block: &BlockData,
     txout_receiver: &mut broadcast::Receiver<TxOut>,
     output_sender: &mut mpsc::Sender<OutPoint>,
     utxo_cache: &mut HashMap<OutPoint, UtxoEntryBuf>,
+    inscription_owners: &mut HashMap<u32, Option<RelicOwner>>,
     wtx: &'wtx WriteTransaction,
     inscription_id_to_sequence_number: &mut Table<'wtx, (u128, u128, u32), u32>,
     statistic_to_count: &mut Table<'wtx, u64, u64>,
     sat_ranges_written: &mut u64,
     outputs_in_block: &mut u64,
+    spent_script_pubkeys: &mut HashMap<OutPoint, ScriptBuf>,
     emitter: &mut EventEmitter<'_, 'wtx>,
   ) -> Result<(), Error> {
     let mut height_to_last_sequence_number = wtx.open_table(HEIGHT_TO_LAST_SEQUENCE_NUMBER)?;
@@ -581,6 +749,7 @@ impl Updater<'_> {
       home_inscription_count,
       home_inscriptions: &mut home_inscriptions,
       id_to_sequence_number: inscription_id_to_sequence_number,
+      inscription_owners,
       inscription_number_to_sequence_number: &mut inscription_number_to_sequence_number,
       lost_sats,
       next_sequence_number,
@@ -665,6 +834,16 @@ impl Updater<'_> {
         .map(|entry| entry.parse(self.index))
         .collect::<Vec<ParsedUtxoEntry>>();
 
+      // spent outputs leave the UTXO index, keep their script pubkeys for the relic holders
+      if self.index.index_addresses {
+        for (input, entry) in tx.input.iter().zip(&input_utxo_entries) {
+          spent_script_pubkeys.insert(
+            input.previous_output,
+            ScriptBuf::from_bytes(entry.script_pubkey().to_vec()),
+          );
+        }
+      }
+
       let mut output_utxo_entries = tx
         .output
         .iter()
@@ -939,6 +1118,8 @@ impl Updater<'_> {
     Index::increment_statistic(&wtx, Statistic::Commits, 1)?;
     wtx.commit()?;
 
+    self.index.publish_events();
+
     // Commit twice since due to a bug redb will only reuse pages freed in the
     // transaction before last.
     self.index.begin_write()?.commit()?;
@@ -46,6 +46,9 @@ pub(super) struct InscriptionUpdater<'a, 'tx, 'emitter> {
   pub(super) home_inscription_count: u64,
   pub(super) home_inscriptions: &'a mut Table<'tx, u32, InscriptionIdValue>,
   pub(super) id_to_sequence_number: &'a mut Table<'tx, InscriptionIdValue, u32>,
+  /// owner of every inscription that moved since the last commit, only tracked if Relics are
+  /// indexed, `None` if the inscription is unbound or lost
+  pub(super) inscription_owners: &'a mut HashMap<u32, Option<RelicOwner>>,
   pub(super) inscription_number_to_sequence_number: &'a mut Table<'tx, i32, u32>,
   pub(super) lost_sats: u64,
   pub(super) next_sequence_number: u32,
@@ -336,14 +339,14 @@ impl InscriptionUpdater<'_, '_, '_> {
         new_locations.push((
           new_satpoint,
           inscriptions.next().unwrap(),
-          txout.script_pubkey.is_op_return(),
+          txout.script_pubkey.as_script(),
         ));
       }
 
       output_value = end;
     }
 
-    for (new_satpoint, flotsam, op_return) in new_locations.into_iter() {
+    for (new_satpoint, flotsam, script_pubkey) in new_locations.into_iter() {
       let output_utxo_entry =
         &mut output_utxo_entries[usize::try_from(new_satpoint.outpoint.vout).unwrap()];
 
@@ -352,7 +355,7 @@ impl InscriptionUpdater<'_, '_, '_> {
         input_sat_ranges,
         flotsam,
         new_satpoint,
-        op_return,
+        Some(script_pubkey),
         Some(output_utxo_entry),
         utxo_cache,
         index,
@@ -370,7 +373,7 @@ impl InscriptionUpdater<'_, '_, '_> {
           input_sat_ranges,
           flotsam,
           new_satpoint,
-          false,
+          None,
           None,
           utxo_cache,
           index,
@@ -414,12 +417,13 @@ impl InscriptionUpdater<'_, '_, '_> {
     input_sat_ranges: Option<&Vec<&[u8]>>,
     flotsam: Flotsam,
     new_satpoint: SatPoint,
-    op_return: bool,
+    script_pubkey: Option<&Script>,
     mut normal_output_utxo_entry: Option<&mut UtxoEntryBuf>,
     utxo_cache: &mut HashMap<OutPoint, UtxoEntryBuf>,
     index: &Index,
   ) -> Result {
     let inscription_id = flotsam.inscription_id;
+    let op_return = script_pubkey.is_some_and(Script::is_op_return);
     let store = flotsam.keep;
     let (unbound, sequence_number) = match flotsam.origin {
       Origin::Old {
@@ -629,6 +633,15 @@ impl InscriptionUpdater<'_, '_, '_> {
       output_utxo_entry.push_inscription(sequence_number, satpoint.offset, index);
     }
 
+    if index.index_relics {
+      self.inscription_owners.insert(
+        sequence_number,
+        script_pubkey
+          .filter(|_| !unbound)
+          .map(|script_pubkey| RelicOwner(script_pubkey.script_hash())),
+      );
+    }
+
     Ok(())
   }
 }
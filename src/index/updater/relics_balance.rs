@@ -1,4 +1,11 @@
-use super::*;
+use {super::*, crate::relics::RelicAllocation};
+
+/// Change of the Relic balance held by a script pubkey
+#[derive(Default)]
+pub struct HolderChange {
+  pub received: Lot,
+  pub spent: Lot,
+}
 
 pub struct RelicsBalance {
   total: HashMap<RelicId, Lot>,
@@ -7,6 +14,11 @@ pub struct RelicsBalance {
   allocated: Vec<HashMap<RelicId, Lot>>,
   incoming: HashMap<(Address, RelicId), Lot>,
   outgoing: HashMap<(Address, RelicId), Lot>,
+  /// Relics spent by each script pubkey, only tracked if addresses are indexed
+  spent_by_script: HashMap<(ScriptBuf, RelicId), Lot>,
+  shares: HashMap<RelicId, Lot>,
+  allocated_shares: Vec<HashMap<RelicId, Lot>>,
+  burned_shares: HashMap<RelicId, Lot>,
 }
 
 impl<'a, 'tx, 'emitter> RelicsBalance {
@@ -14,7 +26,10 @@ impl<'a, 'tx, 'emitter> RelicsBalance {
     tx: &Transaction,
     unsafe_txids: &HashSet<Txid>,
     outpoint_to_balances: &'a mut Table<'tx, &'static OutPointValue, &'static [u8]>,
+    outpoint_to_shares: &'a mut Table<'tx, &'static OutPointValue, &'static [u8]>,
+    spent_script_pubkeys: &HashMap<OutPoint, ScriptBuf>,
     index: &Index,
+    simulation: bool,
   ) -> Result<Self> {
     // map of RelicsId to unallocated balance of that Relic
     let mut total: HashMap<RelicId, Lot> = HashMap::new();
@@ -22,22 +37,44 @@ impl<'a, 'tx, 'emitter> RelicsBalance {
     let mut safe: HashMap<RelicId, Lot> = HashMap::new();
     // tracks which address contributed which Relic
     let mut incoming: HashMap<(Address, RelicId), Lot> = HashMap::new();
+    // tracks which script pubkey contributed which Relic
+    let mut spent_by_script: HashMap<(ScriptBuf, RelicId), Lot> = HashMap::new();
 
-    // increment unallocated Relics with the Relics in tx inputs
+    // increment unallocated Relics with the Relics in tx inputs, outputs spent by simulated pending
+    // transactions are kept, so the outputs of every pending transaction can be read afterwards
     for input in &tx.input {
-      let Some(guard) = outpoint_to_balances.remove(&input.previous_output.store())? else {
+      let key = input.previous_output.store();
+      let guard = if simulation {
+        outpoint_to_balances.get(&key)?
+      } else {
+        outpoint_to_balances.remove(&key)?
+      };
+      let Some(guard) = guard else {
         continue;
       };
-      let sender = if let Some(tx) = index.get_transaction(input.previous_output.txid)? {
-        let output = &tx.output[input.previous_output.vout as usize];
+      // holders are only updated for mined transactions, whose spent outputs always have a known
+      // script pubkey if addresses are indexed
+      let script_pubkey = match spent_script_pubkeys.get(&input.previous_output) {
+        Some(script_pubkey) => Some(script_pubkey.clone()),
+        None if index.index_addresses && !simulation => bail!(
+          "script pubkey of output {} holding relics not found",
+          input.previous_output
+        ),
+        None => index
+          .get_transaction(input.previous_output.txid)?
+          .map(|tx| {
+            tx.output[input.previous_output.vout as usize]
+              .script_pubkey
+              .clone()
+          }),
+      };
+      let sender = script_pubkey.as_ref().and_then(|script_pubkey| {
         index
           .settings
           .chain()
-          .address_from_script(&output.script_pubkey)
+          .address_from_script(script_pubkey)
           .ok()
-      } else {
-        None
-      };
+      });
       let buffer = guard.value();
       let mut i = 0;
       while i < buffer.len() {
@@ -53,8 +90,33 @@ impl<'a, 'tx, 'emitter> RelicsBalance {
         if let Some(sender) = sender.clone() {
           *incoming.entry((sender, id)).or_default() += balance;
         }
+        if let Some(script_pubkey) = script_pubkey.clone().filter(|_| index.index_addresses) {
+          *spent_by_script.entry((script_pubkey, id)).or_default() += balance;
+        }
+      }
+    }
+
+    // map of RelicId to the pool shares of that Relic in tx inputs
+    let mut shares: HashMap<RelicId, Lot> = HashMap::new();
+    for input in &tx.input {
+      let key = input.previous_output.store();
+      let guard = if simulation {
+        outpoint_to_shares.get(&key)?
+      } else {
+        outpoint_to_shares.remove(&key)?
+      };
+      let Some(guard) = guard else {
+        continue;
+      };
+      let buffer = guard.value();
+      let mut i = 0;
+      while i < buffer.len() {
+        let ((id, amount), len) = Index::decode_rune_balance(&buffer[i..]).unwrap();
+        i += len;
+        *shares.entry(id).or_default() += amount;
       }
     }
+
     Ok(RelicsBalance {
       total,
       safe,
@@ -62,6 +124,10 @@ impl<'a, 'tx, 'emitter> RelicsBalance {
       allocated: vec![HashMap::new(); tx.output.len()],
       incoming,
       outgoing: HashMap::new(),
+      spent_by_script,
+      shares,
+      allocated_shares: vec![HashMap::new(); tx.output.len()],
+      burned_shares: HashMap::new(),
     })
   }
 
@@ -105,23 +171,46 @@ impl<'a, 'tx, 'emitter> RelicsBalance {
     *self.safe.entry(id).or_default() += amount;
   }
 
+  pub fn get_shares(&self, id: RelicId) -> u128 {
+    RelicsBalance::lookup(&self.shares, id)
+  }
+
+  pub fn add_shares(&mut self, id: RelicId, amount: Lot) {
+    *self.shares.entry(id).or_default() += amount;
+  }
+
+  /// This will panic if there are not enough shares.
+  pub fn remove_shares(&mut self, id: RelicId, amount: Lot) {
+    *self.shares.entry(id).or_default() -= amount;
+  }
+
   pub fn burn(&mut self, id: RelicId, amount: Lot) {
     *self.burned.entry(id).or_default() += amount;
   }
 
+  /// Burn all Relics and pool shares.
+  /// Burned shares are locked in their pool, the liquidity behind them can never be removed.
   pub fn burn_all(&mut self) {
     for (id, balance) in self.total.clone() {
       self.burn(id, balance);
     }
+    for (id, shares) in self.shares.drain() {
+      *self.burned_shares.entry(id).or_default() += shares;
+    }
     self.total.clear();
     self.safe.clear();
   }
 
-  // Allocate all
+  /// Allocate all Relics and pool shares.
   pub fn allocate_all(&mut self, output: usize) {
     for (id, balance) in self.total.clone() {
       self.allocate(output, id, balance);
     }
+    for (id, shares) in self.shares.drain() {
+      if shares > 0 {
+        *self.allocated_shares[output].entry(id).or_default() += shares;
+      }
+    }
     self.total.clear();
     self.safe.clear();
   }
@@ -143,87 +232,38 @@ impl<'a, 'tx, 'emitter> RelicsBalance {
   ) {
     // this algorithm does not handle safe balance, therefore it is just cleared
     self.safe.clear();
-    for Transfer { id, amount, output } in transfers.iter().copied() {
-      let amount = Lot(amount);
-
-      // edicts with output values greater than the number of outputs
-      // should never be produced by the edict parser
-      let output = usize::try_from(output).unwrap();
-      assert!(output <= tx.output.len());
-
-      let id = if id == RelicId::default() {
-        let Some(id) = default else {
-          continue;
-        };
-        id
-      } else {
-        id
-      };
-
-      let Some(balance) = self.total.get_mut(&id) else {
-        continue;
-      };
-
-      let mut allocate = |balance: &mut Lot, amount: Lot, output: usize| {
-        if amount > 0 {
-          *balance -= amount;
-          *self.allocated[output].entry(id).or_default() += amount;
-        }
-      };
-
-      if output == tx.output.len() {
-        // find non-OP_RETURN outputs
-        let destinations = tx
-          .output
-          .iter()
-          .enumerate()
-          .filter_map(|(output, tx_out)| (!tx_out.script_pubkey.is_op_return()).then_some(output))
-          .collect::<Vec<usize>>();
-
-        if !destinations.is_empty() {
-          if amount == 0 {
-            // if amount is zero, divide balance between eligible outputs
-            let amount = *balance / destinations.len() as u128;
-            let remainder = usize::try_from(*balance % destinations.len() as u128).unwrap();
-
-            for (i, output) in destinations.iter().enumerate() {
-              allocate(
-                balance,
-                if i < remainder { amount + 1 } else { amount },
-                *output,
-              );
-            }
-          } else {
-            // if amount is non-zero, distribute amount to eligible outputs
-            for output in destinations {
-              allocate(balance, amount.min(*balance), output);
-            }
-          }
-        }
-      } else {
-        // Get the allocatable amount
-        let amount = if amount == 0 {
-          *balance
-        } else {
-          amount.min(*balance)
-        };
+    for (output, id, amount) in
+      RelicAllocation::transfers(tx, transfers, default, |id| self.get(id))
+    {
+      *self.total.get_mut(&id).unwrap() -= Lot(amount);
+      self.allocate(output, id, Lot(amount));
+    }
+  }
 
-        allocate(balance, amount, output);
-      }
+  /// Allocate pool shares based on the given share transfers.
+  pub fn allocate_share_transfers(&mut self, transfers: &[Transfer], tx: &Transaction) {
+    for (output, id, amount) in
+      RelicAllocation::transfers(tx, transfers, None, |id| self.get_shares(id))
+    {
+      *self.shares.get_mut(&id).unwrap() -= Lot(amount);
+      *self.allocated_shares[output].entry(id).or_default() += Lot(amount);
     }
   }
 
-  /// Assign allocated balances to outpoints, update burned balances, track unsafe outpoints.
+  /// Assign allocated balances and pool shares to outpoints, update burned balances, track unsafe
+  /// outpoints. Returns the burned pool shares.
   pub fn finalize(
     mut self,
     tx: &Transaction,
     txid: Txid,
     outpoint_to_balances: &'a mut Table<'tx, &'static OutPointValue, &'static [u8]>,
+    outpoint_to_shares: &'a mut Table<'tx, &'static OutPointValue, &'static [u8]>,
     unsafe_txids: &'a mut HashSet<Txid>,
     burned: &'a mut HashMap<RelicId, Lot>,
+    holder_changes: &'a mut HashMap<(ScriptBuf, RelicId), HolderChange>,
     event_emitter: &'a mut EventEmitter<'emitter, 'tx>,
     index: &Index,
-  ) -> Result {
+  ) -> Result<HashMap<RelicId, Lot>> {
     // update outpoint balances
     let mut buffer: Vec<u8> = Vec::new();
     for (vout, balances) in self.allocated.into_iter().enumerate() {
@@ -258,6 +298,12 @@ impl<'a, 'tx, 'emitter> RelicsBalance {
         if let Ok(receiver) = index.settings.chain().address_from_script(output_script) {
           *self.outgoing.entry((receiver, id)).or_default() += balance;
         }
+        if index.index_addresses {
+          holder_changes
+            .entry((output_script.clone(), id))
+            .or_default()
+            .received += balance;
+        }
 
         event_emitter.emit(
           txid,
@@ -272,6 +318,42 @@ impl<'a, 'tx, 'emitter> RelicsBalance {
       outpoint_to_balances.insert(&outpoint.store(), buffer.as_slice())?;
     }
 
+    // update outpoint shares, shares sent to an OP_RETURN output are burned
+    for (vout, shares) in self.allocated_shares.into_iter().enumerate() {
+      if shares.is_empty() {
+        continue;
+      }
+
+      if tx.output[vout].script_pubkey.is_op_return() {
+        for (id, amount) in shares {
+          *self.burned_shares.entry(id).or_default() += amount;
+        }
+        continue;
+      }
+
+      let mut shares = shares.into_iter().collect::<Vec<(RelicId, Lot)>>();
+      shares.sort();
+
+      buffer.clear();
+      for (id, amount) in shares {
+        Index::encode_rune_balance(id, amount.n(), &mut buffer);
+      }
+
+      let outpoint = OutPoint {
+        txid,
+        vout: vout.try_into().unwrap(),
+      };
+
+      outpoint_to_shares.insert(&outpoint.store(), buffer.as_slice())?;
+    }
+
+    for ((script_pubkey, relic_id), spent) in self.spent_by_script {
+      holder_changes
+        .entry((script_pubkey, relic_id))
+        .or_default()
+        .spent += spent;
+    }
+
     for ((address, relic_id), spent) in self.incoming {
       let info = if let Some(received) = self.outgoing.remove(&(address.clone(), relic_id)) {
         if received > spent {
@@ -324,9 +406,24 @@ impl<'a, 'tx, 'emitter> RelicsBalance {
       )?;
     }
 
+    self.burned_shares.retain(|_, shares| *shares > 0);
+
+    let mut burned_shares = self.burned_shares.iter().collect::<Vec<(&RelicId, &Lot)>>();
+    burned_shares.sort();
+
+    for (id, shares) in burned_shares {
+      event_emitter.emit(
+        txid,
+        EventInfo::RelicSharesBurned {
+          relic_id: *id,
+          shares: shares.n(),
+        },
+      )?;
+    }
+
     // Sandwich Protection: mark OutPoints from this Tx as unsafe
     unsafe_txids.insert(txid);
 
-    Ok(())
+    Ok(self.burned_shares)
   }
 }
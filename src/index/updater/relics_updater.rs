@@ -1,6 +1,8 @@
 use super::*;
-use crate::index::updater::relics_balance::RelicsBalance;
-use std::hash::{DefaultHasher, Hash, Hasher};
+use {
+  crate::index::updater::relics_balance::{HolderChange, RelicsBalance},
+  crate::relics::RelicAllocation,
+};
 
 pub(super) struct RelicUpdater<'a, 'tx, 'client, 'emitter> {
   pub(super) block_time: u32,
@@ -9,13 +11,30 @@ pub(super) struct RelicUpdater<'a, 'tx, 'client, 'emitter> {
   pub(super) unsafe_txids: HashSet<Txid>,
   pub(super) index: &'client Index,
   pub(super) height: u32,
+  pub(super) holder_changes: HashMap<(ScriptBuf, RelicId), HolderChange>,
   pub(super) id_to_entry: &'a mut Table<'tx, RelicIdValue, RelicEntryValue>,
   pub(super) inscription_id_to_sequence_number: &'a Table<'tx, InscriptionIdValue, u32>,
+  pub(super) inscription_owners: &'a mut HashMap<u32, Option<RelicOwner>>,
   pub(super) mints_in_block: HashMap<RelicId, u32>,
   pub(super) outpoint_to_balances: &'a mut Table<'tx, &'static OutPointValue, &'static [u8]>,
+  /// creation height, spending height, script pubkey and balances of every output that held
+  /// Relics, only tracked if the history is indexed
+  pub(super) outpoint_to_history: Option<
+    &'a mut Table<'tx, &'static OutPointValue, (u32, Option<u32>, &'static [u8], &'static [u8])>,
+  >,
+  pub(super) outpoint_to_shares: &'a mut Table<'tx, &'static OutPointValue, &'static [u8]>,
+  pub(super) pool_prices: Option<&'a mut Table<'tx, (RelicIdValue, u32, u32), PoolPriceValue>>,
+  pub(super) relic_id_to_holders:
+    Option<&'a mut Table<'tx, (RelicIdValue, u128, &'static [u8]), ()>>,
   pub(super) relic_owner_to_claimable: &'a mut Table<'tx, &'static RelicOwnerValue, u128>,
   pub(super) relic_to_id: &'a mut Table<'tx, u128, RelicIdValue>,
   pub(super) relics: u64,
+  pub(super) script_pubkey_to_relic_balance:
+    Option<&'a mut Table<'tx, (&'static [u8], RelicIdValue), u128>>,
+  /// pending transactions are simulated, their failures are not reported
+  pub(super) simulation: bool,
+  /// script pubkeys of the outputs spent in the block, only tracked if addresses are indexed
+  pub(super) spent_script_pubkeys: &'a HashMap<OutPoint, ScriptBuf>,
   pub(super) statistic_to_count: &'a mut Table<'tx, u64, u64>,
   pub(super) transaction_id_to_relic: &'a mut Table<'tx, &'static TxidValue, u128>,
   pub(super) utxo_cache: &'a HashMap<OutPoint, UtxoEntryBuf>,
@@ -27,14 +46,45 @@ pub(super) struct RelicUpdater<'a, 'tx, 'client, 'emitter> {
 }
 
 impl RelicUpdater<'_, '_, '_, '_> {
+  /// Report a failed operation, unless pending transactions are simulated
+  fn report(&self, message: fmt::Arguments) {
+    if !self.simulation {
+      eprintln!("{message}");
+    }
+  }
+
   pub(super) fn index_relics(&mut self, tx_index: u32, tx: &Transaction, txid: Txid) -> Result<()> {
-    let artifact = Keepsake::decipher(tx);
+    let artifact = Keepsake::decipher_at(tx, self.index.settings.chain().network(), self.height);
+
+    if let Some(history) = self.outpoint_to_history.as_mut() {
+      for input in &tx.input {
+        let key = input.previous_output.store();
+        let Some((created, script_pubkey, balances)) = history.get(&key)?.map(|record| {
+          let (created, _spent, script_pubkey, balances) = record.value();
+          (created, script_pubkey.to_vec(), balances.to_vec())
+        }) else {
+          continue;
+        };
+        history.insert(
+          &key,
+          (
+            created,
+            Some(self.height),
+            script_pubkey.as_slice(),
+            balances.as_slice(),
+          ),
+        )?;
+      }
+    }
 
     let mut balances = RelicsBalance::new(
       tx,
       &self.unsafe_txids,
       self.outpoint_to_balances,
+      self.outpoint_to_shares,
+      self.spent_script_pubkeys,
       self.index,
+      self.simulation,
     )?;
 
     if let Some(amount) = self.mint_base_token(txid, tx)? {
@@ -50,7 +100,7 @@ impl RelicUpdater<'_, '_, '_, '_> {
             balances.burn(RELIC_ID, sealing_fee);
           }
           Err(error) => {
-            eprintln!("Sealing error: {error}");
+            self.report(format_args!("Sealing error: {error}"));
             self.event_emitter.emit(
               txid,
               EventInfo::RelicError {
@@ -77,7 +127,7 @@ impl RelicUpdater<'_, '_, '_, '_> {
             Some(id)
           }
           Err(error) => {
-            eprintln!("Enshrine error: {error}");
+            self.report(format_args!("Enshrine error: {error}"));
             self.event_emitter.emit(
               txid,
               EventInfo::RelicError {
@@ -92,12 +142,14 @@ impl RelicUpdater<'_, '_, '_, '_> {
         None
       };
 
-      if let Some(swap) = &keepsake.swap {
+      // swaps are executed in order, a failing swap does not affect the other swaps
+      for (index, swap) in keepsake.swaps.iter().enumerate() {
+        let index = u32::try_from(index).unwrap();
         let input = swap.input.unwrap_or(RELIC_ID);
         let output = swap.output.unwrap_or(RELIC_ID);
         // note: use safe balance here for Sandwich protection:
         // this will prevent swapping the same Relics twice within a block
-        match self.swap(txid, swap, input, output, balances.get_safe(input))? {
+        match self.swap(txid, index, swap, input, output, balances.get_safe(input))? {
           Ok((input_amount, output_amount, fees)) => {
             balances.remove_safe(input, Lot(input_amount));
             balances.add(output, Lot(output_amount));
@@ -112,11 +164,48 @@ impl RelicUpdater<'_, '_, '_, '_> {
             }
           }
           Err(error) => {
-            eprintln!("Swap error: {error}");
+            self.report(format_args!("Swap error: {error}"));
             self.event_emitter.emit(
               txid,
               EventInfo::RelicError {
-                operation: RelicOperation::Swap,
+                operation: RelicOperation::Swap { index },
+                error,
+              },
+            )?;
+          }
+        }
+      }
+
+      if let Some(liquidity) = keepsake.liquidity {
+        let id = liquidity.relic;
+        match self.liquidity(
+          txid,
+          liquidity,
+          balances.get(RELIC_ID),
+          balances.get(id),
+          balances.get_shares(id),
+        )? {
+          Ok(diff) => {
+            if diff.is_remove {
+              balances.remove_shares(id, Lot(diff.shares));
+              balances.add(RELIC_ID, Lot(diff.base_amount));
+              balances.add(id, Lot(diff.quote_amount));
+            } else {
+              balances.remove(RELIC_ID, Lot(diff.base_amount));
+              balances.remove(id, Lot(diff.quote_amount));
+              balances.add_shares(id, Lot(diff.shares));
+            }
+          }
+          Err(error) => {
+            self.report(format_args!("Liquidity error: {error}"));
+            self.event_emitter.emit(
+              txid,
+              EventInfo::RelicError {
+                operation: if liquidity.is_remove {
+                  RelicOperation::RemoveLiquidity
+                } else {
+                  RelicOperation::AddLiquidity
+                },
                 error,
               },
             )?;
@@ -135,7 +224,9 @@ impl RelicUpdater<'_, '_, '_, '_> {
           if multi.is_unmint {
             // Unmint not allowed if an enshrined relic is present
             if enshrined_relic.is_some() {
-              eprintln!("Unmint error: Unmint not allowed in transaction with enshrined relic");
+              self.report(format_args!(
+                "Unmint error: Unmint not allowed in transaction with enshrined relic"
+              ));
               self.event_emitter.emit(
                 txid,
                 EventInfo::RelicError {
@@ -165,7 +256,7 @@ impl RelicUpdater<'_, '_, '_, '_> {
                   balances.add(RELIC_ID, Lot(total_base));
                 }
                 Err(error) => {
-                  eprintln!("MultiUnmint error: {error}");
+                  self.report(format_args!("MultiUnmint error: {error}"));
                   self.event_emitter.emit(
                     txid,
                     EventInfo::RelicError {
@@ -204,7 +295,7 @@ impl RelicUpdater<'_, '_, '_, '_> {
                 balances.add(id, Lot(total_relic));
               }
               Err(error) => {
-                eprintln!("MultiMint error: {error}");
+                self.report(format_args!("MultiMint error: {error}"));
                 self.event_emitter.emit(
                   txid,
                   EventInfo::RelicError {
@@ -227,7 +318,7 @@ impl RelicUpdater<'_, '_, '_, '_> {
           // handle fee collection: assign all fees claimable by the given owner
           balances.allocate(claim, RELIC_ID, amount);
         } else {
-          eprintln!("Claim error: no balance to claim");
+          self.report(format_args!("Claim error: no balance to claim"));
           self.event_emitter.emit(
             txid,
             EventInfo::RelicError {
@@ -239,47 +330,86 @@ impl RelicUpdater<'_, '_, '_, '_> {
       }
 
       balances.allocate_transfers(&keepsake.transfers, enshrined_relic, tx);
+      balances.allocate_share_transfers(&keepsake.share_transfers, tx);
     }
 
-    let first_non_op_return_output = || {
-      tx.output
-        .iter()
-        .enumerate()
-        .find(|(_vout, tx_out)| !tx_out.script_pubkey.is_op_return())
-        .map(|(vout, _tx_out)| vout)
-    };
-
-    let default_output = match artifact {
-      // no protocol message: pass through to first non-op_return
-      None => first_non_op_return_output(),
-      // valid protocol message: use pointer as output or default to the first non-op_return
-      Some(RelicArtifact::Keepsake(keepsake)) => keepsake
-        .pointer
-        .map(|pointer| pointer as usize)
-        .or_else(first_non_op_return_output),
+    if let Some(RelicArtifact::Cenotaph(_)) = &artifact {
       // invalid protocol message: explicitly burn all Relics
-      Some(RelicArtifact::Cenotaph(_)) => {
-        eprintln!("Cenotaph encountered in tx {}: burning all relics", txid);
-        None
-      }
-    };
+      self.report(format_args!(
+        "Cenotaph encountered in tx {}: burning all relics",
+        txid
+      ));
+    }
 
-    if let Some(vout) = default_output {
+    if let Some(vout) = RelicAllocation::default_output(tx, artifact.as_ref()) {
       // note: vout might still point to an OP_RETURN output resulting in a burn on finalize
       balances.allocate_all(vout);
     } else {
       balances.burn_all();
     }
 
-    balances.finalize(
+    let burned_shares = balances.finalize(
       tx,
       txid,
       self.outpoint_to_balances,
+      self.outpoint_to_shares,
       &mut self.unsafe_txids,
       &mut self.burned,
+      &mut self.holder_changes,
       self.event_emitter,
       self.index,
-    )
+    )?;
+
+    // burned shares are locked in their pool
+    for (id, shares) in burned_shares {
+      let mut entry = self
+        .load_relic_entry(id)?
+        .ok_or_else(|| anyhow!("shares of unknown relic {id} burned in {txid}"))?;
+      let fee = entry.burn_shares(shares.n()).map_err(|err| {
+        anyhow!(
+          "failed to lock {} shares of relic {id} burned in {txid}: {err}",
+          shares.n()
+        )
+      })?;
+      self.id_to_entry.insert(&id.store(), entry.store())?;
+      if fee == 0 {
+        continue;
+      }
+      // provider fees nobody is left to claim are paid to the owner, or burned if there is none
+      let owner = match entry.owner_sequence_number {
+        Some(sequence_number) => self.get_inscription_owner(sequence_number)?,
+        None => None,
+      };
+      if let Some(owner) = owner {
+        *self.claimable.entry(owner).or_default() += fee;
+      } else {
+        *self.burned.entry(RELIC_ID).or_default() += Lot(fee);
+      }
+    }
+
+    if let Some(history) = self.outpoint_to_history.as_mut() {
+      for (vout, output) in tx.output.iter().enumerate() {
+        let key = OutPoint {
+          txid,
+          vout: vout.try_into().unwrap(),
+        }
+        .store();
+        let Some(balances) = self.outpoint_to_balances.get(&key)? else {
+          continue;
+        };
+        history.insert(
+          &key,
+          (
+            self.height,
+            None,
+            output.script_pubkey.as_bytes(),
+            balances.value(),
+          ),
+        )?;
+      }
+    }
+
+    Ok(())
   }
 
   pub(super) fn update(self) -> Result {
@@ -302,6 +432,44 @@ impl RelicUpdater<'_, '_, '_, '_> {
         .insert(&owner.store(), current.checked_add(amount).unwrap())?;
     }
 
+    // update balances by script pubkey and the holders sorted by balance
+    if let (Some(script_pubkey_to_relic_balance), Some(relic_id_to_holders)) = (
+      self.script_pubkey_to_relic_balance,
+      self.relic_id_to_holders,
+    ) {
+      for ((script_pubkey, relic_id), change) in self.holder_changes {
+        let script_pubkey = script_pubkey.as_bytes();
+        let id = relic_id.store();
+        let balance = script_pubkey_to_relic_balance
+          .get((script_pubkey, id))?
+          .map(|balance| balance.value())
+          .unwrap_or_default();
+        let updated = balance
+          .checked_add(change.received.n())
+          .and_then(|balance| balance.checked_sub(change.spent.n()))
+          .ok_or_else(|| {
+            anyhow!(
+              "balance {balance} of relic {relic_id} held by {} can not receive {} and spend {}",
+              Script::from_bytes(script_pubkey),
+              change.received.n(),
+              change.spent.n(),
+            )
+          })?;
+        if updated == balance {
+          continue;
+        }
+        if balance > 0 {
+          relic_id_to_holders.remove((id, balance, script_pubkey))?;
+        }
+        if updated > 0 {
+          script_pubkey_to_relic_balance.insert((script_pubkey, id), updated)?;
+          relic_id_to_holders.insert((id, updated, script_pubkey), ())?;
+        } else {
+          script_pubkey_to_relic_balance.remove((script_pubkey, id))?;
+        }
+      }
+    }
+
     Ok(())
   }
 
@@ -350,6 +518,9 @@ impl RelicUpdater<'_, '_, '_, '_> {
         burned: 0,
         mints: 0,
         unmints: 0,
+        paid: 0,
+        legacy_mints: 0,
+        issued: 0,
       },
       pool: None,
       timestamp: self.block_time.into(),
@@ -361,8 +532,11 @@ impl RelicUpdater<'_, '_, '_, '_> {
         entry.pool = Some(Pool {
           base_supply: 0,
           quote_supply: 0,
-          fee_bps: entry.fee.min(1_000),
+          fee_bps: entry.fee.min(Enshrining::MAX_FEE_BPS),
           subsidy: subsidy_amount,
+          shares: 0,
+          locked_shares: 0,
+          provider_fees: 0,
         });
       }
     }
@@ -547,6 +721,7 @@ impl RelicUpdater<'_, '_, '_, '_> {
   fn swap(
     &mut self,
     txid: Txid,
+    swap_index: u32,
     swap: &Swap,
     input: RelicId,
     output: RelicId,
@@ -556,135 +731,35 @@ impl RelicUpdater<'_, '_, '_, '_> {
       input, output,
       "the parser produced an invalid Swap with input Relic == output Relic"
     );
-    let input_entry = self.load_relic_entry(input)?;
-    let output_entry = self.load_relic_entry(output)?;
-    match self.swap_calculate(
-      swap,
-      input,
-      &input_entry,
-      output,
-      &output_entry,
-      input_balance,
-    ) {
-      Ok((sell, buy)) => {
-        let mut fees = Vec::new();
-        if let Some(diff) = sell {
-          fees.push(self.swap_apply(swap, txid, input, &mut input_entry.unwrap(), diff)?);
-        }
-        if let Some(diff) = buy {
-          fees.push(self.swap_apply(swap, txid, output, &mut output_entry.unwrap(), diff)?);
-        }
-        match (sell, buy) {
-          (Some(sell), None) => Ok(Ok((sell.input, sell.output, fees))),
-          (None, Some(buy)) => Ok(Ok((buy.input, buy.output, fees))),
-          (Some(sell), Some(buy)) => Ok(Ok((sell.input, buy.output, fees))),
-          (None, None) => unreachable!(),
-        }
-      }
-      Err(cause) => Ok(Err(cause)),
+    let diffs =
+      match relics_swap::calculate(swap, Some(input_balance), |id| self.load_relic_entry(id))? {
+        Ok(diffs) => diffs,
+        Err(cause) => return Ok(Err(cause)),
+      };
+    let mut fees = Vec::new();
+    for (id, diff) in &diffs {
+      // reload the entry on every leg, a route trades against the same pool twice for every hop
+      let mut entry = self.load_relic_entry(*id)?.unwrap();
+      fees.push(self.swap_apply(swap, swap_index, txid, *id, &mut entry, *diff)?);
     }
+    let input_amount = diffs.first().unwrap().1.input;
+    let output_amount = diffs.last().unwrap().1.output;
+    Ok(Ok((input_amount, output_amount, fees)))
   }
-
-  fn swap_calculate(
-    &self,
-    swap: &Swap,
-    input: RelicId,
-    input_entry: &Option<RelicEntry>,
-    output: RelicId,
-    output_entry: &Option<RelicEntry>,
-    input_balance: u128,
-  ) -> Result<(Option<BalanceDiff>, Option<BalanceDiff>), RelicError> {
-    let simple_swap = |direction: SwapDirection| {
-      if swap.is_exact_input {
-        PoolSwap::Input {
-          direction,
-          input: swap.input_amount.unwrap_or_default(),
-          min_output: swap.output_amount,
-        }
-      } else {
-        PoolSwap::Output {
-          direction,
-          output: swap.output_amount.unwrap_or_default(),
-          max_input: swap.input_amount,
-        }
-      }
-    };
-    let input_entry = input_entry.ok_or(RelicError::RelicNotFound(input))?;
-    let output_entry = output_entry.ok_or(RelicError::RelicNotFound(output))?;
-    match (input, output) {
-      // buy output relic
-      (RELIC_ID, _) => Ok((
-        None,
-        Some(output_entry.swap(simple_swap(SwapDirection::BaseToQuote), Some(input_balance))?),
-      )),
-      // sell input relic
-      (_, RELIC_ID) => Ok((
-        Some(input_entry.swap(simple_swap(SwapDirection::QuoteToBase), Some(input_balance))?),
-        None,
-      )),
-      // dual swap: sell input relic to buy output relic
-      _ => {
-        if swap.is_exact_input {
-          // sell input
-          let diff_sell = input_entry.swap(
-            PoolSwap::Input {
-              direction: SwapDirection::QuoteToBase,
-              input: swap.input_amount.unwrap_or_default(),
-              // no slippage check here, we check on the other swap
-              min_output: None,
-            },
-            Some(input_balance),
-          )?;
-          // buy output
-          let diff_buy = output_entry.swap(
-            PoolSwap::Input {
-              direction: SwapDirection::BaseToQuote,
-              input: diff_sell.output,
-              // slippage check is performed on the second swap, on slippage error both swaps will not be executed
-              min_output: swap.output_amount,
-            },
-            None,
-          )?;
-          Ok((Some(diff_sell), Some(diff_buy)))
-        } else {
-          // calculate the "buy" first to determine how many base tokens we need to get out of the "sell"
-          let diff_buy = output_entry.swap(
-            PoolSwap::Output {
-              direction: SwapDirection::BaseToQuote,
-              output: swap.output_amount.unwrap_or_default(),
-              // no slippage check here, we check on the other swap
-              max_input: None,
-            },
-            None,
-          )?;
-          // sell input
-          let diff_sell = input_entry.swap(
-            PoolSwap::Output {
-              direction: SwapDirection::QuoteToBase,
-              output: diff_buy.input,
-              // slippage check is performed on the second swap, on slippage error both swaps will not be executed
-              max_input: swap.input_amount,
-            },
-            Some(input_balance),
-          )?;
-          Ok((Some(diff_sell), Some(diff_buy)))
-        }
-      }
+  fn get_inscription_owner(&mut self, sequence_number: u32) -> Result<Option<RelicOwner>> {
+    // inscriptions that moved since the last commit are tracked by the inscription updater, as are
+    // the owners looked up below until they move
+    if let Some(owner) = self.inscription_owners.get(&sequence_number) {
+      return Ok(*owner);
     }
+    let owner = self.load_inscription_owner(sequence_number)?;
+    self.inscription_owners.insert(sequence_number, owner);
+    Ok(owner)
   }
 
-  fn get_inscription_owner(&self, sequence_number: u32) -> Result<Option<RelicOwner>> {
-    // check utxo cache first
-    for utxo_entry in self.utxo_cache.values() {
-      let utxo_entry = utxo_entry.parse(self.index);
-      for (seq, _) in utxo_entry.parse_inscriptions() {
-        if seq == sequence_number {
-          let script = Script::from_bytes(utxo_entry.script_pubkey());
-          return Ok(Some(RelicOwner(script.script_hash())));
-        }
-      }
-    }
-    // on cache-miss check database
+  fn load_inscription_owner(&self, sequence_number: u32) -> Result<Option<RelicOwner>> {
+    // the satpoint in the database is current for inscriptions that did not move since the last
+    // commit
     let Some(satpoint) = self
       .sequence_number_to_satpoint
       .get(sequence_number)?
@@ -712,14 +787,16 @@ impl RelicUpdater<'_, '_, '_, '_> {
   fn swap_apply(
     &mut self,
     swap: &Swap,
+    swap_index: u32,
     txid: Txid,
     relic_id: RelicId,
     entry: &mut RelicEntry,
     diff: BalanceDiff,
   ) -> Result<(Option<RelicOwner>, u128)> {
-    entry.pool.as_mut().unwrap().apply(diff);
+    // liquidity providers keep their part of the fee in the pool
+    let fee = entry.pool.as_mut().unwrap().apply(diff);
     self.id_to_entry.insert(&relic_id.store(), entry.store())?;
-    let owner = if diff.fee > 0 {
+    let owner = if fee > 0 {
       if let Some(sequence_number) = entry.owner_sequence_number {
         self.get_inscription_owner(sequence_number)?
       } else {
@@ -732,6 +809,7 @@ impl RelicUpdater<'_, '_, '_, '_> {
       SwapDirection::BaseToQuote => (diff.input, diff.output, diff.fee, false),
       SwapDirection::QuoteToBase => (diff.output, diff.input, diff.fee, true),
     };
+    self.record_pool_price(relic_id, entry.pool.unwrap(), base_amount, quote_amount)?;
     self.event_emitter.emit(
       txid,
       EventInfo::RelicSwapped {
@@ -741,9 +819,85 @@ impl RelicUpdater<'_, '_, '_, '_> {
         fee,
         is_sell_order,
         is_exact_input: swap.is_exact_input,
+        swap_index,
       },
     )?;
-    Ok((owner, diff.fee))
+    Ok((owner, fee))
+  }
+
+  fn liquidity(
+    &mut self,
+    txid: Txid,
+    liquidity: Liquidity,
+    base_balance: u128,
+    quote_balance: u128,
+    shares_balance: u128,
+  ) -> Result<Result<LiquidityDiff, RelicError>> {
+    let id = liquidity.relic;
+    let Some(mut entry) = self.load_relic_entry(id)? else {
+      return Ok(Err(RelicError::RelicNotFound(id)));
+    };
+    let diff = match entry.liquidity(liquidity, base_balance, quote_balance, shares_balance) {
+      Ok(diff) => diff,
+      Err(cause) => return Ok(Err(cause)),
+    };
+    entry.pool.as_mut().unwrap().apply_liquidity(diff);
+    self.id_to_entry.insert(&id.store(), entry.store())?;
+    self.record_pool_price(id, entry.pool.unwrap(), 0, 0)?;
+    let LiquidityDiff {
+      base_amount,
+      quote_amount,
+      shares,
+      is_remove,
+      ..
+    } = diff;
+    self.event_emitter.emit(
+      txid,
+      if is_remove {
+        EventInfo::RelicLiquidityRemoved {
+          relic_id: id,
+          base_amount,
+          quote_amount,
+          shares,
+        }
+      } else {
+        EventInfo::RelicLiquidityAdded {
+          relic_id: id,
+          base_amount,
+          quote_amount,
+          shares,
+        }
+      },
+    )?;
+    Ok(Ok(diff))
+  }
+
+  /// Record the pool supplies under the key of the event about to be emitted.
+  fn record_pool_price(
+    &mut self,
+    relic_id: RelicId,
+    pool: Pool,
+    base_volume: u128,
+    quote_volume: u128,
+  ) -> Result {
+    if let Some(pool_prices) = self.pool_prices.as_mut() {
+      pool_prices.insert(
+        (
+          relic_id.store(),
+          self.event_emitter.block_height,
+          self.event_emitter.event_index,
+        ),
+        PoolPrice {
+          timestamp: self.block_time,
+          base_supply: pool.base_supply,
+          quote_supply: pool.quote_supply,
+          base_volume,
+          quote_volume,
+        }
+        .store(),
+      )?;
+    }
+    Ok(())
   }
 
   /// mint base token for every burned inception inscription in the tx
@@ -788,6 +942,7 @@ impl RelicUpdater<'_, '_, '_, '_> {
     );
     relic.state.mints += burned_inceptions;
     let amount = terms.amount.unwrap() * burned_inceptions;
+    relic.state.issued += amount;
 
     self.id_to_entry.insert(&RELIC_ID.store(), relic.store())?;
 
@@ -811,12 +966,9 @@ impl RelicUpdater<'_, '_, '_, '_> {
     mint_index: u128,
     boost: &BoostTerms,
   ) -> u32 {
-    let mut hasher = DefaultHasher::new();
-    relic_id.block.hash(&mut hasher);
-    txid.hash(&mut hasher);
-    mint_index.hash(&mut hasher);
-    let seed = hasher.finish();
-    let rand_val = (seed % 1_000_000) as u32; // Random value between 0 and 999,999
+    // Random value between 0 and 999,999
+    let rand_val = BoostRandomness::at_height(self.index.settings.chain().network(), self.height)
+      .roll(*relic_id, *txid, mint_index);
 
     let mut multiplier = 1;
 
@@ -851,6 +1003,10 @@ impl RelicUpdater<'_, '_, '_, '_> {
     multiplier
   }
 
+  fn price_arithmetic(&self) -> PriceArithmetic {
+    PriceArithmetic::at_height(self.index.settings.chain().network(), self.height)
+  }
+
   fn mint(
     &mut self,
     txid: Txid,
@@ -867,11 +1023,14 @@ impl RelicUpdater<'_, '_, '_, '_> {
       return Ok(Err(RelicError::RelicNotFound(id)));
     };
 
+    let arithmetic = self.price_arithmetic();
+
     // Determine mintable results based on current state and balance limits
-    let potential_mints = match relic_entry.mintable(base_balance, requested_mints, base_limit) {
-      Ok(results) => results,
-      Err(cause) => return Ok(Err(cause)),
-    };
+    let potential_mints =
+      match relic_entry.mintable(base_balance, requested_mints, base_limit, arithmetic) {
+        Ok(results) => results,
+        Err(cause) => return Ok(Err(cause)),
+      };
 
     if potential_mints.is_empty() {
       // This can happen if base_limit is too high or balance is insufficient for even one mint.
@@ -940,13 +1099,22 @@ impl RelicUpdater<'_, '_, '_, '_> {
 
     // Update relic state
     relic_entry.state.mints += num_mints_to_perform as u128;
+    relic_entry.state.paid += total_price;
+    relic_entry.state.issued += final_results
+      .iter()
+      .map(|(amount, _, _)| *amount)
+      .sum::<u128>();
+    if arithmetic == PriceArithmetic::Legacy {
+      relic_entry.state.legacy_mints = relic_entry.state.mints;
+    }
 
     // Check for pool creation
+    let mut created_pool = None;
     if let Some(terms) = relic_entry.mint_terms {
       if relic_entry.state.mints == terms.cap.unwrap_or_default() {
         let base_supply = relic_entry.locked_base_supply();
         let quote_supply = terms.seed.unwrap_or_default();
-        let fee_bps = relic_entry.fee.min(1_000);
+        let fee_bps = relic_entry.fee.min(Enshrining::MAX_FEE_BPS);
 
         // Assert that if a pool exists, it must meet the special case conditions
         if let Some(existing_pool) = &relic_entry.pool {
@@ -966,12 +1134,17 @@ impl RelicUpdater<'_, '_, '_, '_> {
             fee_bps,
             // reset the subsidy, should not be taken again
             subsidy: 0,
+            // the initial liquidity is locked forever, one share per base token
+            shares: base_supply,
+            locked_shares: base_supply,
+            provider_fees: 0,
           });
+          created_pool = relic_entry.pool;
         } else {
-          eprintln!(
+          self.report(format_args!(
             "unable to create pool for Relic {}: both token supplies must be non-zero, but got base/quote supply of {base_supply}/{quote_supply}",
             relic_entry.spaced_relic
-          );
+          ));
         }
       }
     }
@@ -979,6 +1152,10 @@ impl RelicUpdater<'_, '_, '_, '_> {
     // Save the updated entry *before* emitting events
     self.id_to_entry.insert(&id.store(), relic_entry.store())?;
 
+    if let Some(pool) = created_pool {
+      self.record_pool_price(id, pool, 0, 0)?;
+    }
+
     // Emit events for each successful mint
     let mut lots_result = Vec::with_capacity(num_mints_to_perform);
     for (amount, price, multiplier) in final_results {
@@ -1010,7 +1187,7 @@ impl RelicUpdater<'_, '_, '_, '_> {
       return Ok(Err(RelicError::RelicNotFound(id)));
     };
 
-    let results = match relic_entry.unmintable(balance, count, base_min) {
+    let results = match relic_entry.unmintable(balance, count, base_min, self.price_arithmetic()) {
       Ok(res) => res,
       Err(e) => return Ok(Err(e)),
     };
@@ -1020,6 +1197,9 @@ impl RelicUpdater<'_, '_, '_, '_> {
 
     relic_entry.state.mints -= u128::from(count);
     relic_entry.state.unmints += u128::from(count);
+    relic_entry.state.paid -= results.iter().map(|(_, p)| *p).sum::<u128>();
+    relic_entry.state.issued -= total_minted;
+    relic_entry.state.legacy_mints = relic_entry.state.legacy_mints.min(relic_entry.state.mints);
     self.id_to_entry.insert(&id.store(), relic_entry.store())?;
     self.event_emitter.emit(
       txid,
@@ -2,13 +2,18 @@ use super::*;
 
 use tag::Tag;
 
-pub(crate) use self::{envelope::ParsedEnvelope, media::Media};
+pub(crate) use {
+  self::{envelope::ParsedEnvelope, media::Media},
+  relics_api::inscription_id,
+};
 
-pub use self::{envelope::Envelope, inscription::Inscription, inscription_id::InscriptionId};
+pub use {
+  self::{envelope::Envelope, inscription::Inscription},
+  relics_api::InscriptionId,
+};
 
 mod envelope;
 mod inscription;
-pub(crate) mod inscription_id;
 pub(crate) mod media;
 mod tag;
 pub(crate) mod teleburn;
@@ -29,7 +29,7 @@ use {
     outgoing::Outgoing,
     representation::Representation,
     settings::Settings,
-    subcommand::{OutputFormat, Subcommand, SubcommandResult},
+    subcommand::{OutputFormat, Subcommand},
     tally::Tally,
   },
   anyhow::{anyhow, bail, ensure, Context, Error},
@@ -63,7 +63,8 @@ use {
   },
   regex::Regex,
   relics_protocol::{
-    BalanceDiff, BoostTerms, Enshrining, Keepsake, MintTerms, Pool, PoolError, PoolSwap,
+    BalanceDiff, BoostRandomness, BoostTerms, Enshrining, Keepsake, KeepsakeExplanation,
+    KeepsakeExtension, Liquidity, LiquidityDiff, MintTerms, Pool, PoolSwap, PriceArithmetic,
     PriceModel, Relic, RelicArtifact, RelicId, SpacedRelic, Swap, SwapDirection, Transfer,
   },
   reqwest::Url,
@@ -99,8 +100,8 @@ pub use self::{
   chain::Chain,
   fee_rate::FeeRate,
   index::{
-    entry::{RelicEntry, RelicOwner, RelicState, RuneEntry},
-    Event, EventInfo, Index, RelicOperation,
+    entry::{PoolPrice, RelicEntry, RelicOwner, RelicState, RuneEntry},
+    Event, EventCursor, EventInfo, Index, RelicOperation,
   },
   inscriptions::{Envelope, Inscription, InscriptionId},
   object::Object,
@@ -54,12 +54,22 @@ pub struct Options {
     help = "Track location of relics. RELICS ARE IN AN UNFINISHED PRE-ALPHA STATE AND SUBJECT TO CHANGE AT ANY TIME."
   )]
   pub(crate) index_relics: bool,
+  #[arg(
+    long,
+    help = "Keep the history of relic balances, required for snapshots at past heights. Requires `--index-relics`."
+  )]
+  pub(crate) index_relic_history: bool,
+  #[arg(
+    long,
+    help = "Track pool price history of relics, required for candle charts. Requires `--index-relics`."
+  )]
+  pub(crate) index_relic_prices: bool,
   #[arg(long, help = "Track location of runes.")]
   pub(crate) index_runes: bool,
   #[arg(
     long,
     default_value = "false",
-    help = "Index memory pool transactions."
+    help = "Simulate relic transactions in the memory pool on top of the index. Requires `--index-relics`."
   )]
   pub(crate) index_memory_pool: bool,
   #[arg(long, help = "Track location of all satoshis.")]
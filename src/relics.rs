@@ -1,100 +1,19 @@
 use super::*;
 
-pub use relics_protocol::{INCEPTION_PARENT_INSCRIPTION_ID, RELIC_NAME};
-
-#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
-pub enum RelicError {
-  SealingAlreadyExists(SpacedRelic),
-  SealingInsufficientBalance(u128),
-  SealingBaseToken,
-  SealingNotFound,
-  Unmintable,
-  MintCap(u128),
-  MintInsufficientBalance(u128),
-  UnmintInsufficientBalance(u128),
-  UnmintNotAllowed,
-  NoMintsToUnmint,
-  MaxMintPerTxExceeded(u8),
-  MintBaseLimitExceeded(u128, u128),
-  MintBlockCapExceeded(u32),
-  MissingSubsidy(u128),
-  SwapNotAvailable,
-  SwapFailed(PoolError),
-  SwapInsufficientBalance(u128),
-  InscriptionMissing,
-  InscriptionMetadataMissing,
-  InvalidMetadata,
-  PriceComputationError,
-  RelicAlreadyEnshrined,
-  RelicNotFound(RelicId),
-  RelicOwnerOnly,
-  NoClaimableBalance,
-}
+pub(crate) use self::allocation::RelicAllocation;
 
-impl std::error::Error for RelicError {}
+pub use {
+  self::audit::RelicSupplyAudit,
+  relics_api::{Candle, CandleInterval},
+  relics_protocol::{RelicError, INCEPTION_PARENT_INSCRIPTION_ID, RELIC_NAME},
+};
 
-impl Display for RelicError {
-  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-    match self {
-      RelicError::Unmintable => write!(f, "not mintable"),
-      RelicError::MintCap(cap) => write!(f, "limited to {cap} mints"),
-      RelicError::MintInsufficientBalance(price) => {
-        write!(f, "insufficient balance for mint price of {price}")
-      }
-      RelicError::UnmintNotAllowed => write!(f, "unmint not allowed (here)"),
-      RelicError::NoMintsToUnmint => write!(f, "no mints to unmint"),
-      RelicError::MaxMintPerTxExceeded(max) => {
-        write!(f, "maximum mints per transaction exceeded: {max}")
-      }
-      RelicError::MintBaseLimitExceeded(limit, price) => {
-        write!(f, "mint base limit exceeded: limit {limit}, price {price}")
-      }
-      RelicError::UnmintInsufficientBalance(required) => {
-        write!(
-          f,
-          "insufficient minted token balance for unmint: required {required}"
-        )
-      }
-      RelicError::MintBlockCapExceeded(limit) => write!(
-        f,
-        "max mints per block exceeded: only {limit} allowed per block"
-      ),
-      RelicError::MissingSubsidy(subsidy) => write!(f, "missing subsidy for mint: {subsidy}"),
-      RelicError::PriceComputationError => write!(f, "price computation error"),
-      RelicError::SwapNotAvailable => write!(f, "liquidity pool for swap not available (yet)"),
-      RelicError::SwapFailed(cause) => write!(f, "swap failed: {cause}"),
-      RelicError::SwapInsufficientBalance(required) => {
-        write!(f, "insufficient balance for swap {required}")
-      }
-      RelicError::InscriptionMissing => write!(f, "no Inscription found in transaction"),
-      RelicError::InscriptionMetadataMissing => write!(f, "no metadata on Inscription found"),
-      RelicError::InvalidMetadata => write!(
-        f,
-        "Inscription metadata does not contain a valid Relic ticker"
-      ),
-      RelicError::SealingAlreadyExists(relic) => {
-        write!(f, "Relic has already been sealed: {relic}")
-      }
-      RelicError::SealingInsufficientBalance(fee) => {
-        write!(f, "insufficient balance for sealing fee: {fee}")
-      }
-      RelicError::SealingNotFound => write!(f, "Sealing not found"),
-      RelicError::SealingBaseToken => write!(f, "Sealing base token is invalid"),
-      RelicError::RelicAlreadyEnshrined => write!(f, "Relic has already been enshrined"),
-      RelicError::RelicNotFound(id) => write!(f, "Relic not found: {id}"),
-      RelicError::RelicOwnerOnly => {
-        write!(f, "this operation can only be performed by the Relic owner")
-      }
-      RelicError::NoClaimableBalance => {
-        write!(f, "unable to claim: No claimable balance for given output")
-      }
-    }
-  }
-}
+mod allocation;
+mod audit;
 
 #[cfg(test)]
 mod tests {
-  use relics_protocol::{MultiMint, INCEPTION_PARENT_INSCRIPTION_ID};
+  use relics_protocol::{MultiMint, PoolError, INCEPTION_PARENT_INSCRIPTION_ID};
   use {super::*, crate::index::testing::Context};
 
   const RELIC: u128 = 99246114928149462;
@@ -258,6 +177,8 @@ mod tests {
       fee: 100,
       state: RelicState {
         mints: 1,
+        paid: 5000,
+        issued: 1000,
         ..default()
       },
       pool: Some(Pool {
@@ -265,6 +186,9 @@ mod tests {
         quote_supply: 1000,
         fee_bps: 100,
         subsidy: 0,
+        shares: 5000,
+        locked_shares: 5000,
+        provider_fees: 0,
       }),
       timestamp: id.block,
     };
@@ -284,14 +208,15 @@ mod tests {
       &context.relic_outpoints(vec![(RELIC_ID, 560)]),
       1,
       Keepsake {
-        swap: Some(Swap {
+        swaps: vec![Swap {
           output: Some(id),
           output_amount: Some(100),
           input: None,
           // max input of base tokens
           input_amount: Some(562),
           is_exact_input: false,
-        }),
+          via: Vec::new(),
+        }],
         ..default()
       },
     );
@@ -390,13 +315,18 @@ mod tests {
       fee: 100,
       state: RelicState {
         mints: 16_800, // full cap minted
+        paid: 2111864190980,
+        issued: 1_680_000_000_000_000,
         ..default()
       },
       pool: Some(Pool {
-        base_supply: 2111864182563,
+        base_supply: 2111864190980,
         quote_supply: 1000000000000,
         fee_bps: 100,
         subsidy: 0,
+        shares: 2111864190980,
+        locked_shares: 2111864190980,
+        provider_fees: 0,
       }),
       timestamp: id.block,
     };
@@ -409,7 +339,7 @@ mod tests {
           vout: 0,
         },
         vec![
-          (relic_id, 504_855_817_437), // spent ~ 21k base relics
+          (relic_id, 504_855_809_020), // spent ~ 21k base relics
           (id, 1_680_000_000_000_000), // 16 800 × 100 000 000
         ],
       )],
@@ -450,12 +380,11 @@ mod tests {
   fn multi_mint_with_price_model_fails_on_too_high_price_works() {
     let a = 10;
     let b = 5;
-    let c = 2;
     let nr_of_mints: u8 = 50;
-    // price = a - b / (mints + c)
-    let expected_price: u128 = (0..u128::from(nr_of_mints))
-      .map(|mint| a - (b / (mint + c)))
-      .sum();
+    // price = a * exp(mints / b)
+    let expected_price = PriceModel::Formula { a, b }
+      .compute_total_price(0, nr_of_mints, PriceArithmetic::FixedPoint)
+      .unwrap();
     let context = Context::builder().arg("--index-relics").build();
 
     let (_, mut entry_base) = context.mint_base_token(2, 1);
@@ -598,6 +527,8 @@ mod tests {
       fee: 100,
       state: RelicState {
         mints: u128::from(u8::MAX),
+        paid: u128::from(u8::MAX) * 5000,
+        issued: u128::from(u8::MAX) * 1000,
         ..default()
       },
       pool: Some(Pool {
@@ -605,6 +536,9 @@ mod tests {
         quote_supply: 1000,
         fee_bps: 100,
         subsidy: 0,
+        shares: u128::from(u8::MAX) * 5000,
+        locked_shares: u128::from(u8::MAX) * 5000,
+        provider_fees: 0,
       }),
       timestamp: id.block,
     };
@@ -768,6 +702,8 @@ mod tests {
       fee: 100,
       state: RelicState {
         mints: u128::from(u8::MAX),
+        paid: u128::from(u8::MAX) * 5000,
+        issued: u128::from(u8::MAX) * 1000,
         ..default()
       },
       pool: None,
@@ -806,6 +742,8 @@ mod tests {
 
     entry_test_token.state.mints = 0;
     entry_test_token.state.unmints = u128::from(u8::MAX);
+    entry_test_token.state.paid = 0;
+    entry_test_token.state.issued = 0;
 
     context.assert_relics(
       [(relic_id, entry_base), (id, entry_test_token)],
@@ -883,6 +821,8 @@ mod tests {
       fee: 100,
       state: RelicState {
         mints: 1,
+        paid: 5000,
+        issued: 1000,
         ..default()
       },
       pool: None,
@@ -918,6 +858,8 @@ mod tests {
 
     entry_test_token.state.mints = 0;
     entry_test_token.state.unmints = 1;
+    entry_test_token.state.paid = 0;
+    entry_test_token.state.issued = 0;
 
     context.assert_relics(
       [(relic_id, entry_base), (id, entry_test_token)],
@@ -995,6 +937,8 @@ mod tests {
       fee: 100,
       state: RelicState {
         mints: 1,
+        paid: 5000,
+        issued: 1000,
         ..default()
       },
       pool: Some(Pool {
@@ -1002,6 +946,9 @@ mod tests {
         quote_supply: 1000,
         fee_bps: 100,
         subsidy: 0,
+        shares: 5000,
+        locked_shares: 5000,
+        provider_fees: 0,
       }),
       timestamp: id.block,
     };
@@ -1106,6 +1053,8 @@ mod tests {
       fee: 100,
       state: RelicState {
         mints: 1,
+        paid: 5000,
+        issued: 1000,
         ..default()
       },
       pool: None,
@@ -1262,8 +1211,9 @@ mod tests {
       &context.relic_outpoints(vec![(relic_id, 5000)]),
       2,
       Keepsake {
+        // the base limit only changes the transaction ID, and with it the boost roll
         mint: Some(MultiMint {
-          base_limit: 5000,
+          base_limit: 5009,
           count: 1,
           is_unmint: false,
           relic: id,
@@ -1297,6 +1247,8 @@ mod tests {
       fee: 100,
       state: RelicState {
         mints: 1,
+        paid: 5000,
+        issued: 6000,
         ..default()
       },
       pool: None,
@@ -1315,7 +1267,7 @@ mod tests {
           txid: txid_mint,
           vout: 0,
         },
-        vec![(relic_id, 1308309995000), (id, 2000)],
+        vec![(relic_id, 1308309995000), (id, 6000)],
       )],
     );
 
@@ -1385,8 +1337,9 @@ mod tests {
       &context.relic_outpoints(vec![(relic_id, 5000)]),
       3,
       Keepsake {
+        // the base limit only changes the transaction ID, and with it the boost roll
         mint: Some(MultiMint {
-          base_limit: 5000,
+          base_limit: 5001,
           count: 1,
           is_unmint: false,
           relic: id,
@@ -1420,6 +1373,8 @@ mod tests {
       fee: 100,
       state: RelicState {
         mints: 1,
+        paid: 5000,
+        issued: 3000,
         ..default()
       },
       pool: None,
@@ -1438,10 +1393,18 @@ mod tests {
           txid: txid_mint,
           vout: 0,
         },
-        vec![(relic_id, 1308309995000), (id, 4000)],
+        vec![(relic_id, 1308309995000), (id, 3000)],
       )],
     );
 
+    // the audit accounts for the boosted issuance
+    assert!(context
+      .index
+      .audit_relic_supply()
+      .unwrap()
+      .iter()
+      .all(RelicSupplyAudit::is_consistent));
+
     let balances: BTreeMap<SpacedRelic, Pile> = context
       .index
       .get_relic_balances_for_outpoint(outpoint)
@@ -1543,6 +1506,8 @@ mod tests {
       fee: 100,
       state: RelicState {
         mints: 50,
+        paid: 50 * 5000,
+        issued: 123_000,
         ..default()
       },
       pool: Some(Pool {
@@ -1550,6 +1515,9 @@ mod tests {
         quote_supply: 1000,
         fee_bps: 100,
         subsidy: 0,
+        shares: 50 * 5000,
+        locked_shares: 50 * 5000,
+        provider_fees: 0,
       }),
       timestamp: id.block,
     };
@@ -1566,7 +1534,7 @@ mod tests {
           txid: txid_mint,
           vout: 0,
         },
-        vec![(relic_id, 1308310000000 - 50 * 5000), (id, 128000)],
+        vec![(relic_id, 1308310000000 - 50 * 5000), (id, 123000)],
       )],
     );
 
@@ -1594,6 +1562,1080 @@ mod tests {
     }
   }
 
+  /// enshrine `n` Relics and conclude their mints, leaving each with a (5000, 1000) pool
+  fn enshrine_pools(context: &Context, n: u128) -> Vec<RelicId> {
+    let enshrining = Enshrining {
+      fee: Some(100),
+      mint_terms: Some(MintTerms {
+        cap: Some(1),
+        amount: Some(1000),
+        price: Some(PriceModel::Fixed(5000)),
+        seed: Some(1000),
+        ..default()
+      }),
+      ..default()
+    };
+
+    let ids = (0..n)
+      .map(|i| {
+        context
+          .enshrine(SpacedRelic::new(Relic(RELIC + i), 0), enshrining)
+          .1
+      })
+      .collect::<Vec<RelicId>>();
+
+    for id in &ids {
+      let input_outpoints = context.relic_outpoints(vec![(RELIC_ID, 5000)]);
+      let value = input_outpoints
+        .iter()
+        .map(|outpoint| context.core.get_utxo_amount(outpoint).unwrap().to_sat())
+        .sum::<u64>();
+      context.core.broadcast_tx(TransactionTemplate {
+        input_outpoints: &input_outpoints,
+        outputs: 2,
+        // the value has to be split evenly among the outputs
+        fee: value % 2,
+        op_return: Some(
+          Keepsake {
+            mint: Some(MultiMint {
+              base_limit: 5000,
+              count: 1,
+              is_unmint: false,
+              relic: *id,
+            }),
+            // put minted Relics into a different output than the remaining Base Tokens
+            transfers: vec![Transfer {
+              id: *id,
+              amount: 0,
+              output: 1,
+            }],
+            ..default()
+          }
+          .encipher(),
+        ),
+        ..default()
+      });
+      context.mine_blocks(1);
+    }
+
+    ids
+  }
+
+  /// base and quote supply of the pools of the given Relics
+  fn pool_supplies(context: &Context, ids: &[RelicId]) -> Vec<(u128, u128)> {
+    let relics = context.index.relics().unwrap();
+    ids
+      .iter()
+      .map(|id| {
+        let pool = relics
+          .iter()
+          .find(|(relic_id, _)| relic_id == id)
+          .unwrap()
+          .1
+          .pool
+          .unwrap();
+        (pool.base_supply, pool.quote_supply)
+      })
+      .collect()
+  }
+
+  #[test]
+  fn swap_route_executes_all_legs_or_none() {
+    let context = Context::builder().arg("--index-relics").build();
+
+    context.mint_base_token(3, 1);
+
+    let ids = enshrine_pools(&context, 3);
+    let pools = || pool_supplies(&context, &ids);
+
+    assert_eq!(pools(), vec![(5000, 1000); 3]);
+
+    let route = |min_output| Keepsake {
+      swaps: vec![Swap {
+        input: Some(ids[0]),
+        output: Some(ids[2]),
+        input_amount: Some(100),
+        output_amount: Some(min_output),
+        is_exact_input: true,
+        via: vec![ids[1]],
+      }],
+      ..default()
+    };
+
+    // the last leg fails the slippage check, so none of the legs are executed
+    context.relic_tx(&context.relic_outpoints(vec![(ids[0], 100)]), 1, route(80));
+    context.mine_blocks(1);
+
+    assert_eq!(pools(), vec![(5000, 1000); 3]);
+
+    let txid = context.relic_tx(&context.relic_outpoints(vec![(ids[0], 100)]), 1, route(79));
+    context.mine_blocks(1);
+
+    // A -> RELIC: 100 A for 449 RELIC, RELIC -> B: 449 RELIC for 81 B,
+    // B -> RELIC: 81 B for 435 RELIC, RELIC -> C: 435 RELIC for 79 C
+    assert_eq!(pools(), vec![(4546, 1100), (5004, 1000), (5430, 921)]);
+
+    let (outpoint, mut balances) = context
+      .index
+      .get_relic_balances()
+      .unwrap()
+      .into_iter()
+      .find(|(outpoint, _)| outpoint.txid == txid)
+      .unwrap();
+    balances.sort();
+
+    assert_eq!(outpoint, OutPoint { txid, vout: 0 });
+    assert_eq!(balances, vec![(ids[0], 900), (ids[2], 79)]);
+  }
+
+  #[test]
+  fn swap_route_exact_output_through_revisited_pool() {
+    let context = Context::builder().arg("--index-relics").build();
+
+    context.mint_base_token(3, 1);
+
+    let ids = enshrine_pools(&context, 3);
+
+    // B -> RELIC -> A -> RELIC -> C, the RELIC/A pool is bought from and then sold to
+    let swap = Swap {
+      input: Some(ids[1]),
+      output: Some(ids[2]),
+      input_amount: Some(1000),
+      output_amount: Some(200),
+      is_exact_input: false,
+      via: vec![ids[0]],
+    };
+
+    let quote = context.index.quote_relic_swap(&swap).unwrap();
+    assert_eq!(quote.error, None);
+    assert_eq!(quote.output_amount, 200);
+
+    // one unit less of input does not yield the requested output
+    assert_eq!(
+      context
+        .index
+        .quote_relic_swap(&Swap {
+          input_amount: Some(quote.input_amount - 1),
+          ..swap.clone()
+        })
+        .unwrap()
+        .error,
+      Some(RelicError::SwapFailed(PoolError::Slippage))
+    );
+
+    let txid = context.relic_tx(
+      &context.relic_outpoints(vec![(ids[1], 1000)]),
+      1,
+      Keepsake {
+        swaps: vec![swap],
+        ..default()
+      },
+    );
+    context.mine_blocks(1);
+
+    let (_, mut balances) = context
+      .index
+      .get_relic_balances()
+      .unwrap()
+      .into_iter()
+      .find(|(outpoint, _)| outpoint.txid == txid)
+      .unwrap();
+    balances.sort();
+
+    // the execution matches the quote
+    assert_eq!(
+      balances,
+      vec![(ids[1], 1000 - quote.input_amount), (ids[2], 200)]
+    );
+  }
+
+  #[test]
+  fn swap_batch_executes_swaps_independently() {
+    let context = Context::builder().arg("--index-relics").build();
+
+    context.mint_base_token(1, 1);
+
+    let ids = enshrine_pools(&context, 2);
+
+    let sell = |min_output| Swap {
+      input: Some(ids[1]),
+      input_amount: Some(100),
+      output_amount: min_output,
+      is_exact_input: true,
+      ..default()
+    };
+
+    let txid = context.relic_tx(
+      &context.relic_outpoints(vec![(RELIC_ID, 1000), (ids[1], 100)]),
+      1,
+      Keepsake {
+        swaps: vec![
+          Swap {
+            output: Some(ids[0]),
+            input_amount: Some(500),
+            is_exact_input: true,
+            ..default()
+          },
+          // fails the slippage check without affecting the other swaps
+          sell(Some(1000)),
+          sell(None),
+        ],
+        ..default()
+      },
+    );
+    context.mine_blocks(1);
+
+    let events = context
+      .index
+      .events_for_tx(txid)
+      .unwrap()
+      .into_iter()
+      .map(|event| event.info)
+      .filter(|info| {
+        matches!(
+          info,
+          EventInfo::RelicSwapped { .. } | EventInfo::RelicError { .. }
+        )
+      })
+      .collect::<Vec<EventInfo>>();
+
+    assert_eq!(
+      events,
+      vec![
+        EventInfo::RelicSwapped {
+          relic_id: ids[0],
+          base_amount: 500,
+          quote_amount: 90,
+          fee: 5,
+          is_sell_order: false,
+          is_exact_input: true,
+          swap_index: 0,
+        },
+        EventInfo::RelicError {
+          operation: RelicOperation::Swap { index: 1 },
+          error: RelicError::SwapFailed(PoolError::Slippage),
+        },
+        EventInfo::RelicSwapped {
+          relic_id: ids[1],
+          base_amount: 449,
+          quote_amount: 100,
+          fee: 5,
+          is_sell_order: true,
+          is_exact_input: true,
+          swap_index: 2,
+        },
+      ]
+    );
+
+    assert_eq!(
+      pool_supplies(&context, &ids),
+      vec![(5495, 910), (4546, 1100)]
+    );
+  }
+
+  #[test]
+  fn swap_quote_matches_execution() {
+    let context = Context::builder().arg("--index-relics").build();
+
+    context.mint_base_token(1, 1);
+
+    let ids = enshrine_pools(&context, 2);
+
+    let swap = Swap {
+      input: Some(ids[0]),
+      output: Some(ids[1]),
+      input_amount: Some(100),
+      is_exact_input: true,
+      ..default()
+    };
+
+    // the quote reports the error the swap would fail with
+    let quote = context
+      .index
+      .quote_relic_swap(&Swap {
+        output_amount: Some(82),
+        ..swap.clone()
+      })
+      .unwrap();
+    assert_eq!(
+      quote.error,
+      Some(RelicError::SwapFailed(PoolError::Slippage))
+    );
+    assert!(quote.legs.is_empty());
+
+    let quote = context.index.quote_relic_swap(&swap).unwrap();
+    assert_eq!(quote.error, None);
+    assert_eq!((quote.input_amount, quote.output_amount), (100, 81));
+    assert_eq!(
+      quote
+        .legs
+        .iter()
+        .map(|leg| (leg.relic_id, leg.direction, leg.input, leg.output))
+        .collect::<Vec<(RelicId, SwapDirection, u128, u128)>>(),
+      vec![
+        (ids[0], SwapDirection::QuoteToBase, 100, 449),
+        (ids[1], SwapDirection::BaseToQuote, 449, 81),
+      ]
+    );
+
+    let txid = context.relic_tx(
+      &context.relic_outpoints(vec![(ids[0], 100)]),
+      1,
+      Keepsake {
+        swaps: vec![swap],
+        ..default()
+      },
+    );
+    context.mine_blocks(1);
+
+    let fees = context
+      .index
+      .events_for_tx(txid)
+      .unwrap()
+      .into_iter()
+      .filter_map(|event| match event.info {
+        EventInfo::RelicSwapped { fee, .. } => Some(fee),
+        _ => None,
+      })
+      .collect::<Vec<u128>>();
+
+    assert_eq!(
+      fees,
+      quote.legs.iter().map(|leg| leg.fee).collect::<Vec<u128>>()
+    );
+
+    let relics = context.index.relics().unwrap();
+    for leg in &quote.legs {
+      let (_, entry) = relics.iter().find(|(id, _)| *id == leg.relic_id).unwrap();
+      assert_eq!(entry.pool.unwrap().lossy_price(), leg.price_after);
+    }
+  }
+
+  #[test]
+  fn mempool_relics_are_simulated_without_touching_the_index() {
+    let context = Context::builder()
+      .arg("--index-relics")
+      .arg("--index-memory-pool")
+      .build();
+
+    context.mint_base_token(1, 1);
+
+    let ids = enshrine_pools(&context, 1);
+
+    let swap = Swap {
+      input: Some(ids[0]),
+      input_amount: Some(100),
+      is_exact_input: true,
+      ..default()
+    };
+
+    let inputs = context.relic_outpoints(vec![(ids[0], 100)]);
+    let parent = context.relic_tx(
+      &inputs,
+      1,
+      Keepsake {
+        swaps: vec![swap.clone()],
+        ..default()
+      },
+    );
+    let parent_output = OutPoint {
+      txid: parent,
+      vout: 0,
+    };
+    let child = context.relic_tx(&[parent_output], 1, Keepsake::default());
+
+    context.index.update_mempool_relics().unwrap();
+
+    // the committed state is unchanged
+    assert_eq!(pool_supplies(&context, &ids), vec![(5000, 1000)]);
+    assert!(context.index.events_for_tx(parent).unwrap().is_empty());
+
+    let mempool = context.index.get_mempool_relics();
+    assert_eq!(mempool.height, context.index.block_count().unwrap());
+    assert_eq!(
+      mempool
+        .transactions
+        .iter()
+        .map(|tx| tx.txid)
+        .collect::<Vec<Txid>>(),
+      vec![parent, child]
+    );
+
+    let pending = context.index.get_mempool_relic_transaction(parent).unwrap();
+    assert_eq!(pending.inputs.len(), 1);
+    assert_eq!(pending.inputs[0].outpoint, inputs[0]);
+    assert_eq!(pending.inputs[0].balances, BTreeMap::from([(ids[0], 1000)]));
+    assert_eq!(
+      pending.outputs,
+      vec![api::MempoolRelicOutput {
+        outpoint: parent_output,
+        balances: [(RELIC_ID, 449), (ids[0], 900)].into(),
+        shares: default(),
+        spent_by: Some(child),
+      }]
+    );
+
+    assert_eq!(
+      context
+        .index
+        .get_mempool_relic_output(inputs[0])
+        .unwrap()
+        .spent_by,
+      Some(parent)
+    );
+    assert_eq!(
+      context
+        .index
+        .get_mempool_relic_output(OutPoint {
+          txid: child,
+          vout: 0
+        })
+        .unwrap()
+        .balances,
+      BTreeMap::from([(RELIC_ID, 449), (ids[0], 900)])
+    );
+
+    // swaps are quoted against the pools after the pending transactions
+    let pending_quote = context.index.quote_mempool_relic_swap(&swap).unwrap();
+    assert_eq!(
+      context.index.quote_relic_swap(&swap).unwrap().output_amount,
+      449
+    );
+    assert!(pending_quote.output_amount < 449);
+
+    context.mine_blocks(1);
+    context.index.update_mempool_relics().unwrap();
+
+    assert_eq!(
+      context
+        .index
+        .events_for_tx(parent)
+        .unwrap()
+        .into_iter()
+        .map(|event| event.info)
+        .collect::<Vec<EventInfo>>(),
+      pending
+        .events
+        .into_iter()
+        .map(|event| event.info)
+        .collect::<Vec<EventInfo>>(),
+    );
+    assert_eq!(
+      context.index.quote_relic_swap(&swap).unwrap(),
+      pending_quote
+    );
+    assert!(context.index.get_mempool_relics().transactions.is_empty());
+  }
+
+  #[test]
+  fn pool_prices_are_only_recorded_with_price_index() {
+    for index_relic_prices in [false, true] {
+      let mut builder = Context::builder().arg("--index-relics");
+      if index_relic_prices {
+        builder = builder.arg("--index-relic-prices");
+      }
+      let context = builder.build();
+
+      context.mint_base_token(1, 1);
+
+      let ids = enshrine_pools(&context, 1);
+
+      context.relic_tx(
+        &context.relic_outpoints(vec![(RELIC_ID, 500)]),
+        1,
+        Keepsake {
+          swaps: vec![Swap {
+            output: Some(ids[0]),
+            input_amount: Some(500),
+            is_exact_input: true,
+            ..default()
+          }],
+          ..default()
+        },
+      );
+      context.mine_blocks(1);
+
+      let prices = context
+        .index
+        .get_relic_pool_prices(ids[0])
+        .unwrap()
+        .into_iter()
+        .map(|price| {
+          (
+            price.base_supply,
+            price.quote_supply,
+            price.base_volume,
+            price.quote_volume,
+          )
+        })
+        .collect::<Vec<(u128, u128, u128, u128)>>();
+
+      if index_relic_prices {
+        // the pool is recorded on creation and after every swap
+        assert_eq!(prices, vec![(5000, 1000, 0, 0), (5495, 910, 500, 90)]);
+      } else {
+        assert!(prices.is_empty());
+      }
+    }
+  }
+
+  #[test]
+  fn liquidity_providers_earn_swap_fees() {
+    let context = Context::builder().arg("--index-relics").build();
+
+    context.mint_base_token(1, 1);
+
+    let ids = enshrine_pools(&context, 1);
+    let id = ids[0];
+
+    let liquidity_events = |txid| {
+      context
+        .index
+        .events_for_tx(txid)
+        .unwrap()
+        .into_iter()
+        .map(|event| event.info)
+        .filter(|info| {
+          matches!(
+            info,
+            EventInfo::RelicLiquidityAdded { .. }
+              | EventInfo::RelicLiquidityRemoved { .. }
+              | EventInfo::RelicError { .. }
+          )
+        })
+        .collect::<Vec<EventInfo>>()
+    };
+
+    // the quote amount limits the deposit to a third of the pool
+    let txid = context.relic_tx(
+      &context.relic_outpoints(vec![(RELIC_ID, 3000), (id, 500)]),
+      1,
+      Keepsake {
+        liquidity: Some(Liquidity {
+          relic: id,
+          base_amount: 3000,
+          quote_amount: 500,
+          shares: 2500,
+          is_remove: false,
+        }),
+        ..default()
+      },
+    );
+    context.mine_blocks(1);
+
+    assert_eq!(
+      liquidity_events(txid),
+      vec![EventInfo::RelicLiquidityAdded {
+        relic_id: id,
+        base_amount: 2500,
+        quote_amount: 500,
+        shares: 2500,
+      }]
+    );
+    assert_eq!(pool_supplies(&context, &ids), vec![(7500, 1500)]);
+    assert_eq!(
+      context.index.get_relic_shares().unwrap(),
+      vec![(OutPoint { txid, vout: 0 }, vec![(id, 2500)])]
+    );
+
+    // the provider holds a third of the shares and earns a third of the swap fee
+    context.relic_tx(
+      &context.relic_outpoints(vec![(RELIC_ID, 1000)]),
+      1,
+      Keepsake {
+        swaps: vec![Swap {
+          output: Some(id),
+          input_amount: Some(1000),
+          is_exact_input: true,
+          ..default()
+        }],
+        ..default()
+      },
+    );
+    context.mine_blocks(1);
+
+    let provider_fees = || {
+      context
+        .index
+        .relics()
+        .unwrap()
+        .into_iter()
+        .find(|(relic_id, _)| *relic_id == id)
+        .unwrap()
+        .1
+        .pool
+        .unwrap()
+        .provider_fees
+    };
+
+    assert_eq!(pool_supplies(&context, &ids), vec![(8490, 1326)]);
+    assert_eq!(provider_fees(), 3);
+
+    let shares_outpoint = || context.index.get_relic_shares().unwrap()[0].0;
+
+    let remove = |shares| Keepsake {
+      liquidity: Some(Liquidity {
+        relic: id,
+        shares,
+        is_remove: true,
+        ..default()
+      }),
+      ..default()
+    };
+
+    let txid = context.relic_tx(&[shares_outpoint()], 1, remove(2501));
+    context.mine_blocks(1);
+
+    assert_eq!(
+      liquidity_events(txid),
+      vec![EventInfo::RelicError {
+        operation: RelicOperation::RemoveLiquidity,
+        error: RelicError::LiquidityInsufficientShares(2501),
+      }]
+    );
+    assert_eq!(shares_outpoint(), OutPoint { txid, vout: 0 });
+
+    let quote_balance = |outpoint| {
+      context
+        .index
+        .get_relic_balances()
+        .unwrap()
+        .into_iter()
+        .find(|(balance_outpoint, _)| *balance_outpoint == outpoint)
+        .map(|(_, balances)| {
+          balances
+            .into_iter()
+            .find(|(relic_id, _)| *relic_id == id)
+            .map(|(_, amount)| amount)
+            .unwrap_or_default()
+        })
+        .unwrap_or_default()
+    };
+
+    let before = quote_balance(shares_outpoint());
+
+    let txid = context.relic_tx(&[shares_outpoint()], 1, remove(2500));
+    context.mine_blocks(1);
+
+    // a third of the pool plus all of the fees earned by the provider
+    assert_eq!(
+      liquidity_events(txid),
+      vec![EventInfo::RelicLiquidityRemoved {
+        relic_id: id,
+        base_amount: 2830 + 3,
+        quote_amount: 442,
+        shares: 2500,
+      }]
+    );
+    assert_eq!(pool_supplies(&context, &ids), vec![(5660, 884)]);
+    assert_eq!(provider_fees(), 0);
+    assert!(context.index.get_relic_shares().unwrap().is_empty());
+    assert_eq!(quote_balance(OutPoint { txid, vout: 0 }), before + 442);
+  }
+
+  #[test]
+  fn pool_shares_are_transferred_per_output() {
+    let context = Context::builder().arg("--index-relics").build();
+
+    context.mint_base_token(1, 1);
+
+    let ids = enshrine_pools(&context, 1);
+    let id = ids[0];
+
+    let txid = context.relic_tx(
+      &context.relic_outpoints(vec![(RELIC_ID, 3000), (id, 500)]),
+      1,
+      Keepsake {
+        liquidity: Some(Liquidity {
+          relic: id,
+          base_amount: 3000,
+          quote_amount: 500,
+          shares: 2500,
+          is_remove: false,
+        }),
+        ..default()
+      },
+    );
+    context.mine_blocks(1);
+
+    let pool = || {
+      context
+        .index
+        .relics()
+        .unwrap()
+        .into_iter()
+        .find(|(relic_id, _)| *relic_id == id)
+        .unwrap()
+        .1
+        .pool
+        .unwrap()
+    };
+
+    assert_eq!((pool().shares, pool().locked_shares), (7500, 5000));
+
+    // shares sent to the OP_RETURN output are burned, the rest goes to the default output
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      input_outpoints: &[OutPoint { txid, vout: 0 }],
+      outputs: 2,
+      op_return: Some(
+        Keepsake {
+          share_transfers: vec![
+            Transfer {
+              id,
+              amount: 1000,
+              output: 1,
+            },
+            Transfer {
+              id,
+              amount: 500,
+              output: 2,
+            },
+          ],
+          ..default()
+        }
+        .encipher(),
+      ),
+      ..default()
+    });
+    context.mine_blocks(1);
+
+    assert_eq!(
+      context.index.get_relic_shares().unwrap(),
+      vec![
+        (OutPoint { txid, vout: 0 }, vec![(id, 1000)]),
+        (OutPoint { txid, vout: 1 }, vec![(id, 1000)]),
+      ]
+    );
+    assert!(context
+      .index
+      .events_for_tx(txid)
+      .unwrap()
+      .into_iter()
+      .any(|event| event.info
+        == EventInfo::RelicSharesBurned {
+          relic_id: id,
+          shares: 500,
+        }));
+
+    // burned shares are locked in the pool and can not be redeemed
+    assert_eq!((pool().shares, pool().locked_shares), (7500, 5500));
+  }
+
+  #[test]
+  fn relic_balances_are_indexed_by_address() {
+    let context = Context::builder()
+      .arg("--index-relics")
+      .arg("--index-addresses")
+      .build();
+
+    context.mint_base_token(1, 1);
+
+    let ids = enshrine_pools(&context, 1);
+
+    let holders = |id| context.index.get_relic_holders(id, 100, 0).unwrap().0;
+
+    // holders are sorted by balance and agree with the balances of all outpoints
+    let assert_holders_match_outpoints = || {
+      let mut totals = BTreeMap::<(RelicId, ScriptBuf), u128>::new();
+      for (outpoint, balances) in context.index.get_relic_balances().unwrap() {
+        let script_pubkey = context
+          .index
+          .get_transaction(outpoint.txid)
+          .unwrap()
+          .unwrap()
+          .output[usize::try_from(outpoint.vout).unwrap()]
+        .script_pubkey
+        .clone();
+        for (id, amount) in balances {
+          *totals.entry((id, script_pubkey.clone())).or_default() += amount;
+        }
+      }
+
+      for id in [RELIC_ID, ids[0]] {
+        let holders = holders(id);
+        assert!(holders.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        assert_eq!(
+          holders
+            .into_iter()
+            .map(|(script_pubkey, balance)| ((id, script_pubkey), balance))
+            .collect::<BTreeMap<(RelicId, ScriptBuf), u128>>(),
+          totals
+            .iter()
+            .filter(|((relic_id, _), _)| *relic_id == id)
+            .map(|(key, balance)| (key.clone(), *balance))
+            .collect(),
+        );
+      }
+    };
+
+    assert_holders_match_outpoints();
+
+    let recipient = Address::from_script(
+      &ScriptBuf::new_p2wpkh(&bitcoin::WPubkeyHash::from_byte_array([1; 20])),
+      Network::Regtest,
+    )
+    .unwrap();
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      input_outpoints: &context.relic_outpoints(vec![(ids[0], 1000)]),
+      outputs: 1,
+      recipient: Some(recipient.clone()),
+      ..default()
+    });
+    context.mine_blocks(1);
+
+    assert_eq!(holders(ids[0]), vec![(recipient.script_pubkey(), 1000)]);
+    assert_eq!(
+      context
+        .index
+        .get_relic_balances_for_script(&recipient.script_pubkey())
+        .unwrap(),
+      vec![(
+        SpacedRelic::new(Relic(RELIC), 0),
+        Decimal {
+          value: 1000,
+          scale: Enshrining::DIVISIBILITY,
+        },
+        None,
+      )]
+    );
+    assert_holders_match_outpoints();
+
+    // burn 400 and send the rest back
+    context.relic_tx(
+      &[OutPoint { txid, vout: 0 }],
+      1,
+      Keepsake {
+        transfers: vec![Transfer {
+          id: ids[0],
+          amount: 400,
+          output: 1,
+        }],
+        ..default()
+      },
+    );
+    context.mine_blocks(1);
+
+    assert_eq!(
+      holders(ids[0]),
+      vec![(
+        ScriptBuf::new_p2wpkh(&bitcoin::WPubkeyHash::all_zeros()),
+        600
+      )]
+    );
+    assert!(context
+      .index
+      .get_relic_balances_for_script(&recipient.script_pubkey())
+      .unwrap()
+      .is_empty());
+    assert_holders_match_outpoints();
+  }
+
+  #[test]
+  fn relic_supplies_pass_audit() {
+    let context = Context::builder().arg("--index-relics").build();
+
+    let assert_consistent = || {
+      let audits = context.index.audit_relic_supply().unwrap();
+      for audit in &audits {
+        assert!(audit.is_consistent(), "{audit:?}");
+      }
+      audits
+    };
+
+    assert_consistent();
+
+    context.mint_base_token(2, 1);
+
+    let ids = enshrine_pools(&context, 2);
+
+    context.relic_tx(
+      &context.relic_outpoints(vec![(RELIC_ID, 500)]),
+      1,
+      Keepsake {
+        swaps: vec![Swap {
+          output: Some(ids[1]),
+          input_amount: Some(500),
+          is_exact_input: true,
+          ..default()
+        }],
+        ..default()
+      },
+    );
+    context.mine_blocks(1);
+
+    context.relic_tx(
+      &context.relic_outpoints(vec![(ids[0], 1000)]),
+      1,
+      Keepsake {
+        transfers: vec![Transfer {
+          id: ids[0],
+          amount: 300,
+          output: 1,
+        }],
+        ..default()
+      },
+    );
+    context.mine_blocks(1);
+
+    context.relic_tx(
+      &context.relic_outpoints(vec![(RELIC_ID, 3000), (ids[0], 500)]),
+      1,
+      Keepsake {
+        liquidity: Some(Liquidity {
+          relic: ids[0],
+          base_amount: 3000,
+          quote_amount: 500,
+          shares: 2500,
+          is_remove: false,
+        }),
+        ..default()
+      },
+    );
+    context.mine_blocks(1);
+
+    let audits = assert_consistent();
+    assert_eq!(audits.len(), 3);
+    assert!(audits[0].locked > 0);
+    // the transfer to the OP_RETURN output burned relics
+    assert_eq!(audits[1].burned, 300);
+    assert_eq!(audits[1].shares, 2500);
+  }
+
+  #[test]
+  fn relic_balances_are_snapshotted_at_past_heights() {
+    let context = Context::builder()
+      .arg("--index-relics")
+      .arg("--index-relic-history")
+      .build();
+
+    let balances = || {
+      context
+        .index
+        .get_relic_balances()
+        .unwrap()
+        .into_iter()
+        .flat_map(|(outpoint, balances)| {
+          balances
+            .into_iter()
+            .map(move |(id, amount)| (Some(outpoint), id, amount))
+        })
+        .collect::<Vec<(Option<OutPoint>, RelicId, u128)>>()
+    };
+
+    let snapshot = |height, by_address| {
+      context
+        .index
+        .get_relic_snapshot(height, by_address, usize::MAX, None)
+        .unwrap()
+        .balances
+        .into_iter()
+        .map(|balance| (balance.outpoint, balance.id, balance.amount))
+        .collect::<Vec<(Option<OutPoint>, RelicId, u128)>>()
+    };
+
+    let height = || context.index.block_count().unwrap() - 1;
+
+    assert!(snapshot(height(), false).is_empty());
+
+    context.mint_base_token(1, 1);
+
+    let ids = enshrine_pools(&context, 1);
+
+    let before = (height(), balances());
+    assert_eq!(snapshot(before.0, false), before.1);
+
+    context.relic_tx(
+      &context.relic_outpoints(vec![(ids[0], 1000)]),
+      2,
+      Keepsake {
+        transfers: vec![Transfer {
+          id: ids[0],
+          amount: 400,
+          output: 1,
+        }],
+        ..default()
+      },
+    );
+    context.mine_blocks(1);
+
+    let after = (height(), balances());
+    assert_ne!(before.1, after.1);
+
+    assert_eq!(snapshot(before.0, false), before.1);
+    assert_eq!(snapshot(after.0, false), after.1);
+
+    let total = |balances: &[(Option<OutPoint>, RelicId, u128)], id| {
+      balances
+        .iter()
+        .filter(|(_, relic_id, _)| *relic_id == id)
+        .map(|(_, _, amount)| amount)
+        .sum::<u128>()
+    };
+
+    let aggregated = snapshot(after.0, true);
+    assert!(aggregated.len() < after.1.len());
+    assert!(aggregated.iter().all(|(outpoint, _, _)| outpoint.is_none()));
+    for id in [RELIC_ID, ids[0]] {
+      assert_eq!(total(&aggregated, id), total(&after.1, id));
+    }
+
+    for by_address in [false, true] {
+      let mut paged = Vec::new();
+      let mut pages = 0;
+      let mut cursor = None;
+      loop {
+        let page = context
+          .index
+          .get_relic_snapshot(after.0, by_address, 1, cursor.as_ref())
+          .unwrap();
+        pages += 1;
+        assert!(
+          page
+            .balances
+            .iter()
+            .map(|balance| (balance.outpoint, balance.script_pubkey.clone()))
+            .collect::<HashSet<(Option<OutPoint>, ScriptBuf)>>()
+            .len()
+            <= 1
+        );
+        paged.extend(
+          page
+            .balances
+            .into_iter()
+            .map(|balance| (balance.outpoint, balance.id, balance.amount)),
+        );
+        cursor = page.next;
+        if cursor.is_none() {
+          break;
+        }
+      }
+      assert_eq!(
+        pages,
+        context
+          .index
+          .get_relic_snapshot(after.0, by_address, usize::MAX, None)
+          .unwrap()
+          .balances
+          .into_iter()
+          .map(|balance| (balance.outpoint, balance.script_pubkey))
+          .collect::<HashSet<(Option<OutPoint>, ScriptBuf)>>()
+          .len()
+      );
+      assert_eq!(paged, snapshot(after.0, by_address));
+    }
+
+    assert!(context
+      .index
+      .get_relic_snapshot(
+        after.0,
+        true,
+        2,
+        Some(&api::RelicSnapshotCursor::Output(OutPoint::null()))
+      )
+      .is_err());
+  }
+
   #[test]
   fn relic_events() {
     let (event_sender, mut event_receiver) = tokio::sync::mpsc::channel(1024);
@@ -1682,7 +2724,7 @@ mod tests {
       &context.relic_outpoints(vec![(id1, 600)]),
       1,
       Keepsake {
-        swap: Some(Swap {
+        swaps: vec![Swap {
           input: Some(id1),
           output: Some(id2),
           // max input of token 1
@@ -1690,7 +2732,8 @@ mod tests {
           // expected output of token 2
           output_amount: Some(100),
           is_exact_input: false,
-        }),
+          via: Vec::new(),
+        }],
         ..default()
       },
     );
@@ -2113,6 +3156,7 @@ mod tests {
             fee: 6,
             is_sell_order: true,
             is_exact_input: false,
+            swap_index: 0,
           },
         },
         Event {
@@ -2126,6 +3170,7 @@ mod tests {
             fee: 6,
             is_sell_order: false,
             is_exact_input: false,
+            swap_index: 0,
           },
         },
         Event {
@@ -0,0 +1,498 @@
+use {super::*, bitcoin::psbt::raw::ProprietaryKey};
+
+/// Prefix of the proprietary PSBT fields holding Relic balances
+const PROPRIETARY_PREFIX: &[u8] = b"ord";
+/// Relic balances of an input, or the expected Relic allocation of an output
+const PROPRIETARY_SUBTYPE_RELICS: u8 = 0;
+/// Marks a PSBT as an offer. Holds the Relics the seller expects to receive in the output paired
+/// with the offered input, which must be empty since offers are priced in bitcoin.
+const PROPRIETARY_SUBTYPE_OFFER: u8 = 1;
+/// Pool shares of an input, or the expected pool shares of an output
+const PROPRIETARY_SUBTYPE_SHARES: u8 = 2;
+
+/// Where the transfers and the pointer of a Keepsake send the Relics and pool shares of the inputs
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct RelicAllocation {
+  pub(crate) outputs: Vec<BTreeMap<RelicId, u128>>,
+  pub(crate) burned: BTreeMap<RelicId, u128>,
+  pub(crate) shares: Vec<BTreeMap<RelicId, u128>>,
+  /// shares sent to an OP_RETURN output or burned by a cenotaph, they stay locked in their pool
+  pub(crate) burned_shares: BTreeMap<RelicId, u128>,
+}
+
+impl RelicAllocation {
+  /// Allocate the input balances and shares like the indexer would in the block at `height`,
+  /// ignoring every operation besides transfers
+  pub(crate) fn new(
+    tx: &Transaction,
+    inputs: &[BTreeMap<RelicId, u128>],
+    input_shares: &[BTreeMap<RelicId, u128>],
+    network: Network,
+    height: u32,
+  ) -> Self {
+    let sum = |inputs: &[BTreeMap<RelicId, u128>]| {
+      let mut total = BTreeMap::<RelicId, u128>::new();
+      for balances in inputs {
+        for (id, amount) in balances {
+          *total.entry(*id).or_default() += amount;
+        }
+      }
+      total.retain(|_, amount| *amount > 0);
+      total
+    };
+
+    let mut unallocated = sum(inputs);
+    let mut shares = sum(input_shares);
+
+    let mut allocation = Self {
+      outputs: vec![BTreeMap::new(); tx.output.len()],
+      burned: BTreeMap::new(),
+      shares: vec![BTreeMap::new(); tx.output.len()],
+      burned_shares: BTreeMap::new(),
+    };
+
+    let artifact = Keepsake::decipher_at(tx, network, height);
+
+    if let Some(RelicArtifact::Keepsake(keepsake)) = &artifact {
+      let allocations = Self::transfers(tx, &keepsake.transfers, None, |id| {
+        unallocated.get(&id).copied().unwrap_or_default()
+      });
+      for (output, id, amount) in allocations {
+        *unallocated.get_mut(&id).unwrap() -= amount;
+        *allocation.outputs[output].entry(id).or_default() += amount;
+      }
+      let allocations = Self::transfers(tx, &keepsake.share_transfers, None, |id| {
+        shares.get(&id).copied().unwrap_or_default()
+      });
+      for (output, id, amount) in allocations {
+        *shares.get_mut(&id).unwrap() -= amount;
+        *allocation.shares[output].entry(id).or_default() += amount;
+      }
+    }
+
+    shares.retain(|_, amount| *amount > 0);
+
+    match Self::default_output(tx, artifact.as_ref()) {
+      Some(vout) => {
+        for (id, amount) in unallocated.into_iter().filter(|(_, amount)| *amount > 0) {
+          *allocation.outputs[vout].entry(id).or_default() += amount;
+        }
+        for (id, amount) in shares {
+          *allocation.shares[vout].entry(id).or_default() += amount;
+        }
+      }
+      None => {
+        allocation.burned = unallocated;
+        allocation.burned.retain(|_, amount| *amount > 0);
+        allocation.burned_shares = shares;
+      }
+    }
+
+    for (vout, tx_out) in tx.output.iter().enumerate() {
+      if tx_out.script_pubkey.is_op_return() {
+        for (id, amount) in mem::take(&mut allocation.outputs[vout]) {
+          *allocation.burned.entry(id).or_default() += amount;
+        }
+        for (id, amount) in mem::take(&mut allocation.shares[vout]) {
+          *allocation.burned_shares.entry(id).or_default() += amount;
+        }
+      }
+    }
+
+    allocation
+  }
+
+  /// Amounts sent to each output by `transfers`, as `(output, id, amount)` in the order the indexer
+  /// allocates them. `balance` is the unallocated balance of a Relic before the transfers, transfers
+  /// of the default id refer to the Relic `enshrined` by the transaction.
+  pub(crate) fn transfers(
+    tx: &Transaction,
+    transfers: &[Transfer],
+    enshrined: Option<RelicId>,
+    balance_of: impl Fn(RelicId) -> u128,
+  ) -> Vec<(usize, RelicId, u128)> {
+    let mut unallocated = BTreeMap::<RelicId, u128>::new();
+    let mut allocations = Vec::new();
+
+    // find non-OP_RETURN outputs
+    let destinations = tx
+      .output
+      .iter()
+      .enumerate()
+      .filter_map(|(vout, tx_out)| (!tx_out.script_pubkey.is_op_return()).then_some(vout))
+      .collect::<Vec<usize>>();
+
+    for Transfer { id, amount, output } in transfers.iter().copied() {
+      // transfers with output values greater than the number of outputs
+      // should never be produced by the parser
+      let output = usize::try_from(output).unwrap();
+      assert!(output <= tx.output.len());
+
+      let id = if id == RelicId::default() {
+        let Some(id) = enshrined else {
+          continue;
+        };
+        id
+      } else {
+        id
+      };
+
+      let balance = unallocated.entry(id).or_insert_with(|| balance_of(id));
+
+      let mut allocate = |balance: &mut u128, amount: u128, output: usize| {
+        if amount > 0 {
+          *balance -= amount;
+          allocations.push((output, id, amount));
+        }
+      };
+
+      if output == tx.output.len() {
+        if destinations.is_empty() {
+          continue;
+        }
+
+        if amount == 0 {
+          // if amount is zero, divide balance between eligible outputs
+          let count = destinations.len() as u128;
+          let amount = *balance / count;
+          let remainder = usize::try_from(*balance % count).unwrap();
+          for (i, vout) in destinations.iter().enumerate() {
+            allocate(balance, amount + u128::from(i < remainder), *vout);
+          }
+        } else {
+          // if amount is non-zero, distribute amount to eligible outputs
+          for vout in &destinations {
+            allocate(balance, amount.min(*balance), *vout);
+          }
+        }
+      } else if amount == 0 {
+        allocate(balance, *balance, output);
+      } else {
+        allocate(balance, amount.min(*balance), output);
+      }
+    }
+
+    allocations
+  }
+
+  /// Output receiving the Relics and pool shares the transfers leave unallocated: the pointer of a
+  /// Keepsake or the first non-OP_RETURN output, `None` if they are burned. The output might be an
+  /// OP_RETURN output, which burns them as well.
+  pub(crate) fn default_output(
+    tx: &Transaction,
+    artifact: Option<&RelicArtifact>,
+  ) -> Option<usize> {
+    let first_non_op_return_output = || {
+      tx.output
+        .iter()
+        .position(|tx_out| !tx_out.script_pubkey.is_op_return())
+    };
+
+    match artifact {
+      // no protocol message: pass through to first non-op_return
+      None => first_non_op_return_output(),
+      // valid protocol message: use pointer as output or default to the first non-op_return
+      Some(RelicArtifact::Keepsake(keepsake)) => keepsake
+        .pointer
+        .map(|pointer| usize::try_from(pointer).unwrap())
+        .or_else(first_non_op_return_output),
+      // invalid protocol message: burn all Relics
+      Some(RelicArtifact::Cenotaph(_)) => None,
+    }
+  }
+
+  pub(crate) fn encode(balances: &BTreeMap<RelicId, u128>) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    for (id, amount) in balances {
+      Index::encode_rune_balance(*id, *amount, &mut buffer);
+    }
+    buffer
+  }
+
+  pub(crate) fn decode(mut buffer: &[u8]) -> Result<BTreeMap<RelicId, u128>> {
+    let mut balances = BTreeMap::new();
+    while !buffer.is_empty() {
+      let ((id, amount), length) = Index::decode_rune_balance(buffer)?;
+      balances.insert(id, amount);
+      buffer = &buffer[length..];
+    }
+    Ok(balances)
+  }
+
+  fn proprietary_key(subtype: u8) -> ProprietaryKey {
+    ProprietaryKey {
+      prefix: PROPRIETARY_PREFIX.to_vec(),
+      subtype,
+      key: Vec::new(),
+    }
+  }
+
+  /// Annotate a PSBT input or output with Relic balances
+  pub(crate) fn annotate(
+    proprietary: &mut BTreeMap<ProprietaryKey, Vec<u8>>,
+    balances: &BTreeMap<RelicId, u128>,
+  ) {
+    proprietary.insert(
+      Self::proprietary_key(PROPRIETARY_SUBTYPE_RELICS),
+      Self::encode(balances),
+    );
+  }
+
+  /// Relic balances annotated on a PSBT input or output, `None` if it has no annotation
+  pub(crate) fn annotation(
+    proprietary: &BTreeMap<ProprietaryKey, Vec<u8>>,
+  ) -> Result<Option<BTreeMap<RelicId, u128>>> {
+    proprietary
+      .get(&Self::proprietary_key(PROPRIETARY_SUBTYPE_RELICS))
+      .map(|buffer| Self::decode(buffer))
+      .transpose()
+  }
+
+  /// Annotate a PSBT input or output with pool shares
+  pub(crate) fn annotate_shares(
+    proprietary: &mut BTreeMap<ProprietaryKey, Vec<u8>>,
+    shares: &BTreeMap<RelicId, u128>,
+  ) {
+    proprietary.insert(
+      Self::proprietary_key(PROPRIETARY_SUBTYPE_SHARES),
+      Self::encode(shares),
+    );
+  }
+
+  /// Pool shares annotated on a PSBT input or output, `None` if it has no annotation
+  pub(crate) fn share_annotation(
+    proprietary: &BTreeMap<ProprietaryKey, Vec<u8>>,
+  ) -> Result<Option<BTreeMap<RelicId, u128>>> {
+    proprietary
+      .get(&Self::proprietary_key(PROPRIETARY_SUBTYPE_SHARES))
+      .map(|buffer| Self::decode(buffer))
+      .transpose()
+  }
+
+  /// Mark a PSBT as an offer in its global map
+  pub(crate) fn annotate_offer(proprietary: &mut BTreeMap<ProprietaryKey, Vec<u8>>) {
+    proprietary.insert(Self::proprietary_key(PROPRIETARY_SUBTYPE_OFFER), Vec::new());
+  }
+
+  /// Relic price claimed by an offer, `None` if the PSBT is not an offer
+  pub(crate) fn offer(
+    proprietary: &BTreeMap<ProprietaryKey, Vec<u8>>,
+  ) -> Result<Option<BTreeMap<RelicId, u128>>> {
+    proprietary
+      .get(&Self::proprietary_key(PROPRIETARY_SUBTYPE_OFFER))
+      .map(|buffer| Self::decode(buffer))
+      .transpose()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const A: RelicId = RelicId { block: 2, tx: 1 };
+  const B: RelicId = RelicId { block: 3, tx: 1 };
+
+  fn tx(keepsake: Option<Keepsake>, outputs: usize) -> Transaction {
+    let mut output = keepsake
+      .map(|keepsake| {
+        vec![TxOut {
+          script_pubkey: keepsake.encipher(),
+          value: Amount::ZERO,
+        }]
+      })
+      .unwrap_or_default();
+    output.extend((0..outputs).map(|_| TxOut {
+      script_pubkey: ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros()),
+      value: Amount::from_sat(1000),
+    }));
+    Transaction {
+      version: Version(2),
+      lock_time: LockTime::ZERO,
+      input: Vec::new(),
+      output,
+    }
+  }
+
+  fn balances(balances: &[(RelicId, u128)]) -> BTreeMap<RelicId, u128> {
+    balances.iter().copied().collect()
+  }
+
+  fn allocate(
+    tx: &Transaction,
+    inputs: &[BTreeMap<RelicId, u128>],
+    shares: &[BTreeMap<RelicId, u128>],
+  ) -> RelicAllocation {
+    RelicAllocation::new(tx, inputs, shares, Network::Regtest, 0)
+  }
+
+  #[test]
+  fn unallocated_relics_go_to_first_non_op_return_output() {
+    assert_eq!(
+      allocate(
+        &tx(None, 2),
+        &[balances(&[(A, 10)]), balances(&[(A, 5)])],
+        &[balances(&[(B, 3)]), BTreeMap::new()],
+      ),
+      RelicAllocation {
+        outputs: vec![balances(&[(A, 15)]), BTreeMap::new()],
+        burned: BTreeMap::new(),
+        shares: vec![balances(&[(B, 3)]), BTreeMap::new()],
+        burned_shares: BTreeMap::new(),
+      }
+    );
+  }
+
+  #[test]
+  fn transfers_and_pointer() {
+    let keepsake = Keepsake {
+      transfers: vec![
+        Transfer {
+          id: A,
+          amount: 4,
+          output: 2,
+        },
+        Transfer {
+          id: B,
+          amount: 0,
+          output: 0,
+        },
+        Transfer {
+          id: A,
+          amount: 1,
+          output: 3,
+        },
+      ],
+      share_transfers: vec![Transfer {
+        id: A,
+        amount: 1,
+        output: 2,
+      }],
+      pointer: Some(1),
+      ..default()
+    };
+
+    assert_eq!(
+      allocate(
+        &tx(Some(keepsake), 2),
+        &[balances(&[(A, 10), (B, 7)])],
+        &[balances(&[(A, 2)])],
+      ),
+      RelicAllocation {
+        outputs: vec![BTreeMap::new(), balances(&[(A, 5)]), balances(&[(A, 5)]),],
+        burned: balances(&[(B, 7)]),
+        shares: vec![BTreeMap::new(), balances(&[(A, 1)]), balances(&[(A, 1)])],
+        burned_shares: BTreeMap::new(),
+      }
+    );
+  }
+
+  #[test]
+  fn cenotaph_burns_all_relics() {
+    let mut transaction = tx(None, 1);
+    transaction.output.insert(
+      0,
+      TxOut {
+        script_pubkey: script::Builder::new()
+          .push_opcode(opcodes::all::OP_RETURN)
+          .push_opcode(Keepsake::MAGIC_NUMBER)
+          .push_opcode(opcodes::all::OP_VERIFY)
+          .into_script(),
+        value: Amount::ZERO,
+      },
+    );
+
+    assert_eq!(
+      allocate(
+        &transaction,
+        &[balances(&[(A, 10)])],
+        &[balances(&[(B, 4)])]
+      ),
+      RelicAllocation {
+        outputs: vec![BTreeMap::new(), BTreeMap::new()],
+        burned: balances(&[(A, 10)]),
+        shares: vec![BTreeMap::new(), BTreeMap::new()],
+        burned_shares: balances(&[(B, 4)]),
+      }
+    );
+  }
+
+  #[test]
+  fn transfers_follow_the_rules_of_the_next_block() {
+    let keepsake = Keepsake {
+      transfers: vec![Transfer {
+        id: A,
+        amount: 4,
+        output: 2,
+      }],
+      liquidity: Some(Liquidity {
+        relic: A,
+        base_amount: 1,
+        quote_amount: 1,
+        shares: 0,
+        is_remove: false,
+      }),
+      ..default()
+    };
+
+    let transaction = tx(Some(keepsake), 2);
+
+    let activation = KeepsakeExtension::Liquidity.activation_height(Network::Signet);
+
+    assert_eq!(
+      RelicAllocation::new(
+        &transaction,
+        &[balances(&[(A, 10)])],
+        &[],
+        Network::Signet,
+        activation,
+      )
+      .outputs,
+      vec![BTreeMap::new(), balances(&[(A, 6)]), balances(&[(A, 4)])],
+    );
+
+    assert_eq!(
+      RelicAllocation::new(
+        &transaction,
+        &[balances(&[(A, 10)])],
+        &[],
+        Network::Signet,
+        activation - 1,
+      )
+      .burned,
+      balances(&[(A, 10)]),
+    );
+  }
+
+  #[test]
+  fn annotations_round_trip() {
+    let balances = balances(&[(A, 10), (B, u128::MAX)]);
+    assert_eq!(
+      RelicAllocation::decode(&RelicAllocation::encode(&balances)).unwrap(),
+      balances
+    );
+  }
+
+  #[test]
+  fn offer_is_annotated_separately() {
+    let mut proprietary = BTreeMap::new();
+
+    assert_eq!(RelicAllocation::offer(&proprietary).unwrap(), None);
+
+    RelicAllocation::annotate(&mut proprietary, &balances(&[(A, 10)]));
+    RelicAllocation::annotate_offer(&mut proprietary);
+    RelicAllocation::annotate_shares(&mut proprietary, &balances(&[(A, 3)]));
+
+    assert_eq!(
+      RelicAllocation::annotation(&proprietary).unwrap(),
+      Some(balances(&[(A, 10)]))
+    );
+    assert_eq!(
+      RelicAllocation::offer(&proprietary).unwrap(),
+      Some(BTreeMap::new())
+    );
+    assert_eq!(
+      RelicAllocation::share_annotation(&proprietary).unwrap(),
+      Some(balances(&[(A, 3)]))
+    );
+  }
+}
@@ -0,0 +1,263 @@
+use {super::*, std::collections::HashMap};
+
+/// Supply of a Relic according to its entry, next to the amounts of it found in the index
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct RelicSupplyAudit {
+  pub id: RelicId,
+  pub spaced_relic: SpacedRelic,
+  /// issued supply, the amounts issued by the mints including their boosts plus the seed of a
+  /// bootstrapped pool
+  pub issued: u128,
+  /// held by outputs
+  pub balances: u128,
+  /// quote supply of the pool of the Relic, or for the base token the base tokens locked by all
+  /// Relics, either in their pools or as mint payments
+  pub locked: u128,
+  /// claimable by Relic owners, only the base token can be claimed
+  pub claimable: u128,
+  pub burned: u128,
+  /// shares of the pool of the Relic that are not locked
+  pub free_shares: u128,
+  /// pool shares held by outputs
+  pub shares: u128,
+}
+
+impl RelicSupplyAudit {
+  /// Audit the supplies of all Relics. `balances` and `shares` are the sums of all output balances
+  /// and pool shares by Relic and `claimable` the sum of all claimable base tokens.
+  ///
+  /// The base tokens locked as payment for mints of a Relic without pool are the amounts recorded
+  /// when the mints were paid, so they stay exact across the activation of
+  /// [`PriceArithmetic::FixedPoint`].
+  pub fn audit(
+    entries: &[(RelicId, RelicEntry)],
+    balances: &HashMap<RelicId, u128>,
+    shares: &HashMap<RelicId, u128>,
+    claimable: u128,
+  ) -> Vec<Self> {
+    entries
+      .iter()
+      .map(|(id, entry)| {
+        // the seed is issued into the pool once it is bootstrapped
+        let seed = entry
+          .pool
+          .filter(|pool| pool.subsidy == 0)
+          .and(entry.mint_terms)
+          .and_then(|terms| terms.seed)
+          .unwrap_or_default();
+
+        let (locked, claimable) = if *id == RELIC_ID {
+          (
+            entries
+              .iter()
+              .filter(|(id, _)| *id != RELIC_ID)
+              .map(|(_, entry)| entry.locked_base_supply())
+              .fold(0, u128::saturating_add),
+            claimable,
+          )
+        } else {
+          (
+            entry.pool.map(|pool| pool.quote_supply).unwrap_or_default(),
+            0,
+          )
+        };
+
+        Self {
+          id: *id,
+          spaced_relic: entry.spaced_relic,
+          issued: entry.state.issued.saturating_add(seed),
+          balances: balances.get(id).copied().unwrap_or_default(),
+          locked,
+          claimable,
+          burned: entry.state.burned,
+          free_shares: entry
+            .pool
+            .map(|pool| pool.shares.saturating_sub(pool.locked_shares))
+            .unwrap_or_default(),
+          shares: shares.get(id).copied().unwrap_or_default(),
+        }
+      })
+      .collect()
+  }
+
+  /// sum of all amounts accounted for in the index
+  pub fn accounted(&self) -> u128 {
+    self
+      .balances
+      .saturating_add(self.locked)
+      .saturating_add(self.claimable)
+      .saturating_add(self.burned)
+  }
+
+  pub fn is_consistent(&self) -> bool {
+    self.accounted() == self.issued && self.shares == self.free_shares
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const ID: RelicId = RelicId { block: 5, tx: 1 };
+
+  fn entries() -> Vec<(RelicId, RelicEntry)> {
+    vec![
+      (
+        RELIC_ID,
+        RelicEntry {
+          mint_terms: Some(MintTerms {
+            amount: Some(1000),
+            cap: Some(10),
+            ..default()
+          }),
+          state: RelicState {
+            burned: 50,
+            mints: 3,
+            issued: 3000,
+            ..default()
+          },
+          ..default()
+        },
+      ),
+      (
+        ID,
+        RelicEntry {
+          mint_terms: Some(MintTerms {
+            amount: Some(100),
+            cap: Some(2),
+            price: Some(PriceModel::Fixed(400)),
+            seed: Some(70),
+            ..default()
+          }),
+          state: RelicState {
+            burned: 10,
+            mints: 2,
+            paid: 800,
+            issued: 200,
+            ..default()
+          },
+          pool: Some(Pool {
+            base_supply: 900,
+            quote_supply: 60,
+            fee_bps: 0,
+            subsidy: 0,
+            shares: 900,
+            locked_shares: 900,
+            provider_fees: 0,
+          }),
+          ..default()
+        },
+      ),
+    ]
+  }
+
+  #[test]
+  fn consistent_supplies() {
+    let audits = RelicSupplyAudit::audit(
+      &entries(),
+      &[(RELIC_ID, 1950), (ID, 200)].into_iter().collect(),
+      &HashMap::new(),
+      100,
+    );
+
+    assert_eq!(
+      audits[0],
+      RelicSupplyAudit {
+        id: RELIC_ID,
+        spaced_relic: SpacedRelic::default(),
+        issued: 3000,
+        balances: 1950,
+        locked: 900,
+        claimable: 100,
+        burned: 50,
+        free_shares: 0,
+        shares: 0,
+      }
+    );
+    assert_eq!(audits[1].issued, 270);
+    assert_eq!(audits[1].accounted(), 270);
+    assert!(audits.iter().all(RelicSupplyAudit::is_consistent));
+  }
+
+  #[test]
+  fn inconsistent_supply() {
+    let audits = RelicSupplyAudit::audit(
+      &entries(),
+      &[(RELIC_ID, 1950), (ID, 199)].into_iter().collect(),
+      &HashMap::new(),
+      100,
+    );
+
+    assert!(audits[0].is_consistent());
+    assert!(!audits[1].is_consistent());
+  }
+
+  #[test]
+  fn mint_payments_are_locked_until_the_pool_is_created() {
+    let mut entries = entries();
+    entries[1].1.pool = None;
+    entries[1].1.state.mints = 1;
+    entries[1].1.state.paid = 400;
+    entries[1].1.state.issued = 100;
+
+    let audits = RelicSupplyAudit::audit(
+      &entries,
+      &[(RELIC_ID, 2450), (ID, 90)].into_iter().collect(),
+      &HashMap::new(),
+      100,
+    );
+
+    assert_eq!(audits[0].locked, 400);
+    assert_eq!(audits[1].issued, 100);
+    assert!(audits.iter().all(RelicSupplyAudit::is_consistent));
+  }
+
+  #[test]
+  fn boosted_mints_are_audited_exactly() {
+    let mut entries = entries();
+    entries[1].1.boost_terms = Some(BoostTerms {
+      rare_chance: Some(100),
+      rare_multiplier_cap: Some(3),
+      ultra_rare_chance: None,
+      ultra_rare_multiplier_cap: None,
+    });
+    // one of the two mints was boosted threefold
+    entries[1].1.state.issued = 400;
+
+    let audit = |balance| {
+      RelicSupplyAudit::audit(
+        &entries,
+        &[(ID, balance)].into_iter().collect(),
+        &HashMap::new(),
+        0,
+      )[1]
+        .is_consistent()
+    };
+
+    assert!(!audit(399));
+    assert!(audit(400));
+    assert!(!audit(401));
+    assert!(!audit(600));
+  }
+
+  #[test]
+  fn pool_shares_are_reconciled() {
+    let mut entries = entries();
+    entries[1].1.pool.as_mut().unwrap().shares = 1000;
+
+    let audit = |shares: &[(RelicId, u128)]| {
+      RelicSupplyAudit::audit(
+        &entries,
+        &[(ID, 200)].into_iter().collect(),
+        &shares.iter().copied().collect(),
+        0,
+      )
+      .remove(1)
+    };
+
+    assert_eq!(audit(&[(ID, 100)]).free_shares, 100);
+    assert!(audit(&[(ID, 100)]).is_consistent());
+    assert!(!audit(&[(ID, 99)]).is_consistent());
+    assert!(!audit(&[]).is_consistent());
+  }
+}
@@ -20,6 +20,9 @@ pub struct Settings {
   index: Option<PathBuf>,
   index_addresses: bool,
   index_cache_size: Option<usize>,
+  index_memory_pool: bool,
+  index_relic_history: bool,
+  index_relic_prices: bool,
   index_relics: bool,
   index_runes: bool,
   index_sats: bool,
@@ -135,6 +138,9 @@ impl Settings {
       index: self.index.or(source.index),
       index_addresses: self.index_addresses || source.index_addresses,
       index_cache_size: self.index_cache_size.or(source.index_cache_size),
+      index_memory_pool: self.index_memory_pool || source.index_memory_pool,
+      index_relic_history: self.index_relic_history || source.index_relic_history,
+      index_relic_prices: self.index_relic_prices || source.index_relic_prices,
       index_relics: self.index_relics || source.index_relics,
       index_runes: self.index_runes || source.index_runes,
       index_sats: self.index_sats || source.index_sats,
@@ -172,6 +178,9 @@ impl Settings {
       index: options.index,
       index_addresses: options.index_addresses,
       index_cache_size: options.index_cache_size,
+      index_memory_pool: options.index_memory_pool,
+      index_relic_history: options.index_relic_history,
+      index_relic_prices: options.index_relic_prices,
       index_relics: options.index_relics,
       index_runes: options.index_runes,
       index_sats: options.index_sats,
@@ -262,6 +271,9 @@ impl Settings {
       index: get_path("INDEX"),
       index_addresses: get_bool("INDEX_ADDRESSES"),
       index_cache_size: get_usize("INDEX_CACHE_SIZE")?,
+      index_memory_pool: get_bool("INDEX_MEMORY_POOL"),
+      index_relic_history: get_bool("INDEX_RELIC_HISTORY"),
+      index_relic_prices: get_bool("INDEX_RELIC_PRICES"),
       index_relics: get_bool("INDEX_RELICS"),
       index_runes: get_bool("INDEX_RUNES"),
       index_sats: get_bool("INDEX_SATS"),
@@ -294,6 +306,9 @@ impl Settings {
       index: None,
       index_addresses: true,
       index_cache_size: None,
+      index_memory_pool: false,
+      index_relic_history: false,
+      index_relic_prices: false,
       index_relics: true,
       index_runes: true,
       index_sats: true,
@@ -370,6 +385,9 @@ impl Settings {
           usize::try_from(sys.total_memory() / 4)?
         }
       }),
+      index_memory_pool: self.index_memory_pool,
+      index_relic_history: self.index_relic_history,
+      index_relic_prices: self.index_relic_prices,
       index_relics: self.index_relics,
       index_runes: self.index_runes,
       index_sats: self.index_sats,
@@ -552,6 +570,18 @@ impl Settings {
     !self.no_index_inscriptions
   }
 
+  pub fn index_memory_pool(&self) -> bool {
+    self.index_memory_pool
+  }
+
+  pub fn index_relic_history_raw(&self) -> bool {
+    self.index_relic_history
+  }
+
+  pub fn index_relic_prices_raw(&self) -> bool {
+    self.index_relic_prices
+  }
+
   pub fn index_relics_raw(&self) -> bool {
     self.index_relics
   }
@@ -942,6 +972,24 @@ mod tests {
     assert!(!parse(&[]).index_relics_raw());
   }
 
+  #[test]
+  fn index_relic_history() {
+    assert!(parse(&["--index-relics", "--index-relic-history"]).index_relic_history_raw());
+    assert!(!parse(&["--index-relics"]).index_relic_history_raw());
+  }
+
+  #[test]
+  fn index_relic_prices() {
+    assert!(parse(&["--index-relics", "--index-relic-prices"]).index_relic_prices_raw());
+    assert!(!parse(&["--index-relics"]).index_relic_prices_raw());
+  }
+
+  #[test]
+  fn index_memory_pool() {
+    assert!(parse(&["--index-relics", "--index-memory-pool"]).index_memory_pool());
+    assert!(!parse(&["--index-relics"]).index_memory_pool());
+  }
+
   #[test]
   fn index_runes() {
     assert!(parse(&["--chain=signet", "--index-runes"]).index_runes_raw());
@@ -1051,6 +1099,9 @@ mod tests {
       ("INDEX", "index"),
       ("INDEX_CACHE_SIZE", "4"),
       ("INDEX_ADDRESSES", "1"),
+      ("INDEX_MEMORY_POOL", "1"),
+      ("INDEX_RELIC_HISTORY", "1"),
+      ("INDEX_RELIC_PRICES", "1"),
       ("INDEX_RELICS", "1"),
       ("INDEX_RUNES", "1"),
       ("INDEX_SATS", "1"),
@@ -1097,6 +1148,9 @@ mod tests {
         index: Some("index".into()),
         index_addresses: true,
         index_cache_size: Some(4),
+        index_memory_pool: true,
+        index_relic_history: true,
+        index_relic_prices: true,
         index_relics: true,
         index_runes: true,
         index_sats: true,
@@ -1132,6 +1186,9 @@ mod tests {
           "--index-addresses",
           "--index-cache-size=4",
           "--index-runes",
+          "--index-memory-pool",
+          "--index-relic-history",
+          "--index-relic-prices",
           "--index-relics",
           "--index-sats",
           "--index-transactions",
@@ -1162,6 +1219,9 @@ mod tests {
         index: Some("index".into()),
         index_addresses: true,
         index_cache_size: Some(4),
+        index_memory_pool: true,
+        index_relic_history: true,
+        index_relic_prices: true,
         index_relics: true,
         index_runes: true,
         index_sats: true,
@@ -4,12 +4,18 @@ use super::*;
 pub struct CompactOutput {
   pub inscriptions: Vec<CompactInscription>,
   pub runestone: Option<Artifact>,
+  pub keepsake: Option<RelicArtifact>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub keepsake_explanation: Option<KeepsakeExplanation>,
 }
 
 #[derive(Serialize, Eq, PartialEq, Deserialize, Debug)]
 pub struct RawOutput {
   pub inscriptions: Vec<ParsedEnvelope>,
   pub runestone: Option<Artifact>,
+  pub keepsake: Option<RelicArtifact>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub keepsake_explanation: Option<KeepsakeExplanation>,
 }
 
 #[serde_with::skip_serializing_none]
@@ -61,13 +67,22 @@ pub(crate) struct Decode {
     help = "Fetch transaction with <TXID> from Bitcoin Core."
   )]
   txid: Option<Txid>,
-  #[arg(long, conflicts_with = "txid", help = "Load transaction from <FILE>.")]
+  #[arg(
+    long,
+    conflicts_with = "txid",
+    help = "Load transaction from <FILE>, either raw or hex encoded."
+  )]
   file: Option<PathBuf>,
   #[arg(
     long,
     help = "Serialize inscriptions in a compact, human-readable format."
   )]
   compact: bool,
+  #[arg(
+    long,
+    help = "Explain the keepsake tag by tag, including the flaw that made it a cenotaph."
+  )]
+  explain: bool,
 }
 
 impl Decode {
@@ -77,15 +92,24 @@ impl Decode {
         .bitcoin_rpc_client(None)?
         .get_raw_transaction(&txid, None)?
     } else if let Some(file) = self.file {
-      Transaction::consensus_decode(&mut io::BufReader::new(File::open(file)?))?
+      Self::parse_transaction(fs::read(file)?)?
     } else {
-      Transaction::consensus_decode(&mut io::BufReader::new(io::stdin()))?
+      let mut buffer = Vec::new();
+      io::stdin().read_to_end(&mut buffer)?;
+      Self::parse_transaction(buffer)?
     };
 
     let inscriptions = ParsedEnvelope::from_transaction(&transaction);
 
     let runestone = Runestone::decipher(&transaction);
 
+    let keepsake = Keepsake::decipher(&transaction);
+
+    let keepsake_explanation = self
+      .explain
+      .then(|| Keepsake::explain(&transaction))
+      .flatten();
+
     if self.compact {
       Ok(Some(Box::new(CompactOutput {
         inscriptions: inscriptions
@@ -94,12 +118,30 @@ impl Decode {
           .map(|inscription| inscription.payload.try_into())
           .collect::<Result<Vec<CompactInscription>>>()?,
         runestone,
+        keepsake,
+        keepsake_explanation,
       })))
     } else {
       Ok(Some(Box::new(RawOutput {
         inscriptions,
         runestone,
+        keepsake,
+        keepsake_explanation,
       })))
     }
   }
+
+  /// Transactions are accepted raw or hex encoded, as returned by `getrawtransaction`
+  fn parse_transaction(bytes: Vec<u8>) -> Result<Transaction> {
+    if let Ok(text) = std::str::from_utf8(&bytes) {
+      let text = text.trim();
+      if !text.is_empty() && text.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return Ok(Transaction::consensus_decode(
+          &mut hex::decode(text)?.as_slice(),
+        )?);
+      }
+    }
+
+    Ok(Transaction::consensus_decode(&mut bytes.as_slice())?)
+  }
 }
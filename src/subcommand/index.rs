@@ -1,15 +1,23 @@
 use super::*;
 
+mod audit_relics;
 mod export;
 pub mod info;
+mod snapshot_relics;
 mod update;
 
 #[derive(Debug, Parser)]
 pub(crate) enum IndexSubcommand {
-  #[command(about = "Write inscription numbers and ids to a tab-separated file")]
+  #[command(about = "Check relic supplies against balances, pools and claimable amounts")]
+  AuditRelics,
+  #[command(
+    about = "Write inscription numbers and ids to a tab-separated file, or relics to CSV files"
+  )]
   Export(export::Export),
   #[command(about = "Print index statistics")]
   Info(info::Info),
+  #[command(about = "Write relic balances at a past block height")]
+  SnapshotRelics(snapshot_relics::SnapshotRelics),
   #[command(about = "Update the index", alias = "run")]
   Update,
 }
@@ -17,8 +25,10 @@ pub(crate) enum IndexSubcommand {
 impl IndexSubcommand {
   pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
     match self {
+      Self::AuditRelics => audit_relics::run(settings),
       Self::Export(export) => export.run(settings),
       Self::Info(info) => info.run(settings),
+      Self::SnapshotRelics(snapshot_relics) => snapshot_relics.run(settings),
       Self::Update => update::run(settings),
     }
   }
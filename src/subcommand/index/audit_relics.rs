@@ -0,0 +1,53 @@
+use super::*;
+
+#[derive(Serialize, Deserialize)]
+pub struct Output {
+  pub relics: usize,
+}
+
+pub(crate) fn run(settings: Settings) -> SubcommandResult {
+  let index = Index::open(&settings)?;
+
+  ensure!(
+    index.has_relic_index(),
+    "`ord index audit-relics` requires index created with `--index-relics` flag",
+  );
+
+  index.update()?;
+
+  let audits = index.audit_relic_supply()?;
+
+  let mut discrepancies = 0;
+
+  for audit in &audits {
+    if audit.is_consistent() {
+      continue;
+    }
+
+    discrepancies += 1;
+
+    eprintln!(
+      "{} ({}): issued {}, accounted {} (balances {}, locked {}, claimable {}, burned {}), free pool shares {}, held {}",
+      audit.spaced_relic,
+      audit.id,
+      audit.issued,
+      audit.accounted(),
+      audit.balances,
+      audit.locked,
+      audit.claimable,
+      audit.burned,
+      audit.free_shares,
+      audit.shares,
+    );
+  }
+
+  ensure!(
+    discrepancies == 0,
+    "found supply discrepancies for {discrepancies} of {} relics",
+    audits.len(),
+  );
+
+  Ok(Some(Box::new(Output {
+    relics: audits.len(),
+  })))
+}
@@ -4,16 +4,39 @@ use super::*;
 pub(crate) struct Export {
   #[arg(long, help = "Include addresses in export")]
   include_addresses: bool,
-  #[arg(long, help = "Write export to <TSV>")]
-  tsv: String,
+  #[arg(
+    long,
+    required_unless_present = "relics",
+    help = "Write export to <TSV>"
+  )]
+  tsv: Option<String>,
+  #[arg(
+    long,
+    value_name = "DIR",
+    conflicts_with_all = ["tsv", "include_addresses"],
+    help = "Write relic entries, balances and events as CSV files to <DIR>"
+  )]
+  relics: Option<PathBuf>,
 }
 
 impl Export {
   pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
     let index = Index::open(&settings)?;
 
+    if self.relics.is_some() {
+      ensure!(
+        index.has_relic_index(),
+        "`ord index export --relics` requires index created with `--index-relics` flag",
+      );
+    }
+
     index.update()?;
-    index.export(&self.tsv, self.include_addresses)?;
+
+    match (self.relics, self.tsv) {
+      (Some(dir), _) => index.export_relics(&dir)?,
+      (None, Some(tsv)) => index.export(&tsv, self.include_addresses)?,
+      (None, None) => bail!("`ord index export` requires `--tsv` or `--relics`"),
+    }
 
     Ok(None)
   }
@@ -0,0 +1,126 @@
+use super::*;
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+enum SnapshotFormat {
+  Csv,
+  Json,
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct SnapshotRelics {
+  #[arg(long, help = "Snapshot relic balances at the end of block <HEIGHT>.")]
+  height: u32,
+  #[arg(
+    long,
+    value_enum,
+    default_value = "json",
+    help = "Write snapshot as <FORMAT>. JSON follows the global `--format` option."
+  )]
+  format: SnapshotFormat,
+  #[arg(
+    long,
+    help = "Aggregate balances by address instead of listing outputs."
+  )]
+  by_address: bool,
+}
+
+impl SnapshotRelics {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    let index = Index::open(&settings)?;
+
+    ensure!(
+      index.has_relic_history_index(),
+      "`ord index snapshot-relics` requires index created with `--index-relic-history` flag",
+    );
+
+    index.update()?;
+
+    let block_count = index.block_count()?;
+
+    ensure!(
+      self.height < block_count,
+      "height {} has not been indexed yet, index is at height {}",
+      self.height,
+      block_count.saturating_sub(1),
+    );
+
+    let snapshot = index.get_relic_snapshot(self.height, self.by_address, usize::MAX, None)?;
+
+    match self.format {
+      SnapshotFormat::Json => Ok(Some(Box::new(snapshot))),
+      SnapshotFormat::Csv => {
+        Self::write_csv(&snapshot, io::stdout())?;
+        Ok(None)
+      }
+    }
+  }
+
+  fn write_csv(snapshot: &api::RelicSnapshot, writer: impl io::Write) -> Result {
+    let mut writer = csv::Writer::from_writer(writer);
+
+    writer.write_record([
+      "height",
+      "outpoint",
+      "address",
+      "script_pubkey",
+      "id",
+      "relic",
+      "amount",
+    ])?;
+
+    for balance in &snapshot.balances {
+      writer.write_record([
+        snapshot.height.to_string(),
+        balance
+          .outpoint
+          .map(|outpoint| outpoint.to_string())
+          .unwrap_or_default(),
+        balance
+          .address
+          .as_ref()
+          .map(|address| address.clone().assume_checked().to_string())
+          .unwrap_or_default(),
+        balance.script_pubkey.to_hex_string(),
+        balance.id.to_string(),
+        balance.spaced_relic.to_string(),
+        balance.amount.to_string(),
+      ])?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn csv() {
+    let mut buffer = Vec::new();
+
+    SnapshotRelics::write_csv(
+      &api::RelicSnapshot {
+        height: 7,
+        balances: vec![api::RelicSnapshotBalance {
+          outpoint: None,
+          address: None,
+          script_pubkey: ScriptBuf::from_bytes(vec![0x51]),
+          id: RelicId { block: 2, tx: 1 },
+          spaced_relic: "A•B".parse().unwrap(),
+          amount: 1000,
+        }],
+        next: None,
+      },
+      &mut buffer,
+    )
+    .unwrap();
+
+    assert_eq!(
+      String::from_utf8(buffer).unwrap(),
+      "height,outpoint,address,script_pubkey,id,relic,amount\n7,,,51,2:1,A•B,1000\n",
+    );
+  }
+}
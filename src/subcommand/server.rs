@@ -3,26 +3,33 @@ use {
     accept_encoding::AcceptEncoding,
     accept_json::AcceptJson,
     error::{OptionExt, ServerError, ServerResult},
+    event_stream::EventStreamQuery,
+    openapi::{untyped, Operation},
   },
   super::*,
+  crate::relics::{Candle, CandleInterval},
   crate::templates::{
     AddressHtml, BlockHtml, BlocksHtml, ChildrenHtml, ClockSvg, CollectionsHtml, HomeHtml,
     InputHtml, InscriptionHtml, InscriptionsBlockHtml, InscriptionsHtml, OutputHtml, PageContent,
     PageHtml, ParentsHtml, PreviewAudioHtml, PreviewCodeHtml, PreviewFontHtml, PreviewImageHtml,
     PreviewMarkdownHtml, PreviewModelHtml, PreviewPdfHtml, PreviewTextHtml, PreviewUnknownHtml,
     PreviewVideoHtml, RareTxt, RelicEventsHtml, RelicHtml, RelicsHtml, RuneHtml, RunesHtml,
-    SatHtml, SealingHtml, SealingsHtml, TransactionHtml,
+    SatHtml, SealingHtml, SealingsHtml, SearchHtml, TransactionHtml,
   },
   axum::{
     body,
     extract::{DefaultBodyLimit, Extension, Json, Path, Query},
-    http::{header, HeaderValue, StatusCode, Uri},
-    response::{IntoResponse, Redirect, Response},
-    routing::{get, post},
+    http::{header, HeaderMap, HeaderValue, StatusCode, Uri},
+    response::{
+      sse::{KeepAlive, Sse},
+      IntoResponse, Redirect, Response,
+    },
+    routing::{get, post, MethodRouter},
     Router,
   },
   axum_server::Handle,
-  bitcoin::consensus,
+  base64::Engine,
+  bitcoin::{consensus, psbt::Psbt},
   brotli::Decompressor,
   rust_embed::RustEmbed,
   rustls_acme::{
@@ -31,6 +38,7 @@ use {
     caches::DirCache,
     AcmeConfig,
   },
+  schemars::JsonSchema,
   std::{str, sync::Arc},
   tokio_stream::StreamExt,
   tower_http::{
@@ -46,6 +54,8 @@ pub use server_config::ServerConfig;
 mod accept_encoding;
 mod accept_json;
 mod error;
+mod event_stream;
+mod openapi;
 pub mod query;
 mod server_config;
 
@@ -55,13 +65,14 @@ enum SpawnConfig {
   Redirect(String),
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 pub(crate) struct OutputsQuery {
+  /// only return outputs of this type
   #[serde(rename = "type")]
   pub(crate) ty: Option<OutputType>,
 }
 
-#[derive(Clone, Copy, Deserialize, Default, PartialEq)]
+#[derive(Clone, Copy, Deserialize, Default, JsonSchema, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub(crate) enum OutputType {
   #[default]
@@ -76,6 +87,87 @@ struct Search {
   query: String,
 }
 
+#[derive(Deserialize, JsonSchema)]
+struct QuoteQuery {
+  /// Relic to sell, the base token if omitted
+  #[schemars(with = "Option<relics_api::schema::RelicId>")]
+  input: Option<DeserializeFromStr<RelicId>>,
+  /// Relic to buy, the base token if omitted
+  #[schemars(with = "Option<relics_api::schema::RelicId>")]
+  output: Option<DeserializeFromStr<RelicId>>,
+  /// comma separated Relic IDs to route the swap through
+  via: Option<String>,
+  /// input or output amount
+  #[schemars(with = "u128")]
+  amount: DeserializeFromStr<u128>,
+  /// minimum output of exact-input swaps, maximum input of exact-output swaps
+  #[schemars(with = "Option<u128>")]
+  limit: Option<DeserializeFromStr<u128>>,
+  /// whether `amount` is the input amount, defaults to true
+  exact_input: Option<bool>,
+}
+
+impl QuoteQuery {
+  fn swap(self) -> ServerResult<Swap> {
+    let via = self
+      .via
+      .unwrap_or_default()
+      .split(',')
+      .filter(|id| !id.is_empty())
+      .map(RelicId::from_str)
+      .collect::<Result<Vec<RelicId>, _>>()
+      .map_err(|err| ServerError::BadRequest(format!("invalid relic ID in route: {err}")))?;
+
+    let amount = self.amount.0;
+    let limit = self.limit.map(|limit| limit.0);
+    let is_exact_input = self.exact_input.unwrap_or(true);
+
+    let swap = Swap {
+      input: self.input.map(|id| id.0),
+      output: self.output.map(|id| id.0),
+      input_amount: if is_exact_input { Some(amount) } else { limit },
+      output_amount: if is_exact_input { limit } else { Some(amount) },
+      is_exact_input,
+      via,
+    };
+
+    if swap.via.len() > Swap::MAX_HOPS {
+      return Err(ServerError::BadRequest(format!(
+        "a swap must not be routed through more than {} relics",
+        Swap::MAX_HOPS
+      )));
+    }
+
+    if !swap.is_valid() {
+      return Err(ServerError::BadRequest(
+        "a swap must not trade a relic against itself or route through the same relic twice"
+          .to_string(),
+      ));
+    }
+
+    Ok(swap)
+  }
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct EventsQuery {
+  /// only return events after this cursor, in chain order
+  after: Option<EventCursor>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct SnapshotQuery {
+  /// only return balances after this cursor
+  after: Option<api::RelicSnapshotCursor>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+struct CandlesQuery {
+  /// time span of each candle, defaults to 1h
+  #[serde(default)]
+  interval: CandleInterval,
+}
+
 #[derive(RustEmbed)]
 #[folder = "static"]
 struct StaticAssets;
@@ -155,6 +247,12 @@ impl Server {
           if let Err(error) = index_clone.update() {
             log::warn!("Updating index: {error}");
           }
+
+          if index_clone.has_mempool_relic_index() {
+            if let Err(error) = index_clone.update_mempool_relics() {
+              log::warn!("Updating memory pool relics: {error}");
+            }
+          }
         }
 
         thread::sleep(if integration_test {
@@ -180,130 +278,12 @@ impl Server {
         relics_light_mode: settings.store_relics_only(),
       });
 
-      let router = Router::new()
-        .route("/", get(Self::home))
-        .route("/address/:address", get(Self::address))
-        .route("/block/:query", get(Self::block))
-        .route("/blockcount", get(Self::block_count))
-        .route("/blockhash", get(Self::block_hash))
-        .route("/blockhash/:height", get(Self::block_hash_from_height))
-        .route("/blockheight", get(Self::block_height))
-        .route("/blocks", get(Self::blocks))
-        .route("/blocktime", get(Self::block_time))
-        .route("/children/:inscription_id", get(Self::children))
-        .route(
-          "/children/:inscription_id/:page",
-          get(Self::children_paginated),
-        )
-        .route("/clock", get(Self::clock))
-        .route("/collections", get(Self::collections))
-        .route("/collections/:page", get(Self::collections_paginated))
-        .route("/content/:inscription_id", get(Self::content))
-        .route("/favicon.ico", get(Self::favicon))
-        .route("/feed.xml", get(Self::feed))
-        .route("/input/:block/:transaction/:input", get(Self::input))
-        .route("/inscription/:inscription_query", get(Self::inscription))
-        .route(
-          "/inscription/:inscription_query/:child",
-          get(Self::inscription_child),
-        )
-        .route("/inscriptions", get(Self::inscriptions))
-        .route("/inscriptions", post(Self::inscriptions_json))
-        .route("/inscriptions/:page", get(Self::inscriptions_paginated))
-        .route(
-          "/inscriptions/block/:height",
-          get(Self::inscriptions_in_block),
-        )
-        .route(
-          "/inscriptions/block/:height/:page",
-          get(Self::inscriptions_in_block_paginated),
-        )
-        .route("/install.sh", get(Self::install_script))
-        .route("/ordinal/:sat", get(Self::ordinal))
-        .route("/output/:output", get(Self::output))
-        .route("/outputs", post(Self::outputs))
-        .route("/outputs/:address", get(Self::outputs_address))
-        .route("/parents/:inscription_id", get(Self::parents))
-        .route(
-          "/parents/:inscription_id/:page",
-          get(Self::parents_paginated),
-        )
-        .route("/preview/:inscription_id", get(Self::preview))
-        .route("/r/blockhash", get(Self::block_hash_json))
-        .route(
-          "/r/blockhash/:height",
-          get(Self::block_hash_from_height_json),
-        )
-        .route("/r/blockheight", get(Self::block_height))
-        .route("/r/blocktime", get(Self::block_time))
-        .route("/r/blockinfo/:query", get(Self::block_info))
-        .route(
-          "/r/inscription/:inscription_id",
-          get(Self::inscription_recursive),
-        )
-        .route("/r/children/:inscription_id", get(Self::children_recursive))
-        .route(
-          "/r/children/:inscription_id/:page",
-          get(Self::children_recursive_paginated),
-        )
-        .route(
-          "/r/children/:inscription_id/inscriptions",
-          get(Self::child_inscriptions_recursive),
-        )
-        .route(
-          "/r/children/:inscription_id/inscriptions/:page",
-          get(Self::child_inscriptions_recursive_paginated),
-        )
-        .route(
-          "/r/undelegated-content/:inscription_id",
-          get(Self::undelegated_content),
-        )
-        .route("/r/metadata/:inscription_id", get(Self::metadata))
-        .route("/r/parents/:inscription_id", get(Self::parents_recursive))
-        .route(
-          "/r/parents/:inscription_id/:page",
-          get(Self::parents_recursive_paginated),
-        )
-        .route("/r/sat/:sat_number", get(Self::sat_inscriptions))
-        .route(
-          "/r/sat/:sat_number/:page",
-          get(Self::sat_inscriptions_paginated),
-        )
-        .route(
-          "/r/sat/:sat_number/at/:index",
-          get(Self::sat_inscription_at_index),
-        )
-        .route("/rare.txt", get(Self::rare_txt))
-        .route("/events", post(Self::tx_events))
-        .route("/events/:relic/:page", get(Self::relic_events_paginated))
-        .route("/events/:block", get(Self::block_events))
-        .route("/sealing/:relic", get(Self::sealing_info))
-        .route("/sealings/:page", get(Self::sealings_paginated))
-        .route("/relic/:relic", get(Self::relic))
-        .route("/relics", get(Self::relics))
-        .route("/relics/:page", get(Self::relics_paginated))
-        .route("/relics/balances", get(Self::relics_balances))
-        .route("/relics/claimable", get(Self::relics_claimable))
-        .route(
-          "/relics/decipher/:txid",
-          get(Self::decipher_transaction_relics),
-        )
-        .route(
-          "/relics/decipher/raw/:hex_tx",
-          get(Self::decipher_raw_transaction_relics),
+      let router = Self::routes()
+        .into_iter()
+        .fold(
+          Router::new(),
+          |router, (path, method_router, _operation)| router.route(path, method_router),
         )
-        .route("/rune/:rune", get(Self::rune))
-        .route("/runes", get(Self::runes))
-        .route("/runes/:page", get(Self::runes_paginated))
-        .route("/sat/:sat", get(Self::sat))
-        .route("/satpoint/:satpoint", get(Self::satpoint))
-        .route("/search", get(Self::search_by_query))
-        .route("/search/*query", get(Self::search_by_path))
-        .route("/static/*path", get(Self::static_asset))
-        .route("/status", get(Self::status))
-        .route("/tx/:txid", get(Self::transaction))
-        .route("/decode/:txid", get(Self::decode))
-        .route("/update", get(Self::update))
         .fallback(Self::fallback)
         .layer(Extension(index))
         .layer(Extension(server_config.clone()))
@@ -389,6 +369,480 @@ impl Server {
     })
   }
 
+  /// Routes of the server, with the documentation of the JSON routes served at `/openapi.json`
+  fn routes() -> Vec<(
+    &'static str,
+    MethodRouter<Arc<ServerConfig>>,
+    Option<Operation>,
+  )> {
+    vec![
+      ("/", get(Self::home), None),
+      (
+        "/address/:address",
+        get(Self::address),
+        Some(Operation::get::<untyped::AddressInfo>(
+          "Outputs, inscriptions and balances of an address",
+        )),
+      ),
+      (
+        "/block/:query",
+        get(Self::block),
+        Some(Operation::get::<untyped::Block>("Block by height or hash")),
+      ),
+      ("/blockcount", get(Self::block_count), None),
+      ("/blockhash", get(Self::block_hash), None),
+      (
+        "/blockhash/:height",
+        get(Self::block_hash_from_height),
+        None,
+      ),
+      ("/blockheight", get(Self::block_height), None),
+      (
+        "/blocks",
+        get(Self::blocks),
+        Some(Operation::get::<untyped::Blocks>("Latest blocks")),
+      ),
+      ("/blocktime", get(Self::block_time), None),
+      ("/children/:inscription_id", get(Self::children), None),
+      (
+        "/children/:inscription_id/:page",
+        get(Self::children_paginated),
+        None,
+      ),
+      ("/clock", get(Self::clock), None),
+      ("/collections", get(Self::collections), None),
+      ("/collections/:page", get(Self::collections_paginated), None),
+      ("/content/:inscription_id", get(Self::content), None),
+      ("/favicon.ico", get(Self::favicon), None),
+      ("/feed.xml", get(Self::feed), None),
+      ("/input/:block/:transaction/:input", get(Self::input), None),
+      (
+        "/inscription/:inscription_query",
+        get(Self::inscription),
+        Some(Operation::get::<api::Inscription>(
+          "Inscription by ID or number",
+        )),
+      ),
+      (
+        "/inscription/:inscription_query/:child",
+        get(Self::inscription_child),
+        Some(Operation::get::<api::Inscription>(
+          "Child of an inscription by index",
+        )),
+      ),
+      (
+        "/inscriptions",
+        get(Self::inscriptions),
+        Some(Operation::get::<untyped::Inscriptions>(
+          "Latest inscriptions",
+        )),
+      ),
+      (
+        "/inscriptions",
+        post(Self::inscriptions_json),
+        Some(Operation::post::<Vec<InscriptionId>, Vec<api::Inscription>>("Inscriptions by ID")),
+      ),
+      (
+        "/inscriptions/:page",
+        get(Self::inscriptions_paginated),
+        Some(Operation::get::<untyped::Inscriptions>(
+          "Page of inscriptions",
+        )),
+      ),
+      (
+        "/inscriptions/block/:height",
+        get(Self::inscriptions_in_block),
+        Some(Operation::get::<untyped::Inscriptions>(
+          "Inscriptions of a block",
+        )),
+      ),
+      (
+        "/inscriptions/block/:height/:page",
+        get(Self::inscriptions_in_block_paginated),
+        Some(Operation::get::<untyped::Inscriptions>(
+          "Page of the inscriptions of a block",
+        )),
+      ),
+      ("/install.sh", get(Self::install_script), None),
+      ("/ordinal/:sat", get(Self::ordinal), None),
+      (
+        "/output/:output",
+        get(Self::output),
+        Some(Operation::get::<api::Output>(
+          "Output with its inscriptions and balances",
+        )),
+      ),
+      (
+        "/outputs",
+        post(Self::outputs),
+        Some(Operation::post::<
+          Vec<relics_api::schema::OutPoint>,
+          Vec<api::Output>,
+        >("Outputs by outpoint")),
+      ),
+      (
+        "/outputs/:address",
+        get(Self::outputs_address),
+        Some(
+          Operation::get::<Vec<api::Output>>("Unspent outputs of an address")
+            .query::<OutputsQuery>(),
+        ),
+      ),
+      ("/parents/:inscription_id", get(Self::parents), None),
+      (
+        "/parents/:inscription_id/:page",
+        get(Self::parents_paginated),
+        None,
+      ),
+      ("/preview/:inscription_id", get(Self::preview), None),
+      (
+        "/r/blockhash",
+        get(Self::block_hash_json),
+        Some(Operation::get::<relics_api::schema::BlockHash>(
+          "Hash of the latest block",
+        )),
+      ),
+      (
+        "/r/blockhash/:height",
+        get(Self::block_hash_from_height_json),
+        Some(Operation::get::<relics_api::schema::BlockHash>(
+          "Hash of the block at a height",
+        )),
+      ),
+      ("/r/blockheight", get(Self::block_height), None),
+      ("/r/blocktime", get(Self::block_time), None),
+      (
+        "/r/blockinfo/:query",
+        get(Self::block_info),
+        Some(Operation::get::<untyped::BlockInfo>(
+          "Block header and statistics by height or hash",
+        )),
+      ),
+      (
+        "/r/inscription/:inscription_id",
+        get(Self::inscription_recursive),
+        Some(Operation::get::<untyped::InscriptionRecursive>(
+          "Inscription as seen by recursive inscriptions",
+        )),
+      ),
+      (
+        "/r/children/:inscription_id",
+        get(Self::children_recursive),
+        Some(Operation::get::<untyped::Children>("Child inscription IDs")),
+      ),
+      (
+        "/r/children/:inscription_id/:page",
+        get(Self::children_recursive_paginated),
+        Some(Operation::get::<untyped::Children>(
+          "Page of child inscription IDs",
+        )),
+      ),
+      (
+        "/r/children/:inscription_id/inscriptions",
+        get(Self::child_inscriptions_recursive),
+        Some(Operation::get::<untyped::ChildInscriptions>(
+          "Child inscriptions",
+        )),
+      ),
+      (
+        "/r/children/:inscription_id/inscriptions/:page",
+        get(Self::child_inscriptions_recursive_paginated),
+        Some(Operation::get::<untyped::ChildInscriptions>(
+          "Page of child inscriptions",
+        )),
+      ),
+      (
+        "/r/undelegated-content/:inscription_id",
+        get(Self::undelegated_content),
+        None,
+      ),
+      (
+        "/r/metadata/:inscription_id",
+        get(Self::metadata),
+        Some(Operation::get::<untyped::Metadata>(
+          "Metadata of an inscription, hex encoded",
+        )),
+      ),
+      (
+        "/r/parents/:inscription_id",
+        get(Self::parents_recursive),
+        Some(Operation::get::<untyped::Inscriptions>(
+          "Parent inscription IDs",
+        )),
+      ),
+      (
+        "/r/parents/:inscription_id/:page",
+        get(Self::parents_recursive_paginated),
+        Some(Operation::get::<untyped::Inscriptions>(
+          "Page of parent inscription IDs",
+        )),
+      ),
+      (
+        "/r/sat/:sat_number",
+        get(Self::sat_inscriptions),
+        Some(Operation::get::<untyped::SatInscriptions>(
+          "Inscriptions on a sat",
+        )),
+      ),
+      (
+        "/r/sat/:sat_number/:page",
+        get(Self::sat_inscriptions_paginated),
+        Some(Operation::get::<untyped::SatInscriptions>(
+          "Page of the inscriptions on a sat",
+        )),
+      ),
+      (
+        "/r/sat/:sat_number/at/:index",
+        get(Self::sat_inscription_at_index),
+        Some(Operation::get::<untyped::SatInscription>(
+          "Inscription on a sat by index, negative indices count from the \
+ latest",
+        )),
+      ),
+      ("/rare.txt", get(Self::rare_txt), None),
+      (
+        "/events",
+        post(Self::tx_events),
+        Some(
+          Operation::post::<Vec<relics_api::schema::Txid>, Vec<Event>>("Events of transactions")
+            .query::<EventsQuery>(),
+        ),
+      ),
+      (
+        "/events/stream",
+        get(Self::event_stream),
+        Some(
+          Operation::get::<Event>(
+            "Server-sent events as they are indexed, the data of each message is an event and \
+ its ID the event cursor",
+          )
+          .query::<EventStreamQuery>()
+          .event_stream(),
+        ),
+      ),
+      (
+        "/events/:relic/:page",
+        get(Self::relic_events_paginated),
+        Some(
+          Operation::get::<api::RelicEvents>(
+            "Page of the events of a Relic, newest first, or the events after a cursor, \
+ oldest first",
+          )
+          .query::<EventsQuery>(),
+        ),
+      ),
+      (
+        "/events/:block",
+        get(Self::block_events),
+        Some(Operation::get::<Vec<Event>>("Events of a block").query::<EventsQuery>()),
+      ),
+      (
+        "/sealing/:relic",
+        get(Self::sealing_info),
+        Some(Operation::get::<api::Sealing>(
+          "Inscription that sealed a Relic ticker",
+        )),
+      ),
+      (
+        "/sealings/:page",
+        get(Self::sealings_paginated),
+        Some(Operation::get::<api::Sealings>(
+          "Page of sealed Relic tickers",
+        )),
+      ),
+      (
+        "/relic/:relic",
+        get(Self::relic),
+        Some(Operation::get::<api::Relic>("Relic by ID, number or name")),
+      ),
+      (
+        "/relic/:relic/candles",
+        get(Self::relic_candles),
+        Some(
+          Operation::get::<api::RelicCandles>("Price history of the pool of a Relic")
+            .query::<CandlesQuery>(),
+        ),
+      ),
+      (
+        "/relic/:relic/holders",
+        get(Self::relic_holders),
+        Some(Operation::get::<api::RelicHolders>("Holders of a Relic")),
+      ),
+      (
+        "/relic/:relic/holders/:page",
+        get(Self::relic_holders_paginated),
+        Some(Operation::get::<api::RelicHolders>(
+          "Page of the holders of a Relic",
+        )),
+      ),
+      (
+        "/relic/:relic/shares",
+        get(Self::relic_shares),
+        Some(Operation::get::<api::RelicShares>("Pool shares of a Relic")),
+      ),
+      (
+        "/relics",
+        get(Self::relics),
+        Some(Operation::get::<api::Relics>("Latest Relics")),
+      ),
+      (
+        "/relics/:page",
+        get(Self::relics_paginated),
+        Some(Operation::get::<api::Relics>("Page of Relics")),
+      ),
+      (
+        "/relics/balances",
+        get(Self::relics_balances),
+        Some(Operation::get::<
+          BTreeMap<SpacedRelic, BTreeMap<relics_api::schema::OutPoint, u128>>,
+        >("Relic balances of all outputs")),
+      ),
+      (
+        "/relics/claimable",
+        get(Self::relics_claimable),
+        Some(Operation::get::<BTreeMap<RelicOwner, u128>>(
+          "Base token claimable by each Relic owner",
+        )),
+      ),
+      (
+        "/relics/offer",
+        post(Self::relics_offer),
+        Some(Operation::post::<String, api::RelicOfferValidation>(
+          "Validate an offer PSBT, encoded as base64",
+        )),
+      ),
+      (
+        "/relics/quote",
+        get(Self::relics_quote),
+        Some(
+          Operation::get::<api::RelicSwapQuote>("Quote a swap against the current pool states")
+            .query::<QuoteQuery>(),
+        ),
+      ),
+      (
+        "/relics/shares",
+        get(Self::relics_shares),
+        Some(Operation::get::<
+          BTreeMap<SpacedRelic, BTreeMap<relics_api::schema::OutPoint, u128>>,
+        >("Pool shares of all outputs")),
+      ),
+      (
+        "/relics/snapshot/:height",
+        get(Self::relics_snapshot),
+        Some(
+          Operation::get::<api::RelicSnapshot>(
+            "Relic balances of all outputs at the end of a block",
+          )
+          .query::<SnapshotQuery>(),
+        ),
+      ),
+      (
+        "/relics/snapshot/:height/addresses",
+        get(Self::relics_snapshot_addresses),
+        Some(
+          Operation::get::<api::RelicSnapshot>(
+            "Relic balances of all addresses at the end of a block",
+          )
+          .query::<SnapshotQuery>(),
+        ),
+      ),
+      (
+        "/relics/decipher/:txid",
+        get(Self::decipher_transaction_relics),
+        Some(Operation::get::<Option<untyped::RelicArtifact>>(
+          "Relic artifact of a transaction",
+        )),
+      ),
+      (
+        "/relics/decipher/raw/:hex_tx",
+        get(Self::decipher_raw_transaction_relics),
+        Some(Operation::get::<Option<untyped::RelicArtifact>>(
+          "Relic artifact of a hex encoded transaction",
+        )),
+      ),
+      (
+        "/mempool/relics",
+        get(Self::mempool_relics),
+        Some(Operation::get::<api::MempoolRelics>(
+          "Relic transactions in the memory pool",
+        )),
+      ),
+      (
+        "/mempool/relics/output/:outpoint",
+        get(Self::mempool_relic_output),
+        Some(Operation::get::<api::MempoolRelicOutput>(
+          "Balances of an output including pending transactions",
+        )),
+      ),
+      (
+        "/mempool/relics/quote",
+        get(Self::mempool_relics_quote),
+        Some(
+          Operation::get::<api::RelicSwapQuote>(
+            "Quote a swap against the pool states after the pending transactions",
+          )
+          .query::<QuoteQuery>(),
+        ),
+      ),
+      (
+        "/mempool/relics/tx/:txid",
+        get(Self::mempool_relic_transaction),
+        Some(Operation::get::<api::MempoolRelicTransaction>(
+          "Pending Relic transaction",
+        )),
+      ),
+      (
+        "/openapi.json",
+        get(Self::openapi),
+        Some(Operation::get::<untyped::OpenApi>("This document")),
+      ),
+      (
+        "/rune/:rune",
+        get(Self::rune),
+        Some(Operation::get::<untyped::Rune>(
+          "Rune by ID, number or name",
+        )),
+      ),
+      (
+        "/runes",
+        get(Self::runes),
+        Some(Operation::get::<untyped::Runes>("Latest Runes")),
+      ),
+      (
+        "/runes/:page",
+        get(Self::runes_paginated),
+        Some(Operation::get::<untyped::Runes>("Page of Runes")),
+      ),
+      (
+        "/sat/:sat",
+        get(Self::sat),
+        Some(Operation::get::<untyped::Sat>("Sat")),
+      ),
+      ("/satpoint/:satpoint", get(Self::satpoint), None),
+      ("/search", get(Self::search_by_query), None),
+      ("/search/*query", get(Self::search_by_path), None),
+      ("/static/*path", get(Self::static_asset), None),
+      (
+        "/status",
+        get(Self::status),
+        Some(Operation::get::<untyped::Status>("Server status")),
+      ),
+      (
+        "/tx/:txid",
+        get(Self::transaction),
+        Some(Operation::get::<untyped::Transaction>("Transaction")),
+      ),
+      (
+        "/decode/:txid",
+        get(Self::decode),
+        Some(Operation::get::<untyped::Decode>(
+          "Runestone and inscriptions of a transaction",
+        )),
+      ),
+      ("/update", get(Self::update), None),
+    ]
+  }
+
   fn spawn(
     &self,
     settings: &Settings,
@@ -933,7 +1387,9 @@ impl Server {
         }
       }
 
-      let mintable = entry.mintable(u128::MAX, 1, u128::MAX).is_ok();
+      let arithmetic =
+        PriceArithmetic::at_height(server_config.chain.network(), index.block_count()?);
+      let mintable = entry.mintable(u128::MAX, 1, u128::MAX, arithmetic).is_ok();
 
       Ok(if accept_json {
         Json(api::Relic {
@@ -961,6 +1417,7 @@ impl Server {
   async fn tx_events(
     Extension(index): Extension<Arc<Index>>,
     AcceptJson(accept_json): AcceptJson,
+    Query(query): Query<EventsQuery>,
     Json(txids): Json<Vec<Txid>>,
   ) -> ServerResult {
     task::block_in_place(|| {
@@ -969,7 +1426,9 @@ impl Server {
         for txid in txids {
           if let Ok(events) = index.events_for_tx(txid) {
             for event in events {
-              response.push(event);
+              if query.after.map_or(true, |after| event.cursor() > after) {
+                response.push(event);
+              }
             }
           }
         }
@@ -980,6 +1439,21 @@ impl Server {
     })
   }
 
+  async fn event_stream(
+    Extension(index): Extension<Arc<Index>>,
+    Query(query): Query<EventStreamQuery>,
+    headers: HeaderMap,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      let after = query.after(&headers)?;
+      Ok(
+        Sse::new(event_stream::stream(index, after, query.filter())?)
+          .keep_alive(KeepAlive::default())
+          .into_response(),
+      )
+    })
+  }
+
   async fn relic_events_paginated(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
@@ -988,7 +1462,10 @@ impl Server {
       DeserializeFromStr<usize>,
     )>,
     AcceptJson(accept_json): AcceptJson,
+    Query(query): Query<EventsQuery>,
   ) -> ServerResult {
+    const PAGE_SIZE: usize = 1_000;
+
     task::block_in_place(|| {
       if !index.has_relic_index() {
         return Err(ServerError::NotFound(
@@ -996,6 +1473,12 @@ impl Server {
         ));
       }
 
+      if query.after.is_some() && page_index != 0 {
+        return Err(ServerError::BadRequest(
+          "`after` can only be combined with page 0".to_string(),
+        ));
+      }
+
       let relic = match relic_query {
         query::Relic::Spaced(spaced_relic) => spaced_relic.relic,
         query::Relic::Id(relic_id) => index
@@ -1010,20 +1493,118 @@ impl Server {
         .relic(relic)?
         .ok_or_not_found(|| format!("relic {relic}"))?;
 
-      let events = index
-        .events_for_relic(relic, 1_000, page_index)?
-        .ok_or_not_found(|| format!("relic {relic}"))?;
+      let (events, next) = match query.after {
+        Some(after) => {
+          let mut events = index
+            .events_for_relic_after(relic, after, PAGE_SIZE)?
+            .ok_or_not_found(|| format!("relic {relic}"))?;
+
+          let more = events.len() > PAGE_SIZE;
+
+          events.truncate(PAGE_SIZE);
+
+          let next = events.last().filter(|_| more).map(Event::cursor);
+
+          (events, next)
+        }
+        None => {
+          let events = index
+            .events_for_relic(relic, PAGE_SIZE, page_index)?
+            .ok_or_not_found(|| format!("relic {relic}"))?;
+
+          // page 0 starts with the newest event, the events after it are the ones yet to come
+          let next = events
+            .first()
+            .filter(|_| page_index == 0)
+            .map(Event::cursor);
+
+          (events, next)
+        }
+      };
 
       Ok(if accept_json {
         Json(api::RelicEvents {
           spaced_relic: entry.spaced_relic,
           events,
+          next,
         })
         .into_response()
       } else {
         RelicEventsHtml {
           spaced_relic: entry.spaced_relic,
           events,
+          next,
+        }
+        .page(server_config)
+        .into_response()
+      })
+    })
+  }
+
+  async fn block_events(
+    Extension(index): Extension<Arc<Index>>,
+    AcceptJson(accept_json): AcceptJson,
+    Path(block_number): Path<u32>,
+    Query(query): Query<EventsQuery>,
+  ) -> ServerResult<Response> {
+    task::block_in_place(|| {
+      Ok(if accept_json {
+        let mut events = index
+          .events_for_block(block_number)?
+          .ok_or_not_found(|| format!("block {}", block_number))?;
+
+        if let Some(after) = query.after {
+          events.retain(|event| event.cursor() > after);
+        }
+
+        Json(events).into_response()
+      } else {
+        StatusCode::NOT_FOUND.into_response()
+      })
+    })
+  }
+
+  async fn relics(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    accept_json: AcceptJson,
+  ) -> ServerResult<Response> {
+    Self::relics_paginated(
+      Extension(server_config),
+      Extension(index),
+      Path(0),
+      accept_json,
+    )
+    .await
+  }
+
+  async fn relics_paginated(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(page_index): Path<usize>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      let (entries, more) = index.relics_paginated(50, page_index)?;
+
+      let prev = page_index.checked_sub(1);
+
+      let next = more.then_some(page_index + 1);
+
+      Ok(if accept_json {
+        Json(api::Relics {
+          entries,
+          more,
+          prev,
+          next,
+        })
+        .into_response()
+      } else {
+        RelicsHtml {
+          entries,
+          more,
+          prev,
+          next,
         }
         .page(server_config)
         .into_response()
@@ -1031,131 +1612,411 @@ impl Server {
     })
   }
 
-  async fn block_events(
+  async fn relics_snapshot(
+    Extension(index): Extension<Arc<Index>>,
+    Path(height): Path<u32>,
+    accept_json: AcceptJson,
+    Query(query): Query<SnapshotQuery>,
+  ) -> ServerResult {
+    Self::relic_snapshot(index, height, false, query.after, accept_json)
+  }
+
+  async fn relics_snapshot_addresses(
+    Extension(index): Extension<Arc<Index>>,
+    Path(height): Path<u32>,
+    accept_json: AcceptJson,
+    Query(query): Query<SnapshotQuery>,
+  ) -> ServerResult {
+    Self::relic_snapshot(index, height, true, query.after, accept_json)
+  }
+
+  fn relic_snapshot(
+    index: Arc<Index>,
+    height: u32,
+    by_address: bool,
+    after: Option<api::RelicSnapshotCursor>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !accept_json {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+      }
+
+      if !index.has_relic_history_index() {
+        return Err(ServerError::NotFound(
+          "this server has no relic history index".to_string(),
+        ));
+      }
+
+      if height >= index.block_count()? {
+        return Err(ServerError::NotFound(format!(
+          "block at height {height} has not been indexed"
+        )));
+      }
+
+      if let Some(after) = &after {
+        if by_address != matches!(after, api::RelicSnapshotCursor::ScriptPubkey(_)) {
+          return Err(ServerError::BadRequest(format!(
+            "snapshot cursor {after} does not match the snapshot"
+          )));
+        }
+      }
+
+      Ok(Json(index.get_relic_snapshot(height, by_address, 1000, after.as_ref())?).into_response())
+    })
+  }
+
+  async fn relics_balances(
+    Extension(index): Extension<Arc<Index>>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      Ok(if accept_json {
+        Json(
+          index
+            .get_relic_balance_map()?
+            .into_iter()
+            .map(|(relic, balances)| {
+              (
+                relic,
+                balances
+                  .into_iter()
+                  .map(|(outpoint, pile)| (outpoint, pile.amount))
+                  .collect(),
+              )
+            })
+            .collect::<BTreeMap<SpacedRelic, BTreeMap<OutPoint, u128>>>(),
+        )
+        .into_response()
+      } else {
+        StatusCode::NOT_FOUND.into_response()
+      })
+    })
+  }
+
+  async fn relics_claimable(
+    Extension(index): Extension<Arc<Index>>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      Ok(if accept_json {
+        Json(
+          index
+            .get_relic_claimable()?
+            .into_iter()
+            .collect::<BTreeMap<RelicOwner, u128>>(),
+        )
+        .into_response()
+      } else {
+        StatusCode::NOT_FOUND.into_response()
+      })
+    })
+  }
+
+  async fn relics_quote(
+    Extension(index): Extension<Arc<Index>>,
+    Query(query): Query<QuoteQuery>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !accept_json {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+      }
+
+      if !index.has_relic_index() {
+        return Err(ServerError::NotFound(
+          "this server has no relic index".to_string(),
+        ));
+      }
+
+      Ok(Json(index.quote_relic_swap(&query.swap()?)?).into_response())
+    })
+  }
+
+  async fn relics_offer(
+    Extension(index): Extension<Arc<Index>>,
+    AcceptJson(accept_json): AcceptJson,
+    Json(psbt): Json<String>,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !accept_json {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+      }
+
+      if !index.has_relic_index() {
+        return Err(ServerError::NotFound(
+          "this server has no relic index".to_string(),
+        ));
+      }
+
+      let psbt = base64::engine::general_purpose::STANDARD
+        .decode(psbt)
+        .ok()
+        .and_then(|buffer| Psbt::deserialize(&buffer).ok())
+        .ok_or_else(|| ServerError::BadRequest("invalid PSBT".to_string()))?;
+
+      Ok(Json(index.validate_relic_offer(&psbt)?).into_response())
+    })
+  }
+
+  async fn mempool_relics(
+    Extension(index): Extension<Arc<Index>>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !accept_json {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+      }
+
+      if !index.has_mempool_relic_index() {
+        return Err(ServerError::NotFound(
+          "this server does not simulate memory pool relics".to_string(),
+        ));
+      }
+
+      Ok(Json(index.get_mempool_relics()).into_response())
+    })
+  }
+
+  async fn mempool_relic_output(
+    Extension(index): Extension<Arc<Index>>,
+    Path(outpoint): Path<OutPoint>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !accept_json {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+      }
+
+      if !index.has_mempool_relic_index() {
+        return Err(ServerError::NotFound(
+          "this server does not simulate memory pool relics".to_string(),
+        ));
+      }
+
+      Ok(Json(index.get_mempool_relic_output(outpoint)?).into_response())
+    })
+  }
+
+  async fn mempool_relics_quote(
+    Extension(index): Extension<Arc<Index>>,
+    Query(query): Query<QuoteQuery>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !accept_json {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+      }
+
+      if !index.has_mempool_relic_index() {
+        return Err(ServerError::NotFound(
+          "this server does not simulate memory pool relics".to_string(),
+        ));
+      }
+
+      Ok(Json(index.quote_mempool_relic_swap(&query.swap()?)?).into_response())
+    })
+  }
+
+  async fn mempool_relic_transaction(
     Extension(index): Extension<Arc<Index>>,
+    Path(txid): Path<Txid>,
     AcceptJson(accept_json): AcceptJson,
-    Path(block_number): Path<u32>,
-  ) -> ServerResult<Response> {
+  ) -> ServerResult {
     task::block_in_place(|| {
-      Ok(if accept_json {
-        let block = index
-          .get_block_by_height(block_number)?
-          .ok_or_not_found(|| format!("block {}", block_number))?;
+      if !accept_json {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+      }
 
-        let txids = block
-          .txdata
-          .iter()
-          .map(|tx| tx.compute_txid())
-          .collect::<Vec<Txid>>();
+      if !index.has_mempool_relic_index() {
+        return Err(ServerError::NotFound(
+          "this server does not simulate memory pool relics".to_string(),
+        ));
+      }
 
-        let mut response = Vec::new();
+      Ok(
+        Json(
+          index
+            .get_mempool_relic_transaction(txid)
+            .ok_or_not_found(|| format!("pending relic transaction {txid}"))?,
+        )
+        .into_response(),
+      )
+    })
+  }
 
-        for txid in txids {
-          if let Ok(events) = index.events_for_tx(txid) {
-            for event in events {
-              response.push(event);
-            }
-          }
-        }
-        Json(response).into_response()
+  async fn relics_shares(
+    Extension(index): Extension<Arc<Index>>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      Ok(if accept_json {
+        Json(index.get_relic_share_map()?).into_response()
       } else {
         StatusCode::NOT_FOUND.into_response()
       })
     })
   }
 
-  async fn relics(
+  async fn relic_holders(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
+    Path(DeserializeFromStr(relic_query)): Path<DeserializeFromStr<query::Relic>>,
     accept_json: AcceptJson,
-  ) -> ServerResult<Response> {
-    Self::relics_paginated(
+  ) -> ServerResult {
+    Self::relic_holders_paginated(
       Extension(server_config),
       Extension(index),
-      Path(0),
+      Path((DeserializeFromStr(relic_query), 0)),
       accept_json,
     )
     .await
   }
 
-  async fn relics_paginated(
+  async fn relic_holders_paginated(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
-    Path(page_index): Path<usize>,
+    Path((DeserializeFromStr(relic_query), page)): Path<(DeserializeFromStr<query::Relic>, usize)>,
     AcceptJson(accept_json): AcceptJson,
   ) -> ServerResult {
     task::block_in_place(|| {
-      let (entries, more) = index.relics_paginated(50, page_index)?;
+      if !accept_json {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+      }
 
-      let prev = page_index.checked_sub(1);
+      if !index.has_relic_address_index() {
+        return Err(ServerError::NotFound(
+          "this server has no relic address index".to_string(),
+        ));
+      }
 
-      let next = more.then_some(page_index + 1);
+      let relic = match relic_query {
+        query::Relic::Spaced(spaced_relic) => spaced_relic.relic,
+        query::Relic::Id(relic_id) => index
+          .get_relic_by_id(relic_id)?
+          .ok_or_not_found(|| format!("relic {relic_id}"))?,
+        query::Relic::Number(number) => index
+          .get_relic_by_number(usize::try_from(number).unwrap())?
+          .ok_or_not_found(|| format!("relic number {number}"))?,
+      };
 
-      Ok(if accept_json {
-        Json(RelicsHtml {
-          entries,
+      let (id, entry, _owner) = index
+        .relic(relic)?
+        .ok_or_not_found(|| format!("relic {relic}"))?;
+
+      let (holders, more) = index.get_relic_holders(id, 100, page)?;
+
+      Ok(
+        Json(api::RelicHolders {
+          id,
+          spaced_relic: entry.spaced_relic,
+          holders: holders
+            .into_iter()
+            .map(|(script_pubkey, balance)| api::RelicHolder {
+              address: server_config
+                .chain
+                .address_from_script(&script_pubkey)
+                .ok()
+                .map(|address| uncheck(&address)),
+              script_pubkey,
+              balance,
+            })
+            .collect(),
           more,
-          prev,
-          next,
+          page,
         })
-        .into_response()
-      } else {
-        RelicsHtml {
-          entries,
-          more,
-          prev,
-          next,
-        }
-        .page(server_config)
-        .into_response()
-      })
+        .into_response(),
+      )
     })
   }
 
-  async fn relics_balances(
+  async fn relic_shares(
     Extension(index): Extension<Arc<Index>>,
+    Path(DeserializeFromStr(relic_query)): Path<DeserializeFromStr<query::Relic>>,
     AcceptJson(accept_json): AcceptJson,
   ) -> ServerResult {
     task::block_in_place(|| {
-      Ok(if accept_json {
-        Json(
-          index
-            .get_relic_balance_map()?
-            .into_iter()
-            .map(|(relic, balances)| {
-              (
-                relic,
-                balances
-                  .into_iter()
-                  .map(|(outpoint, pile)| (outpoint, pile.amount))
-                  .collect(),
-              )
-            })
-            .collect::<BTreeMap<SpacedRelic, BTreeMap<OutPoint, u128>>>(),
-        )
-        .into_response()
-      } else {
-        StatusCode::NOT_FOUND.into_response()
-      })
+      if !accept_json {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+      }
+
+      if !index.has_relic_index() {
+        return Err(ServerError::NotFound(
+          "this server has no relic index".to_string(),
+        ));
+      }
+
+      let relic = match relic_query {
+        query::Relic::Spaced(spaced_relic) => spaced_relic.relic,
+        query::Relic::Id(relic_id) => index
+          .get_relic_by_id(relic_id)?
+          .ok_or_not_found(|| format!("relic {relic_id}"))?,
+        query::Relic::Number(number) => index
+          .get_relic_by_number(usize::try_from(number).unwrap())?
+          .ok_or_not_found(|| format!("relic number {number}"))?,
+      };
+
+      let (id, entry, _owner) = index
+        .relic(relic)?
+        .ok_or_not_found(|| format!("relic {relic}"))?;
+
+      let shares = index
+        .get_relic_share_map()?
+        .remove(&entry.spaced_relic)
+        .unwrap_or_default();
+
+      Ok(
+        Json(api::RelicShares {
+          id,
+          spaced_relic: entry.spaced_relic,
+          pool: entry.pool,
+          shares,
+        })
+        .into_response(),
+      )
     })
   }
 
-  async fn relics_claimable(
+  async fn relic_candles(
     Extension(index): Extension<Arc<Index>>,
+    Path(DeserializeFromStr(relic_query)): Path<DeserializeFromStr<query::Relic>>,
+    Query(query): Query<CandlesQuery>,
     AcceptJson(accept_json): AcceptJson,
   ) -> ServerResult {
     task::block_in_place(|| {
-      Ok(if accept_json {
-        Json(
-          index
-            .get_relic_claimable()?
-            .into_iter()
-            .collect::<BTreeMap<RelicOwner, u128>>(),
-        )
-        .into_response()
-      } else {
-        StatusCode::NOT_FOUND.into_response()
-      })
+      if !accept_json {
+        return Ok(StatusCode::NOT_FOUND.into_response());
+      }
+
+      if !index.has_relic_price_index() {
+        return Err(ServerError::NotFound(
+          "this server has no relic price index".to_string(),
+        ));
+      }
+
+      let relic = match relic_query {
+        query::Relic::Spaced(spaced_relic) => spaced_relic.relic,
+        query::Relic::Id(relic_id) => index
+          .get_relic_by_id(relic_id)?
+          .ok_or_not_found(|| format!("relic {relic_id}"))?,
+        query::Relic::Number(number) => index
+          .get_relic_by_number(usize::try_from(number).unwrap())?
+          .ok_or_not_found(|| format!("relic number {number}"))?,
+      };
+
+      let (id, entry, _owner) = index
+        .relic(relic)?
+        .ok_or_not_found(|| format!("relic {relic}"))?;
+
+      Ok(
+        Json(api::RelicCandles {
+          id,
+          spaced_relic: entry.spaced_relic,
+          interval: query.interval,
+          candles: Candle::aggregate(&index.get_relic_pool_prices(id)?, query.interval),
+        })
+        .into_response(),
+      )
     })
   }
 
@@ -1187,7 +2048,7 @@ impl Server {
       // Decide on JSON or HTML
       Ok(if accept_json {
         // Return raw JSON
-        Json(SealingHtml {
+        Json(api::Sealing {
           inscription,
           enshrining_tx,
         })
@@ -1221,7 +2082,7 @@ impl Server {
       // Decide on JSON or HTML
       Ok(if accept_json {
         // Return raw JSON
-        Json(SealingsHtml {
+        Json(api::Sealings {
           entries,
           more,
           prev,
@@ -1312,12 +2173,19 @@ impl Server {
 
       let runes_balances = index.get_aggregated_rune_balances_for_outputs(&outputs)?;
 
+      let relics_balances = if index.has_relic_address_index() {
+        index.get_relic_balances_for_script(&address.script_pubkey())?
+      } else {
+        Vec::new()
+      };
+
       Ok(if accept_json {
         Json(api::AddressInfo {
           sat_balance,
           outputs,
           inscriptions,
           runes_balances,
+          relics_balances,
         })
         .into_response()
       } else {
@@ -1327,6 +2195,7 @@ impl Server {
           inscriptions,
           sat_balance,
           runes_balances,
+          relics_balances,
         }
         .page(server_config)
         .into_response()
@@ -1460,11 +2329,14 @@ impl Server {
 
       let inscriptions = ParsedEnvelope::from_transaction(&transaction);
       let runestone = Runestone::decipher(&transaction);
+      let keepsake = Keepsake::decipher(&transaction);
 
       Ok(if accept_json {
         Json(api::Decode {
           inscriptions,
           runestone,
+          keepsake,
+          keepsake_explanation: None,
         })
         .into_response()
       } else {
@@ -1586,6 +2458,10 @@ impl Server {
     })
   }
 
+  async fn openapi() -> Json<serde_json::Value> {
+    Json(openapi::spec(Self::routes()))
+  }
+
   async fn status(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
@@ -1604,57 +2480,146 @@ impl Server {
   }
 
   async fn search_by_query(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
     Query(search): Query<Search>,
-  ) -> ServerResult<Redirect> {
-    Self::search(index, search.query).await
+  ) -> ServerResult {
+    Self::search(server_config, index, search.query).await
   }
 
   async fn search_by_path(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
     Path(search): Path<Search>,
-  ) -> ServerResult<Redirect> {
-    Self::search(index, search.query).await
+  ) -> ServerResult {
+    Self::search(server_config, index, search.query).await
   }
 
-  async fn search(index: Arc<Index>, query: String) -> ServerResult<Redirect> {
-    Self::search_inner(index, query).await
+  async fn search(
+    server_config: Arc<ServerConfig>,
+    index: Arc<Index>,
+    query: String,
+  ) -> ServerResult {
+    Self::search_inner(server_config, index, query).await
   }
 
-  async fn search_inner(index: Arc<Index>, query: String) -> ServerResult<Redirect> {
+  async fn search_inner(
+    server_config: Arc<ServerConfig>,
+    index: Arc<Index>,
+    query: String,
+  ) -> ServerResult {
     task::block_in_place(|| {
       let query = query.trim();
 
       if re::HASH.is_match(query) {
         if index.block_header(query.parse().unwrap())?.is_some() {
-          Ok(Redirect::to(&format!("/block/{query}")))
+          Ok(Redirect::to(&format!("/block/{query}")).into_response())
+        } else if let Some(relic) = index.get_enshrining(query.parse().unwrap())? {
+          Ok(Redirect::to(&format!("/relic/{relic}")).into_response())
         } else {
-          Ok(Redirect::to(&format!("/tx/{query}")))
+          Ok(Redirect::to(&format!("/tx/{query}")).into_response())
         }
       } else if re::OUTPOINT.is_match(query) {
-        Ok(Redirect::to(&format!("/output/{query}")))
-      } else if re::INSCRIPTION_ID.is_match(query) || re::INSCRIPTION_NUMBER.is_match(query) {
-        Ok(Redirect::to(&format!("/inscription/{query}")))
+        Ok(Redirect::to(&format!("/output/{query}")).into_response())
+      } else if re::INSCRIPTION_ID.is_match(query) {
+        let inscription_id = query
+          .parse::<InscriptionId>()
+          .map_err(|err| ServerError::BadRequest(err.to_string()))?;
+
+        if let Some(relic) = index.get_sealed_relic(inscription_id)? {
+          Ok(Redirect::to(&format!("/sealing/{relic}")).into_response())
+        } else {
+          Ok(Redirect::to(&format!("/inscription/{query}")).into_response())
+        }
+      } else if re::INSCRIPTION_NUMBER.is_match(query) {
+        Ok(Redirect::to(&format!("/inscription/{query}")).into_response())
       } else if re::SPACED_RUNE.is_match(query) {
-        Ok(Redirect::to(&format!("/rune/{query}")))
+        let rune = match query.parse::<SpacedRune>() {
+          Ok(spaced_rune) => index
+            .rune(spaced_rune.rune)?
+            .map(|(_, entry, _)| entry.spaced_rune),
+          Err(_) => None,
+        };
+
+        let relic = match query.parse::<SpacedRelic>() {
+          Ok(spaced_relic) => Self::search_relic(&index, spaced_relic.relic)?,
+          Err(_) => None,
+        };
+
+        Ok(
+          Self::rune_or_relic(server_config, query, rune, relic)
+            .unwrap_or_else(|| Redirect::to(&format!("/rune/{query}")).into_response()),
+        )
       } else if re::RUNE_ID.is_match(query) {
         let id = query
           .parse::<RuneId>()
           .map_err(|err| ServerError::BadRequest(err.to_string()))?;
 
-        let rune = index.get_rune_by_id(id)?.ok_or_not_found(|| "rune ID")?;
+        let rune = index
+          .get_rune_by_id(id)?
+          .map(|rune| SpacedRune::new(rune, 0));
+
+        let relic = match index.get_relic_by_id(id)? {
+          Some(relic) => Self::search_relic(&index, relic)?,
+          None => None,
+        };
 
-        Ok(Redirect::to(&format!("/rune/{rune}")))
+        Self::rune_or_relic(server_config, query, rune, relic)
+          .ok_or_not_found(|| "rune or relic ID")
       } else if re::ADDRESS.is_match(query) {
-        Ok(Redirect::to(&format!("/address/{query}")))
+        Ok(Redirect::to(&format!("/address/{query}")).into_response())
       } else if re::SATPOINT.is_match(query) {
-        Ok(Redirect::to(&format!("/satpoint/{query}")))
+        Ok(Redirect::to(&format!("/satpoint/{query}")).into_response())
       } else {
-        Ok(Redirect::to(&format!("/sat/{query}")))
+        Ok(Redirect::to(&format!("/sat/{query}")).into_response())
       }
     })
   }
 
+  /// Spacing of `relic` as enshrined, or as sealed if it was not enshrined yet, and whether it was
+  /// enshrined
+  fn search_relic(index: &Index, relic: Relic) -> ServerResult<Option<(SpacedRelic, bool)>> {
+    if let Some((_, entry, _)) = index.relic(relic)? {
+      return Ok(Some((entry.spaced_relic, true)));
+    }
+
+    Ok(
+      index
+        .sealing(relic)?
+        .0
+        .and_then(|inscription| inscription.relic_sealed)
+        .map(|spaced_relic| (spaced_relic, false)),
+    )
+  }
+
+  /// Redirect to the page of the Rune or Relic a search matched, or a page linking both if it
+  /// matched a Rune and a Relic
+  fn rune_or_relic(
+    server_config: Arc<ServerConfig>,
+    query: &str,
+    rune: Option<SpacedRune>,
+    relic: Option<(SpacedRelic, bool)>,
+  ) -> Option<Response> {
+    match (rune, relic) {
+      (None, None) => None,
+      (Some(rune), None) => Some(Redirect::to(&format!("/rune/{rune}")).into_response()),
+      (None, Some((relic, true))) => Some(Redirect::to(&format!("/relic/{relic}")).into_response()),
+      (None, Some((relic, false))) => {
+        Some(Redirect::to(&format!("/sealing/{relic}")).into_response())
+      }
+      (Some(rune), Some((relic, enshrined))) => Some(
+        SearchHtml {
+          query: query.into(),
+          rune,
+          relic,
+          enshrined,
+        }
+        .page(server_config)
+        .into_response(),
+      ),
+    }
+  }
+
   async fn favicon() -> ServerResult {
     Ok(
       Self::static_asset(Path("/favicon.png".to_string()))
@@ -2640,6 +3605,7 @@ impl Server {
   }
 
   async fn decipher_transaction_relics(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
     Path(txid): Path<Txid>,
     AcceptJson(accept_json): AcceptJson,
@@ -2649,7 +3615,12 @@ impl Server {
         .get_transaction(txid)?
         .ok_or_not_found(|| format!("transaction {txid}"))?;
 
-      let relic_artifact = Keepsake::decipher(&transaction);
+      // deciphered with the rules of the next block, like the transactions of the memory pool
+      let relic_artifact = Keepsake::decipher_at(
+        &transaction,
+        server_config.chain.network(),
+        index.block_count()?,
+      );
 
       Ok(if accept_json {
         Json(relic_artifact).into_response()
@@ -2660,6 +3631,8 @@ impl Server {
   }
 
   async fn decipher_raw_transaction_relics(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
     Path(hex_tx): Path<String>,
     AcceptJson(accept_json): AcceptJson,
   ) -> ServerResult {
@@ -2671,7 +3644,11 @@ impl Server {
         ServerError::BadRequest(format!("Failed to deserialize transaction: {}", e))
       })?;
 
-      let relic_artifact = Keepsake::decipher(&transaction);
+      let relic_artifact = Keepsake::decipher_at(
+        &transaction,
+        server_config.chain.network(),
+        index.block_count()?,
+      );
 
       Ok(if accept_json {
         Json(relic_artifact).into_response()
@@ -4095,6 +5072,7 @@ mod tests {
         .into_iter()
         .collect(),
         relics: default(),
+        shares: default(),
         spent: false,
       }
     );
@@ -4121,6 +5099,39 @@ mod tests {
       .assert_redirect("/", &format!("https://{}/", System::host_name().unwrap()));
   }
 
+  #[test]
+  fn openapi() {
+    pretty_assert_eq!(
+      TestServer::new().get_json::<serde_json::Value>("/openapi.json"),
+      openapi::spec(Server::routes()),
+    );
+  }
+
+  #[test]
+  fn relic_responses_match_openapi_schemas() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_flag("--index-relics")
+      .ord_flag("--index-addresses")
+      .build();
+
+    let coinbase = server.mine_blocks(1)[0].txdata[0].compute_txid();
+
+    for (schema, path) in [
+      ("Relic", format!("/relic/{RELIC_ID}")),
+      ("Relics", "/relics".into()),
+      ("RelicHolders", format!("/relic/{RELIC_ID}/holders")),
+      ("RelicShares", format!("/relic/{RELIC_ID}/shares")),
+      ("Output", format!("/output/{}", OutPoint::new(coinbase, 0))),
+      (
+        "RelicSwapQuote",
+        "/relics/quote?output=2:1&amount=100".into(),
+      ),
+    ] {
+      openapi::tests::assert_matches_schema(schema, &server.get_json::<serde_json::Value>(path));
+    }
+  }
+
   #[test]
   fn status() {
     let server = TestServer::builder().chain(Chain::Regtest).build();
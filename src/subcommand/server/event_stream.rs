@@ -0,0 +1,345 @@
+use {
+  super::*,
+  axum::response::sse,
+  futures::stream::{self, Stream},
+  std::collections::VecDeque,
+  tokio::sync::broadcast::{self, error::RecvError},
+};
+
+#[derive(Deserialize, JsonSchema)]
+pub(super) struct EventStreamQuery {
+  /// only stream events after this cursor, stored events are replayed first
+  after: Option<EventCursor>,
+  /// only stream events of this Relic
+  #[schemars(with = "Option<relics_api::schema::RelicId>")]
+  relic: Option<DeserializeFromStr<RelicId>>,
+  /// only stream events of this transaction
+  #[schemars(with = "Option<relics_api::schema::Txid>")]
+  txid: Option<Txid>,
+  /// only stream events spending from or sending to this address
+  #[schemars(with = "Option<relics_api::schema::Address>")]
+  address: Option<Address<NetworkUnchecked>>,
+  /// comma separated `EventInfo` variants, e.g. `RelicMinted,RelicSwapped`
+  kind: Option<String>,
+}
+
+impl EventStreamQuery {
+  /// the cursor to resume from, browsers reconnect with the ID of the last event they received
+  pub(super) fn after(&self, headers: &HeaderMap) -> ServerResult<Option<EventCursor>> {
+    if self.after.is_some() {
+      return Ok(self.after);
+    }
+
+    headers
+      .get("last-event-id")
+      .map(|id| {
+        id.to_str()
+          .ok()
+          .and_then(|id| id.parse().ok())
+          .ok_or_else(|| ServerError::BadRequest("invalid Last-Event-ID".into()))
+      })
+      .transpose()
+  }
+
+  pub(super) fn filter(self) -> EventFilter {
+    EventFilter {
+      relic: self.relic.map(|relic| relic.0),
+      txid: self.txid,
+      address: self.address,
+      kinds: self.kind.map(|kinds| {
+        kinds
+          .split(',')
+          .filter(|kind| !kind.is_empty())
+          .map(str::to_string)
+          .collect()
+      }),
+    }
+  }
+}
+
+pub(super) struct EventFilter {
+  relic: Option<RelicId>,
+  txid: Option<Txid>,
+  address: Option<Address<NetworkUnchecked>>,
+  kinds: Option<HashSet<String>>,
+}
+
+impl EventFilter {
+  fn matches(&self, event: &Event) -> bool {
+    self
+      .relic
+      .map_or(true, |relic| event.relic_id() == Some(relic))
+      && self.txid.map_or(true, |txid| event.txid == txid)
+      && self
+        .address
+        .as_ref()
+        .map_or(true, |address| event.address() == Some(address))
+      && self
+        .kinds
+        .as_ref()
+        .map_or(true, |kinds| kinds.contains(event.kind()))
+  }
+}
+
+struct EventStream {
+  index: Arc<Index>,
+  receiver: broadcast::Receiver<Event>,
+  filter: EventFilter,
+  /// last event that was streamed or filtered out
+  cursor: Option<EventCursor>,
+  /// stored events of the current block that still have to be replayed
+  replay: VecDeque<Event>,
+  /// next block to replay events from, replaying stops at `tip`
+  next_height: u32,
+  tip: u32,
+  /// block count at the time of subscribing
+  start: u32,
+  /// number of reorgs published by the index that were already handled
+  reorgs: usize,
+}
+
+impl EventStream {
+  async fn next(&mut self) -> Option<Result<sse::Event, axum::Error>> {
+    loop {
+      let event = match self.replay.pop_front() {
+        Some(event) => event,
+        None if self.next_height < self.tip => {
+          match task::block_in_place(|| self.index.events_for_block(self.next_height)) {
+            Ok(events) => self.replay.extend(events.unwrap_or_default()),
+            Err(err) => return Some(Err(axum::Error::new(err))),
+          }
+          self.next_height += 1;
+          continue;
+        }
+        None => match self.receiver.recv().await {
+          // the events of blocks indexed before subscribing are stored without being published,
+          // so they are replayed up to and including the block of the event
+          Ok(event)
+            if !matches!(event.info, EventInfo::Reorg { .. })
+              && event.block_height > self.next_height =>
+          {
+            self.tip = event.block_height + 1;
+            continue;
+          }
+          Ok(event) => {
+            if !matches!(event.info, EventInfo::Reorg { .. }) {
+              self.next_height = self.next_height.max(event.block_height + 1);
+            }
+            event
+          }
+          // events are only published once committed, so a lagging subscriber can catch up on the
+          // stored events
+          Err(RecvError::Lagged(_)) => {
+            self.next_height = self.cursor.map_or(self.start, |cursor| cursor.block_height);
+            self.tip = match task::block_in_place(|| self.index.block_count()) {
+              Ok(tip) => tip,
+              Err(err) => return Some(Err(axum::Error::new(err))),
+            };
+            // missed reorgs are merged into one notification covering all rolled back blocks
+            let (reorgs, missed) = self.index.reorgs_since(self.reorgs);
+            self.reorgs = reorgs;
+            let Some((first_height, last_height)) = missed
+              .iter()
+              .filter_map(|event| match event.info {
+                EventInfo::Reorg {
+                  first_height,
+                  last_height,
+                } => Some((first_height, last_height)),
+                _ => None,
+              })
+              .reduce(|(first, last), (first_height, last_height)| {
+                (first.min(first_height), last.max(last_height))
+              })
+            else {
+              continue;
+            };
+            return Some(self.reorg(Event::reorg(first_height, last_height)));
+          }
+          Err(RecvError::Closed) => return None,
+        },
+      };
+
+      if let EventInfo::Reorg { .. } = event.info {
+        self.reorgs += 1;
+        return Some(self.reorg(event));
+      }
+
+      if self.cursor.is_some_and(|cursor| event.cursor() <= cursor) {
+        continue;
+      }
+
+      self.cursor = Some(event.cursor());
+
+      if self.filter.matches(&event) {
+        return Some(
+          sse::Event::default()
+            .id(event.cursor().to_string())
+            .json_data(&event)
+            .map_err(axum::Error::new),
+        );
+      }
+    }
+  }
+
+  /// Rewind to the first rolled back block, its events and the ones of the following blocks are
+  /// streamed again from the new chain. Reorg notifications are never filtered.
+  fn reorg(&mut self, event: Event) -> Result<sse::Event, axum::Error> {
+    let EventInfo::Reorg { first_height, .. } = event.info else {
+      unreachable!()
+    };
+
+    self.replay.clear();
+    self.next_height = self.next_height.min(first_height);
+    self.tip = task::block_in_place(|| self.index.block_count()).map_err(axum::Error::new)?;
+    self.cursor = first_height.checked_sub(1).map(|block_height| EventCursor {
+      block_height,
+      event_index: u32::MAX,
+    });
+
+    // resuming from the ID of the notification continues with the first event of the new chain
+    sse::Event::default()
+      .id(
+        self
+          .cursor
+          .map(|cursor| cursor.to_string())
+          .unwrap_or_default(),
+      )
+      .json_data(&event)
+      .map_err(axum::Error::new)
+  }
+}
+
+/// Stream events as they are committed to the index. If a cursor is given, the stored events
+/// after it are replayed first.
+pub(super) fn stream(
+  index: Arc<Index>,
+  after: Option<EventCursor>,
+  filter: EventFilter,
+) -> Result<impl Stream<Item = Result<sse::Event, axum::Error>>> {
+  // subscribe before reading the block count, so no event is missed between replaying stored
+  // events and receiving new ones
+  let (receiver, reorgs) = index.subscribe_events_and_reorgs();
+  let tip = index.block_count()?;
+
+  let event_stream = EventStream {
+    receiver,
+    filter,
+    cursor: after,
+    replay: VecDeque::new(),
+    next_height: after.map_or(tip, |cursor| cursor.block_height),
+    tip,
+    start: tip,
+    reorgs,
+    index,
+  };
+
+  Ok(stream::unfold(
+    event_stream,
+    |mut event_stream| async move {
+      let event = event_stream.next().await?;
+      Some((event, event_stream))
+    },
+  ))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn event(info: EventInfo) -> Event {
+    Event {
+      block_height: 1,
+      event_index: 0,
+      txid: txid(1),
+      info,
+    }
+  }
+
+  fn query() -> EventStreamQuery {
+    EventStreamQuery {
+      after: None,
+      relic: None,
+      txid: None,
+      address: None,
+      kind: None,
+    }
+  }
+
+  #[test]
+  fn filter_events() {
+    let id = RelicId { block: 1, tx: 2 };
+    let address = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+      .parse::<Address<NetworkUnchecked>>()
+      .unwrap();
+
+    let minted = event(EventInfo::RelicMinted {
+      relic_id: id,
+      amount: 1,
+      multiplier: 1,
+      is_unmint: false,
+    });
+    let received = event(EventInfo::RelicReceived {
+      relic_id: RELIC_ID,
+      amount: 1,
+      address: address.clone(),
+    });
+
+    assert!(query().filter().matches(&minted));
+
+    let by_relic = EventStreamQuery {
+      relic: Some(DeserializeFromStr(id)),
+      ..query()
+    }
+    .filter();
+    assert!(by_relic.matches(&minted));
+    assert!(!by_relic.matches(&received));
+
+    let by_txid = |n| {
+      EventStreamQuery {
+        txid: Some(txid(n)),
+        ..query()
+      }
+      .filter()
+    };
+    assert!(by_txid(1).matches(&minted));
+    assert!(!by_txid(2).matches(&minted));
+
+    let by_address = EventStreamQuery {
+      address: Some(address),
+      ..query()
+    }
+    .filter();
+    assert!(by_address.matches(&received));
+    assert!(!by_address.matches(&minted));
+
+    let by_kind = |kind: &str| {
+      EventStreamQuery {
+        kind: Some(kind.into()),
+        ..query()
+      }
+      .filter()
+    };
+    assert!(by_kind("RelicSwapped,RelicMinted").matches(&minted));
+    assert!(!by_kind("RelicSwapped").matches(&minted));
+  }
+
+  #[test]
+  fn resume_from_last_event_id() {
+    let query = query();
+
+    let mut headers = HeaderMap::new();
+    assert_eq!(query.after(&headers).unwrap(), None);
+
+    headers.insert("last-event-id", "5:3".parse().unwrap());
+    assert_eq!(
+      query.after(&headers).unwrap(),
+      Some(EventCursor {
+        block_height: 5,
+        event_index: 3,
+      })
+    );
+
+    headers.insert("last-event-id", "5".parse().unwrap());
+    assert!(query.after(&headers).is_err());
+  }
+}
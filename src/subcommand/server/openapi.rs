@@ -0,0 +1,378 @@
+use {
+  super::*,
+  axum::routing::MethodRouter,
+  schemars::{generate::SchemaSettings, json_schema, Schema, SchemaGenerator},
+  serde_json::{json, Map, Value},
+  std::borrow::Cow,
+};
+
+/// Response types of routes inherited from `ord`, which are not described field by field
+pub(super) mod untyped {
+  use super::*;
+
+  macro_rules! untyped {
+    ($($name:ident: $description:literal,)*) => {
+      $(
+        pub(crate) enum $name {}
+
+        impl JsonSchema for $name {
+          fn schema_name() -> Cow<'static, str> {
+            stringify!($name).into()
+          }
+
+          fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+            json_schema!({ "type": "object", "description": $description })
+          }
+        }
+      )*
+    };
+  }
+
+  untyped! {
+    AddressInfo: "outputs, inscriptions and balances of an address",
+    Block: "block with its inscriptions, Relics and Runes",
+    BlockInfo: "block header and statistics",
+    Blocks: "latest blocks",
+    ChildInscriptions: "page of child inscriptions with their details",
+    Children: "page of child inscription IDs",
+    Decode: "runestone and inscriptions decoded from a transaction",
+    InscriptionRecursive: "inscription as seen by recursive inscriptions",
+    Inscriptions: "page of inscription IDs",
+    OpenApi: "OpenAPI document describing the JSON API",
+    RelicArtifact: "Keepsake or Cenotaph of a transaction",
+    Rune: "Rune with its etching",
+    Runes: "page of Runes",
+    Sat: "sat with its rarity and inscriptions",
+    SatInscription: "inscription on a sat",
+    SatInscriptions: "page of inscriptions on a sat",
+    Status: "status of the server and its index",
+    Transaction: "transaction with its inscriptions and artifacts",
+  }
+
+  pub(crate) enum Metadata {}
+
+  relics_protocol::string_schema!(
+    Metadata,
+    "Metadata",
+    "^([0-9a-f]{2})*$",
+    "CBOR metadata of an inscription, hex encoded"
+  );
+}
+
+type SchemaFn = fn(&mut SchemaGenerator) -> Schema;
+
+/// Documentation of a JSON route, registered with the route in `Server::routes`
+pub(super) struct Operation {
+  method: &'static str,
+  summary: &'static str,
+  query: Option<SchemaFn>,
+  body: Option<SchemaFn>,
+  content_type: &'static str,
+  response: SchemaFn,
+}
+
+impl Operation {
+  pub(super) fn get<T: JsonSchema>(summary: &'static str) -> Self {
+    Self {
+      method: "get",
+      summary,
+      query: None,
+      body: None,
+      content_type: "application/json",
+      response: SchemaGenerator::subschema_for::<T>,
+    }
+  }
+
+  pub(super) fn post<B: JsonSchema, T: JsonSchema>(summary: &'static str) -> Self {
+    Self {
+      method: "post",
+      body: Some(SchemaGenerator::subschema_for::<B>),
+      ..Self::get::<T>(summary)
+    }
+  }
+
+  /// Document the fields of `Q` as query parameters
+  pub(super) fn query<Q: JsonSchema>(self) -> Self {
+    Self {
+      query: Some(Q::json_schema),
+      ..self
+    }
+  }
+
+  pub(super) fn event_stream(self) -> Self {
+    Self {
+      content_type: "text/event-stream",
+      ..self
+    }
+  }
+
+  fn to_json(&self, path: &str, generator: &mut SchemaGenerator) -> Value {
+    let mut parameters = path
+      .split('/')
+      .filter_map(|segment| segment.strip_prefix([':', '*']))
+      .map(|name| {
+        let schema = match name {
+          "block" | "height" | "page" => json!({ "type": "integer", "minimum": 0 }),
+          "index" => json!({ "type": "integer" }),
+          _ => json!({ "type": "string" }),
+        };
+
+        json!({
+          "name": name,
+          "in": "path",
+          "required": true,
+          "schema": schema,
+        })
+      })
+      .collect::<Vec<Value>>();
+
+    if let Some(query) = self.query {
+      let query = Value::from(query(generator));
+
+      let required = query["required"].as_array().cloned().unwrap_or_default();
+
+      for (name, schema) in query["properties"].as_object().into_iter().flatten() {
+        let mut schema = schema.clone();
+
+        let mut parameter = json!({
+          "name": name,
+          "in": "query",
+          "required": required.contains(&json!(name)),
+        });
+
+        if let Some(description) = schema.as_object_mut().unwrap().remove("description") {
+          parameter["description"] = description;
+        }
+
+        parameter["schema"] = without_null(schema);
+
+        parameters.push(parameter);
+      }
+    }
+
+    let mut operation = json!({
+      "summary": self.summary,
+      "parameters": parameters,
+      "responses": {
+        "200": {
+          "description": self.summary,
+          "content": {
+            self.content_type: {
+              "schema": (self.response)(generator),
+            },
+          },
+        },
+      },
+    });
+
+    if let Some(body) = self.body {
+      operation["requestBody"] = json!({
+        "required": true,
+        "content": {
+          "application/json": {
+            "schema": body(generator),
+          },
+        },
+      });
+    }
+
+    operation
+  }
+}
+
+/// `schema` of an optional query parameter without the `null` alternative, since absent
+/// parameters are omitted rather than null
+fn without_null(mut schema: Value) -> Value {
+  if let Some(alternatives) = schema.get_mut("anyOf").and_then(Value::as_array_mut) {
+    alternatives.retain(|alternative| *alternative != json!({ "type": "null" }));
+
+    if alternatives.len() == 1 {
+      return alternatives.remove(0);
+    }
+  }
+
+  if let Some(types) = schema.get_mut("type").and_then(Value::as_array_mut) {
+    types.retain(|ty| ty != "null");
+
+    if types.len() == 1 {
+      schema["type"] = types.remove(0);
+    }
+  }
+
+  schema
+}
+
+/// OpenAPI document describing the JSON routes of `routes`, with schemas derived from the types
+/// of the responses
+pub(super) fn spec<S>(routes: Vec<(&'static str, MethodRouter<S>, Option<Operation>)>) -> Value {
+  let mut generator = SchemaSettings::draft2020_12()
+    .with(|settings| {
+      settings.definitions_path = "/components/schemas".into();
+      settings.meta_schema = None;
+    })
+    .into_generator();
+
+  let mut paths = Map::new();
+
+  for (path, _method_router, operation) in routes {
+    let Some(operation) = operation else {
+      continue;
+    };
+
+    let openapi_path = path
+      .split('/')
+      .map(|segment| match segment.strip_prefix([':', '*']) {
+        Some(name) => format!("{{{name}}}"),
+        None => segment.into(),
+      })
+      .collect::<Vec<String>>()
+      .join("/");
+
+    paths.entry(openapi_path).or_insert_with(|| json!({}))[operation.method] =
+      operation.to_json(path, &mut generator);
+  }
+
+  let mut schemas = generator.take_definitions(true);
+
+  schemas.sort_keys();
+
+  json!({
+    "openapi": "3.1.0",
+    "info": {
+      "title": "ord",
+      "version": env!("CARGO_PKG_VERSION"),
+      "description": "JSON API of `ord server`. Routes that also serve HTML only respond with \
+        JSON when requested with `Accept: application/json`.",
+    },
+    "paths": paths,
+    "components": {
+      "schemas": schemas,
+    },
+  })
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+  use super::*;
+
+  fn spec() -> Value {
+    super::spec(Server::routes())
+  }
+
+  /// Panic unless `value`, as served by the server, matches the schema `name`
+  #[track_caller]
+  pub(in super::super) fn assert_matches_schema(name: &str, value: &Value) {
+    let schema = json!({
+      "$ref": format!("#/components/schemas/{name}"),
+      "components": spec()["components"],
+    });
+
+    if let Err(err) = jsonschema::draft202012::validate(&schema, value) {
+      panic!("response does not match the schema {name}: {err}");
+    }
+  }
+
+  #[test]
+  fn references_resolve() {
+    let spec = spec();
+    let schemas = spec["components"]["schemas"].as_object().unwrap();
+
+    for captures in Regex::new("#/components/schemas/([A-Za-z0-9]+)")
+      .unwrap()
+      .captures_iter(&spec.to_string())
+    {
+      assert!(
+        schemas.contains_key(&captures[1]),
+        "no schema named {}",
+        &captures[1],
+      );
+    }
+  }
+
+  #[test]
+  fn schemas_are_derived_from_api_types() {
+    let spec = spec();
+    let schemas = spec["components"]["schemas"].as_object().unwrap();
+
+    assert!(schemas
+      .keys()
+      .all(|name| name.chars().all(|c| c.is_ascii_alphanumeric())));
+
+    assert_eq!(
+      schemas["RelicEntry"]["properties"]["enshrining"],
+      json!({ "$ref": "#/components/schemas/Txid" }),
+    );
+
+    assert_eq!(
+      schemas["SwapDirection"]["enum"],
+      json!(["BaseToQuote", "QuoteToBase"]),
+    );
+
+    assert!(schemas["RelicState"]["properties"]
+      .as_object()
+      .unwrap()
+      .contains_key("issued"));
+  }
+
+  #[test]
+  fn paths_use_openapi_parameters() {
+    let spec = spec();
+    let paths = spec["paths"].as_object().unwrap();
+
+    assert!(paths.contains_key("/events/{relic}/{page}"));
+    assert!(paths.contains_key("/relic/{relic}/holders/{page}"));
+    assert!(!paths.keys().any(|path| path.contains(':')));
+    assert!(!paths.contains_key("/"));
+
+    assert_eq!(
+      paths["/relics/snapshot/{height}"]["get"]["parameters"][0],
+      json!({
+        "name": "height",
+        "in": "path",
+        "required": true,
+        "schema": { "type": "integer", "minimum": 0 },
+      }),
+    );
+
+    assert_eq!(
+      paths["/events"]["post"]["requestBody"]["content"]["application/json"]["schema"],
+      json!({ "type": "array", "items": { "$ref": "#/components/schemas/Txid" } }),
+    );
+  }
+
+  #[test]
+  fn query_parameters_are_derived_from_query_types() {
+    let spec = spec();
+
+    let parameters = spec["paths"]["/relics/quote"]["get"]["parameters"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|parameter| (parameter["name"].as_str().unwrap(), parameter))
+      .collect::<BTreeMap<&str, &Value>>();
+
+    assert_eq!(
+      parameters.keys().copied().collect::<Vec<&str>>(),
+      ["amount", "exact_input", "input", "limit", "output", "via"],
+    );
+
+    assert_eq!(parameters["amount"]["required"], true);
+    assert_eq!(parameters["input"]["required"], false);
+
+    assert_eq!(
+      parameters["input"]["schema"],
+      json!({ "$ref": "#/components/schemas/RelicId" }),
+    );
+
+    assert_eq!(
+      parameters["via"]["description"],
+      "comma separated Relic IDs to route the swap through",
+    );
+
+    assert_eq!(
+      spec["paths"]["/events/stream"]["get"]["responses"]["200"]["content"]["text/event-stream"]
+        ["schema"],
+      json!({ "$ref": "#/components/schemas/Event" }),
+    );
+  }
+}
@@ -7,6 +7,7 @@ use {
 pub mod addresses;
 pub mod balance;
 mod batch_command;
+pub mod broadcast_psbt;
 pub mod burn;
 pub mod cardinals;
 pub mod claim;
@@ -16,9 +17,11 @@ pub mod enshrine;
 pub mod inscribe;
 pub mod inscriptions;
 mod label;
+pub mod launch;
 pub mod mint;
 pub mod mint_base_relic;
 pub mod mint_relic;
+pub mod offer;
 pub mod outputs;
 pub mod pending;
 pub mod receive;
@@ -58,6 +61,8 @@ pub(crate) enum Subcommand {
   Balance,
   #[command(about = "Create inscriptions and runes")]
   Batch(batch_command::Batch),
+  #[command(about = "Verify the relic allocation of a signed PSBT and broadcast it")]
+  BroadcastPsbt(broadcast_psbt::BroadcastPsbt),
   #[command(about = "Burn an inscription")]
   Burn(burn::Burn),
   #[command(about = "List unspent cardinal outputs in wallet")]
@@ -76,12 +81,16 @@ pub(crate) enum Subcommand {
   Inscriptions,
   #[command(about = "Export output labels")]
   Label,
+  #[command(about = "Seal and enshrine a relic defined in a launch file")]
+  Launch(launch::Launch),
   #[command(about = "Mint a rune")]
   Mint(mint::Mint),
   #[command(about = "Mint a base relic")]
   MintBaseRelic(mint_base_relic::MintBaseRelic),
   #[command(about = "Mint a relic by spending the base relic")]
   MintRelic(mint_relic::MintRelic),
+  #[command(subcommand, about = "Offer relics for sale")]
+  Offer(offer::Offer),
   #[command(about = "List all unspent outputs in wallet")]
   Outputs(outputs::Outputs),
   #[command(about = "List pending etchings")]
@@ -136,6 +145,7 @@ impl WalletCommand {
       Subcommand::Addresses => addresses::run(wallet),
       Subcommand::Balance => balance::run(wallet),
       Subcommand::Batch(batch) => batch.run(wallet),
+      Subcommand::BroadcastPsbt(broadcast_psbt) => broadcast_psbt.run(wallet),
       Subcommand::Burn(burn) => burn.run(wallet),
       Subcommand::Cardinals => cardinals::run(wallet),
       Subcommand::Claim(claim) => claim.run(wallet),
@@ -145,9 +155,11 @@ impl WalletCommand {
       Subcommand::Inscribe(inscribe) => inscribe.run(wallet),
       Subcommand::Inscriptions => inscriptions::run(wallet),
       Subcommand::Label => label::run(wallet),
+      Subcommand::Launch(launch) => launch.run(wallet),
       Subcommand::Mint(mint) => mint.run(wallet),
       Subcommand::MintBaseRelic(mint_base_relic) => mint_base_relic.run(wallet),
       Subcommand::MintRelic(mint_relic) => mint_relic.run(wallet),
+      Subcommand::Offer(offer) => offer.run(wallet),
       Subcommand::Outputs(outputs) => outputs.run(wallet),
       Subcommand::Pending(pending) => pending.run(wallet),
       Subcommand::Receive(receive) => receive.run(wallet),
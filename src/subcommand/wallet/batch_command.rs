@@ -14,20 +14,32 @@ pub(crate) struct Batch {
 
 impl Batch {
   pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
-    let utxos = wallet.utxos();
-
     let batchfile = batch::File::load(&self.batch)?;
 
+    Ok(Some(Box::new(Self::inscribe(
+      &wallet,
+      batchfile,
+      &self.shared,
+    )?)))
+  }
+
+  pub(super) fn inscribe(
+    wallet: &Wallet,
+    batchfile: batch::File,
+    shared: &SharedArgs,
+  ) -> Result<batch::Output> {
+    let utxos = wallet.utxos();
+
     let parent_info = wallet.get_parent_info(&batchfile.parents)?;
 
     let (inscriptions, reveal_satpoints, postages, destinations) = batchfile.inscriptions(
-      &wallet,
+      wallet,
       utxos,
       parent_info
         .iter()
         .map(|info| info.tx_out.value.to_sat())
         .collect(),
-      self.shared.compress,
+      shared.compress,
     )?;
 
     let mut locked_utxos = wallet.locked_utxos().clone();
@@ -39,23 +51,23 @@ impl Batch {
     );
 
     if let Some(etching) = batchfile.etching {
-      Self::check_etching(&wallet, &etching)?;
+      Self::check_etching(wallet, &etching)?;
     }
 
     batch::Plan {
-      commit_fee_rate: self.shared.commit_fee_rate.unwrap_or(self.shared.fee_rate),
+      commit_fee_rate: shared.commit_fee_rate.unwrap_or(shared.fee_rate),
       destinations,
-      dry_run: self.shared.dry_run,
+      dry_run: shared.dry_run,
       etching: batchfile.etching,
       sealing: batchfile.sealing,
       inscriptions,
       mode: batchfile.mode,
-      no_backup: self.shared.no_backup,
-      no_limit: self.shared.no_limit,
+      no_backup: shared.no_backup,
+      no_limit: shared.no_limit,
       parent_info,
       postages,
       reinscribe: batchfile.reinscribe,
-      reveal_fee_rate: self.shared.fee_rate,
+      reveal_fee_rate: shared.fee_rate,
       reveal_satpoints,
       satpoint: if let Some(sat) = batchfile.sat {
         Some(wallet.find_sat_in_outputs(sat)?)
@@ -68,7 +80,7 @@ impl Batch {
       wallet.get_relic_balances()?,
       wallet.get_runic_outputs()?,
       utxos,
-      &wallet,
+      wallet,
     )
   }
 
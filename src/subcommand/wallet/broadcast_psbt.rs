@@ -0,0 +1,58 @@
+use {
+  super::*,
+  base64::{engine::general_purpose, Engine},
+  bitcoin::psbt::Psbt,
+};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub txid: Txid,
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct BroadcastPsbt {
+  #[arg(long, help = "Finalize and broadcast base64 encoded <PSBT>.")]
+  psbt: String,
+}
+
+impl BroadcastPsbt {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    ensure!(
+      wallet.has_relic_index(),
+      "`ord wallet broadcast-psbt` requires index created with `--index-relics` flag",
+    );
+
+    let psbt = Psbt::deserialize(&general_purpose::STANDARD.decode(&self.psbt)?)?;
+
+    wallet.verify_relic_psbt(&psbt)?;
+
+    let result = wallet.bitcoin_client().finalize_psbt(&self.psbt, None)?;
+
+    ensure!(result.complete, "PSBT is not fully signed");
+
+    let signed_transaction = result
+      .hex
+      .ok_or_else(|| anyhow!("finalized PSBT has no transaction"))?;
+
+    let transaction: Transaction = consensus::encode::deserialize(&signed_transaction)?;
+
+    ensure!(
+      transaction.compute_txid() == psbt.unsigned_tx.compute_txid(),
+      "finalized transaction does not match the PSBT",
+    );
+
+    let burn_amount = transaction
+      .output
+      .iter()
+      .filter(|tx_out| tx_out.script_pubkey.is_op_return())
+      .map(|tx_out| tx_out.value)
+      .sum::<Amount>();
+
+    let txid = wallet.send_raw_transaction(
+      &signed_transaction,
+      (burn_amount > Amount::ZERO).then_some(burn_amount),
+    )?;
+
+    Ok(Some(Box::new(Output { txid })))
+  }
+}
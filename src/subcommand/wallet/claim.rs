@@ -11,6 +11,12 @@ pub(crate) struct Claim {
   postage: Option<Amount>,
   #[clap(long, help = "Send minted relics to <DESTINATION>.")]
   destination: Option<Address<NetworkUnchecked>>,
+  #[clap(
+    long,
+    help = "Write an unsigned PSBT annotated with relic balances instead of signing and \
+    broadcasting the transaction."
+  )]
+  psbt: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
@@ -68,6 +74,12 @@ impl Claim {
       &unfunded_transaction,
     )?;
 
+    if self.psbt {
+      return Ok(Some(Box::new(wallet.create_relic_psbt(
+        consensus::encode::deserialize(&unsigned_transaction)?,
+      )?)));
+    }
+
     let signed_transaction = wallet
       .bitcoin_client()
       .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
@@ -15,12 +15,12 @@ pub(crate) struct Enshrine {
   mint_price: Option<Decimal>,
   #[clap(
     long,
-    help = "Formula price parameter a. Price = a - b/(c+x) where x is the mint index."
+    help = "Formula price parameter a. Price = a * exp(x / b) where x is the mint index."
   )]
   formula_a: Option<u128>,
   #[clap(
     long,
-    help = "Formula price parameter b. Price = a - b/(c+x) where x is the mint index."
+    help = "Formula price parameter b. Price = a * exp(x / b) where x is the mint index."
   )]
   formula_b: Option<u128>,
   #[clap(long, help = "Maximum transactions that can mint <RELIC>.")]
@@ -52,13 +52,19 @@ pub(crate) struct Enshrine {
   symbol: char,
   #[clap(
     long,
-    help = "Liquidity pool fee in basis points (0-10000). [default: 0]"
+    help = "Liquidity pool fee in basis points, fees above 1000 are capped. [default: 0]"
   )]
   lp_fee: Option<u16>,
   #[clap(long, help = "Max unmints for the enshrined relic. [default: 0]")]
   max_unmints: Option<u32>,
   #[clap(long, help = "Simulate the command without sending the transaction.")]
   dry_run: bool,
+  #[clap(
+    long,
+    help = "Write an unsigned PSBT annotated with relic balances instead of signing and \
+    broadcasting the transaction."
+  )]
+  psbt: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
@@ -122,6 +128,26 @@ impl Enshrine {
       }
     };
 
+    let enshrining = Enshrining {
+      symbol: Some(self.symbol),
+      boost_terms: None,
+      fee: self.lp_fee.filter(|&fee| fee > 0),
+      subsidy: self
+        .subsidy
+        .filter(|subsidy| subsidy.value > 0)
+        .map(|subsidy| subsidy.to_integer(Enshrining::DIVISIBILITY))
+        .transpose()?,
+      mint_terms: Some(MintTerms {
+        amount: Some(self.mint_amount.to_integer(Enshrining::DIVISIBILITY)?),
+        block_cap: None,
+        cap: Some(self.mint_cap),
+        max_unmints: self.max_unmints.filter(|&unmints| unmints > 0),
+        price: Some(price_model),
+        seed: Some(self.seed.to_integer(Enshrining::DIVISIBILITY)?),
+        tx_cap: self.tx_cap,
+      }),
+    };
+
     let sealing = wallet
       .inscription_info()
       .iter()
@@ -141,11 +167,118 @@ impl Enshrine {
       panic!("sealing inscription not found for relic: {}", self.relic);
     };
 
-    let postage = self.postage.unwrap_or(MIN_POSTAGE);
-    let change_addresses = [wallet.get_change_address()?, wallet.get_change_address()?]; // For fund_raw_transaction if it needs them explicitly, or for our own use
     let destination_address =
       Address::from_str(&sealing_address)?.require_network(wallet.chain().network())?;
 
+    if self.psbt {
+      let (unsigned_transaction, _keepsake) = Self::create_unsigned_transaction(
+        &wallet,
+        enshrining,
+        sealing_satpoint,
+        &destination_address,
+        self.postage.unwrap_or(MIN_POSTAGE),
+        self.fee_rate,
+        &BTreeSet::new(),
+      )?;
+
+      return Ok(Some(Box::new(
+        wallet.create_relic_psbt(unsigned_transaction)?,
+      )));
+    }
+
+    let (signed_transaction, keepsake) = Self::create_transaction(
+      &wallet,
+      enshrining,
+      sealing_satpoint,
+      &destination_address,
+      self.postage.unwrap_or(MIN_POSTAGE),
+      self.fee_rate,
+      &BTreeSet::new(),
+    )?;
+
+    if self.dry_run {
+      // For dry run, include more details in the output
+      Ok(Some(Box::new(Output {
+        enshrining: Txid::all_zeros(),
+        relic: self.relic,
+        dry_run: Some(true),
+        keepsake: Some(keepsake),
+        signed_transaction: Some(hex::encode(consensus::encode::serialize(
+          &signed_transaction,
+        ))),
+      })))
+    } else {
+      let transaction = wallet
+        .bitcoin_client()
+        .send_raw_transaction(&signed_transaction)?;
+
+      Ok(Some(Box::new(Output {
+        enshrining: transaction,
+        relic: self.relic,
+        dry_run: None,
+        keepsake: None,
+        signed_transaction: None,
+      })))
+    }
+  }
+
+  /// Build and sign the transaction enshrining the Relic sealed to the inscription at
+  /// `sealing_satpoint`, which is sent on to `destination_address`. Outputs in `spent` were spent
+  /// since the wallet was loaded, they are neither locked nor selected to pay the subsidy.
+  pub(super) fn create_transaction(
+    wallet: &Wallet,
+    enshrining: Enshrining,
+    sealing_satpoint: SatPoint,
+    destination_address: &Address,
+    postage: Amount,
+    fee_rate: FeeRate,
+    spent: &BTreeSet<OutPoint>,
+  ) -> Result<(Transaction, Keepsake)> {
+    let (unsigned_transaction, keepsake) = Self::create_unsigned_transaction(
+      wallet,
+      enshrining,
+      sealing_satpoint,
+      destination_address,
+      postage,
+      fee_rate,
+      spent,
+    )?;
+
+    let signed_transaction = wallet
+      .bitcoin_client()
+      .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
+      .hex;
+
+    let signed_transaction = consensus::encode::deserialize(&signed_transaction)?;
+
+    assert_eq!(
+      Keepsake::decipher(&signed_transaction),
+      Some(RelicArtifact::Keepsake(keepsake.clone())),
+    );
+
+    Ok((signed_transaction, keepsake))
+  }
+
+  /// Like `create_transaction`, but the funded transaction is returned unsigned
+  fn create_unsigned_transaction(
+    wallet: &Wallet,
+    enshrining: Enshrining,
+    sealing_satpoint: SatPoint,
+    destination_address: &Address,
+    postage: Amount,
+    fee_rate: FeeRate,
+    spent: &BTreeSet<OutPoint>,
+  ) -> Result<(Transaction, Keepsake)> {
+    // the index would not enshrine the Relic, so fail before anything is spent, the enshrining must
+    // be valid with either arithmetic, since the reveal may be mined after the activation height
+    for arithmetic in [PriceArithmetic::Legacy, PriceArithmetic::FixedPoint] {
+      enshrining
+        .validate(arithmetic)
+        .map_err(|flaw| anyhow!("{flaw}"))?;
+    }
+
+    let change_addresses = [wallet.get_change_address()?, wallet.get_change_address()?]; // For fund_raw_transaction if it needs them explicitly, or for our own use
+
     ensure!(
       destination_address.script_pubkey().minimal_non_dust() < postage,
       "postage below dust limit of {}sat",
@@ -169,8 +302,6 @@ impl Enshrine {
 
     let base_token_spaced_relic = SpacedRelic::from_str(RELIC_NAME)?; // RELIC
 
-    let has_subsidy = self.subsidy.is_some() && self.subsidy.unwrap().value > 0;
-
     // Order: Inscription Output, then Relic Change Output (if any)
 
     // 1. Add Sealing Inscription Output (will be absolute index 1)
@@ -180,41 +311,38 @@ impl Enshrine {
     });
 
     // 2. Conditionally add Base RELIC change output (will be absolute index 2)
-    if has_subsidy {
-      let subsidy_decimal = self.subsidy.unwrap();
-      let subsidy_amount_integer = subsidy_decimal.to_integer(Enshrining::DIVISIBILITY)?;
-
-      if subsidy_amount_integer > 0 {
-        let (relic_utxos_to_spend, input_relic_balances) = wallet
-          .get_required_relic_outputs(vec![(base_token_spaced_relic, subsidy_amount_integer)])?;
-
-        for utxo in relic_utxos_to_spend {
-          inputs_for_tx.push(TxIn {
-            previous_output: utxo,
-            script_sig: ScriptBuf::new(),
-            sequence: Sequence::MAX,
-            witness: Witness::new(),
-          });
-        }
-
-        let base_tokens_from_inputs = input_relic_balances
-          .get(&base_token_spaced_relic)
-          .cloned()
-          .unwrap_or(0);
-        let base_relic_change_amount =
-          base_tokens_from_inputs.saturating_sub(subsidy_amount_integer);
-
-        if base_relic_change_amount > 0 {
-          outputs_for_tx_payload.push(TxOut {
-            value: postage, // Base relic change output also gets postage
-            script_pubkey: change_addresses[0].script_pubkey(), // Use first change address for relic change
-          });
-          keepsake_transfers.push(relics_protocol::Transfer {
-            id: RELIC_ID, // ID for the base RELIC token
-            amount: base_relic_change_amount,
-            output: 2, // <<< Absolute output index: 0=OP_RETURN, 1=Inscription, 2=Relic Change
-          });
-        }
+    if let Some(subsidy_amount_integer) = enshrining.subsidy.filter(|&subsidy| subsidy > 0) {
+      let (relic_utxos_to_spend, input_relic_balances) = wallet
+        .get_required_relic_outputs_excluding(
+          vec![(base_token_spaced_relic, subsidy_amount_integer)],
+          spent,
+        )?;
+
+      for utxo in relic_utxos_to_spend {
+        inputs_for_tx.push(TxIn {
+          previous_output: utxo,
+          script_sig: ScriptBuf::new(),
+          sequence: Sequence::MAX,
+          witness: Witness::new(),
+        });
+      }
+
+      let base_tokens_from_inputs = input_relic_balances
+        .get(&base_token_spaced_relic)
+        .cloned()
+        .unwrap_or(0);
+      let base_relic_change_amount = base_tokens_from_inputs.saturating_sub(subsidy_amount_integer);
+
+      if base_relic_change_amount > 0 {
+        outputs_for_tx_payload.push(TxOut {
+          value: postage, // Base relic change output also gets postage
+          script_pubkey: change_addresses[0].script_pubkey(), // Use first change address for relic change
+        });
+        keepsake_transfers.push(relics_protocol::Transfer {
+          id: RELIC_ID, // ID for the base RELIC token
+          amount: base_relic_change_amount,
+          output: 2, // <<< Absolute output index: 0=OP_RETURN, 1=Inscription, 2=Relic Change
+        });
       }
     }
 
@@ -222,36 +350,12 @@ impl Enshrine {
     // let sealing_inscription_output_abs_idx = 1; // For reference
 
     let keepsake = Keepsake {
-      enshrining: Some(Enshrining {
-        symbol: Some(self.symbol),
-        boost_terms: None,
-        fee: self.lp_fee.filter(|&fee| fee > 0),
-        subsidy: self
-          .subsidy
-          .filter(|subsidy| subsidy.value > 0)
-          .map(|subsidy| subsidy.to_integer(Enshrining::DIVISIBILITY))
-          .transpose()?,
-        mint_terms: Some(MintTerms {
-          amount: Some(self.mint_amount.to_integer(Enshrining::DIVISIBILITY)?),
-          block_cap: None,
-          cap: Some(self.mint_cap),
-          max_unmints: self.max_unmints.filter(|&unmints| unmints > 0),
-          price: Some(price_model),
-          seed: Some(self.seed.to_integer(Enshrining::DIVISIBILITY)?),
-          tx_cap: self.tx_cap,
-        }),
-      }),
+      enshrining: Some(enshrining),
       transfers: keepsake_transfers, // Add relic transfers here
       ..default()
     };
 
-    let op_return_script_pubkey = keepsake.encipher();
-
-    ensure!(
-      op_return_script_pubkey.len() <= 82,
-      "keepsake greater than maximum OP_RETURN size: {} > 82",
-      op_return_script_pubkey.len()
-    );
+    let op_return_script_pubkey = Self::encipher(&keepsake)?;
 
     let mut final_outputs = Vec::new();
     final_outputs.push(TxOut {
@@ -267,50 +371,27 @@ impl Enshrine {
       output: final_outputs,
     };
 
-    wallet.lock_non_cardinal_outputs()?;
+    wallet.lock_non_cardinal_outputs_excluding(spent)?;
 
     let bitcoin_client = wallet.bitcoin_client();
     let unsigned_transaction =
-      fund_raw_transaction(bitcoin_client, self.fee_rate, &unfunded_transaction)?;
-
-    let signed_transaction = wallet
-      .bitcoin_client()
-      .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
-      .hex;
+      fund_raw_transaction(bitcoin_client, fee_rate, &unfunded_transaction)?;
 
-    let signed_transaction = consensus::encode::deserialize(&signed_transaction)?;
+    Ok((
+      consensus::encode::deserialize(&unsigned_transaction)?,
+      keepsake,
+    ))
+  }
 
-    // Clone the keepsake before using it in the assertion
-    let keepsake_clone = keepsake.clone();
+  pub(super) fn encipher(keepsake: &Keepsake) -> Result<ScriptBuf> {
+    let script_pubkey = keepsake.encipher();
 
-    assert_eq!(
-      Keepsake::decipher(&signed_transaction),
-      Some(RelicArtifact::Keepsake(keepsake_clone)),
+    ensure!(
+      script_pubkey.len() <= 82,
+      "keepsake greater than maximum OP_RETURN size: {} > 82",
+      script_pubkey.len()
     );
 
-    if self.dry_run {
-      // For dry run, include more details in the output
-      Ok(Some(Box::new(Output {
-        enshrining: Txid::all_zeros(),
-        relic: self.relic,
-        dry_run: Some(true),
-        keepsake: Some(keepsake),
-        signed_transaction: Some(hex::encode(consensus::encode::serialize(
-          &signed_transaction,
-        ))),
-      })))
-    } else {
-      let transaction = wallet
-        .bitcoin_client()
-        .send_raw_transaction(&signed_transaction)?;
-
-      Ok(Some(Box::new(Output {
-        enshrining: transaction,
-        relic: self.relic,
-        dry_run: None,
-        keepsake: None,
-        signed_transaction: None,
-      })))
-    }
+    Ok(script_pubkey)
   }
 }
@@ -60,7 +60,7 @@ impl Inscribe {
       }
     }
 
-    batch::Plan {
+    let output = batch::Plan {
       commit_fee_rate: self.shared.commit_fee_rate.unwrap_or(self.shared.fee_rate),
       destinations: vec![match self.destination.clone() {
         Some(destination) => destination.require_network(chain.network())?,
@@ -100,7 +100,9 @@ impl Inscribe {
       wallet.get_runic_outputs()?,
       wallet.utxos(),
       &wallet,
-    )
+    )?;
+
+    Ok(Some(Box::new(output)))
   }
 }
 
@@ -0,0 +1,202 @@
+use {super::*, batch_command::Batch, enshrine::Enshrine, launchfile::Launchfile};
+
+mod launchfile;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Launch {
+  #[command(flatten)]
+  shared: SharedArgs,
+  #[arg(
+    long,
+    help = "Seal and enshrine the Relic defined in YAML <LAUNCH_FILE>.",
+    value_name = "LAUNCH_FILE"
+  )]
+  pub(crate) launch: PathBuf,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Output {
+  pub relic: SpacedRelic,
+  /// `None` if the relic was already sealed to an inscription in the wallet
+  pub sealing: Option<batch::Output>,
+  /// `None` in a dry run, since the enshrining spends the unsigned reveal transaction
+  pub enshrining: Option<Txid>,
+  pub keepsake: Keepsake,
+}
+
+impl Launch {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    ensure!(
+      wallet.has_relic_index(),
+      "`ord wallet launch` requires index created with `--index-relics` flag",
+    );
+
+    let launchfile = Launchfile::load(&self.launch)?;
+
+    let relic = launchfile.relic;
+
+    ensure!(
+      wallet.get_relic(relic.relic)?.is_none(),
+      "relic `{relic}` has already been enshrined",
+    );
+
+    let keepsake = Keepsake {
+      enshrining: Some(launchfile.enshrining),
+      ..default()
+    };
+
+    Enshrine::encipher(&keepsake)?;
+
+    // a launch that was interrupted after sealing continues with the enshrining
+    let sealed = wallet
+      .inscription_info()
+      .into_values()
+      .find(|inscription| inscription.relic_sealed == Some(relic));
+
+    let (sealing, sealing_satpoint, destination, spent, locked) = match sealed {
+      Some(inscription) => (
+        None,
+        inscription.satpoint,
+        inscription
+          .address
+          .context("unsupported script on the sealing inscription")?
+          .parse::<Address<NetworkUnchecked>>()?,
+        BTreeSet::new(),
+        Vec::new(),
+      ),
+      None => {
+        let sealing = Batch::inscribe(
+          &wallet,
+          batch::File {
+            postage: launchfile.postage.map(Amount::to_sat),
+            sealing: true,
+            inscriptions: vec![batch::Entry {
+              file: Some(launchfile.thumbnail),
+              metadata: Some(relic.to_metadata_yaml()),
+              ..default()
+            }],
+            ..default()
+          },
+          &self.shared,
+        )?;
+
+        if self.shared.dry_run {
+          return Ok(Some(Box::new(Output {
+            relic,
+            sealing: Some(sealing),
+            enshrining: None,
+            keepsake,
+          })));
+        }
+
+        let inscription = sealing
+          .inscriptions
+          .first()
+          .expect("sealing batch contains one inscription")
+          .clone();
+
+        let (spent, locked) = Self::wait_for_confirmation(&wallet, &sealing, inscription.location)?;
+
+        (
+          Some(sealing),
+          inscription.location,
+          inscription.destination,
+          spent,
+          locked,
+        )
+      }
+    };
+
+    let transaction = Enshrine::create_transaction(
+      &wallet,
+      launchfile.enshrining,
+      sealing_satpoint,
+      &destination.require_network(wallet.chain().network())?,
+      launchfile.postage.unwrap_or(MIN_POSTAGE),
+      self.shared.fee_rate,
+      &spent,
+    );
+
+    // the enshrining is funded, later commands load the wallet after the index caught up with the
+    // reveal transaction and know which of its outputs hold relics
+    if !locked.is_empty() && !wallet.bitcoin_client().unlock_unspent(&locked)? {
+      bail!("failed to unlock outputs of reveal transaction");
+    }
+
+    let (signed_transaction, keepsake) = transaction?;
+
+    let enshrining = if self.shared.dry_run {
+      None
+    } else {
+      Some(
+        wallet
+          .bitcoin_client()
+          .send_raw_transaction(&signed_transaction)?,
+      )
+    };
+
+    Ok(Some(Box::new(Output {
+      relic,
+      sealing,
+      enshrining,
+      keepsake,
+    })))
+  }
+
+  /// The sealing inscription is only spent by the enshrining once the reveal transaction creating
+  /// it was mined. Returns the outputs spent by the reveal transaction and the outputs of the
+  /// reveal transaction locked until the enshrining is funded.
+  fn wait_for_confirmation(
+    wallet: &Wallet,
+    sealing: &batch::Output,
+    inscription: SatPoint,
+  ) -> Result<(BTreeSet<OutPoint>, Vec<OutPoint>)> {
+    eprintln!(
+      "Waiting for sealing reveal transaction {} to confirm…",
+      sealing.reveal
+    );
+
+    let reveal = loop {
+      if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+        bail!("sealing not confirmed yet, run `ord wallet launch` again to enshrine the relic");
+      }
+
+      if let Some(transaction) = wallet
+        .bitcoin_client()
+        .get_transaction(&sealing.reveal, Some(true))
+        .into_option()?
+      {
+        if transaction.info.confirmations > 0 {
+          break transaction.transaction()?;
+        }
+      }
+
+      if !wallet.integration_test() {
+        thread::sleep(Duration::from_secs(5));
+      }
+    };
+
+    // the wallet was loaded before the sealing, so the new outputs of the reveal transaction, like
+    // base token change, are not known to hold relics and must not be spent as cardinals
+    let outputs = (0..reveal.output.len())
+      .map(|vout| OutPoint {
+        txid: sealing.reveal,
+        vout: vout.try_into().unwrap(),
+      })
+      .filter(|outpoint| *outpoint != inscription.outpoint)
+      .collect::<Vec<OutPoint>>();
+
+    if !outputs.is_empty() && !wallet.bitcoin_client().lock_unspent(&outputs)? {
+      bail!("failed to lock outputs of reveal transaction");
+    }
+
+    Ok((
+      reveal
+        .input
+        .iter()
+        .map(|input| input.previous_output)
+        .collect(),
+      outputs,
+    ))
+  }
+}
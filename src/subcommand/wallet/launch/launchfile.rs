@@ -0,0 +1,303 @@
+use super::*;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LaunchfileUnchecked {
+  relic: SpacedRelic,
+  symbol: char,
+  thumbnail: PathBuf,
+  postage: Option<DeserializeFromStr<Amount>>,
+  fee: Option<u16>,
+  subsidy: Option<Decimal>,
+  mint_terms: MintTermsUnchecked,
+  boost_terms: Option<BoostTermsUnchecked>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MintTermsUnchecked {
+  amount: Decimal,
+  cap: u128,
+  price: Option<Decimal>,
+  formula: Option<FormulaUnchecked>,
+  seed: Decimal,
+  block_cap: Option<u32>,
+  tx_cap: Option<u8>,
+  max_unmints: Option<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FormulaUnchecked {
+  a: u128,
+  b: u128,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BoostTermsUnchecked {
+  rare_chance: u32,
+  rare_multiplier_cap: u16,
+  ultra_rare_chance: u32,
+  ultra_rare_multiplier_cap: u16,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Launchfile {
+  pub(crate) relic: SpacedRelic,
+  pub(crate) thumbnail: PathBuf,
+  pub(crate) postage: Option<Amount>,
+  pub(crate) enshrining: Enshrining,
+}
+
+impl Launchfile {
+  pub(crate) fn load(path: &Path) -> Result<Self> {
+    let unchecked = Self::load_unchecked(path)?;
+
+    let base_token = SpacedRelic::from_str(RELIC_NAME)?;
+
+    ensure!(
+      unchecked.relic.relic != base_token.relic,
+      "launch file cannot seal the base token `{base_token}`",
+    );
+
+    let terms = unchecked.mint_terms;
+
+    let price = match (terms.price, terms.formula) {
+      (Some(price), None) => PriceModel::Fixed(price.to_integer(Enshrining::DIVISIBILITY)?),
+      (None, Some(FormulaUnchecked { a, b })) => PriceModel::Formula { a, b },
+      (Some(_), Some(_)) => bail!("launch file cannot set both `price` and `formula`"),
+      (None, None) => bail!("launch file must set either `price` or `formula`"),
+    };
+
+    if let Some(fee) = unchecked.fee {
+      ensure!(
+        fee <= Enshrining::MAX_FEE_BPS,
+        "`fee` must be at most {} basis points: {fee}",
+        Enshrining::MAX_FEE_BPS,
+      );
+    }
+
+    let enshrining = Enshrining {
+      boost_terms: unchecked.boost_terms.map(|boost_terms| BoostTerms {
+        rare_chance: Some(boost_terms.rare_chance),
+        rare_multiplier_cap: Some(boost_terms.rare_multiplier_cap),
+        ultra_rare_chance: Some(boost_terms.ultra_rare_chance),
+        ultra_rare_multiplier_cap: Some(boost_terms.ultra_rare_multiplier_cap),
+      }),
+      fee: unchecked.fee.filter(|&fee| fee > 0),
+      subsidy: unchecked
+        .subsidy
+        .map(|subsidy| subsidy.to_integer(Enshrining::DIVISIBILITY))
+        .transpose()?,
+      symbol: Some(unchecked.symbol),
+      mint_terms: Some(MintTerms {
+        amount: Some(terms.amount.to_integer(Enshrining::DIVISIBILITY)?),
+        block_cap: terms.block_cap,
+        cap: Some(terms.cap),
+        max_unmints: terms.max_unmints.filter(|&unmints| unmints > 0),
+        price: Some(price),
+        seed: Some(terms.seed.to_integer(Enshrining::DIVISIBILITY)?),
+        tx_cap: terms.tx_cap,
+      }),
+    };
+
+    // checked against the current rules, enshrining checks it again for the block it is mined in
+    enshrining
+      .validate(PriceArithmetic::FixedPoint)
+      .map_err(|flaw| anyhow!("{flaw}"))?;
+
+    Ok(Self {
+      relic: unchecked.relic,
+      thumbnail: unchecked.thumbnail,
+      postage: unchecked.postage.map(|DeserializeFromStr(postage)| postage),
+      enshrining,
+    })
+  }
+
+  fn load_unchecked(path: &Path) -> Result<LaunchfileUnchecked> {
+    Ok(serde_yaml::from_reader(File::open(path)?)?)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, tempfile::TempDir};
+
+  fn load(yaml: &str) -> Result<Launchfile> {
+    let tempdir = TempDir::new().unwrap();
+    let path = tempdir.path().join("launch.yaml");
+    fs::write(&path, yaml).unwrap();
+    Launchfile::load(&path)
+  }
+
+  #[test]
+  fn example_launch_file_is_valid() {
+    Launchfile::load("launch.yaml".as_ref()).unwrap();
+  }
+
+  #[test]
+  fn launch_file_is_loaded() {
+    assert_eq!(
+      load(
+        "relic: THE•BEST•RELIC
+symbol: $
+thumbnail: relic.png
+postage: 10000 sat
+fee: 100
+mint_terms:
+  amount: 100
+  cap: 1000
+  formula:
+    a: 10000
+    b: 500
+  seed: 50000.5
+  block_cap: 10
+  tx_cap: 5
+boost_terms:
+  rare_chance: 10000
+  rare_multiplier_cap: 5
+  ultra_rare_chance: 1000
+  ultra_rare_multiplier_cap: 10
+"
+      )
+      .unwrap(),
+      Launchfile {
+        relic: "THE•BEST•RELIC".parse().unwrap(),
+        thumbnail: "relic.png".into(),
+        postage: Some(Amount::from_sat(10_000)),
+        enshrining: Enshrining {
+          boost_terms: Some(BoostTerms {
+            rare_chance: Some(10_000),
+            rare_multiplier_cap: Some(5),
+            ultra_rare_chance: Some(1_000),
+            ultra_rare_multiplier_cap: Some(10),
+          }),
+          fee: Some(100),
+          subsidy: None,
+          symbol: Some('$'),
+          mint_terms: Some(MintTerms {
+            amount: Some(100_00000000),
+            block_cap: Some(10),
+            cap: Some(1000),
+            max_unmints: None,
+            price: Some(PriceModel::Formula { a: 10_000, b: 500 }),
+            seed: Some(5_000_050_000_000),
+            tx_cap: Some(5),
+          }),
+        },
+      }
+    );
+  }
+
+  #[test]
+  fn price_or_formula_is_required() {
+    let terms = |price: &str| {
+      load(&format!(
+        "relic: RELICS
+symbol: R
+thumbnail: relic.png
+mint_terms:
+  amount: 1
+  cap: 10
+  seed: 10
+{price}"
+      ))
+      .unwrap_err()
+      .to_string()
+    };
+
+    assert_eq!(
+      terms(""),
+      "launch file must set either `price` or `formula`"
+    );
+    assert_eq!(
+      terms("  price: 1\n  formula:\n    a: 1\n    b: 1\n"),
+      "launch file cannot set both `price` and `formula`"
+    );
+  }
+
+  #[test]
+  fn invalid_enshrining_is_rejected() {
+    assert_eq!(
+      load(
+        "relic: RELICS
+symbol: R
+thumbnail: relic.png
+mint_terms:
+  amount: 1
+  cap: 10
+  price: 1
+  seed: 10
+  block_cap: 20
+"
+      )
+      .unwrap_err()
+      .to_string(),
+      "invalid enshrining: cap hierarchy (cap >= block_cap >= tx_cap) violated",
+    );
+
+    assert_eq!(
+      load(
+        "relic: RELICS
+symbol: R
+thumbnail: relic.png
+mint_terms:
+  amount: 1
+  cap: 10
+  price: 1
+  seed: 10
+  max_unmints: 5
+boost_terms:
+  rare_chance: 10000
+  rare_multiplier_cap: 5
+  ultra_rare_chance: 1000
+  ultra_rare_multiplier_cap: 10
+"
+      )
+      .unwrap_err()
+      .to_string(),
+      "invalid enshrining: boost can't be combined with unmints",
+    );
+  }
+
+  #[test]
+  fn fee_above_the_cap_is_rejected() {
+    assert_eq!(
+      load(
+        "relic: RELICS
+symbol: R
+thumbnail: relic.png
+fee: 1001
+mint_terms:
+  amount: 1
+  cap: 10
+  price: 1
+  seed: 10
+"
+      )
+      .unwrap_err()
+      .to_string(),
+      "`fee` must be at most 1000 basis points: 1001",
+    );
+  }
+
+  #[test]
+  fn unknown_field_is_rejected() {
+    assert!(load(
+      "relic: RELICS
+symbol: R
+thumbnail: relic.png
+mint_terms:
+  amount: 1
+  cap: 10
+  price: 1
+  seed: 10
+  premine: 5
+"
+    )
+    .unwrap_err()
+    .to_string()
+    .contains("unknown field `premine`"));
+  }
+}
@@ -30,6 +30,12 @@ pub(crate) struct MintRelic {
     help = "Maximum percentage slippage allowed. [default: 10%]"
   )]
   slippage: u64,
+  #[clap(
+    long,
+    help = "Write an unsigned PSBT annotated with relic balances instead of signing and \
+    broadcasting the transaction."
+  )]
+  psbt: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
@@ -52,6 +58,11 @@ impl MintRelic {
     let base_token = SpacedRelic::from_str(RELIC_NAME)?;
     ensure!(self.relic != base_token, "use mint-base-relic instead",);
 
+    // the transaction is priced as if it was mined in the next block
+    let next_height = wallet.bitcoin_client().get_block_count()? + 1;
+    let arithmetic =
+      PriceArithmetic::at_height(wallet.chain().network(), u32::try_from(next_height)?);
+
     if self.unmint {
       ensure!(
         self.destination.is_none(),
@@ -68,7 +79,7 @@ impl MintRelic {
         .ok_or_else(|| anyhow!("base relic {} not found", base_token))?;
 
       let result = entry
-        .unmintable(u128::MAX, self.num_mints, 0)
+        .unmintable(u128::MAX, self.num_mints, 0, arithmetic)
         .map_err(|err| anyhow!("relic not unmintable {}: {}", self.relic, err))?;
 
       // Extract the first element from the vector
@@ -214,6 +225,12 @@ impl MintRelic {
       )
       .build_transaction()?;
 
+      if self.psbt {
+        return Ok(Some(Box::new(
+          wallet.create_relic_psbt(unsigned_transaction)?,
+        )));
+      }
+
       let signed_transaction = wallet
         .bitcoin_client()
         .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
@@ -258,7 +275,7 @@ impl MintRelic {
       let total_price = entry
         .mint_terms
         .unwrap()
-        .compute_total_price(total_minted, self.num_mints)
+        .compute_total_price(total_minted, self.num_mints, arithmetic)
         .unwrap();
 
       // Apply slippage to the price
@@ -283,7 +300,12 @@ impl MintRelic {
 
       // Apply slippage to the mintable check
       let result = entry
-        .mintable(base_balance, self.num_mints, total_price_with_slippage)
+        .mintable(
+          base_balance,
+          self.num_mints,
+          total_price_with_slippage,
+          arithmetic,
+        )
         .map_err(|err| anyhow!("relic not mintable {}: {}", self.relic, err))?;
 
       // Extract the first element from the vector
@@ -431,6 +453,12 @@ impl MintRelic {
         "Keepsake decipher mismatch"
       );
 
+      if self.psbt {
+        return Ok(Some(Box::new(
+          wallet.create_relic_psbt(unsigned_transaction)?,
+        )));
+      }
+
       let signed_transaction = wallet
         .bitcoin_client()
         .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
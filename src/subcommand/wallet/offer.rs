@@ -0,0 +1,26 @@
+use {
+  super::*,
+  crate::relics::RelicAllocation,
+  base64::{engine::general_purpose, Engine},
+  bitcoin::psbt::Psbt,
+};
+
+pub mod accept;
+pub mod create;
+
+#[derive(Debug, Parser)]
+pub(crate) enum Offer {
+  #[command(about = "Buy relics offered for sale")]
+  Accept(accept::Accept),
+  #[command(about = "Offer the relics of an output for sale")]
+  Create(create::Create),
+}
+
+impl Offer {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    match self {
+      Self::Accept(accept) => accept.run(wallet),
+      Self::Create(create) => create.run(wallet),
+    }
+  }
+}
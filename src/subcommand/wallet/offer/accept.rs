@@ -0,0 +1,172 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Accept {
+  #[arg(long, help = "Use <FEE_RATE> sats/vbyte for the transaction.")]
+  fee_rate: FeeRate,
+  #[arg(long, help = "Accept base64 encoded offer <PSBT>.")]
+  psbt: String,
+  #[arg(
+    long,
+    help = "Include <AMOUNT> postage with the output receiving the relics. [default: 546sat]"
+  )]
+  postage: Option<Amount>,
+  #[arg(long, help = "Don't sign or broadcast the transaction.")]
+  dry_run: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Output {
+  pub txid: Txid,
+  /// unsigned PSBT of the accepting transaction in a dry run
+  pub psbt: Option<String>,
+  pub relics: BTreeMap<SpacedRelic, Decimal>,
+}
+
+impl Accept {
+  /// Outputs of the accepting transaction, the offered input is paired with the seller output
+  const SELLER_OUTPUT: usize = 0;
+  const BUYER_OUTPUT: usize = 2;
+
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    ensure!(
+      wallet.has_relic_index(),
+      "`ord wallet offer accept` requires index created with `--index-relics` flag",
+    );
+
+    let offer = Psbt::deserialize(&general_purpose::STANDARD.decode(&self.psbt)?)?;
+
+    let validation = wallet.validate_relic_offer(&self.psbt)?;
+
+    ensure!(
+      validation.valid,
+      "invalid offer: {}",
+      validation.errors.join(", ")
+    );
+
+    ensure!(
+      offer.unsigned_tx.input.len() == 1 && offer.unsigned_tx.output.len() == 1,
+      "offer was already accepted",
+    );
+
+    let seller_input = offer.unsigned_tx.input[0].clone();
+    let seller_output = offer.unsigned_tx.output[0].clone();
+
+    let seller_value = offer.inputs[0]
+      .witness_utxo
+      .as_ref()
+      .map(|tx_out| tx_out.value)
+      .context("offer has no witness UTXO")?;
+
+    ensure!(
+      seller_output.value >= seller_value,
+      "offer pays the seller less than the value of the offered output",
+    );
+
+    // an unsigned offer will be signed by the seller with a single schnorr signature
+    let seller_vsize = TxIn {
+      witness: offer.inputs[0]
+        .final_script_witness
+        .clone()
+        .unwrap_or_else(|| Witness::from_slice(&[[0; 65]])),
+      ..seller_input.clone()
+    }
+    .segwit_weight()
+    .to_vbytes_ceil();
+
+    let postage = self.postage.unwrap_or(MIN_POSTAGE);
+
+    let destination = wallet.get_change_address()?;
+
+    ensure!(
+      destination.script_pubkey().minimal_non_dust() < postage,
+      "postage below dust limit of {}sat",
+      destination.script_pubkey().minimal_non_dust().to_sat()
+    );
+
+    let keepsake = Keepsake {
+      pointer: Some(Self::BUYER_OUTPUT.try_into().unwrap()),
+      ..default()
+    };
+
+    // the offered input is added after funding, so the buyer only funds what the seller output
+    // takes beyond the offered input, and the fee for the offered input
+    let unfunded_transaction = Transaction {
+      version: Version(2),
+      lock_time: LockTime::ZERO,
+      input: Vec::new(),
+      output: vec![
+        TxOut {
+          script_pubkey: seller_output.script_pubkey.clone(),
+          value: seller_output.value - seller_value
+            + self.fee_rate.fee(seller_vsize.try_into().unwrap()),
+        },
+        TxOut {
+          script_pubkey: keepsake.encipher(),
+          value: Amount::ZERO,
+        },
+        TxOut {
+          script_pubkey: destination.script_pubkey(),
+          value: postage,
+        },
+      ],
+    };
+
+    wallet.lock_non_cardinal_outputs()?;
+
+    let mut unsigned_transaction: Transaction =
+      consensus::encode::deserialize(&fund_raw_transaction(
+        wallet.bitcoin_client(),
+        self.fee_rate,
+        &unfunded_transaction,
+      )?)?;
+
+    unsigned_transaction.input.insert(0, seller_input);
+    unsigned_transaction.output[Self::SELLER_OUTPUT] = seller_output;
+
+    let mut psbt = Psbt::from_unsigned_tx(unsigned_transaction)?;
+    psbt.inputs[0] = offer.inputs[0].clone();
+    psbt.proprietary = offer.proprietary;
+
+    let accepted = wallet.annotate_relic_psbt(psbt)?;
+
+    let validation = wallet.validate_relic_offer(&accepted.psbt)?;
+
+    ensure!(
+      validation.valid,
+      "accepting transaction is invalid: {}",
+      validation.errors.join(", ")
+    );
+
+    let relics = accepted.outputs[Self::BUYER_OUTPUT].relics.clone();
+
+    if self.dry_run {
+      return Ok(Some(Box::new(Output {
+        txid: accepted.txid,
+        psbt: Some(accepted.psbt),
+        relics,
+      })));
+    }
+
+    let signed =
+      wallet
+        .bitcoin_client()
+        .wallet_process_psbt(&accepted.psbt, Some(true), None, None)?;
+
+    let result = wallet.bitcoin_client().finalize_psbt(&signed.psbt, None)?;
+
+    ensure!(result.complete, "failed to sign accepting transaction");
+
+    let signed_transaction = result
+      .hex
+      .ok_or_else(|| anyhow!("finalized PSBT has no transaction"))?;
+
+    let txid = wallet.send_raw_transaction(&signed_transaction, None)?;
+
+    Ok(Some(Box::new(Output {
+      txid,
+      psbt: None,
+      relics,
+    })))
+  }
+}
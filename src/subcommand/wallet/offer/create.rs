@@ -0,0 +1,110 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Create {
+  #[arg(long, help = "Offer the relics of <OUTPOINT>.")]
+  outpoint: OutPoint,
+  #[arg(long, help = "Sell for <PRICE>, a bitcoin amount like `0.1btc`.")]
+  price: Outgoing,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Output {
+  pub psbt: String,
+  pub outpoint: OutPoint,
+  pub relics: BTreeMap<SpacedRelic, Decimal>,
+  pub price: Outgoing,
+}
+
+impl Create {
+  pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
+    ensure!(
+      wallet.has_relic_index(),
+      "`ord wallet offer create` requires index created with `--index-relics` flag",
+    );
+
+    let relics = wallet.get_relics_balances_in_output(&self.outpoint)?;
+
+    ensure!(
+      !relics.is_empty(),
+      "output {} does not hold any relics",
+      self.outpoint
+    );
+
+    ensure!(
+      wallet.get_inscriptions_in_output(&self.outpoint).is_empty(),
+      "output {} contains inscriptions",
+      self.outpoint
+    );
+
+    ensure!(
+      wallet
+        .get_runes_balances_in_output(&self.outpoint)?
+        .is_empty(),
+      "output {} contains runes",
+      self.outpoint
+    );
+
+    let tx_out = wallet
+      .utxos()
+      .get(&self.outpoint)
+      .or_else(|| wallet.locked_utxos().get(&self.outpoint))
+      .with_context(|| format!("output {} not found in wallet", self.outpoint))?
+      .clone();
+
+    // the seller receives the price in the output paired with the offered input. Offers are priced
+    // in bitcoin only: even a keepsake output signed by the seller can't make the buyer pay relics,
+    // since transfers only move the relics the inputs hold and never fail.
+    let Outgoing::Amount(value) = self.price else {
+      bail!("offer price must be a bitcoin amount");
+    };
+
+    ensure!(
+      value >= tx_out.value,
+      "offer price must be at least the value of the offered output, {}",
+      tx_out.value
+    );
+
+    let unsigned_transaction = Transaction {
+      version: Version(2),
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: self.outpoint,
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::MAX,
+        witness: Witness::new(),
+      }],
+      output: vec![TxOut {
+        script_pubkey: wallet.get_change_address()?.script_pubkey(),
+        value,
+      }],
+    };
+
+    let mut psbt = Psbt::from_unsigned_tx(unsigned_transaction)?;
+
+    RelicAllocation::annotate_offer(&mut psbt.proprietary);
+
+    let offer = wallet.annotate_relic_psbt(psbt)?;
+
+    // the signature only commits to the offered input and the paired output, so the buyer can add
+    // inputs and outputs
+    let signed = wallet.bitcoin_client().wallet_process_psbt(
+      &offer.psbt,
+      Some(true),
+      Some(bitcoin::sighash::EcdsaSighashType::SinglePlusAnyoneCanPay.into()),
+      None,
+    )?;
+
+    Ok(Some(Box::new(Output {
+      psbt: signed.psbt,
+      outpoint: self.outpoint,
+      relics: offer
+        .inputs
+        .into_iter()
+        .next()
+        .map(|input| input.relics)
+        .unwrap_or_default(),
+      price: self.price,
+    })))
+  }
+}
@@ -1,4 +1,6 @@
-use super::*;
+use {super::*, crate::wallet::relic_psbt::RelicPsbtOutput, recipient::Recipient};
+
+mod recipient;
 
 #[derive(Debug, Parser)]
 pub(crate) struct Send {
@@ -12,8 +14,33 @@ pub(crate) struct Send {
     value_name = "AMOUNT"
   )]
   pub(crate) postage: Option<Amount>,
-  address: Address<NetworkUnchecked>,
-  outgoing: Outgoing,
+  #[arg(
+    long,
+    conflicts_with = "dry_run",
+    help = "Write an unsigned PSBT annotated with relic balances instead of signing and \
+    broadcasting the transaction."
+  )]
+  psbt: bool,
+  #[arg(
+    long = "recipient",
+    value_name = "RECIPIENT",
+    conflicts_with_all = ["address", "outgoing"],
+    help = "Send relics to <RECIPIENT> of the form `<ADDRESS>:<AMOUNT>:r:<RELIC>`. May be given \
+    multiple times."
+  )]
+  recipients: Vec<Recipient>,
+  #[arg(
+    long,
+    value_name = "RECIPIENTS_FILE",
+    conflicts_with_all = ["address", "outgoing"],
+    help = "Send relics to the recipients in CSV <RECIPIENTS_FILE> with `address`, `relic`, and \
+    `amount` columns."
+  )]
+  recipients_file: Option<PathBuf>,
+  #[arg(required_unless_present_any = ["recipients", "recipients_file"])]
+  address: Option<Address<NetworkUnchecked>>,
+  #[arg(required_unless_present_any = ["recipients", "recipients_file"])]
+  outgoing: Option<Outgoing>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -24,14 +51,28 @@ pub struct Output {
   pub fee: u64,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecipientsOutput {
+  pub txid: Txid,
+  pub psbt: String,
+  pub fee: u64,
+  /// Relics received by the relic change output and the output of each recipient address
+  pub outputs: Vec<RelicPsbtOutput>,
+}
+
 impl Send {
   pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
-    let address = self
-      .address
-      .clone()
-      .require_network(wallet.chain().network())?;
+    if !self.recipients.is_empty() || self.recipients_file.is_some() {
+      return self.send_to_recipients(wallet);
+    }
+
+    let (Some(address), Some(outgoing)) = (self.address.clone(), self.outgoing.clone()) else {
+      bail!("`ord wallet send` requires an address and an outgoing amount");
+    };
 
-    let unsigned_transaction = match self.outgoing {
+    let address = address.require_network(wallet.chain().network())?;
+
+    let unsigned_transaction = match outgoing {
       Outgoing::Amount(amount) => {
         Self::create_unsigned_send_amount_transaction(&wallet, address, amount, self.fee_rate)?
       }
@@ -81,17 +122,55 @@ impl Send {
       )?,
     };
 
+    if self.psbt {
+      return Ok(Some(Box::new(
+        wallet.create_relic_psbt(unsigned_transaction)?,
+      )));
+    }
+
     let (txid, psbt, fee) =
       wallet.sign_and_broadcast_transaction(unsigned_transaction, self.dry_run, None)?;
 
     Ok(Some(Box::new(Output {
       txid,
       psbt,
-      outgoing: self.outgoing,
+      outgoing,
       fee,
     })))
   }
 
+  fn send_to_recipients(self, wallet: Wallet) -> SubcommandResult {
+    let mut recipients = self.recipients;
+
+    if let Some(path) = &self.recipients_file {
+      recipients.extend(Recipient::load(path)?);
+    }
+
+    let (unsigned_transaction, outputs) =
+      Self::create_unsigned_send_relics_to_recipients_transaction(
+        &wallet,
+        &recipients,
+        self.postage.unwrap_or(MIN_POSTAGE),
+        self.fee_rate,
+      )?;
+
+    if self.psbt {
+      return Ok(Some(Box::new(
+        wallet.create_relic_psbt(unsigned_transaction)?,
+      )));
+    }
+
+    let (txid, psbt, fee) =
+      wallet.sign_and_broadcast_transaction(unsigned_transaction, self.dry_run, None)?;
+
+    Ok(Some(Box::new(RecipientsOutput {
+      txid,
+      psbt,
+      fee,
+      outputs,
+    })))
+  }
+
   fn create_unsigned_send_amount_transaction(
     wallet: &Wallet,
     destination: Address,
@@ -262,6 +341,186 @@ impl Send {
     Ok(unsigned_transaction)
   }
 
+  /// Send relics to several recipients in one transaction, with one output per recipient address
+  /// and a single relic change output
+  fn create_unsigned_send_relics_to_recipients_transaction(
+    wallet: &Wallet,
+    recipients: &[Recipient],
+    postage: Amount,
+    fee_rate: FeeRate,
+  ) -> Result<(Transaction, Vec<RelicPsbtOutput>)> {
+    ensure!(
+      wallet.has_relic_index(),
+      "sending relics with `ord send` requires index created with `--index-relics` flag",
+    );
+
+    ensure!(!recipients.is_empty(), "no recipients to send relics to");
+
+    wallet.lock_non_cardinal_outputs()?;
+
+    let mut spaced_relics = BTreeMap::<Relic, SpacedRelic>::new();
+    let mut relic_ids = BTreeMap::<SpacedRelic, RelicId>::new();
+    let mut required = BTreeMap::<SpacedRelic, u128>::new();
+    let mut destinations = Vec::<(Address, BTreeMap<SpacedRelic, u128>)>::new();
+
+    for recipient in recipients {
+      let address = recipient
+        .address
+        .clone()
+        .require_network(wallet.chain().network())?;
+
+      let spaced_relic = match spaced_relics.get(&recipient.relic.relic) {
+        Some(spaced_relic) => *spaced_relic,
+        None => {
+          let (id, entry, _parent) = wallet
+            .get_relic(recipient.relic.relic)?
+            .with_context(|| format!("relic `{}` has not been enshrined", recipient.relic.relic))?;
+          spaced_relics.insert(recipient.relic.relic, entry.spaced_relic);
+          relic_ids.insert(entry.spaced_relic, id);
+          entry.spaced_relic
+        }
+      };
+
+      let amount = recipient.amount.to_integer(Enshrining::DIVISIBILITY)?;
+
+      ensure!(amount > 0, "cannot send zero {spaced_relic} to {address}");
+
+      let total = required.entry(spaced_relic).or_default();
+      *total = total
+        .checked_add(amount)
+        .with_context(|| format!("total amount of {spaced_relic} overflows"))?;
+
+      let destination = match destinations
+        .iter()
+        .position(|(destination, _)| *destination == address)
+      {
+        Some(destination) => destination,
+        None => {
+          destinations.push((address, BTreeMap::new()));
+          destinations.len() - 1
+        }
+      };
+
+      *destinations[destination].1.entry(spaced_relic).or_default() += amount;
+    }
+
+    let (inputs, input_relic_balances) =
+      wallet.get_required_relic_outputs(required.clone().into_iter().collect())?;
+
+    let change = input_relic_balances
+      .iter()
+      .map(|(relic, balance)| {
+        (
+          *relic,
+          balance - required.get(relic).copied().unwrap_or_default(),
+        )
+      })
+      .filter(|(_, amount)| *amount > 0)
+      .collect::<BTreeMap<SpacedRelic, u128>>();
+
+    let first = if change.is_empty() { 1 } else { 2 };
+
+    let keepsake = Keepsake {
+      transfers: destinations
+        .iter()
+        .enumerate()
+        .flat_map(|(i, (_, relics))| {
+          relics
+            .iter()
+            .map(move |(relic, amount)| (first + i, *relic, *amount))
+        })
+        .map(|(output, relic, amount)| Transfer {
+          id: relic_ids[&relic],
+          amount,
+          output: output.try_into().unwrap(),
+        })
+        .collect(),
+      ..default()
+    };
+
+    let script_pubkey = keepsake.encipher();
+
+    ensure!(
+      script_pubkey.len() <= MAX_STANDARD_OP_RETURN_SIZE,
+      "keepsake size {} over maximum standard OP_RETURN size {MAX_STANDARD_OP_RETURN_SIZE}, send \
+      to fewer recipients",
+      script_pubkey.len(),
+    );
+
+    let change_address = wallet.get_change_address()?;
+
+    let mut output = vec![TxOut {
+      script_pubkey,
+      value: Amount::ZERO,
+    }];
+
+    let mut allocation = Vec::new();
+
+    let decimals = |relics: &BTreeMap<SpacedRelic, u128>| {
+      relics
+        .iter()
+        .map(|(relic, amount)| {
+          (
+            *relic,
+            Decimal {
+              value: *amount,
+              scale: Enshrining::DIVISIBILITY,
+            },
+          )
+        })
+        .collect::<BTreeMap<SpacedRelic, Decimal>>()
+    };
+
+    for (address, relics) in (!change.is_empty())
+      .then_some((&change_address, &change))
+      .into_iter()
+      .chain(
+        destinations
+          .iter()
+          .map(|(address, relics)| (address, relics)),
+      )
+    {
+      allocation.push(RelicPsbtOutput {
+        vout: output.len().try_into().unwrap(),
+        address: Some(uncheck(address)),
+        relics: decimals(relics),
+        shares: BTreeMap::new(),
+      });
+
+      output.push(TxOut {
+        script_pubkey: address.script_pubkey(),
+        value: postage,
+      });
+    }
+
+    let unfunded_transaction = Transaction {
+      version: Version(2),
+      lock_time: LockTime::ZERO,
+      input: inputs
+        .into_iter()
+        .map(|previous_output| TxIn {
+          previous_output,
+          script_sig: ScriptBuf::new(),
+          sequence: Sequence::MAX,
+          witness: Witness::new(),
+        })
+        .collect(),
+      output,
+    };
+
+    let unsigned_transaction =
+      fund_raw_transaction(wallet.bitcoin_client(), fee_rate, &unfunded_transaction)?;
+
+    let unsigned_transaction = consensus::encode::deserialize(&unsigned_transaction)?;
+
+    assert_eq!(
+      Keepsake::decipher(&unsigned_transaction),
+      Some(RelicArtifact::Keepsake(keepsake)),
+    );
+
+    Ok((unsigned_transaction, allocation))
+  }
+
   fn create_unsigned_send_runes_transaction(
     wallet: &Wallet,
     destination: Address,
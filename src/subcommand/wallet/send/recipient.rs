@@ -0,0 +1,102 @@
+use super::*;
+
+/// Relics sent to an address by `ord wallet send` with multiple recipients
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Recipient {
+  pub(crate) address: Address<NetworkUnchecked>,
+  pub(crate) relic: SpacedRelic,
+  pub(crate) amount: Decimal,
+}
+
+impl FromStr for Recipient {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let (address, outgoing) = s
+      .split_once(':')
+      .context("recipient must be of the form `<ADDRESS>:<AMOUNT>:r:<RELIC>`")?;
+
+    let Outgoing::Relic { decimal, relic } = outgoing.parse()? else {
+      bail!("recipient must be of the form `<ADDRESS>:<AMOUNT>:r:<RELIC>`");
+    };
+
+    Ok(Self {
+      address: address.parse()?,
+      relic,
+      amount: decimal,
+    })
+  }
+}
+
+impl Recipient {
+  /// Load recipients from a CSV file with `address`, `relic`, and `amount` columns
+  pub(crate) fn load(path: &Path) -> Result<Vec<Self>> {
+    csv::Reader::from_path(path)
+      .with_context(|| format!("failed to open recipients file `{}`", path.display()))?
+      .deserialize()
+      .map(|recipient| {
+        recipient.with_context(|| format!("invalid recipient in `{}`", path.display()))
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, tempfile::TempDir};
+
+  const ADDRESS: &str = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+
+  #[test]
+  fn recipient_from_str() {
+    assert_eq!(
+      format!("{ADDRESS}:1.5:r:MBTC")
+        .parse::<Recipient>()
+        .unwrap(),
+      Recipient {
+        address: ADDRESS.parse().unwrap(),
+        relic: "MBTC".parse().unwrap(),
+        amount: "1.5".parse().unwrap(),
+      }
+    );
+
+    assert_eq!(
+      format!("{ADDRESS}:1btc")
+        .parse::<Recipient>()
+        .unwrap_err()
+        .to_string(),
+      "recipient must be of the form `<ADDRESS>:<AMOUNT>:r:<RELIC>`"
+    );
+
+    assert!(ADDRESS.parse::<Recipient>().is_err());
+  }
+
+  #[test]
+  fn recipients_are_loaded_from_csv() {
+    let tempdir = TempDir::new().unwrap();
+    let path = tempdir.path().join("recipients.csv");
+
+    fs::write(
+      &path,
+      format!("address,relic,amount\n{ADDRESS},MBTC,100\n{ADDRESS},BASIC•TEST•RELIC,0.5\n"),
+    )
+    .unwrap();
+
+    assert_eq!(
+      Recipient::load(&path).unwrap(),
+      [
+        Recipient {
+          address: ADDRESS.parse().unwrap(),
+          relic: "MBTC".parse().unwrap(),
+          amount: "100".parse().unwrap(),
+        },
+        Recipient {
+          address: ADDRESS.parse().unwrap(),
+          relic: "BASIC•TEST•RELIC".parse().unwrap(),
+          amount: "0.5".parse().unwrap(),
+        },
+      ]
+    );
+  }
+}
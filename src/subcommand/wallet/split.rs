@@ -13,7 +13,19 @@ enum Error {
     value: Amount,
     threshold: Amount,
   },
+  KeepsakeSize {
+    size: usize,
+  },
   NoOutputs,
+  RelicShortfall {
+    relic: SpacedRelic,
+    have: Pile,
+    need: Pile,
+  },
+  RelicZeroValue {
+    output: usize,
+    relic: SpacedRelic,
+  },
   RunestoneSize {
     size: usize,
   },
@@ -42,7 +54,17 @@ impl Display for Error {
       Self::DustPostage { value, threshold } => {
         write!(f, "postage value {value} below dust threshold {threshold}")
       }
+      Self::KeepsakeSize { size } => write!(
+        f,
+        "keepsake size {size} over maximum standard OP_RETURN size {MAX_STANDARD_OP_RETURN_SIZE}"
+      ),
       Self::NoOutputs => write!(f, "split file must contain at least one output"),
+      Self::RelicShortfall { relic, have, need } => {
+        write!(f, "wallet contains {have} of {relic} but need {need}")
+      }
+      Self::RelicZeroValue { output, relic } => {
+        write!(f, "output {output} has zero value for relic {relic}")
+      }
       Self::RunestoneSize { size } => write!(
         f,
         "runestone size {size} over maximum standard OP_RETURN size {MAX_STANDARD_OP_RETURN_SIZE}"
@@ -73,7 +95,7 @@ pub(crate) struct Split {
   pub(crate) postage: Option<Amount>,
   #[arg(
     long,
-    help = "Split outputs multiple inscriptions and rune defined in YAML <SPLIT_FILE>.",
+    help = "Split outputs multiple inscriptions and runes or relics defined in YAML <SPLIT_FILE>.",
     value_name = "SPLIT_FILE"
   )]
   pub(crate) splits: PathBuf,
@@ -92,13 +114,17 @@ pub struct Output {
   pub txid: Txid,
   pub psbt: String,
   pub fee: u64,
+  /// transactions continuing a relic split that does not fit into a single keepsake, each
+  /// spending the relic change output of the previous one
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub chained: Vec<Output>,
 }
 
 impl Split {
   pub(crate) fn run(self, wallet: Wallet) -> SubcommandResult {
     ensure!(
-      wallet.has_rune_index(),
-      "`ord wallet split` requires index created with `--index-runes`",
+      wallet.has_rune_index() || wallet.has_relic_index(),
+      "`ord wallet split` requires index created with `--index-runes` or `--index-relics`",
     );
 
     wallet.lock_non_cardinal_outputs()?;
@@ -111,6 +137,10 @@ impl Split {
       .map(|satpoint| satpoint.outpoint)
       .collect::<HashSet<OutPoint>>();
 
+    if !splits.relic_info.is_empty() {
+      return self.split_relics(&wallet, &splits, &inscribed_outputs);
+    }
+
     let balances = wallet
       .get_runic_outputs()?
       .into_iter()
@@ -147,7 +177,128 @@ impl Split {
     let (txid, psbt, fee) =
       wallet.sign_and_broadcast_transaction(unsigned_transaction, self.dry_run, None)?;
 
-    Ok(Some(Box::new(Output { txid, psbt, fee })))
+    Ok(Some(Box::new(Output {
+      txid,
+      psbt,
+      fee,
+      chained: Vec::new(),
+    })))
+  }
+
+  fn split_relics(
+    self,
+    wallet: &Wallet,
+    splits: &Splitfile,
+    inscribed_outputs: &HashSet<OutPoint>,
+  ) -> SubcommandResult {
+    let runic_outputs = wallet.get_runic_outputs()?;
+
+    let balances = wallet
+      .get_relic_balances()?
+      .into_iter()
+      .filter(|(output, _)| !inscribed_outputs.contains(output) && !runic_outputs.contains(output))
+      .collect();
+
+    let transactions = Self::build_relic_transactions(
+      self.no_limit,
+      balances,
+      &wallet.get_change_address()?,
+      self.postage,
+      splits,
+    )?;
+
+    ensure!(
+      !self.dry_run || transactions.len() == 1,
+      "split requires {} chained transactions, which cannot be funded in a dry run",
+      transactions.len(),
+    );
+
+    let count = transactions.len();
+
+    let mut outputs = Vec::new();
+    let mut unconfirmed = BTreeMap::new();
+    let mut relic_change = None;
+
+    for (i, (mut unfunded_transaction, mut keepsake)) in transactions.into_iter().enumerate() {
+      if let Some(input) = unfunded_transaction
+        .input
+        .first_mut()
+        .filter(|input| input.previous_output == OutPoint::null())
+      {
+        input.previous_output = relic_change.take().unwrap();
+      }
+
+      let unsigned_transaction = fund_raw_transaction(
+        wallet.bitcoin_client(),
+        self.fee_rate,
+        &unfunded_transaction,
+      )?;
+
+      let mut unsigned_transaction: Transaction =
+        consensus::encode::deserialize(&unsigned_transaction)?;
+
+      if unsigned_transaction.output.len() == unfunded_transaction.output.len() {
+        // no change output was added, so transfers to all outputs must use one output less
+        let all = u32::try_from(unsigned_transaction.output.len()).unwrap();
+        for transfer in &mut keepsake.transfers {
+          if transfer.output == all + 1 {
+            transfer.output = all;
+          }
+        }
+        unsigned_transaction.output[0].script_pubkey = keepsake.encipher();
+      }
+
+      assert_eq!(
+        Keepsake::decipher(&unsigned_transaction),
+        Some(RelicArtifact::Keepsake(keepsake.clone())),
+      );
+
+      let (txid, psbt, fee) = wallet.sign_and_broadcast_chained_transaction(
+        unsigned_transaction.clone(),
+        self.dry_run,
+        None,
+        &unconfirmed,
+      )?;
+
+      if i + 1 < count {
+        // keep the funding of the following transactions from spending split outputs received by
+        // the wallet or the relic change output
+        let locked = (1..unfunded_transaction.output.len())
+          .map(|vout| OutPoint {
+            txid,
+            vout: vout.try_into().unwrap(),
+          })
+          .collect::<Vec<OutPoint>>();
+
+        if !wallet.bitcoin_client().lock_unspent(&locked)? {
+          bail!("failed to lock outputs of split transaction {txid}");
+        }
+
+        relic_change = keepsake.pointer.map(|vout| OutPoint { txid, vout });
+
+        for (vout, output) in unsigned_transaction.output.into_iter().enumerate() {
+          unconfirmed.insert(
+            OutPoint {
+              txid,
+              vout: vout.try_into().unwrap(),
+            },
+            output,
+          );
+        }
+      }
+
+      outputs.push(Output {
+        txid,
+        psbt,
+        fee,
+        chained: Vec::new(),
+      });
+    }
+
+    let mut output = outputs.remove(0);
+    output.chained = outputs;
+
+    Ok(Some(Box::new(output)))
   }
 
   fn build_transaction(
@@ -323,11 +474,263 @@ impl Split {
 
     Ok(tx)
   }
+
+  /// Build the unfunded transactions of a relic split, together with their keepsakes.
+  ///
+  /// Outputs are laid out as the keepsake, the split outputs and, if any relics are left over, a
+  /// relic change output, which the keepsake points to. Split outputs are spread over as many
+  /// transactions as needed to keep each keepsake within the maximum standard OP_RETURN size. All
+  /// transactions after the first spend the relic change output of the previous one from an input
+  /// with a null outpoint, since its txid is only known once the previous transaction is funded.
+  fn build_relic_transactions(
+    no_keepsake_limit: bool,
+    balances: BTreeMap<OutPoint, BTreeMap<SpacedRelic, Pile>>,
+    change_address: &Address,
+    postage: Option<Amount>,
+    splits: &Splitfile,
+  ) -> Result<Vec<(Transaction, Keepsake)>, Error> {
+    if splits.outputs.is_empty() {
+      return Err(Error::NoOutputs);
+    }
+
+    let postage = postage.unwrap_or(MIN_POSTAGE);
+
+    let change_script_pubkey = change_address.script_pubkey();
+
+    let change_dust_threshold = change_script_pubkey.minimal_non_dust();
+
+    if postage < change_dust_threshold {
+      return Err(Error::DustPostage {
+        value: postage,
+        threshold: change_dust_threshold,
+      });
+    }
+
+    let mut required = BTreeMap::<SpacedRelic, u128>::new();
+
+    for (i, output) in splits.outputs.iter().enumerate() {
+      for (relic, &amount) in &output.relics {
+        let spaced_relic = splits.relic_info[relic].spaced_relic;
+        if amount == 0 {
+          return Err(Error::RelicZeroValue {
+            relic: spaced_relic,
+            output: i,
+          });
+        }
+        let total = required.entry(spaced_relic).or_default();
+        *total = (*total).checked_add(amount).unwrap();
+      }
+    }
+
+    for (&relic, &need) in &required {
+      let have = balances
+        .values()
+        .filter_map(|relics| relics.get(&relic))
+        .map(|pile| pile.amount)
+        .sum::<u128>();
+      if have < need {
+        let symbol = splits.relic_info[&relic.relic].symbol;
+        return Err(Error::RelicShortfall {
+          relic,
+          have: Pile {
+            amount: have,
+            divisibility: Enshrining::DIVISIBILITY,
+            symbol,
+          },
+          need: Pile {
+            amount: need,
+            divisibility: Enshrining::DIVISIBILITY,
+            symbol,
+          },
+        });
+      }
+    }
+
+    let (mut inputs, input_balances) =
+      Wallet::select_required_relic_outputs(&balances, &required).unwrap();
+
+    let mut split_outputs = Vec::new();
+
+    for (i, split_output) in splits.outputs.iter().enumerate() {
+      let script_pubkey = split_output.address.script_pubkey();
+      let threshold = script_pubkey.minimal_non_dust();
+      let value = split_output.value.unwrap_or(threshold);
+      if value < threshold {
+        return Err(Error::DustOutput {
+          output: i,
+          threshold,
+          value,
+        });
+      }
+      split_outputs.push(TxOut {
+        script_pubkey,
+        value,
+      });
+    }
+
+    let mut balances = input_balances
+      .into_iter()
+      .map(|(spaced_relic, amount)| (spaced_relic.relic, amount))
+      .collect::<BTreeMap<Relic, u128>>();
+
+    let mut transactions = Vec::new();
+
+    let mut start = 0;
+
+    while start < splits.outputs.len() {
+      let mut end = if no_keepsake_limit {
+        splits.outputs.len()
+      } else {
+        start + 1
+      };
+
+      let (mut keepsake, mut leftover) =
+        Self::relic_keepsake(splits, &splits.outputs[start..end], &balances);
+
+      let size = keepsake.encipher().len();
+
+      if !no_keepsake_limit && size > MAX_STANDARD_OP_RETURN_SIZE {
+        return Err(Error::KeepsakeSize { size });
+      }
+
+      while !no_keepsake_limit && end < splits.outputs.len() {
+        let next = Self::relic_keepsake(splits, &splits.outputs[start..end + 1], &balances);
+
+        if next.0.encipher().len() > MAX_STANDARD_OP_RETURN_SIZE {
+          break;
+        }
+
+        (keepsake, leftover) = next;
+        end += 1;
+      }
+
+      let mut output = vec![TxOut {
+        script_pubkey: keepsake.encipher(),
+        value: Amount::from_sat(0),
+      }];
+
+      output.extend_from_slice(&split_outputs[start..end]);
+
+      if keepsake.pointer.is_some() {
+        output.push(TxOut {
+          script_pubkey: change_script_pubkey.clone(),
+          value: postage,
+        });
+      }
+
+      let tx = Transaction {
+        version: Version(2),
+        lock_time: LockTime::ZERO,
+        input: mem::take(&mut inputs)
+          .into_iter()
+          .map(|previous_output| TxIn {
+            previous_output,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+          })
+          .collect(),
+        output,
+      };
+
+      if keepsake.pointer.is_some() {
+        inputs.push(OutPoint::null());
+      }
+
+      transactions.push((tx, keepsake));
+
+      balances = leftover;
+
+      start = end;
+    }
+
+    Ok(transactions)
+  }
+
+  /// Keepsake allocating relics to the split outputs of `chunk`, which start at output 1, and the
+  /// relics of `balances` left over after the allocation.
+  ///
+  /// An amount of a relic that every split output receives is sent with a single transfer to all
+  /// outputs, which counts the change output added by funding. Any leftover of such a relic is
+  /// transferred to the relic change output first, so that none of it reaches the outputs after
+  /// the split outputs.
+  fn relic_keepsake(
+    splits: &Splitfile,
+    chunk: &[splitfile::Output],
+    balances: &BTreeMap<Relic, u128>,
+  ) -> (Keepsake, BTreeMap<Relic, u128>) {
+    let mut leftover = balances.clone();
+
+    let mut allocations = BTreeMap::<Relic, Vec<(u32, u128)>>::new();
+
+    for (i, output) in chunk.iter().enumerate() {
+      for (&relic, &amount) in &output.relics {
+        allocations
+          .entry(relic)
+          .or_default()
+          .push(((i + 1).try_into().unwrap(), amount));
+        *leftover.get_mut(&relic).unwrap() -= amount;
+      }
+    }
+
+    leftover.retain(|_, amount| *amount > 0);
+
+    let change = (!leftover.is_empty()).then(|| u32::try_from(chunk.len() + 1).unwrap());
+
+    let all = u32::try_from(chunk.len() + 2).unwrap() + u32::from(change.is_some());
+
+    let mut transfers = Vec::new();
+
+    for (relic, allocations) in allocations {
+      let id = splits.relic_info[&relic].id;
+
+      let amount = allocations[0].1;
+
+      if chunk.len() > 1
+        && allocations.len() == chunk.len()
+        && allocations.iter().all(|allocation| allocation.1 == amount)
+      {
+        if let Some(&leftover) = leftover.get(&relic) {
+          transfers.push(Transfer {
+            id,
+            amount: leftover,
+            output: change.unwrap(),
+          });
+        }
+
+        transfers.push(Transfer {
+          id,
+          amount,
+          output: all,
+        });
+      } else {
+        transfers.extend(allocations.into_iter().map(|(output, amount)| Transfer {
+          id,
+          amount,
+          output,
+        }));
+      }
+    }
+
+    transfers.sort_by_key(|transfer| transfer.id);
+
+    (
+      Keepsake {
+        transfers,
+        pointer: change,
+        ..default()
+      },
+      leftover,
+    )
+  }
 }
 
 #[cfg(test)]
 mod tests {
-  use {super::*, splitfile::RuneInfo};
+  use {
+    super::*,
+    splitfile::{RelicInfo, RuneInfo},
+  };
 
   #[test]
   fn splits_must_have_at_least_one_output() {
@@ -340,6 +743,7 @@ mod tests {
         &Splitfile {
           outputs: Vec::new(),
           rune_info: BTreeMap::new(),
+          relic_info: BTreeMap::new(),
         },
       )
       .unwrap_err(),
@@ -360,8 +764,10 @@ mod tests {
             address: address(0),
             runes: [(Rune(0), 1000)].into(),
             value: Some(Amount::from_sat(1000)),
+            relics: BTreeMap::new(),
           }],
           rune_info: BTreeMap::new(),
+          relic_info: BTreeMap::new(),
         },
       )
       .unwrap_err(),
@@ -385,6 +791,7 @@ mod tests {
             address: address(0),
             runes: [(Rune(0), 0)].into(),
             value: Some(Amount::from_sat(1000)),
+            relics: BTreeMap::new(),
           }],
           rune_info: [(
             Rune(0),
@@ -398,7 +805,8 @@ mod tests {
               },
             },
           )]
-          .into()
+          .into(),
+          relic_info: BTreeMap::new(),
         },
       )
       .unwrap_err(),
@@ -423,11 +831,13 @@ mod tests {
               address: address(0),
               runes: [(Rune(0), 100)].into(),
               value: Some(Amount::from_sat(1000)),
+              relics: BTreeMap::new(),
             },
             splitfile::Output {
               address: address(0),
               runes: [(Rune(0), 0)].into(),
               value: Some(Amount::from_sat(1000)),
+              relics: BTreeMap::new(),
             },
           ],
           rune_info: [(
@@ -442,7 +852,8 @@ mod tests {
               },
             },
           )]
-          .into()
+          .into(),
+          relic_info: BTreeMap::new(),
         },
       )
       .unwrap_err(),
@@ -469,6 +880,7 @@ mod tests {
             address: address(0),
             runes: [(Rune(0), 1000)].into(),
             value: Some(Amount::from_sat(1000)),
+            relics: BTreeMap::new(),
           }],
           rune_info: [(
             Rune(0),
@@ -483,6 +895,7 @@ mod tests {
             },
           )]
           .into(),
+          relic_info: BTreeMap::new(),
         },
       )
       .unwrap_err(),
@@ -515,6 +928,7 @@ mod tests {
             address: address(0),
             runes: [(Rune(0), 2000)].into(),
             value: Some(Amount::from_sat(1000)),
+            relics: BTreeMap::new(),
           }],
           rune_info: [(
             Rune(0),
@@ -528,7 +942,8 @@ mod tests {
               },
             },
           )]
-          .into()
+          .into(),
+          relic_info: BTreeMap::new(),
         },
       )
       .unwrap_err(),
@@ -564,6 +979,7 @@ mod tests {
             address: address(0),
             runes: [(Rune(0), 1000)].into(),
             value: Some(Amount::from_sat(1)),
+            relics: BTreeMap::new(),
           }],
           rune_info: [(
             Rune(0),
@@ -578,6 +994,7 @@ mod tests {
             },
           )]
           .into(),
+          relic_info: BTreeMap::new(),
         },
       )
       .unwrap_err(),
@@ -600,11 +1017,13 @@ mod tests {
               address: address(0),
               runes: [(Rune(0), 1000)].into(),
               value: Some(Amount::from_sat(1000)),
+              relics: BTreeMap::new(),
             },
             splitfile::Output {
               address: address(0),
               runes: [(Rune(0), 1000)].into(),
               value: Some(Amount::from_sat(10)),
+              relics: BTreeMap::new(),
             },
           ],
           rune_info: [(
@@ -619,7 +1038,8 @@ mod tests {
               },
             },
           )]
-          .into()
+          .into(),
+          relic_info: BTreeMap::new(),
         },
       )
       .unwrap_err(),
@@ -645,6 +1065,7 @@ mod tests {
         address: address.clone(),
         runes: [(rune, 1000)].into(),
         value: None,
+        relics: BTreeMap::new(),
       }],
       rune_info: [(
         rune,
@@ -659,6 +1080,7 @@ mod tests {
         },
       )]
       .into(),
+      relic_info: BTreeMap::new(),
     };
 
     let tx = Split::build_transaction(false, balances, &change(0), None, &splits).unwrap();
@@ -713,6 +1135,7 @@ mod tests {
         address: address.clone(),
         runes: [(rune, 1000)].into(),
         value: None,
+        relics: BTreeMap::new(),
       }],
       rune_info: [(
         rune,
@@ -727,6 +1150,7 @@ mod tests {
         },
       )]
       .into(),
+      relic_info: BTreeMap::new(),
     };
 
     let tx = Split::build_transaction(false, balances, &change, None, &splits).unwrap();
@@ -785,6 +1209,7 @@ mod tests {
         address: address.clone(),
         runes: [(rune, 1000)].into(),
         value: None,
+        relics: BTreeMap::new(),
       }],
       rune_info: [(
         rune,
@@ -799,6 +1224,7 @@ mod tests {
         },
       )]
       .into(),
+      relic_info: BTreeMap::new(),
     };
 
     let tx = Split::build_transaction(
@@ -862,6 +1288,7 @@ mod tests {
         address: address.clone(),
         runes: [(Rune(0), 1000)].into(),
         value: None,
+        relics: BTreeMap::new(),
       }],
       rune_info: [(
         Rune(0),
@@ -876,6 +1303,7 @@ mod tests {
         },
       )]
       .into(),
+      relic_info: BTreeMap::new(),
     };
 
     let tx = Split::build_transaction(false, balances, &change, None, &splits).unwrap();
@@ -936,6 +1364,7 @@ mod tests {
         address: address.clone(),
         runes: [(rune, 1000)].into(),
         value: None,
+        relics: BTreeMap::new(),
       }],
       rune_info: [(
         rune,
@@ -950,6 +1379,7 @@ mod tests {
         },
       )]
       .into(),
+      relic_info: BTreeMap::new(),
     };
 
     let tx = Split::build_transaction(false, balances, &change(0), None, &splits).unwrap();
@@ -1007,6 +1437,7 @@ mod tests {
         address: address.clone(),
         runes: [(rune, 1000)].into(),
         value: None,
+        relics: BTreeMap::new(),
       }],
       rune_info: [(
         rune,
@@ -1021,6 +1452,7 @@ mod tests {
         },
       )]
       .into(),
+      relic_info: BTreeMap::new(),
     };
 
     let tx = Split::build_transaction(false, balances, &change(0), None, &splits).unwrap();
@@ -1077,6 +1509,7 @@ mod tests {
         address: address.clone(),
         runes: [(rune, 2000)].into(),
         value: None,
+        relics: BTreeMap::new(),
       }],
       rune_info: [(
         rune,
@@ -1091,6 +1524,7 @@ mod tests {
         },
       )]
       .into(),
+      relic_info: BTreeMap::new(),
     };
 
     let tx = Split::build_transaction(false, balances, &change(0), None, &splits).unwrap();
@@ -1152,11 +1586,13 @@ mod tests {
           address: address(0),
           runes: [(rune, 500)].into(),
           value: None,
+          relics: BTreeMap::new(),
         },
         splitfile::Output {
           address: address(1),
           runes: [(rune, 500)].into(),
           value: None,
+          relics: BTreeMap::new(),
         },
       ],
       rune_info: [(
@@ -1172,6 +1608,7 @@ mod tests {
         },
       )]
       .into(),
+      relic_info: BTreeMap::new(),
     };
 
     let tx = Split::build_transaction(false, balances, &change(0), None, &splits).unwrap();
@@ -1237,6 +1674,7 @@ mod tests {
         address: address.clone(),
         runes: [(Rune(0), 1000), (Rune(1), 2000)].into(),
         value: None,
+        relics: BTreeMap::new(),
       }],
       rune_info: [
         (
@@ -1265,6 +1703,7 @@ mod tests {
         ),
       ]
       .into(),
+      relic_info: BTreeMap::new(),
     };
 
     let tx = Split::build_transaction(false, balances, &change(0), None, &splits).unwrap();
@@ -1329,6 +1768,7 @@ mod tests {
           address: address(i).clone(),
           runes: [(Rune(0), 1_000_000_000)].into(),
           value: None,
+          relics: BTreeMap::new(),
         })
         .collect(),
       rune_info: [(
@@ -1344,6 +1784,7 @@ mod tests {
         },
       )]
       .into(),
+      relic_info: BTreeMap::new(),
     };
 
     assert_eq!(
@@ -1362,6 +1803,7 @@ mod tests {
           address: address(i).clone(),
           runes: [(Rune(0), 1_000_000_000)].into(),
           value: None,
+          relics: BTreeMap::new(),
         })
         .collect(),
       rune_info: [(
@@ -1377,6 +1819,7 @@ mod tests {
         },
       )]
       .into(),
+      relic_info: BTreeMap::new(),
     };
 
     pretty_assert_eq!(
@@ -1418,4 +1861,292 @@ mod tests {
       }
     );
   }
+
+  fn relic_splits(outputs: Vec<BTreeMap<Relic, u128>>) -> Splitfile {
+    Splitfile {
+      outputs: outputs
+        .into_iter()
+        .enumerate()
+        .map(|(i, relics)| splitfile::Output {
+          address: address(u32::try_from(i).unwrap() % 10),
+          value: None,
+          runes: BTreeMap::new(),
+          relics,
+        })
+        .collect(),
+      rune_info: BTreeMap::new(),
+      relic_info: (0..2)
+        .map(|n| {
+          (
+            Relic(n),
+            RelicInfo {
+              id: RelicId {
+                block: 1,
+                tx: u32::try_from(n).unwrap(),
+              },
+              spaced_relic: SpacedRelic {
+                relic: Relic(n),
+                spacers: 0,
+              },
+              symbol: Some('R'),
+            },
+          )
+        })
+        .collect(),
+    }
+  }
+
+  fn relic_balances(
+    balances: &[(u32, u128, u128)],
+  ) -> BTreeMap<OutPoint, BTreeMap<SpacedRelic, Pile>> {
+    let mut outputs = BTreeMap::<OutPoint, BTreeMap<SpacedRelic, Pile>>::new();
+    for &(output, relic, amount) in balances {
+      outputs.entry(outpoint(output)).or_default().insert(
+        SpacedRelic {
+          relic: Relic(relic),
+          spacers: 0,
+        },
+        Pile {
+          amount,
+          divisibility: Enshrining::DIVISIBILITY,
+          symbol: Some('R'),
+        },
+      );
+    }
+    outputs
+  }
+
+  fn relic_tx_in(previous_output: OutPoint) -> TxIn {
+    TxIn {
+      previous_output,
+      script_sig: ScriptBuf::new(),
+      sequence: Sequence::MAX,
+      witness: Witness::new(),
+    }
+  }
+
+  #[test]
+  fn output_relic_value_may_not_be_zero() {
+    assert_eq!(
+      Split::build_relic_transactions(
+        false,
+        relic_balances(&[(0, 0, 1000)]),
+        &change(0),
+        None,
+        &relic_splits(vec![[(Relic(0), 100)].into(), [(Relic(0), 0)].into()]),
+      )
+      .unwrap_err(),
+      Error::RelicZeroValue {
+        output: 1,
+        relic: SpacedRelic {
+          relic: Relic(0),
+          spacers: 0,
+        },
+      },
+    );
+  }
+
+  #[test]
+  fn wallet_must_have_enough_relics() {
+    assert_eq!(
+      Split::build_relic_transactions(
+        false,
+        relic_balances(&[(0, 0, 500), (1, 1, 5000)]),
+        &change(0),
+        None,
+        &relic_splits(vec![[(Relic(0), 1000)].into()]),
+      )
+      .unwrap_err(),
+      Error::RelicShortfall {
+        relic: SpacedRelic {
+          relic: Relic(0),
+          spacers: 0,
+        },
+        have: Pile {
+          amount: 500,
+          divisibility: Enshrining::DIVISIBILITY,
+          symbol: Some('R'),
+        },
+        need: Pile {
+          amount: 1000,
+          divisibility: Enshrining::DIVISIBILITY,
+          symbol: Some('R'),
+        },
+      },
+    );
+  }
+
+  #[test]
+  fn one_relic_output_with_change() {
+    let keepsake = Keepsake {
+      transfers: vec![Transfer {
+        id: RelicId { block: 1, tx: 0 },
+        amount: 400,
+        output: 1,
+      }],
+      pointer: Some(2),
+      ..default()
+    };
+
+    pretty_assert_eq!(
+      Split::build_relic_transactions(
+        false,
+        relic_balances(&[(0, 0, 1000), (1, 1, 1000)]),
+        &change(0),
+        None,
+        &relic_splits(vec![[(Relic(0), 400)].into()]),
+      )
+      .unwrap(),
+      vec![(
+        Transaction {
+          version: Version(2),
+          lock_time: LockTime::ZERO,
+          input: vec![relic_tx_in(outpoint(0))],
+          output: vec![
+            TxOut {
+              value: Amount::from_sat(0),
+              script_pubkey: keepsake.encipher(),
+            },
+            tx_out(294, address(0)),
+            tx_out(MIN_POSTAGE.to_sat(), change(0)),
+          ],
+        },
+        keepsake,
+      )],
+    );
+  }
+
+  #[test]
+  fn equal_relic_amounts_are_transferred_to_all_outputs() {
+    let (transaction, keepsake) = Split::build_relic_transactions(
+      false,
+      relic_balances(&[(0, 0, 800)]),
+      &change(0),
+      None,
+      &relic_splits(vec![[(Relic(0), 400)].into(), [(Relic(0), 400)].into()]),
+    )
+    .unwrap()
+    .remove(0);
+
+    assert_eq!(transaction.output.len(), 3);
+
+    // funding appends a change output
+    pretty_assert_eq!(
+      keepsake,
+      Keepsake {
+        transfers: vec![Transfer {
+          id: RelicId { block: 1, tx: 0 },
+          amount: 400,
+          output: 4,
+        }],
+        ..default()
+      },
+    );
+  }
+
+  #[test]
+  fn leftover_relics_are_transferred_to_change_before_all_outputs() {
+    let (transaction, keepsake) = Split::build_relic_transactions(
+      false,
+      relic_balances(&[(0, 0, 1000), (0, 1, 50)]),
+      &change(0),
+      None,
+      &relic_splits(vec![[(Relic(0), 100)].into(); 3]),
+    )
+    .unwrap()
+    .remove(0);
+
+    assert_eq!(
+      transaction.output[4],
+      tx_out(MIN_POSTAGE.to_sat(), change(0))
+    );
+
+    pretty_assert_eq!(
+      keepsake,
+      Keepsake {
+        transfers: vec![
+          Transfer {
+            id: RelicId { block: 1, tx: 0 },
+            amount: 700,
+            output: 4,
+          },
+          Transfer {
+            id: RelicId { block: 1, tx: 0 },
+            amount: 100,
+            output: 6,
+          },
+        ],
+        pointer: Some(4),
+        ..default()
+      },
+    );
+  }
+
+  #[test]
+  fn oversize_keepsake_is_split_into_chained_transactions() {
+    let splits = relic_splits(
+      (1..=30)
+        .map(|amount| [(Relic(0), amount * 1000)].into())
+        .collect(),
+    );
+
+    let transactions = Split::build_relic_transactions(
+      false,
+      relic_balances(&[(0, 0, 465_000)]),
+      &change(0),
+      None,
+      &splits,
+    )
+    .unwrap();
+
+    assert!(transactions.len() > 1);
+
+    let mut outputs = 0;
+
+    for (i, (transaction, keepsake)) in transactions.iter().enumerate() {
+      assert!(transaction.output[0].script_pubkey.len() <= MAX_STANDARD_OP_RETURN_SIZE);
+      assert_eq!(transaction.output[0].script_pubkey, keepsake.encipher());
+
+      assert_eq!(
+        transaction.input,
+        [relic_tx_in(if i == 0 {
+          outpoint(0)
+        } else {
+          OutPoint::null()
+        })],
+      );
+
+      let last = i == transactions.len() - 1;
+
+      assert_eq!(
+        keepsake.pointer,
+        (!last).then(|| u32::try_from(transaction.output.len() - 1).unwrap()),
+      );
+
+      for (output, transfer) in keepsake.transfers.iter().enumerate() {
+        assert_eq!(transfer.output, u32::try_from(output + 1).unwrap());
+        assert_eq!(
+          transfer.amount,
+          u128::try_from(outputs + output + 1).unwrap() * 1000,
+        );
+      }
+
+      outputs += keepsake.transfers.len();
+    }
+
+    assert_eq!(outputs, 30);
+
+    assert_eq!(
+      Split::build_relic_transactions(
+        true,
+        relic_balances(&[(0, 0, 465_000)]),
+        &change(0),
+        None,
+        &splits,
+      )
+      .unwrap()
+      .len(),
+      1,
+    );
+  }
 }
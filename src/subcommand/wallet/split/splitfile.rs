@@ -11,18 +11,23 @@ struct SplitfileUnchecked {
 struct OutputUnchecked {
   address: Address<NetworkUnchecked>,
   value: Option<DeserializeFromStr<Amount>>,
+  #[serde(default)]
   runes: BTreeMap<SpacedRune, Decimal>,
+  #[serde(default)]
+  relics: BTreeMap<SpacedRelic, Decimal>,
 }
 
 pub(crate) struct Splitfile {
   pub(crate) outputs: Vec<Output>,
   pub(crate) rune_info: BTreeMap<Rune, RuneInfo>,
+  pub(crate) relic_info: BTreeMap<Relic, RelicInfo>,
 }
 
 pub(crate) struct Output {
   pub(crate) address: Address,
   pub(crate) value: Option<Amount>,
   pub(crate) runes: BTreeMap<Rune, u128>,
+  pub(crate) relics: BTreeMap<Relic, u128>,
 }
 
 #[derive(Clone, Copy)]
@@ -33,14 +38,49 @@ pub(crate) struct RuneInfo {
   pub(crate) symbol: Option<char>,
 }
 
+#[derive(Clone, Copy)]
+pub(crate) struct RelicInfo {
+  pub(crate) id: RelicId,
+  pub(crate) spaced_relic: SpacedRelic,
+  pub(crate) symbol: Option<char>,
+}
+
 impl Splitfile {
   pub(crate) fn load(path: &Path, wallet: &Wallet) -> Result<Self> {
     let network = wallet.chain().network();
 
     let unchecked = Self::load_unchecked(path)?;
 
+    let has_runes = unchecked
+      .outputs
+      .iter()
+      .any(|output| !output.runes.is_empty());
+
+    let has_relics = unchecked
+      .outputs
+      .iter()
+      .any(|output| !output.relics.is_empty());
+
+    // a transaction may only carry a single OP_RETURN output to be standard
+    ensure!(
+      !(has_runes && has_relics),
+      "split file cannot contain both runes and relics",
+    );
+
+    ensure!(
+      !has_runes || wallet.has_rune_index(),
+      "splitting runes requires index created with `--index-runes`",
+    );
+
+    ensure!(
+      !has_relics || wallet.has_relic_index(),
+      "splitting relics requires index created with `--index-relics`",
+    );
+
     let mut rune_info = BTreeMap::<Rune, RuneInfo>::new();
 
+    let mut relic_info = BTreeMap::<Relic, RelicInfo>::new();
+
     let mut outputs = Vec::new();
 
     for output in unchecked.outputs {
@@ -70,14 +110,40 @@ impl Splitfile {
         runes.insert(spaced_rune.rune, amount);
       }
 
+      let mut relics = BTreeMap::new();
+
+      for (spaced_relic, decimal) in output.relics {
+        if let std::collections::btree_map::Entry::Vacant(vacant) =
+          relic_info.entry(spaced_relic.relic)
+        {
+          let (id, entry, _owner) = wallet
+            .get_relic(spaced_relic.relic)?
+            .with_context(|| format!("relic `{}` has not been enshrined", spaced_relic.relic))?;
+          vacant.insert(RelicInfo {
+            id,
+            spaced_relic: entry.spaced_relic,
+            symbol: entry.symbol,
+          });
+        }
+
+        let amount = decimal.to_integer(Enshrining::DIVISIBILITY)?;
+
+        relics.insert(spaced_relic.relic, amount);
+      }
+
       outputs.push(Output {
         address: output.address.require_network(network)?,
         value: output.value.map(|DeserializeFromStr(value)| value),
         runes,
+        relics,
       });
     }
 
-    Ok(Self { outputs, rune_info })
+    Ok(Self {
+      outputs,
+      rune_info,
+      relic_info,
+    })
   }
 
   fn load_unchecked(path: &Path) -> Result<SplitfileUnchecked> {
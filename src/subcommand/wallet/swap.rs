@@ -14,6 +14,11 @@ pub(crate) struct Swap {
     help = "Swap Output Relic, defaults to RELIC. May contain `.` or `•` as spacers."
   )]
   output: Option<SpacedRelic>,
+  #[clap(
+    long,
+    help = "Route swap through <VIA> Relic. May be given multiple times, hops are taken in order."
+  )]
+  via: Vec<SpacedRelic>,
   #[clap(long, help = "Swap Input amount <INPUT>.")]
   input_amount: Option<Decimal>,
   #[clap(long, help = "Swap Output amount <OUTPUT>.")]
@@ -27,6 +32,12 @@ pub(crate) struct Swap {
   postage: Option<Amount>,
   #[clap(long, help = "Send output relics to <DESTINATION>.")]
   destination: Option<Address<NetworkUnchecked>>,
+  #[clap(
+    long,
+    help = "Write an unsigned PSBT annotated with relic balances instead of signing and \
+    broadcasting the transaction."
+  )]
+  psbt: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
@@ -61,6 +72,17 @@ impl Swap {
       "input and output Relic must not be the same"
     );
 
+    let via = self
+      .via
+      .iter()
+      .map(|relic| {
+        wallet
+          .get_relic(relic.relic)?
+          .map(|(id, _, _)| id)
+          .ok_or_else(|| anyhow!("via relic not found: {}", relic))
+      })
+      .collect::<Result<Vec<RelicId>>>()?;
+
     let input_amount = self
       .input_amount
       .map(|amount| amount.to_integer(Enshrining::DIVISIBILITY))
@@ -106,14 +128,38 @@ impl Swap {
       }]
     };
 
+    let swap = relics_protocol::Swap {
+      input: (input_id != RELIC_ID).then_some(input_id),
+      output: (output_id != RELIC_ID).then_some(output_id),
+      input_amount,
+      output_amount,
+      is_exact_input: self.exact_input,
+      via,
+    };
+
+    ensure!(
+      swap.via.len() <= relics_protocol::Swap::MAX_HOPS,
+      "swap must not be routed through more than {} Relics",
+      relics_protocol::Swap::MAX_HOPS
+    );
+
+    ensure!(
+      swap.is_valid(),
+      "swap route must not repeat a Relic or route through {RELIC_NAME}"
+    );
+
+    // the transaction is deciphered as if it was mined in the next block
+    let next_height = u32::try_from(wallet.bitcoin_client().get_block_count()? + 1)?;
+
+    ensure!(
+      swap.via.is_empty()
+        || KeepsakeExtension::SwapRoutes.is_active(wallet.chain().network(), next_height),
+      "swap routes are not active before block {}",
+      KeepsakeExtension::SwapRoutes.activation_height(wallet.chain().network()),
+    );
+
     let keepsake = Keepsake {
-      swap: Some(relics_protocol::Swap {
-        input: (input_id != RELIC_ID).then_some(input_id),
-        output: (output_id != RELIC_ID).then_some(output_id),
-        input_amount,
-        output_amount,
-        is_exact_input: self.exact_input,
-      }),
+      swaps: vec![swap],
       transfers,
       ..default()
     };
@@ -167,6 +213,12 @@ impl Swap {
       &unfunded_transaction,
     )?;
 
+    if self.psbt {
+      return Ok(Some(Box::new(wallet.create_relic_psbt(
+        consensus::encode::deserialize(&unsigned_transaction)?,
+      )?)));
+    }
+
     let signed_transaction = wallet
       .bitcoin_client()
       .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
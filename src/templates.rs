@@ -21,6 +21,7 @@ pub(crate) use {
   },
   rare::RareTxt,
   sat::SatHtml,
+  search::SearchHtml,
 };
 
 pub use {
@@ -54,6 +55,7 @@ pub mod runes;
 pub mod sat;
 pub mod sealing;
 pub mod sealings;
+mod search;
 pub mod status;
 pub mod transaction;
 
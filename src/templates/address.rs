@@ -7,6 +7,7 @@ pub(crate) struct AddressHtml {
   pub(crate) inscriptions: Vec<InscriptionId>,
   pub(crate) sat_balance: u64,
   pub(crate) runes_balances: Vec<(SpacedRune, Decimal, Option<char>)>,
+  pub(crate) relics_balances: Vec<(SpacedRelic, Decimal, Option<char>)>,
 }
 
 impl PageContent for AddressHtml {
@@ -52,6 +53,14 @@ mod tests {
           Some('F'),
         ),
       ],
+      relics_balances: vec![(
+        SpacedRelic::from_str("TESTRELIC").unwrap(),
+        Decimal {
+          scale: 8,
+          value: 150000000,
+        },
+        None,
+      )],
     }
   }
 
@@ -84,6 +93,13 @@ mod tests {
     assert_regex_match!(address_html, expected_pattern);
   }
 
+  #[test]
+  fn test_relics_balances_rendering() {
+    let address_html = setup();
+    let expected_pattern = r#".*<dt>relics balances</dt>\n\s*<dd><a class=monospace href=/relic/TESTRELIC>TESTRELIC</a>: 1.5¤</dd>.*"#;
+    assert_regex_match!(address_html, expected_pattern);
+  }
+
   #[test]
   fn test_outputs_rendering() {
     let address_html = setup();
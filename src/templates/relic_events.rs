@@ -4,6 +4,8 @@ use super::*;
 pub struct RelicEventsHtml {
   pub spaced_relic: SpacedRelic,
   pub events: Vec<Event>,
+  /// cursor of the next page when paginating with `?after=`
+  pub next: Option<EventCursor>,
 }
 
 impl PageContent for RelicEventsHtml {
@@ -0,0 +1,66 @@
+use super::*;
+
+/// Shown instead of redirecting when a search query names both a Rune and a Relic
+#[derive(Boilerplate)]
+pub(crate) struct SearchHtml {
+  pub(crate) query: String,
+  pub(crate) rune: SpacedRune,
+  pub(crate) relic: SpacedRelic,
+  /// whether `relic` was enshrined or only sealed
+  pub(crate) enshrined: bool,
+}
+
+impl PageContent for SearchHtml {
+  fn title(&self) -> String {
+    format!("Search {}", self.query)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn enshrined_relic() {
+    assert_regex_match!(
+      SearchHtml {
+        query: "ABCD".into(),
+        rune: "AB•CD".parse().unwrap(),
+        relic: "ABC•D".parse().unwrap(),
+        enshrined: true,
+      },
+      "<h1>Search</h1>
+      <p>ABCD names both a Rune and a Relic.</p>
+      <dl>
+        <dt>rune</dt>
+        <dd><a href=/rune/AB•CD>AB•CD</a></dd>
+        <dt>relic</dt>
+        <dd><a href=/relic/ABC•D>ABC•D</a></dd>
+      </dl>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn sealed_relic() {
+    assert_regex_match!(
+      SearchHtml {
+        query: "ABCD".into(),
+        rune: "ABCD".parse().unwrap(),
+        relic: "ABCD".parse().unwrap(),
+        enshrined: false,
+      },
+      "<h1>Search</h1>
+      <p>ABCD names both a Rune and a Relic.</p>
+      <dl>
+        <dt>rune</dt>
+        <dd><a href=/rune/ABCD>ABCD</a></dd>
+        <dt>sealed relic</dt>
+        <dd><a href=/sealing/ABCD>ABCD</a></dd>
+      </dl>
+      "
+      .unindent()
+    );
+  }
+}
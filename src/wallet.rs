@@ -23,6 +23,7 @@ use {
 
 pub mod batch;
 pub mod entry;
+pub mod relic_psbt;
 pub mod transaction_builder;
 pub mod wallet_constructor;
 
@@ -83,6 +84,7 @@ pub(crate) struct Wallet {
   rpc_url: Url,
   utxos: BTreeMap<OutPoint, TxOut>,
   ord_client: reqwest::blocking::Client,
+  relics_client: relics_client::blocking::Client,
   inscription_info: BTreeMap<InscriptionId, api::Inscription>,
   output_info: BTreeMap<OutPoint, api::Output>,
   inscriptions: BTreeMap<SatPoint, Vec<InscriptionId>>,
@@ -167,6 +169,15 @@ impl Wallet {
   }
 
   pub(crate) fn lock_non_cardinal_outputs(&self) -> Result {
+    self.lock_non_cardinal_outputs_excluding(&BTreeSet::new())
+  }
+
+  /// Like `lock_non_cardinal_outputs`, but leaves the `excluded` outputs alone, e.g. outputs that
+  /// were spent since the wallet was loaded and can no longer be locked
+  pub(crate) fn lock_non_cardinal_outputs_excluding(
+    &self,
+    excluded: &BTreeSet<OutPoint>,
+  ) -> Result {
     let inscriptions = self
       .inscriptions()
       .keys()
@@ -196,7 +207,7 @@ impl Wallet {
         inscriptions.contains(utxo) || runic_outputs.contains(utxo) || relic_outputs.contains(utxo)
       })
       .cloned()
-      .filter(|utxo| !locked.contains(utxo))
+      .filter(|utxo| !locked.contains(utxo) && !excluded.contains(utxo))
       .collect::<Vec<OutPoint>>();
 
     if !self.bitcoin_client().lock_unspent(&outputs)? {
@@ -332,6 +343,15 @@ impl Wallet {
   pub(crate) fn get_required_relic_outputs(
     &self,
     required: Vec<(SpacedRelic, u128)>,
+  ) -> Result<(Vec<OutPoint>, BTreeMap<SpacedRelic, u128>)> {
+    self.get_required_relic_outputs_excluding(required, &BTreeSet::new())
+  }
+
+  /// Like `get_required_relic_outputs`, but never selects any of the `excluded` outputs
+  pub(crate) fn get_required_relic_outputs_excluding(
+    &self,
+    required: Vec<(SpacedRelic, u128)>,
+    excluded: &BTreeSet<OutPoint>,
   ) -> Result<(Vec<OutPoint>, BTreeMap<SpacedRelic, u128>)> {
     let inscribed_outputs = self
       .inscriptions()
@@ -344,7 +364,11 @@ impl Wallet {
     let balances = self
       .get_relic_outputs()?
       .into_iter()
-      .filter(|output| !inscribed_outputs.contains(output) && !runic_outputs.contains(output))
+      .filter(|output| {
+        !inscribed_outputs.contains(output)
+          && !runic_outputs.contains(output)
+          && !excluded.contains(output)
+      })
       .map(|output| {
         self
           .get_relics_balances_in_output(&output)
@@ -429,6 +453,22 @@ impl Wallet {
     )
   }
 
+  /// Information about `output` from the ord server, for outputs that are not in this wallet
+  pub(crate) fn get_output(&self, output: &OutPoint) -> Result<api::Output> {
+    self
+      .relics_client
+      .output(*output)?
+      .ok_or_else(|| anyhow!("output {output} not found"))
+  }
+
+  /// Validate an offer PSBT with the ord server, see `ord wallet offer`
+  pub(crate) fn validate_relic_offer(&self, psbt: &str) -> Result<api::RelicOfferValidation> {
+    self
+      .relics_client
+      .validate_offer(psbt)
+      .context("failed to validate offer")
+  }
+
   pub(crate) fn get_rune(
     &self,
     rune: Rune,
@@ -458,28 +498,12 @@ impl Wallet {
     &self,
     relic: Relic,
   ) -> Result<Option<(RelicId, RelicEntry, Option<InscriptionId>)>> {
-    let url = self
-      .rpc_url
-      .join(&format!("/relic/{}", SpacedRelic { relic, spacers: 0 }))?;
-
-    let response = self.ord_client.get(url.clone()).send()?;
-
-    if !response.status().is_success() {
-      return Ok(None);
-    }
-
-    let text = response.text()?;
-
-    // First parse as a generic Value so we see *everything* that was returned
-    let json_value: serde_json::Value =
-      serde_json::from_str(&text).map_err(|e| anyhow!("Failed to parse JSON into `Value`: {e}"))?;
-
-    let relic_parsed = parse_json_to_relic(json_value)?;
-    Ok(Some((
-      relic_parsed.id,
-      relic_parsed.entry,
-      relic_parsed.owner,
-    )))
+    Ok(
+      self
+        .relics_client
+        .relic(SpacedRelic { relic, spacers: 0 })?
+        .map(|relic| (relic.id, relic.entry, relic.owner)),
+    )
   }
 
   pub(crate) fn get_change_address(&self) -> Result<Address> {
@@ -948,6 +972,23 @@ impl Wallet {
     unsigned_transaction: Transaction,
     dry_run: bool,
     burn_amount: Option<Amount>,
+  ) -> Result<(Txid, String, u64)> {
+    self.sign_and_broadcast_chained_transaction(
+      unsigned_transaction,
+      dry_run,
+      burn_amount,
+      &BTreeMap::new(),
+    )
+  }
+
+  /// Like `sign_and_broadcast_transaction`, but the transaction may also spend `unconfirmed`
+  /// outputs of transactions that were broadcast since the wallet was loaded
+  pub(super) fn sign_and_broadcast_chained_transaction(
+    &self,
+    unsigned_transaction: Transaction,
+    dry_run: bool,
+    burn_amount: Option<Amount>,
+    unconfirmed: &BTreeMap<OutPoint, TxOut>,
   ) -> Result<(Txid, String, u64)> {
     let unspent_outputs = self.utxos();
 
@@ -987,7 +1028,10 @@ impl Wallet {
 
     let mut fee = 0;
     for txin in unsigned_transaction.input.iter() {
-      let Some(txout) = unspent_outputs.get(&txin.previous_output) else {
+      let Some(txout) = unspent_outputs
+        .get(&txin.previous_output)
+        .or_else(|| unconfirmed.get(&txin.previous_output))
+      else {
         panic!("input {} not found in utxos", txin.previous_output);
       };
       fee += txout.value.to_sat();
@@ -1000,167 +1044,3 @@ impl Wallet {
     Ok((txid, psbt, fee))
   }
 }
-
-fn parse_json_to_relic(json_value: serde_json::Value) -> Result<api::Relic> {
-  use anyhow::{anyhow, Result};
-  use serde_json::Value;
-
-  let get_str = |key| -> Result<&str> {
-    json_value
-      .get(key)
-      .and_then(|v| v.as_str())
-      .ok_or_else(|| anyhow!("Missing or invalid '{}'", key))
-  };
-
-  let get_u64 = |key| -> Result<u64> {
-    json_value
-      .get(key)
-      .and_then(|v| v.as_u64())
-      .ok_or_else(|| anyhow!("Missing or invalid '{}'", key))
-  };
-
-  let parse_json = |key| -> Result<serde_json::Value> {
-    json_value
-      .get(key)
-      .cloned()
-      .ok_or_else(|| anyhow!("Missing '{}'", key))
-  };
-
-  // Inline numeric parsing
-  let parse_u128 = |v: &Value| -> Result<u128> {
-    match v {
-      Value::Number(num) => num
-        .to_string()
-        .parse::<u128>()
-        .map_err(|_| anyhow!("Invalid u128")),
-      Value::String(s) => s
-        .parse::<u128>()
-        .map_err(|_| anyhow!("Invalid u128 string")),
-      _ => Err(anyhow!("Expected number/string for u128")),
-    }
-  };
-
-  let parse_option_u128 = |val: Option<&Value>| -> Result<Option<u128>> {
-    match val {
-      Some(Value::Null) | None => Ok(None),
-      Some(v) => Ok(Some(parse_u128(v)?)),
-    }
-  };
-
-  let parse_option_u32 = |val: Option<&Value>| -> Result<Option<u32>> {
-    match val {
-      Some(Value::Null) | None => Ok(None),
-      Some(v) => {
-        let x = parse_u128(v)?;
-        if x <= u128::from(u32::MAX) {
-          Ok(Some(x.try_into()?))
-        } else {
-          Err(anyhow!("Value exceeds u32::MAX"))
-        }
-      }
-    }
-  };
-
-  let parse_option_u8 = |val: Option<&Value>| -> Result<Option<u8>> {
-    match val {
-      Some(Value::Null) | None => Ok(None),
-      Some(v) => {
-        let x = parse_u128(v)?;
-        if x <= u128::from(u8::MAX) {
-          Ok(Some(x.try_into()?))
-        } else {
-          Err(anyhow!("Value exceeds u8::MAX"))
-        }
-      }
-    }
-  };
-
-  // PriceModel parser
-  let parse_option_price_model = |val: Option<&Value>| -> Result<Option<PriceModel>> {
-    match val {
-      Some(Value::Null) | None => Ok(None),
-      Some(Value::Number(_) | Value::String(_)) => {
-        let fixed = parse_u128(val.unwrap())?;
-        Ok(Some(PriceModel::Fixed(fixed)))
-      }
-      Some(Value::Object(map)) => {
-        let a = parse_u128(map.get("a").ok_or_else(|| anyhow!("Missing 'a'"))?)?;
-        let b = parse_u128(map.get("b").ok_or_else(|| anyhow!("Missing 'b'"))?)?;
-        Ok(Some(PriceModel::Formula { a, b }))
-      }
-      _ => Err(anyhow!("Invalid price model format")),
-    }
-  };
-
-  // Manually parse `mint_terms`
-  let mint_terms: Option<MintTerms> = match json_value.get("mint_terms") {
-    Some(Value::Object(obj)) => Some(MintTerms {
-      amount: parse_option_u128(obj.get("amount"))?,
-      block_cap: parse_option_u32(obj.get("block_cap"))?,
-      cap: parse_option_u128(obj.get("cap"))?,
-      max_unmints: parse_option_u32(obj.get("max_unmints"))?,
-      price: parse_option_price_model(obj.get("price"))?,
-      seed: parse_option_u128(obj.get("seed"))?,
-      tx_cap: parse_option_u8(obj.get("tx_cap"))?,
-    }),
-    _ => None,
-  };
-
-  // Now your existing parsing continues:
-  let id = RelicId::from_str(get_str("id")?)?;
-  let owner = json_value
-    .get("owner")
-    .and_then(|v| v.as_str())
-    .map(str::parse)
-    .transpose()?;
-  let block = get_u64("block")?;
-  let enshrining = Txid::from_str(get_str("enshrining")?)?;
-  let fee = get_u64("fee")?.try_into()?;
-  let number = get_u64("number")?;
-  let timestamp = get_u64("timestamp")?;
-  let mintable = json_value
-    .get("mintable")
-    .and_then(|v| v.as_bool())
-    .ok_or_else(|| anyhow!("Missing or invalid 'mintable'"))?;
-  let spaced_relic = SpacedRelic::from_str(get_str("spaced_relic")?)?;
-
-  let boost_terms = json_value
-    .get("boost_terms")
-    .and_then(|v| serde_json::from_value(v.clone()).ok());
-  let owner_sequence_number = json_value
-    .get("owner_sequence_number")
-    .and_then(|v| serde_json::from_value(v.clone()).ok());
-  let symbol = json_value
-    .get("symbol")
-    .and_then(|v| serde_json::from_value(v.clone()).ok());
-  let pool = json_value
-    .get("pool")
-    .and_then(|v| serde_json::from_value(v.clone()).ok());
-  let state: RelicState = serde_json::from_value(parse_json("state")?)?;
-  let thumb = json_value
-    .get("thumb")
-    .and_then(|v| serde_json::from_value(v.clone()).ok());
-
-  let entry = RelicEntry {
-    block,
-    enshrining,
-    fee,
-    number,
-    spaced_relic,
-    symbol,
-    owner_sequence_number,
-    boost_terms,
-    mint_terms,
-    state,
-    pool,
-    timestamp,
-  };
-
-  Ok(api::Relic {
-    entry,
-    id,
-    mintable,
-    owner,
-    thumb,
-  })
-}
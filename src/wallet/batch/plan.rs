@@ -48,7 +48,7 @@ impl Plan {
     runic_utxos: BTreeSet<OutPoint>,
     utxos: &BTreeMap<OutPoint, TxOut>,
     wallet: &Wallet,
-  ) -> SubcommandResult {
+  ) -> Result<Output> {
     let Transactions {
       commit_tx,
       commit_vout,
@@ -81,7 +81,7 @@ impl Plan {
 
       let reveal_psbt = Psbt::from_unsigned_tx(Self::remove_witnesses(reveal_tx.clone()))?;
 
-      return Ok(Some(Box::new(self.output(
+      return Ok(self.output(
         commit_tx.compute_txid(),
         Some(commit_psbt),
         reveal_tx.compute_txid(),
@@ -90,7 +90,7 @@ impl Plan {
         total_fees,
         self.inscriptions.clone(),
         rune,
-      ))));
+      ));
     }
 
     let signed_commit_tx = wallet
@@ -157,9 +157,7 @@ impl Plan {
         ),
       )?;
 
-      Ok(Some(Box::new(
-        wallet.wait_for_maturation(rune_info.rune.rune)?,
-      )))
+      wallet.wait_for_maturation(rune_info.rune.rune)
     } else {
       let reveal = match wallet
         .bitcoin_client()
@@ -173,7 +171,7 @@ impl Plan {
         }
       };
 
-      Ok(Some(Box::new(self.output(
+      Ok(self.output(
         commit_txid,
         None,
         reveal,
@@ -182,7 +180,7 @@ impl Plan {
         total_fees,
         self.inscriptions.clone(),
         rune,
-      ))))
+      ))
     }
   }
 
@@ -0,0 +1,292 @@
+use {super::*, crate::relics::RelicAllocation};
+
+/// Unsigned PSBT of a Relic transaction, for signing with an external signer
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct RelicPsbt {
+  pub psbt: String,
+  pub txid: Txid,
+  pub inputs: Vec<RelicPsbtInput>,
+  /// expected allocation of the input Relics, Relics created by the operations of the Keepsake are
+  /// not included
+  pub outputs: Vec<RelicPsbtOutput>,
+  pub burned: BTreeMap<SpacedRelic, Decimal>,
+  /// pool shares that stay locked in their pool
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub burned_shares: BTreeMap<SpacedRelic, u128>,
+  /// operations of the Keepsake that change balances beyond its transfers
+  pub operations: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct RelicPsbtInput {
+  pub outpoint: OutPoint,
+  pub relics: BTreeMap<SpacedRelic, Decimal>,
+  /// pool shares, by the Relic of the pool
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub shares: BTreeMap<SpacedRelic, u128>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct RelicPsbtOutput {
+  pub vout: u32,
+  pub address: Option<Address<NetworkUnchecked>>,
+  pub relics: BTreeMap<SpacedRelic, Decimal>,
+  /// pool shares, by the Relic of the pool
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub shares: BTreeMap<SpacedRelic, u128>,
+}
+
+/// Relic balances and pool shares of the inputs of a transaction, by id
+struct RelicInputs {
+  balances: Vec<BTreeMap<RelicId, u128>>,
+  shares: Vec<BTreeMap<RelicId, u128>>,
+  relics: BTreeMap<RelicId, SpacedRelic>,
+}
+
+impl Wallet {
+  /// Relic balances and pool shares of the inputs of `tx`, inputs that are not outputs of this
+  /// wallet are looked up on the ord server
+  fn relic_inputs(&self, tx: &Transaction) -> Result<RelicInputs> {
+    let mut inputs = RelicInputs {
+      balances: Vec::new(),
+      shares: Vec::new(),
+      relics: BTreeMap::new(),
+    };
+    let mut ids = BTreeMap::<SpacedRelic, RelicId>::new();
+
+    let mut id =
+      |spaced_relic: SpacedRelic, relics: &mut BTreeMap<RelicId, SpacedRelic>| -> Result<RelicId> {
+        if let Some(id) = ids.get(&spaced_relic) {
+          return Ok(*id);
+        }
+        let (id, _entry, _owner) = self
+          .get_relic(spaced_relic.relic)?
+          .with_context(|| format!("relic `{spaced_relic}` not found"))?;
+        ids.insert(spaced_relic, id);
+        relics.insert(id, spaced_relic);
+        Ok(id)
+      };
+
+    for input in &tx.input {
+      let output = match self.output_info.get(&input.previous_output) {
+        Some(info) => info.clone(),
+        None => self.get_output(&input.previous_output)?,
+      };
+
+      let mut balances = BTreeMap::new();
+      for (spaced_relic, pile) in output.relics {
+        balances.insert(id(spaced_relic, &mut inputs.relics)?, pile.amount);
+      }
+      inputs.balances.push(balances);
+
+      let mut shares = BTreeMap::new();
+      for (spaced_relic, amount) in output.shares {
+        shares.insert(id(spaced_relic, &mut inputs.relics)?, amount);
+      }
+      inputs.shares.push(shares);
+    }
+
+    Ok(inputs)
+  }
+
+  /// Height of the next block, whose rules apply to the transactions of a PSBT
+  fn next_height(&self) -> Result<u32> {
+    Ok(u32::try_from(self.bitcoin_client().get_block_count()? + 1).unwrap())
+  }
+
+  /// Create an unsigned PSBT for `unsigned_transaction`, annotated with the Relic balances of its
+  /// inputs and the expected allocation to its outputs
+  pub(crate) fn create_relic_psbt(&self, unsigned_transaction: Transaction) -> Result<RelicPsbt> {
+    self.annotate_relic_psbt(Psbt::from_unsigned_tx(unsigned_transaction)?)
+  }
+
+  /// Like `create_relic_psbt`, but keeps the fields of `psbt` that are already set, like the
+  /// signature of the seller of an offer
+  pub(crate) fn annotate_relic_psbt(&self, psbt: Psbt) -> Result<RelicPsbt> {
+    let unsigned_transaction = psbt.unsigned_tx.clone();
+
+    let inputs = self.relic_inputs(&unsigned_transaction)?;
+
+    let height = self.next_height()?;
+
+    let allocation = RelicAllocation::new(
+      &unsigned_transaction,
+      &inputs.balances,
+      &inputs.shares,
+      self.chain().network(),
+      height,
+    );
+
+    let mut psbt = Psbt::deserialize(
+      &base64::engine::general_purpose::STANDARD.decode(
+        self
+          .bitcoin_client()
+          .wallet_process_psbt(
+            &base64::engine::general_purpose::STANDARD.encode(psbt.serialize()),
+            Some(false),
+            None,
+            None,
+          )?
+          .psbt,
+      )?,
+    )?;
+
+    for ((input, balances), shares) in psbt
+      .inputs
+      .iter_mut()
+      .zip(&inputs.balances)
+      .zip(&inputs.shares)
+    {
+      RelicAllocation::annotate(&mut input.proprietary, balances);
+      RelicAllocation::annotate_shares(&mut input.proprietary, shares);
+    }
+
+    for ((output, balances), shares) in psbt
+      .outputs
+      .iter_mut()
+      .zip(&allocation.outputs)
+      .zip(&allocation.shares)
+    {
+      RelicAllocation::annotate(&mut output.proprietary, balances);
+      RelicAllocation::annotate_shares(&mut output.proprietary, shares);
+    }
+
+    let decimals = |balances: &BTreeMap<RelicId, u128>| {
+      balances
+        .iter()
+        .map(|(id, amount)| {
+          (
+            inputs.relics[id],
+            Decimal {
+              value: *amount,
+              scale: Enshrining::DIVISIBILITY,
+            },
+          )
+        })
+        .collect::<BTreeMap<SpacedRelic, Decimal>>()
+    };
+
+    let shares = |shares: &BTreeMap<RelicId, u128>| {
+      shares
+        .iter()
+        .map(|(id, amount)| (inputs.relics[id], *amount))
+        .collect::<BTreeMap<SpacedRelic, u128>>()
+    };
+
+    let operations =
+      match Keepsake::decipher_at(&unsigned_transaction, self.chain().network(), height) {
+        Some(RelicArtifact::Keepsake(keepsake)) => [
+          (keepsake.sealing, "seal"),
+          (keepsake.enshrining.is_some(), "enshrine"),
+          (keepsake.mint.is_some_and(|mint| !mint.is_unmint), "mint"),
+          (keepsake.mint.is_some_and(|mint| mint.is_unmint), "unmint"),
+          (!keepsake.swaps.is_empty(), "swap"),
+          (
+            keepsake
+              .liquidity
+              .is_some_and(|liquidity| !liquidity.is_remove),
+            "add-liquidity",
+          ),
+          (
+            keepsake
+              .liquidity
+              .is_some_and(|liquidity| liquidity.is_remove),
+            "remove-liquidity",
+          ),
+          (keepsake.claim.is_some(), "claim"),
+        ]
+        .into_iter()
+        .filter(|(present, _)| *present)
+        .map(|(_, operation)| operation.to_string())
+        .collect(),
+        _ => Vec::new(),
+      };
+
+    Ok(RelicPsbt {
+      psbt: base64::engine::general_purpose::STANDARD.encode(psbt.serialize()),
+      txid: unsigned_transaction.compute_txid(),
+      inputs: unsigned_transaction
+        .input
+        .iter()
+        .zip(inputs.balances.iter().zip(&inputs.shares))
+        .map(|(input, (balances, input_shares))| RelicPsbtInput {
+          outpoint: input.previous_output,
+          relics: decimals(balances),
+          shares: shares(input_shares),
+        })
+        .collect(),
+      outputs: unsigned_transaction
+        .output
+        .iter()
+        .zip(allocation.outputs.iter().zip(&allocation.shares))
+        .enumerate()
+        .map(
+          |(vout, (tx_out, (balances, output_shares)))| RelicPsbtOutput {
+            vout: vout.try_into().unwrap(),
+            address: self
+              .chain()
+              .address_from_script(&tx_out.script_pubkey)
+              .ok()
+              .map(|address| uncheck(&address)),
+            relics: decimals(balances),
+            shares: shares(output_shares),
+          },
+        )
+        .collect(),
+      burned: decimals(&allocation.burned),
+      burned_shares: shares(&allocation.burned_shares),
+      operations,
+    })
+  }
+
+  /// Check that the Relic balances and pool shares of the inputs of `psbt` did not change since it
+  /// was created and that its outputs receive the annotated allocation
+  pub(crate) fn verify_relic_psbt(&self, psbt: &Psbt) -> Result {
+    let annotated = |proprietary: &BTreeMap<_, Vec<u8>>| -> Result<_> {
+      let missing = "PSBT has no relic annotations, create it with `--psbt`";
+      Ok((
+        RelicAllocation::annotation(proprietary)?.context(missing)?,
+        RelicAllocation::share_annotation(proprietary)?.context(missing)?,
+      ))
+    };
+
+    let inputs = self.relic_inputs(&psbt.unsigned_tx)?;
+
+    for (i, (input, expected)) in psbt
+      .inputs
+      .iter()
+      .zip(inputs.balances.iter().zip(&inputs.shares))
+      .enumerate()
+    {
+      let (balances, shares) = annotated(&input.proprietary)?;
+      ensure!(
+        (&balances, &shares) == expected,
+        "relic balances of input {} changed since the PSBT was created",
+        psbt.unsigned_tx.input[i].previous_output,
+      );
+    }
+
+    let allocation = RelicAllocation::new(
+      &psbt.unsigned_tx,
+      &inputs.balances,
+      &inputs.shares,
+      self.chain().network(),
+      self.next_height()?,
+    );
+
+    for (vout, (output, expected)) in psbt
+      .outputs
+      .iter()
+      .zip(allocation.outputs.iter().zip(&allocation.shares))
+      .enumerate()
+    {
+      let (balances, shares) = annotated(&output.proprietary)?;
+      ensure!(
+        (&balances, &shares) == expected,
+        "relic allocation of output {vout} does not match the PSBT annotation",
+      );
+    }
+
+    Ok(())
+  }
+}
@@ -107,6 +107,9 @@ impl WalletConstructor {
 
     let status = self.get_server_status()?;
 
+    let relics_client =
+      relics_client::blocking::Client::with_client(self.rpc_url.clone(), self.ord_client.clone());
+
     Ok(Wallet {
       bitcoin_client,
       database,
@@ -119,6 +122,7 @@ impl WalletConstructor {
       locked_utxos,
       ord_client: self.ord_client,
       output_info,
+      relics_client,
       rpc_url: self.rpc_url,
       settings: self.settings,
       utxos,
@@ -8,6 +8,9 @@ use {
     subcommand::decode::{CompactInscription, CompactOutput, RawOutput},
     Envelope, Inscription,
   },
+  relics::{
+    Keepsake, KeepsakeExplanation, KeepsakeField, KeepsakeFlag, MultiMint, RelicArtifact, RelicFlaw,
+  },
 };
 
 fn transaction() -> Vec<u8> {
@@ -68,6 +71,8 @@ fn from_file() {
         stutter: false,
       }],
       runestone: Some(Artifact::Runestone(Runestone::default())),
+      keepsake: None,
+      keepsake_explanation: None,
     },
   );
 }
@@ -91,6 +96,8 @@ fn from_stdin() {
         stutter: false,
       }],
       runestone: Some(Artifact::Runestone(Runestone::default())),
+      keepsake: None,
+      keepsake_explanation: None,
     },
   );
 }
@@ -123,6 +130,8 @@ fn from_core() {
         stutter: false,
       }],
       runestone: None,
+      keepsake: None,
+      keepsake_explanation: None,
     },
   );
 }
@@ -147,6 +156,100 @@ fn compact() {
         unrecognized_even_field: false,
       }],
       runestone: Some(Artifact::Runestone(Runestone::default())),
+      keepsake: None,
+      keepsake_explanation: None,
+    },
+  );
+}
+
+fn keepsake_transaction(keepsake: &Keepsake) -> Transaction {
+  Transaction {
+    version: Version(2),
+    lock_time: LockTime::ZERO,
+    input: Vec::new(),
+    output: vec![TxOut {
+      script_pubkey: keepsake.encipher(),
+      value: Amount::from_sat(0),
+    }],
+  }
+}
+
+#[test]
+fn keepsake_from_hex() {
+  let keepsake = Keepsake {
+    mint: Some(MultiMint {
+      count: 2,
+      base_limit: 1000,
+      is_unmint: false,
+      relic: RelicId { block: 5, tx: 1 },
+    }),
+    ..default()
+  };
+
+  pretty_assert_eq!(
+    CommandBuilder::new("decode --compact --file transaction.hex")
+      .write(
+        "transaction.hex",
+        format!(
+          "{}\n",
+          bitcoin::consensus::encode::serialize_hex(&keepsake_transaction(&keepsake))
+        ),
+      )
+      .run_and_deserialize_output::<CompactOutput>(),
+    CompactOutput {
+      inscriptions: Vec::new(),
+      runestone: None,
+      keepsake: Some(RelicArtifact::Keepsake(keepsake)),
+      keepsake_explanation: None,
+    },
+  );
+}
+
+#[test]
+fn explain_keepsake_cenotaph() {
+  // the base token cannot be multi minted
+  let keepsake = Keepsake {
+    mint: Some(MultiMint {
+      count: 1,
+      base_limit: 1,
+      is_unmint: false,
+      relic: RelicId { block: 1, tx: 0 },
+    }),
+    ..default()
+  };
+
+  let mut buffer = Vec::new();
+  keepsake_transaction(&keepsake)
+    .consensus_encode(&mut buffer)
+    .unwrap();
+
+  let output = CommandBuilder::new("decode --explain")
+    .stdin(buffer)
+    .run_and_deserialize_output::<RawOutput>();
+
+  let field = |tag: u128, name: &str, value: u128| KeepsakeField {
+    tag,
+    name: Some(name.into()),
+    value,
+  };
+
+  pretty_assert_eq!(
+    output.keepsake_explanation.unwrap(),
+    KeepsakeExplanation {
+      fields: vec![
+        field(80, "MultiMintCount", 1),
+        field(82, "MultiMintBaseLimit", 1),
+        field(84, "MultiMintRelic", 1),
+        field(84, "MultiMintRelic", 0),
+        field(2, "Flags", 1 << 6),
+      ],
+      flags: vec![KeepsakeFlag {
+        bit: 6,
+        name: Some("MultiMint".into()),
+      }],
+      transfers: Vec::new(),
+      flaw: Some(RelicFlaw::InvalidBaseTokenMint),
+      reason: Some(RelicFlaw::InvalidBaseTokenMint.to_string()),
     },
   );
 }
@@ -370,6 +370,7 @@ fn get_output() {
           .unwrap()
           .assume_checked()
       ),
+      shares: BTreeMap::new(),
       spent: false,
       transaction: txid,
       value: 3 * 50 * COIN_VALUE,
@@ -710,6 +711,8 @@ fn get_decode_tx() {
     api::Decode {
       inscriptions,
       runestone,
+      keepsake: None,
+      keepsake_explanation: None,
     }
   );
 }
@@ -782,6 +785,7 @@ fn outputs_address() {
           .unwrap()
           .assume_checked()
       ),
+      shares: BTreeMap::new(),
       spent: false,
       transaction: cardinal_send.txid,
       value: 2 * COIN_VALUE,
@@ -827,6 +831,7 @@ fn outputs_address() {
           .unwrap()
           .assume_checked()
       ),
+      shares: BTreeMap::new(),
       spent: false,
       transaction: rune_send.txid,
       value: 9901,
@@ -865,6 +870,7 @@ fn outputs_address() {
           .unwrap()
           .assume_checked()
       ),
+      shares: BTreeMap::new(),
       spent: false,
       transaction: inscription_send.txid,
       value: 9901,
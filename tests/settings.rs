@@ -23,6 +23,9 @@ fn default() {
   "index": ".*index\.redb",
   "index_addresses": false,
   "index_cache_size": \d+,
+  "index_memory_pool": false,
+  "index_relic_history": false,
+  "index_relic_prices": false,
   "index_relics": false,
   "index_runes": false,
   "index_sats": false,
@@ -1,6 +1,8 @@
 use {
   super::*,
-  ord::{relics::RELIC_NAME, subcommand::wallet::swap},
+  base64::Engine,
+  bitcoin::psbt::{raw::ProprietaryKey, Psbt},
+  ord::{relics::RELIC_NAME, subcommand::wallet::swap, RELIC_ID},
 };
 
 fn setup() -> (mockcore::Handle, TestServer) {
@@ -70,6 +72,571 @@ fn enshrine_curved_relic_works() {
   relic_enshrine_curved(&core, &ord, relic, 0, 0, 4_200_000);
 }
 
+#[test]
+fn relic_events_can_be_followed_from_the_first_page() {
+  let (core, ord) = setup();
+  mint_base(&core, &ord);
+  let relic = SpacedRelic::from_str("BASIC•TEST•RELIC").unwrap();
+  seal(&core, &ord, relic);
+  relic_enshrine(&core, &ord, relic, 0, 0);
+
+  // the enshrining itself is not part of the history of the relic
+  relic_mint(&core, &ord, relic, 1, None, None);
+  core.mine_blocks(1);
+
+  let events = |path: String| {
+    serde_json::from_str::<api::RelicEvents>(&ord.json_request(path).text().unwrap()).unwrap()
+  };
+
+  let first_page = events(format!("/events/{relic}/0"));
+  let next = first_page.next.unwrap();
+  assert_eq!(next, first_page.events[0].cursor());
+
+  assert!(events(format!("/events/{relic}/0?after={next}"))
+    .events
+    .is_empty());
+
+  relic_mint(&core, &ord, relic, 1, None, None);
+  core.mine_blocks(1);
+
+  let following = events(format!("/events/{relic}/0?after={next}"));
+  assert!(!following.events.is_empty());
+  assert!(following.events.iter().all(|event| event.cursor() > next));
+  assert_eq!(following.next, None);
+}
+
+#[test]
+fn search_resolves_relics() {
+  let (core, ord) = setup();
+  mint_base(&core, &ord);
+  let relic = SpacedRelic::from_str("BASIC•TEST•RELIC").unwrap();
+  let inscription_id = seal(&core, &ord, relic);
+
+  let assert_search = |query: &str, path: &str| {
+    let response = ord.request(format!("/search/{query}"));
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.url(), &ord.url().join(path).unwrap());
+  };
+
+  assert_search("BASICTESTRELIC", &format!("/sealing/{relic}"));
+  assert_search(&inscription_id.to_string(), &format!("/sealing/{relic}"));
+
+  let id = relic_enshrine(&core, &ord, relic, 0, 0);
+
+  let entry = serde_json::from_str::<api::Relic>(
+    &ord.json_request(format!("/relic/{relic}")).text().unwrap(),
+  )
+  .unwrap();
+
+  assert_search("BASICTESTRELIC", &format!("/relic/{relic}"));
+  assert_search(&relic.to_string(), &format!("/relic/{relic}"));
+  assert_search(&id.to_string(), &format!("/relic/{relic}"));
+  assert_search(
+    &entry.entry.enshrining.to_string(),
+    &format!("/relic/{relic}"),
+  );
+}
+
+#[test]
+fn launch_relic_works() {
+  let (core, ord) = setup();
+  mint_base(&core, &ord);
+  let relic = SpacedRelic::from_str("BASIC•TEST•RELIC").unwrap();
+
+  let mut spawn =
+    CommandBuilder::new("--regtest --index-relics wallet launch --fee-rate 1 --launch launch.yaml")
+      .write(
+        "launch.yaml",
+        "relic: BASIC•TEST•RELIC
+symbol: T
+thumbnail: thumbnail.txt
+fee: 30
+mint_terms:
+  amount: 20
+  cap: 100
+  price: 0.01
+  seed: 1000
+  block_cap: 10
+  tx_cap: 5
+boost_terms:
+  rare_chance: 10000
+  rare_multiplier_cap: 5
+  ultra_rare_chance: 1000
+  ultra_rare_multiplier_cap: 10
+",
+      )
+      .write("thumbnail.txt", "thumbnail")
+      .core(&core)
+      .ord(&ord)
+      .spawn();
+
+  let mut buffer = String::new();
+
+  BufReader::new(spawn.child.stderr.as_mut().unwrap())
+    .read_line(&mut buffer)
+    .unwrap();
+
+  // confirm the sealing
+  core.mine_blocks(1);
+
+  let output = spawn.run_and_deserialize_output::<ord::subcommand::wallet::launch::Output>();
+
+  pretty_assert_eq!(output.relic, relic);
+  let sealing = output.sealing.unwrap();
+  assert!(sealing.reveal_broadcast);
+  assert!(output.enshrining.is_some());
+
+  // outputs of the reveal transaction are only locked until the enshrining is funded
+  assert!(core
+    .get_locked()
+    .iter()
+    .all(|outpoint| outpoint.txid != sealing.reveal));
+
+  core.mine_blocks(1);
+  ord.sync_server();
+
+  let response = ord.json_request(format!("/relic/{relic}"));
+  assert_eq!(response.status(), StatusCode::OK);
+
+  let entry: serde_json::Value = serde_json::from_str(&response.text().unwrap()).unwrap();
+  assert_eq!(entry["fee"], 30);
+  assert_eq!(entry["mint_terms"]["block_cap"], 10);
+  assert_eq!(entry["mint_terms"]["tx_cap"], 5);
+  assert_eq!(entry["boost_terms"]["ultra_rare_multiplier_cap"], 10);
+}
+
+#[test]
+fn launch_rejects_invalid_enshrining() {
+  let (core, ord) = setup();
+
+  CommandBuilder::new("--regtest --index-relics wallet launch --fee-rate 1 --launch launch.yaml")
+    .write(
+      "launch.yaml",
+      "relic: BASIC•TEST•RELIC
+symbol: T
+thumbnail: thumbnail.txt
+mint_terms:
+  amount: 20
+  cap: 100
+  price: 0.01
+  seed: 1000
+  block_cap: 10
+  tx_cap: 50
+",
+    )
+    .core(&core)
+    .ord(&ord)
+    .expected_exit_code(1)
+    .expected_stderr(
+      "error: invalid enshrining: cap hierarchy (cap >= block_cap >= tx_cap) violated\n",
+    )
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn split_relics_works() {
+  let (core, ord) = setup();
+  mint_base(&core, &ord);
+  let base = SpacedRelic::from_str(RELIC_NAME).unwrap();
+
+  let output =
+    CommandBuilder::new("--regtest --index-relics wallet split --fee-rate 1 --splits splits.yaml")
+      .write(
+        "splits.yaml",
+        format!(
+          "
+outputs:
+- address: bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw
+  relics:
+    {base}: 100
+- address: bcrt1pyrmadgg78e38ewfv0an8c6eppk2fttv5vnuvz04yza60qau5va0saknu8k
+  relics:
+    {base}: 100
+"
+        ),
+      )
+      .core(&core)
+      .ord(&ord)
+      .run_and_deserialize_output::<Split>();
+
+  assert!(output.chained.is_empty());
+
+  core.mine_blocks(1);
+
+  let relics = relic_balance(&core, &ord).relics.unwrap();
+  assert_eq!(relics[&base].value, 634205);
+  assert_eq!(relics[&base].scale, 2);
+}
+
+#[test]
+fn send_relics_with_psbt() {
+  let (core, ord) = setup();
+  mint_base(&core, &ord);
+  let base = SpacedRelic::from_str(RELIC_NAME).unwrap();
+
+  let output = CommandBuilder::new(format!(
+    "--regtest --index-relics wallet send --psbt --fee-rate 1 \
+    bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw 100:r:{base}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<ord::wallet::relic_psbt::RelicPsbt>();
+
+  assert_eq!(
+    output
+      .inputs
+      .iter()
+      .filter_map(|input| input.relics.get(&base))
+      .map(|decimal| decimal.to_string())
+      .collect::<Vec<String>>(),
+    ["6542.05"],
+  );
+  assert_eq!(output.outputs[1].relics[&base].to_string(), "6442.05");
+  assert_eq!(output.outputs[2].relics[&base].to_string(), "100");
+  assert!(output.burned.is_empty());
+  assert!(output.operations.is_empty());
+
+  // nothing was broadcast yet
+  assert_eq!(
+    relic_balance(&core, &ord).relics.unwrap()[&base].value,
+    654205
+  );
+
+  let broadcast = CommandBuilder::new(format!(
+    "--regtest --index-relics wallet broadcast-psbt --psbt {}",
+    output.psbt
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<ord::subcommand::wallet::broadcast_psbt::Output>();
+
+  assert_eq!(broadcast.txid, output.txid);
+
+  core.mine_blocks(1);
+
+  let relics = relic_balance(&core, &ord).relics.unwrap();
+  assert_eq!(relics[&base].value, 644205);
+  assert_eq!(relics[&base].scale, 2);
+}
+
+#[test]
+fn send_relics_to_multiple_recipients() {
+  let (core, ord) = setup();
+  mint_base(&core, &ord);
+  let base = SpacedRelic::from_str(RELIC_NAME).unwrap();
+
+  let alice = "bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw";
+  let bob = "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080";
+
+  let output = CommandBuilder::new(format!(
+    "--regtest --index-relics wallet send --fee-rate 1 \
+    --recipient {alice}:100:r:{base} \
+    --recipient {bob}:50:r:{base} \
+    --recipient {alice}:25:r:{base}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<ord::subcommand::wallet::send::RecipientsOutput>();
+
+  assert_eq!(
+    output
+      .outputs
+      .iter()
+      .map(|output| (output.vout, output.relics[&base].to_string()))
+      .collect::<Vec<(u32, String)>>(),
+    [(1, "6367.05".into()), (2, "125".into()), (3, "50".into()),],
+  );
+  assert_eq!(
+    output.outputs[2].address,
+    Some(bob.parse::<Address<NetworkUnchecked>>().unwrap()),
+  );
+
+  core.mine_blocks(1);
+
+  let outpoint = |vout| OutPoint {
+    txid: output.txid,
+    vout,
+  };
+
+  let outputs = relic_outputs(&ord);
+
+  assert_eq!(outputs[&base][&outpoint(1)], 6367_05000000);
+  assert_eq!(outputs[&base][&outpoint(2)], 125_00000000);
+  assert_eq!(outputs[&base][&outpoint(3)], 50_00000000);
+}
+
+#[test]
+fn send_relics_to_recipients_file() {
+  let (core, ord) = setup();
+  mint_base(&core, &ord);
+  let base = SpacedRelic::from_str(RELIC_NAME).unwrap();
+
+  let output = CommandBuilder::new(
+    "--regtest --index-relics wallet send --fee-rate 1 --recipients-file recipients.csv",
+  )
+  .write(
+    "recipients.csv",
+    format!(
+      "address,relic,amount\n\
+        bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw,{base},10\n\
+        bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080,{base},0\n"
+    ),
+  )
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .expected_stderr(format!(
+    "error: cannot send zero {base} to bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080\n"
+  ))
+  .run_and_extract_stdout();
+
+  assert!(output.is_empty());
+}
+
+#[test]
+fn export_relics_writes_entries_balances_and_events() {
+  let (core, ord) = setup();
+  mint_base(&core, &ord);
+  core.mine_blocks(1);
+
+  let base = SpacedRelic::from_str(RELIC_NAME).unwrap();
+  let outpoint = *relic_outputs(&ord)[&base].keys().next().unwrap();
+
+  let tempdir = TempDir::new().unwrap();
+
+  CommandBuilder::new(format!(
+    "--regtest --index-relics index export --relics {}",
+    tempdir.path().display(),
+  ))
+  .core(&core)
+  .run_and_extract_stdout();
+
+  let records = |file: &str| {
+    csv::Reader::from_path(tempdir.path().join(file))
+      .unwrap()
+      .into_records()
+      .map(|record| record.unwrap())
+      .collect::<Vec<csv::StringRecord>>()
+  };
+
+  let relics = records("relics.csv");
+  let entry = relics
+    .iter()
+    .find(|record| record[1] == base.to_string())
+    .unwrap();
+  let id = &entry[0];
+
+  let balances = records("balances.csv");
+  let balance = balances
+    .iter()
+    .find(|record| record[0] == outpoint.to_string())
+    .unwrap();
+  assert!(balance[1].starts_with("bcrt1"));
+  assert_eq!(&balance[2], id);
+  assert_eq!(&balance[3], base.to_string());
+  assert_eq!(&balance[4], "654205000000");
+
+  assert!(records("events.csv")
+    .iter()
+    .any(|record| &record[0] == id && &record[4] == "RelicMinted"));
+}
+
+fn relic_outputs(ord: &TestServer) -> BTreeMap<SpacedRelic, BTreeMap<OutPoint, u128>> {
+  serde_json::from_str(&ord.json_request("/relics/balances").text().unwrap()).unwrap()
+}
+
+fn validate_offer(ord: &TestServer, psbt: &str) -> api::RelicOfferValidation {
+  reqwest::blocking::Client::new()
+    .post(ord.url().join("/relics/offer").unwrap())
+    .header(reqwest::header::ACCEPT, "application/json")
+    .json(psbt)
+    .send()
+    .unwrap()
+    .json()
+    .unwrap()
+}
+
+#[test]
+fn relics_can_be_sold_for_bitcoin_with_an_offer() {
+  let (core, ord) = setup();
+  mint_base(&core, &ord);
+  core.mine_blocks(1);
+
+  let base = SpacedRelic::from_str(RELIC_NAME).unwrap();
+  let outpoint = *relic_outputs(&ord)[&base].keys().next().unwrap();
+
+  let offer = CommandBuilder::new(format!(
+    "--regtest --index-relics wallet offer create --outpoint {outpoint} --price 60btc"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<ord::subcommand::wallet::offer::create::Output>();
+
+  assert_eq!(offer.relics[&base].to_string(), "6542.05");
+
+  let validation = validate_offer(&ord, &offer.psbt);
+  assert!(validation.valid, "{:?}", validation.errors);
+  assert!(!validation.accepted);
+  assert!(validation.price.is_empty());
+  assert_eq!(validation.inputs[0].outpoint, outpoint);
+
+  let accept = CommandBuilder::new(format!(
+    "--regtest --index-relics wallet offer accept --fee-rate 1 --psbt {}",
+    offer.psbt
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<ord::subcommand::wallet::offer::accept::Output>();
+
+  assert_eq!(accept.relics[&base].to_string(), "6542.05");
+
+  core.mine_blocks(1);
+
+  let transaction = core.tx_by_id(accept.txid);
+  assert_eq!(transaction.input[0].previous_output, outpoint);
+  assert_eq!(
+    transaction.output[0].value,
+    Amount::from_sat(60 * COIN_VALUE)
+  );
+  // the seller signed with SIGHASH_SINGLE|ANYONECANPAY
+  assert_eq!(
+    transaction.input[0].witness.nth(0).unwrap().last(),
+    Some(&0x83)
+  );
+
+  assert_eq!(
+    relic_outputs(&ord)[&base]
+      .clone()
+      .into_iter()
+      .collect::<Vec<(OutPoint, u128)>>(),
+    [(
+      OutPoint {
+        txid: accept.txid,
+        vout: 2,
+      },
+      6542_05000000
+    )],
+  );
+
+  let validation = validate_offer(&ord, &offer.psbt);
+  assert!(!validation.valid);
+  assert_eq!(validation.errors, [format!("input {outpoint} is spent")]);
+}
+
+#[test]
+fn offers_priced_in_relics_are_rejected() {
+  let (core, ord) = setup();
+  mint_base(&core, &ord);
+
+  let base = SpacedRelic::from_str(RELIC_NAME).unwrap();
+  let relic = SpacedRelic::from_str("BASIC•TEST•RELIC").unwrap();
+  seal(&core, &ord, relic);
+  relic_enshrine(&core, &ord, relic, 0, 0);
+  relic_mint(&core, &ord, relic, 1, None, None);
+  core.mine_blocks(1);
+
+  let outpoint = *relic_outputs(&ord)[&relic].keys().next().unwrap();
+
+  CommandBuilder::new(format!(
+    "--regtest --index-relics wallet offer create --outpoint {outpoint} --price 100:r:{base}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .expected_stderr("error: offer price must be a bitcoin amount\n")
+  .run_and_extract_stdout();
+
+  let offer = CommandBuilder::new(format!(
+    "--regtest --index-relics wallet offer create --outpoint {outpoint} --price 0.1btc"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<ord::subcommand::wallet::offer::create::Output>();
+
+  // the seller signature doesn't commit to the global map, so anyone can make the offer claim a
+  // base token price, which the keepsake of the buyer would never pay
+  let mut psbt = Psbt::deserialize(
+    &base64::engine::general_purpose::STANDARD
+      .decode(&offer.psbt)
+      .unwrap(),
+  )
+  .unwrap();
+
+  let mut price = Vec::new();
+  for integer in [
+    u128::from(RELIC_ID.block),
+    u128::from(RELIC_ID.tx),
+    100_00000000,
+  ] {
+    ordinals::varint::encode_to_vec(integer, &mut price);
+  }
+
+  psbt.proprietary.insert(
+    ProprietaryKey {
+      prefix: b"ord".to_vec(),
+      subtype: 1,
+      key: Vec::new(),
+    },
+    price,
+  );
+
+  let malicious = base64::engine::general_purpose::STANDARD.encode(psbt.serialize());
+
+  let validation = validate_offer(&ord, &malicious);
+  assert!(!validation.valid);
+  assert_eq!(
+    validation.errors,
+    ["offer is priced in relics, only bitcoin prices can be enforced"],
+  );
+  assert_eq!(validation.price[&base].amount, 100_00000000);
+
+  CommandBuilder::new(format!(
+    "--regtest --index-relics wallet offer accept --fee-rate 1 --psbt {malicious}"
+  ))
+  .core(&core)
+  .ord(&ord)
+  .expected_exit_code(1)
+  .expected_stderr(
+    "error: invalid offer: offer is priced in relics, only bitcoin prices can be enforced\n",
+  )
+  .run_and_extract_stdout();
+
+  core.mine_blocks(1);
+
+  assert_eq!(
+    relic_outputs(&ord)[&relic]
+      .keys()
+      .collect::<Vec<&OutPoint>>(),
+    [&outpoint],
+  );
+}
+
+#[test]
+fn split_cannot_mix_runes_and_relics() {
+  let (core, ord) = setup();
+  let base = SpacedRelic::from_str(RELIC_NAME).unwrap();
+
+  CommandBuilder::new("--regtest --index-relics wallet split --fee-rate 1 --splits splits.yaml")
+    .write(
+      "splits.yaml",
+      format!(
+        "
+outputs:
+- address: bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw
+  runes:
+    AAAAAAAAAAAAA: 100
+  relics:
+    {base}: 100
+"
+      ),
+    )
+    .core(&core)
+    .ord(&ord)
+    .expected_stderr("error: split file cannot contain both runes and relics\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
 #[test]
 fn mint_relic_works() {
   let (core, ord) = setup();
@@ -123,8 +690,8 @@ fn mint_curved_relic_works() {
   // Check base balance is reduced by 1 (sealing) + 0.01 (mint)
   assert!(relics.contains_key(&base));
   let base_balance = relics.get(&base).unwrap();
-  assert_eq!(base_balance.value, 65402973487);
-  assert_eq!(base_balance.scale, 7);
+  assert_eq!(base_balance.value, 654029734869);
+  assert_eq!(base_balance.scale, 8);
 }
 
 #[test]
@@ -245,8 +812,8 @@ fn multi_mint_curved_relic_works() {
   // Check base balance is reduced by 1 (sealing) + 0.01 (mint)
   assert!(relics.contains_key(&base));
   let base_balance = relics.get(&base).unwrap();
-  assert_eq!(base_balance.value, 65402973487);
-  assert_eq!(base_balance.scale, 7);
+  assert_eq!(base_balance.value, 654029734869);
+  assert_eq!(base_balance.scale, 8);
 }
 
 #[test]
@@ -377,6 +377,7 @@ inscriptions:
       runes: BTreeMap::new(),
       sat_ranges: Some(vec![(5_000_000_000, 5_000_030_000)]),
       script_pubkey: destination.assume_checked_ref().script_pubkey(),
+      shares: BTreeMap::new(),
       spent: false,
       transaction: reveal_txid,
       value: 30_000,
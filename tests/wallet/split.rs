@@ -11,7 +11,9 @@ fn requires_rune_index() {
   CommandBuilder::new("wallet split --fee-rate 1 --splits splits.yaml")
     .core(&core)
     .ord(&ord)
-    .expected_stderr("error: `ord wallet split` requires index created with `--index-runes`\n")
+    .expected_stderr(
+      "error: `ord wallet split` requires index created with `--index-runes` or `--index-relics`\n",
+    )
     .expected_exit_code(1)
     .run_and_extract_stdout();
 }